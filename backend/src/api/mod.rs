@@ -1,39 +1,85 @@
 use axum::{
+    body::Body,
     extract::Json,
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse},
     routing::post,
     Router,
 };
+use std::convert::Infallible;
+use tokio_stream::{wrappers::{ReceiverStream, UnboundedReceiverStream}, Stream, StreamExt};
 
 use crate::{
-    diff::{compare_texts, aligner::align_articles},
-    models::{CompareRequest, DiffResult},
-    nlp::{NERMode, create_ner_engine},
-    ast::parse_article,
+    diff::{budget::Budget, compare_texts_with_algorithm, elide_unchanged, ignore_format_changes, aligner::{align_articles_with_options, align_parsed, align_parts, align_prepared, average_similarity, count_by_type, parse_prepared, prepare_document, similarity_pairs, AlignmentResult}, stats::entity_deltas, amend::{apply_amendments, parse_amendments}, apply::apply_changes, merge::compare3, timeline::align_versions, patches::to_per_article_patches, penalties::penalty_changes, renumbering::renumbering_map},
+    models::{AlignmentProgress, AmendRequest, AmendResult, ApplyRequest, ArticleChange, ArticleChangeType, ArticleNode, CompareRequest, CompareStats, CompareThreeRequest, ExportRequest, DiffResult, Entity, Glossary, NerInfo, NormalizeNumbersRequest, NormalizeStage, ParseDocumentsRequest, ParseRequest, ParsedCompareRequest, ParsedDocument, ParseResult, ParserOptions, PreambleMode, ProgressSink, SummaryRequest, ThreeWayResult, TimelineRequest, TimelineResult, ValidationResult},
+    export::{akoma_ntoso::to_akoma_ntoso, docx::diff_to_docx, html::diff_to_html, markdown::{ast_to_markdown, diff_to_markdown}, patch::diff_to_patch, pdf::diff_to_pdf, spreadsheet::{to_csv, to_xlsx}},
+    report::change_summary,
+    nlp::formatter::{clean_pdf_artifacts, normalize_legal_text, reflow_hard_wraps},
+    nlp::opencc::convert_script,
+    nlp::{NERMode, create_ner_engine, extract_glossary, normalize_article_numbers},
+    ast::{extract_document_meta, parse_article, parse_article_with_options, parse_document, render_text_with_indent, split_documents, ParseError, ParseErrorBody, stream_articles, check_toc, validate_numbering},
 };
 
 /// Compare two legal texts
-// Helper to extract entities
-fn extract_entities_helper(payload: &CompareRequest) -> Vec<crate::models::Entity> {
+// Helper to extract entities, reporting which engine handled them
+fn extract_entities_helper(payload: &CompareRequest) -> (Vec<Entity>, Option<NerInfo>) {
+    match extract_entities_by_side(payload) {
+        Some((mut all_entities, new_entities, info)) => {
+            all_entities.extend(new_entities);
+            (all_entities, Some(info))
+        }
+        None => (Vec::new(), None),
+    }
+}
+
+// Helper to extract the entities of the old and the new text separately
+fn extract_entities_by_side(payload: &CompareRequest) -> Option<(Vec<Entity>, Vec<Entity>, NerInfo)> {
     let ner_mode = payload.options.ner_mode
         .as_ref()
         .and_then(|s| NERMode::from_str(s.as_str()))
         .unwrap_or_default();
 
-    if payload.options.detect_entities {
-        if let Ok(ner_engine) = create_ner_engine(ner_mode) {
-            let mut all_entities = Vec::new();
-            if let Ok(e) = ner_engine.extract_entities(&payload.old_text) {
-                all_entities.extend(e);
-            }
-            if let Ok(e) = ner_engine.extract_entities(&payload.new_text) {
-                all_entities.extend(e);
-            }
-            return all_entities;
-        }
+    if !payload.options.detect_entities {
+        return None;
+    }
+    let ner_engine = create_ner_engine(ner_mode).ok()?;
+    let old_entities = ner_engine.extract_entities(&payload.old_text).unwrap_or_default();
+    let new_entities = ner_engine.extract_entities(&payload.new_text).unwrap_or_default();
+    let info = NerInfo {
+        engine: ner_engine.name().into(),
+        bert_fallback: ner_engine.fallback_triggered(),
+    };
+    Some((old_entities, new_entities, info))
+}
+
+// Helper for the line diff, after script conversion, PDF cleanup and reflow when requested;
+// format-only edits are folded into Unchanged afterwards, then unchanged lines trimmed to
+// the requested context
+fn line_diff(payload: &CompareRequest, entities: Vec<Entity>) -> DiffResult {
+    let prepare = |text: &str| {
+        let text = convert_script(text, &payload.options);
+        let text = if payload.options.clean_pdf_artifacts { clean_pdf_artifacts(&text) } else { text.into_owned() };
+        if payload.options.normalize_stages.contains(&NormalizeStage::Reflow) { reflow_hard_wraps(&text) } else { text }
+    };
+    let options = &payload.options;
+    let mut result = compare_texts_with_algorithm(
+        &prepare(&payload.old_text), &prepare(&payload.new_text), options.granularity, options.algorithm, entities,
+    );
+    if options.ignore_punctuation || options.ignore_whitespace {
+        ignore_format_changes(&mut result, options.ignore_punctuation, options.ignore_whitespace);
+    }
+    if options.omit_unchanged {
+        result.changes = elide_unchanged(result.changes, None);
+    } else if let Some(context) = options.context_lines {
+        result.changes = elide_unchanged(result.changes, Some(context));
     }
-    Vec::new()
+    result
+}
+
+// Helper to describe both compared versions from their parsed trees
+fn attach_document_meta(result: &mut DiffResult, old: &ArticleNode, new: &ArticleNode) {
+    result.old_meta = Some(extract_document_meta(old));
+    result.new_meta = Some(extract_document_meta(new));
 }
 
 /// Compare two legal texts (Git/Line Diff Only)
@@ -41,8 +87,46 @@ async fn compare_git(
     Json(payload): Json<CompareRequest>,
 ) -> Result<Json<DiffResult>, StatusCode> {
     let result = tokio::task::spawn_blocking(move || {
-        let entities = extract_entities_helper(&payload);
-        compare_texts(&payload.old_text, &payload.new_text, entities)
+        let (entities, ner_info) = extract_entities_helper(&payload);
+        let mut result = line_diff(&payload, entities);
+        result.ner_info = ner_info;
+        // The line diff never parses; each side is parsed once, as the structure diff would
+        let old = parse_prepared(&payload.old_text, &payload.options);
+        let new = parse_prepared(&payload.new_text, &payload.options);
+        attach_document_meta(&mut result, &old, &new);
+        result
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+/// Aggregate numbers of a comparison (change counts, chapters, similarity, entity deltas)
+/// without article texts
+async fn compare_stats(
+    Json(payload): Json<CompareRequest>,
+) -> Result<Json<CompareStats>, StatusCode> {
+    let result = tokio::task::spawn_blocking(move || {
+        let alignment = align_articles_with_options(&payload.old_text, &payload.new_text, &payload.options);
+        let changes = apply_similarity_filter(alignment.changes, &payload.options);
+        let (old_entities, new_entities) = extract_entities_by_side(&payload)
+            .map(|(old, new, _)| (old, new))
+            .unwrap_or_default();
+
+        let side_count = |side: fn(&ArticleChange) -> usize| changes.iter().map(side).sum();
+        CompareStats {
+            old_articles: side_count(|c| c.merged_from.as_ref().map_or(c.old_article.is_some() as usize, Vec::len)),
+            new_articles: side_count(|c| match c.change_type {
+                ArticleChangeType::Deleted => 0,
+                _ => c.new_articles.as_ref().map_or(0, Vec::len),
+            }),
+            articles_by_type: count_by_type(&changes),
+            chapters: alignment.chapter_stats,
+            average_similarity: average_similarity(&changes),
+            structure_changes: alignment.structure_changes.len(),
+            entities: entity_deltas(&old_entities, &new_entities),
+            warnings: alignment.warnings,
+            quality: alignment.quality,
+        }
     }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(result))
@@ -52,52 +136,253 @@ async fn compare_git(
 async fn compare_structure(
     Json(payload): Json<CompareRequest>,
 ) -> Result<Json<DiffResult>, StatusCode> {
-    let article_changes = tokio::task::spawn_blocking(move || {
-        align_articles(
-            &payload.old_text,
-            &payload.new_text,
-            payload.options.align_threshold,
-            payload.options.format_text
-        )
-    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let mut result = DiffResult {
-        changes: vec![], // Empty git changes
-        stats: crate::models::DiffStats { additions: 0, deletions: 0, modifications: 0, unchanged: 0 },
-        similarity: 0.0,
-        entities: vec![],
-        article_changes: None,
-    };
+    // Dropped with this future when the client disconnects, stopping the worker
+    let _cancel = payload.options.cancel.cancel_on_drop();
+    let result = tokio::task::spawn_blocking(move || structure_compare(&payload))
+        .await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Calculate overall similarity as average
-    let total_sim: f32 = article_changes.iter().map(|c| c.similarity.unwrap_or(0.0)).sum();
-    if !article_changes.is_empty() {
-        result.similarity = total_sim / article_changes.len() as f32;
+    Ok(Json(result))
+}
+
+/// Compare like /api/compare/structure, streaming the same events as /api/compare/stream
+async fn compare_structure_stream(
+    Json(payload): Json<CompareRequest>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    stream_compare(payload, structure_compare)
+}
+
+// Helper for the structure diff alone: empty git changes, overall similarity is the
+// article average
+fn structure_compare(payload: &CompareRequest) -> DiffResult {
+    let mut result = DiffResult::default();
+    let alignment = align_documents(payload, &mut result);
+    result.similarity = average_similarity(&alignment.changes);
+    result.warnings = alignment.warnings;
+    result.truncated = alignment.truncated;
+    result.quality = Some(alignment.quality);
+    result.preamble_changes = (payload.options.preamble == PreambleMode::Separate).then_some(alignment.preamble_changes);
+    result.stats.articles_by_type = count_by_type(&alignment.changes);
+    result.stats.chapters = alignment.chapter_stats;
+    result.structure_changes = Some(alignment.structure_changes);
+    result.article_changes = Some(apply_similarity_filter(alignment.changes, &payload.options));
+    result
+}
+
+/// Structure diff of two documents already parsed (/api/parse results), skipping the
+/// parse; without the texts there is no line diff and no source positions
+async fn compare_parsed(
+    Json(payload): Json<ParsedCompareRequest>,
+) -> Result<Json<DiffResult>, StatusCode> {
+    let _cancel = payload.options.cancel.cancel_on_drop();
+    let result = tokio::task::spawn_blocking(move || {
+        let alignment = align_parsed(&payload.old_ast, &payload.new_ast, &payload.options);
+
+        let mut result = DiffResult {
+            similarity: average_similarity(&alignment.changes),
+            warnings: alignment.warnings,
+            truncated: alignment.truncated,
+            quality: Some(alignment.quality),
+            preamble_changes: (payload.options.preamble == PreambleMode::Separate).then_some(alignment.preamble_changes),
+            old_meta: Some(extract_document_meta(&payload.old_ast)),
+            new_meta: Some(extract_document_meta(&payload.new_ast)),
+            ..Default::default()
+        };
+        result.stats.articles_by_type = count_by_type(&alignment.changes);
+        result.stats.chapters = alignment.chapter_stats;
+        result.structure_changes = Some(alignment.structure_changes);
+        result.article_changes = Some(apply_similarity_filter(alignment.changes, &payload.options));
+        result
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+/// Three-way comparison of two drafts against their common base, with the auto-merged text
+async fn compare_three(
+    Json(payload): Json<CompareThreeRequest>,
+) -> Result<Json<ThreeWayResult>, StatusCode> {
+    let result = tokio::task::spawn_blocking(move || {
+        compare3(&payload.base_text, &payload.a_text, &payload.b_text, &payload.options)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+/// Per-article history across an ordered list of versions (oldest first)
+async fn compare_timeline(
+    Json(payload): Json<TimelineRequest>,
+) -> Result<Json<TimelineResult>, StatusCode> {
+    if payload.versions.len() < 2 {
+        return Err(StatusCode::BAD_REQUEST);
     }
+    let result = tokio::task::spawn_blocking(move || {
+        let texts: Vec<&str> = payload.versions.iter().map(String::as_str).collect();
+        align_versions(&texts, &payload.options)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    result.article_changes = Some(apply_similarity_filter(article_changes, &payload.options));
     Ok(Json(result))
 }
 
+/// Markdown summary of a revision: change counts, added/deleted articles, most changed articles
+async fn report_summary(
+    Json(payload): Json<SummaryRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let markdown = tokio::task::spawn_blocking(move || {
+        let alignment = align_articles_with_options(&payload.old_text, &payload.new_text, &payload.options);
+        change_summary(&apply_similarity_filter(alignment.changes, &payload.options), payload.top_n)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown))
+}
+
+/// Structural diff rendered as one unified-diff patch per changed article
+async fn compare_structure_patches(
+    Json(payload): Json<CompareRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let patches = tokio::task::spawn_blocking(move || {
+        let alignment = align_articles_with_options(&payload.old_text, &payload.new_text, &payload.options);
+        to_per_article_patches(&alignment.changes)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let body: Vec<_> = patches.into_iter()
+        .map(|(number, patch)| serde_json::json!({ "number": number, "patch": patch }))
+        .collect();
+    Ok(Json(body))
+}
+
+/// Debug: all article pairs above the similarity floor with their score components
+async fn compare_similarity_pairs(
+    Json(payload): Json<CompareRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let pairs = tokio::task::spawn_blocking(move || {
+        similarity_pairs(&payload.old_text, &payload.new_text, &payload.options)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(pairs))
+}
+
+/// Only the matched articles that gained or lost penalties
+async fn compare_penalties(
+    Json(payload): Json<CompareRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let report = tokio::task::spawn_blocking(move || {
+        let alignment = align_articles_with_options(&payload.old_text, &payload.new_text, &payload.options);
+        penalty_changes(&alignment.changes)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(report))
+}
+
+/// Old→new article number table, for updating cross-references elsewhere
+async fn compare_renumbering(
+    Json(payload): Json<CompareRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let map = tokio::task::spawn_blocking(move || {
+        let alignment = align_articles_with_options(&payload.old_text, &payload.new_text, &payload.options);
+        renumbering_map(&alignment.changes)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(map))
+}
+
 /// Compare two legal texts (Full Analysis)
 async fn compare(
     Json(payload): Json<CompareRequest>,
 ) -> Result<Json<DiffResult>, StatusCode> {
-    let result = tokio::task::spawn_blocking(move || {
-        let entities = extract_entities_helper(&payload);
+    // Dropped with this future when the client disconnects, stopping the worker
+    let _cancel = payload.options.cancel.cancel_on_drop();
+    let result = tokio::task::spawn_blocking(move || full_compare(&payload))
+        .await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
 
-        // 1. Git Diff
-        let mut result = compare_texts(&payload.old_text, &payload.new_text, entities);
+/// Compare like /api/compare, streaming Server-Sent Events while aligning: "parsed" once
+/// both texts are parsed, "matrix" for every tenth of the similarity matrix, "stage" after
+/// each alignment stage, then one "result" carrying the DiffResult
+async fn compare_stream(
+    Json(payload): Json<CompareRequest>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    stream_compare(payload, full_compare)
+}
 
-        // 2. Structure Diff
-        let article_changes = align_articles(
-            &payload.old_text,
-            &payload.new_text,
-            payload.options.align_threshold,
-            payload.options.format_text
-        );
-        result.article_changes = Some(apply_similarity_filter(article_changes, &payload.options));
-        result
+// Helper running `compare` on a worker, forwarding its alignment progress as events
+fn stream_compare(
+    mut payload: CompareRequest,
+    compare: fn(&CompareRequest) -> DiffResult,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let progress_sender = sender.clone();
+    payload.options.progress = ProgressSink::new(move |progress: AlignmentProgress| {
+        let _ = progress_sender.send(Event::default().event(progress.event_name()).json_data(&progress));
+    });
+
+    // Dropped with the stream when the client disconnects, stopping the worker
+    let cancel = payload.options.cancel.cancel_on_drop();
+    tokio::task::spawn_blocking(move || {
+        let result = compare(&payload);
+        let _ = sender.send(Event::default().event("result").json_data(&result));
+    });
+
+    let events = UnboundedReceiverStream::new(receiver).map(move |event| {
+        let _ = &cancel;
+        event
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+// Helper running the git diff and the structure diff together
+fn full_compare(payload: &CompareRequest) -> DiffResult {
+    let (entities, ner_info) = extract_entities_helper(payload);
+
+    // 1. Git Diff
+    let mut result = line_diff(payload, entities);
+    result.ner_info = ner_info;
+
+    // 2. Structure Diff
+    let alignment = align_documents(payload, &mut result);
+    result.warnings.extend(alignment.warnings);
+    result.truncated = alignment.truncated;
+    result.quality = Some(alignment.quality);
+    result.preamble_changes = (payload.options.preamble == PreambleMode::Separate).then_some(alignment.preamble_changes);
+    result.stats.articles_by_type = count_by_type(&alignment.changes);
+    result.stats.chapters = alignment.chapter_stats;
+    result.structure_changes = Some(alignment.structure_changes);
+    result.article_changes = Some(apply_similarity_filter(alignment.changes, &payload.options));
+    result
+}
+
+// Helper for the structure diff: each text is prepared and parsed once, and the same trees
+// feed the alignment, the per-编 results and the document metadata. Both alignments share
+// the request's time budget; running out in either marks the result truncated.
+fn align_documents(payload: &CompareRequest, result: &mut DiffResult) -> AlignmentResult {
+    let old = prepare_document(&payload.old_text, &payload.options);
+    let new = prepare_document(&payload.new_text, &payload.options);
+    let budget = Budget::start(&payload.options);
+    let mut alignment = align_prepared(&old, &new, &payload.options, &budget);
+    if payload.options.partition_by_part {
+        result.part_results = Some(align_parts(&old, &new, &payload.options, &budget));
+    }
+    alignment.truncated |= budget.tripped();
+    attach_document_meta(result, &old.ast, &new.ast);
+    alignment
+}
+
+/// Apply an amendment decision to a base law, returning the consolidated text and its diff
+async fn amend(
+    Json(payload): Json<AmendRequest>,
+) -> Result<Json<AmendResult>, StatusCode> {
+    let result = tokio::task::spawn_blocking(move || {
+        let (instructions, mut warnings) = parse_amendments(&payload.amendment_text);
+        let (text, unapplied) = apply_amendments(&payload.base_text, &instructions);
+        warnings.extend(unapplied);
+
+        let diff = full_compare(&CompareRequest {
+            old_text: payload.base_text,
+            new_text: text.clone(),
+            options: payload.options,
+        });
+        AmendResult { text, instructions, warnings, diff }
     }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(result))
@@ -129,12 +414,327 @@ fn apply_similarity_filter(
 
 
 
-/// Parse legal article text to AST
+/// Apply a change set to a base text, producing the consolidated document
+async fn apply(
+    Json(payload): Json<ApplyRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let text = tokio::task::spawn_blocking(move || {
+        apply_changes(&payload.base_text, &payload.changes)
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(serde_json::json!({ "text": text })))
+}
+
+/// Parse one legal text to its AST, with document metadata and parse diagnostics.
+/// Several documents go to /api/parse/documents; `document_delimiters` here is a 400.
+/// Input with no recognizable structure is rejected with 422 and a `ParseErrorBody`.
 async fn parse(
-    Json(text): Json<String>,
+    Json(payload): Json<ParseRequest>,
+) -> Result<axum::response::Response, StatusCode> {
+    let (text, options) = single_document(payload)?;
+    tokio::task::spawn_blocking(move || match parse_document(&text, &options) {
+        Ok(parsed) => Json(parse_result(parsed)).into_response(),
+        Err(error) => parse_error_response(error, None),
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Parse several legal texts, one /api/parse result per document. The first document
+/// without recognizable structure fails the request with 422, its index in `document`.
+async fn parse_documents(
+    Json(payload): Json<ParseDocumentsRequest>,
+) -> Result<axum::response::Response, StatusCode> {
+    tokio::task::spawn_blocking(move || {
+        let (documents, options) = split_each(payload);
+        let mut results = Vec::new();
+        for (index, text) in documents.iter().enumerate() {
+            match parse_document(text, &options) {
+                Ok(parsed) => results.push(parse_result(parsed)),
+                Err(error) => return parse_error_response(error, Some(index)),
+            }
+        }
+        Json(results).into_response()
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// Helper for the single-document endpoints; delimiters would make several documents
+// of the text, which only the /documents variants return
+fn single_document(payload: ParseRequest) -> Result<(String, ParserOptions), StatusCode> {
+    let (text, options) = match payload {
+        ParseRequest::Text(text) => (text, ParserOptions::default()),
+        ParseRequest::WithOptions { text, options } => (text, options),
+    };
+    if !options.document_delimiters.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok((text, options))
+}
+
+// Helper for the /documents endpoints: the submitted texts, each split on the delimiters
+fn split_each(payload: ParseDocumentsRequest) -> (Vec<String>, ParserOptions) {
+    let (documents, options) = match payload {
+        ParseDocumentsRequest::Documents(documents) => (documents, ParserOptions::default()),
+        ParseDocumentsRequest::WithOptions { documents, options } => (documents, options),
+    };
+    if options.document_delimiters.is_empty() {
+        return (documents, options);
+    }
+    let documents = documents.iter().flat_map(|text| split_documents(text, &options)).collect();
+    (documents, options)
+}
+
+fn parse_result(parsed: ParsedDocument) -> ParseResult {
+    ParseResult {
+        meta: extract_document_meta(&parsed.ast),
+        ast: parsed.ast,
+        diagnostics: parsed.diagnostics,
+    }
+}
+
+fn parse_error_response(error: ParseError, document: Option<usize>) -> axum::response::Response {
+    (StatusCode::UNPROCESSABLE_ENTITY, Json(ParseErrorBody::new(error, document))).into_response()
+}
+
+/// Articles serialized ahead of a slow client before the parser waits for it
+const NDJSON_BUFFERED_LINES: usize = 64;
+
+/// Parse a large document incrementally, one flattened article per NDJSON line, each sent
+/// as soon as it is parsed. With `document_delimiters` the text is streamed document by
+/// document, and `document` on each line indexes the one the article belongs to.
+async fn parse_articles(
+    Json(payload): Json<ParseRequest>,
+) -> impl IntoResponse {
+    let (sender, receiver) = tokio::sync::mpsc::channel(NDJSON_BUFFERED_LINES);
+    tokio::task::spawn_blocking(move || {
+        let (text, options) = match payload {
+            ParseRequest::Text(text) => (text, ParserOptions::default()),
+            ParseRequest::WithOptions { text, options } => (text, options),
+        };
+        let documents = if options.document_delimiters.is_empty() {
+            vec![text]
+        } else {
+            split_documents(&text, &options)
+        };
+        for (document, text) in documents.iter().enumerate() {
+            for article in stream_articles(text.as_bytes(), &options) {
+                let mut value = serde_json::to_value(&article).unwrap_or_default();
+                value["document"] = document.into();
+                let mut line = value.to_string();
+                line.push('\n');
+                // The client went away; stop parsing
+                if sender.blocking_send(Ok::<_, Infallible>(line)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(ReceiverStream::new(receiver)))
+}
+
+/// Report article/heading numbering gaps, duplicates and ordering problems, and headings
+/// that disagree with the document's 目录
+async fn validate(
+    Json(payload): Json<ParseRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (text, options) = single_document(payload)?;
+    let result = tokio::task::spawn_blocking(move || {
+        validate_ast(&parse_article_with_options(&text, &options))
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+/// /api/validate for several documents, one result per document
+async fn validate_documents(
+    Json(payload): Json<ParseDocumentsRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (documents, options) = split_each(payload);
+    let results: Vec<ValidationResult> = tokio::task::spawn_blocking(move || {
+        documents.iter().map(|text| validate_ast(&parse_article_with_options(text, &options))).collect()
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(results))
+}
+
+fn validate_ast(ast: &ArticleNode) -> ValidationResult {
+    let findings = validate_numbering(ast);
+    let toc_findings = check_toc(ast);
+    ValidationResult { valid: findings.is_empty() && toc_findings.is_empty(), findings, toc_findings }
+}
+
+/// Serialize an AST back into legal text, each node indented as it was submitted
+/// (ASTs without recorded indentation come out normalized)
+async fn render(
+    Json(ast): Json<ArticleNode>,
+) -> impl IntoResponse {
+    Json(serde_json::json!({ "text": render_text_with_indent(&ast) }))
+}
+
+/// Export a parsed document or a comparison as Markdown
+async fn export_markdown(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let markdown = tokio::task::spawn_blocking(move || match payload {
+        ExportRequest::Text(text) => ast_to_markdown(&parse_article(&normalize_legal_text(&text))),
+        ExportRequest::Compare(request) => diff_to_markdown(&full_compare(&request)),
+        ExportRequest::Diff(result) => diff_to_markdown(&result),
+        ExportRequest::Ast(ast) => ast_to_markdown(&ast),
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown))
+}
+
+// Helper for the comparison exports: the diff of a request, computed or supplied; a single
+// document has nothing to compare against. With `full_text` a computed diff keeps every
+// unchanged line, for exports rebuilt from the line diff.
+async fn export_diff(payload: ExportRequest, full_text: bool) -> Result<DiffResult, StatusCode> {
+    match payload {
+        ExportRequest::Compare(mut request) => {
+            if full_text {
+                request.options.context_lines = None;
+                request.options.omit_unchanged = false;
+            }
+            tokio::task::spawn_blocking(move || full_compare(&request))
+                .await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        ExportRequest::Diff(result) => Ok(*result),
+        ExportRequest::Text(_) | ExportRequest::Ast(_) => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+/// Export a comparison as a unified diff; a diff trimmed to `context_lines` no longer holds
+/// the texts the hunks number
+async fn export_patch(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = export_diff(payload, true).await?;
+    let patch = diff_to_patch(&result).ok_or(StatusCode::BAD_REQUEST)?;
+    Ok(([(header::CONTENT_TYPE, "text/x-diff; charset=utf-8")], patch))
+}
+
+/// Export a comparison as a standalone HTML redline
+async fn export_html(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = export_diff(payload, false).await?;
+    Ok(([(header::CONTENT_TYPE, "text/html; charset=utf-8")], diff_to_html(&result)))
+}
+
+/// Export a comparison as a .docx of the new version with Word tracked changes
+async fn export_docx(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    // Every unchanged line belongs in the document
+    let result = export_diff(payload, true).await?;
+    let docx = diff_to_docx(&result).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"redline.docx\""),
+        ],
+        docx,
+    ))
+}
+
+/// Export a comparison as a PDF report (summary, per-chapter statistics, article redline)
+async fn export_pdf(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = export_diff(payload, false).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"comparison.pdf\""),
+        ],
+        diff_to_pdf(&result),
+    ))
+}
+
+/// Export the article change table of a comparison as CSV
+async fn export_csv(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let changes = export_diff(payload, false).await?.article_changes.unwrap_or_default();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"changes.csv\""),
+        ],
+        to_csv(&changes),
+    ))
+}
+
+/// Export the article change table of a comparison as an Excel workbook
+async fn export_xlsx(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let changes = export_diff(payload, false).await?.article_changes.unwrap_or_default();
+    let xlsx = to_xlsx(&changes).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"changes.xlsx\""),
+        ],
+        xlsx,
+    ))
+}
+
+/// Export a parsed document as Akoma Ntoso XML; comparisons have no AKN form
+async fn export_akoma_ntoso(
+    Json(payload): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let ast = match payload {
+        ExportRequest::Text(text) => parse_article(&normalize_legal_text(&text)),
+        ExportRequest::Ast(ast) => ast,
+        ExportRequest::Compare(_) | ExportRequest::Diff(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    let xml = to_akoma_ntoso(&ast, &extract_document_meta(&ast));
+    Ok(([(header::CONTENT_TYPE, "application/akn+xml")], xml))
+}
+
+/// Defined terms alone, without the document title: /api/glossary's `terms`
+async fn definitions(
+    Json(payload): Json<ParseRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (text, options) = single_document(payload)?;
+    Ok(Json(glossary_of(text, options).await?.terms))
+}
+
+/// Defined terms (本法所称X，是指Y) with the article defining each
+async fn glossary(
+    Json(payload): Json<ParseRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (text, options) = single_document(payload)?;
+    Ok(Json(glossary_of(text, options).await?))
+}
+
+// Helper shared by /api/glossary and /api/definitions
+async fn glossary_of(text: String, options: ParserOptions) -> Result<Glossary, StatusCode> {
+    tokio::task::spawn_blocking(move || {
+        extract_glossary(&parse_article_with_options(&normalize_legal_text(&text), &options))
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// /api/glossary for several documents, one glossary per document
+async fn glossary_documents(
+    Json(payload): Json<ParseDocumentsRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (documents, options) = split_each(payload);
+    let glossaries: Vec<Glossary> = tokio::task::spawn_blocking(move || {
+        documents.iter()
+            .map(|text| extract_glossary(&parse_article_with_options(&normalize_legal_text(text), &options)))
+            .collect()
+    }).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(glossaries))
+}
+
+/// Rewrite article/chapter numbers between Chinese and arabic numerals
+async fn normalize_numbers(
+    Json(payload): Json<NormalizeNumbersRequest>,
 ) -> impl IntoResponse {
-    let ast = parse_article(&text);
-    Json(ast)
+    let text = normalize_article_numbers(&payload.text, payload.to_arabic);
+    Json(serde_json::json!({ "text": text }))
 }
 
 /// Health check endpoint
@@ -163,9 +763,219 @@ async fn get_examples() -> impl IntoResponse {
 pub fn create_router() -> Router {
     Router::new()
         .route("/api/compare", post(compare))
+        .route("/api/compare/stream", post(compare_stream))
         .route("/api/compare/git", post(compare_git))
         .route("/api/compare/structure", post(compare_structure))
+        .route("/api/compare/structure/stream", post(compare_structure_stream))
+        .route("/api/compare/parsed", post(compare_parsed))
+        .route("/api/compare/structure/patches", post(compare_structure_patches))
+        .route("/api/compare/stats", post(compare_stats))
+        .route("/api/compare/three", post(compare_three))
+        .route("/api/compare/timeline", post(compare_timeline))
+        .route("/api/compare/penalties", post(compare_penalties))
+        .route("/api/compare/renumbering", post(compare_renumbering))
+        .route("/api/similarity-pairs", post(compare_similarity_pairs))
+        .route("/api/apply", post(apply))
+        .route("/api/amend", post(amend))
         .route("/api/parse", post(parse))
+        .route("/api/parse/documents", post(parse_documents))
+        .route("/api/parse/articles", post(parse_articles))
+        .route("/api/validate", post(validate))
+        .route("/api/validate/documents", post(validate_documents))
+        .route("/api/render", post(render))
+        .route("/api/export/markdown", post(export_markdown))
+        .route("/api/export/akoma-ntoso", post(export_akoma_ntoso))
+        .route("/api/export/patch", post(export_patch))
+        .route("/api/export/html", post(export_html))
+        .route("/api/export/docx", post(export_docx))
+        .route("/api/export/pdf", post(export_pdf))
+        .route("/api/export/csv", post(export_csv))
+        .route("/api/export/xlsx", post(export_xlsx))
+        .route("/api/report/summary", post(report_summary))
+        .route("/api/definitions", post(definitions))
+        .route("/api/glossary", post(glossary))
+        .route("/api/glossary/documents", post(glossary_documents))
+        .route("/api/normalize-numbers", post(normalize_numbers))
         .route("/api/examples", axum::routing::get(get_examples))
         .route("/health", axum::routing::get(health))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CompareOptions;
+
+    #[test]
+    fn test_ner_info_reports_regex_engine() {
+        let payload = CompareRequest {
+            old_text: "第一条 违反本法规定的，处一万元以下罚款。".to_string(),
+            new_text: "第一条 违反本法规定的，处五万元以下罚款。".to_string(),
+            options: CompareOptions { ner_mode: Some("regex".to_string()), ..Default::default() },
+        };
+
+        let (entities, info) = extract_entities_helper(&payload);
+        assert!(!entities.is_empty());
+        let info = info.expect("ner info");
+        assert_eq!(info.engine.as_ref(), "Regex NER");
+        assert_eq!(info.bert_fallback, None);
+    }
+
+    #[tokio::test]
+    async fn test_parse_rejects_unstructured_text() {
+        let response = parse(Json(ParseRequest::Text("这里没有任何条文。".to_string()))).await.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["kind"], "noStructure");
+        assert!(body["message"].is_string() && body["document"].is_null());
+
+        let documents = ParseDocumentsRequest::Documents(vec!["第一条 内容。".to_string(), "".to_string()]);
+        let response = parse_documents(Json(documents)).await.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap()["document"], 1);
+
+        let response = parse(Json(ParseRequest::Text("第一条 内容。".to_string()))).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        // The AST stays at the top level, as it was before meta and diagnostics
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["children"][0]["number"], "一");
+        assert!(body["meta"].is_object() && body.get("ast").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_render_keeps_submitted_indentation() {
+        let text = "第一条 为了规范管理，制定本法。\n第二条 有下列情形之一的，予以处罚：\n\u{3000}\u{3000}\u{3000}\u{3000}（一）未经许可的。";
+        let ast: ArticleNode = serde_json::from_value(serde_json::to_value(parse_article(text)).unwrap()).unwrap();
+        let response = render(Json(ast)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["text"].as_str().unwrap(), format!("{}\n", text));
+    }
+
+    #[tokio::test]
+    async fn test_parse_articles_ndjson() {
+        let payload = ParseRequest::Text("第一条 甲。\n第二条 乙。\n第三条 丙。".to_string());
+        let response = parse_articles(Json(payload)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let numbers: Vec<String> = String::from_utf8(body.to_vec()).unwrap().lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["number"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(numbers, vec!["一", "二", "三"]);
+    }
+
+    #[tokio::test]
+    async fn test_definitions_are_glossary_terms() {
+        let text = "网络安全法\n第一条 本法所称网络，是指由计算机组成的系统。".to_string();
+        let read = |response: axum::response::Response| async {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+        let definitions = read(definitions(Json(ParseRequest::Text(text.clone()))).await.into_response()).await;
+        let glossary = read(glossary(Json(ParseRequest::Text(text))).await.into_response()).await;
+        assert_eq!(definitions, glossary["terms"]);
+        assert_eq!(definitions[0]["term"], "网络");
+    }
+
+    #[tokio::test]
+    async fn test_document_delimiters() {
+        let options = ParserOptions { document_delimiters: vec!["---".to_string()], ..Default::default() };
+        let text = "第一条 甲。\n---\n第一条 乙。".to_string();
+
+        let single = ParseRequest::WithOptions { text: text.clone(), options: options.clone() };
+        assert_eq!(parse(Json(single)).await.into_response().status(), StatusCode::BAD_REQUEST);
+
+        let documents = ParseDocumentsRequest::WithOptions { documents: vec![text.clone()], options: options.clone() };
+        let response = parse_documents(Json(documents)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap().as_array().unwrap().len(), 2);
+
+        let response = parse_articles(Json(ParseRequest::WithOptions { text, options })).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(body.to_vec()).unwrap().lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.iter().map(|line| line["document"].as_u64().unwrap()).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(lines[1]["content"], "乙。");
+    }
+
+    #[tokio::test]
+    async fn test_compare_stream_events() {
+        let payload = CompareRequest {
+            old_text: "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。".to_string(),
+            new_text: "第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第二条 国家建立和完善网络安全标准体系。".to_string(),
+            options: CompareOptions::default(),
+        };
+        let response = compare_stream(Json(payload)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let events: Vec<&str> = body.lines().filter_map(|line| line.strip_prefix("event: ")).collect();
+        assert_eq!(events.first(), Some(&"parsed"));
+        assert!(events.contains(&"matrix") && events.contains(&"stage"));
+        assert_eq!(events.last(), Some(&"result"));
+        assert!(body.contains(r#""oldArticles":2"#));
+        assert!(body.contains(r#""name":"one-to-one""#));
+    }
+
+    #[tokio::test]
+    async fn test_compare_structure_stream_events() {
+        let payload = CompareRequest {
+            old_text: "第一编 总则\n第一条 为了保障网络安全，制定本法。\n第二编 分则\n第二条 国家建立网络安全标准体系。".to_string(),
+            new_text: "第一编 总则\n第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第二编 分则\n第二条 国家建立网络安全标准体系。".to_string(),
+            options: CompareOptions { partition_by_part: true, ..Default::default() },
+        };
+        let response = compare_structure_stream(Json(payload)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        // The per-编 alignments stay off the feed: one parse, and the matrix never restarts
+        let events: Vec<&str> = body.lines().filter_map(|line| line.strip_prefix("event: ")).collect();
+        assert_eq!(events.iter().filter(|&&e| e == "parsed").count(), 1);
+        assert_eq!(events.iter().filter(|&&e| e == "stage").count(), 4);
+        assert_eq!(events.last(), Some(&"result"));
+        let data = body.lines().rev().find_map(|line| line.strip_prefix("data: ")).unwrap();
+        let result: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(result["changes"].as_array().unwrap().len(), 0);
+        assert_eq!(result["partResults"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_compare_parsed_asts() {
+        let (old_text, new_text) = (
+            "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。",
+            "第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第二条 国家建立和完善网络安全标准体系。\n第三条 本法自公布之日起施行。",
+        );
+        // ASTs as a client holding /api/parse results would send them
+        let ast_json = |text: &str| serde_json::to_value(parse_article(&normalize_legal_text(text))).unwrap();
+        let payload: ParsedCompareRequest = serde_json::from_value(serde_json::json!({
+            "old_ast": ast_json(old_text),
+            "new_ast": ast_json(new_text),
+        })).unwrap();
+
+        let Json(result) = compare_parsed(Json(payload)).await.unwrap();
+        let types: Vec<ArticleChangeType> = result.article_changes.unwrap().into_iter().map(|c| c.change_type).collect();
+        let expected: Vec<ArticleChangeType> = align_articles_with_options(old_text, new_text, &CompareOptions::default())
+            .changes.into_iter().map(|c| c.change_type).collect();
+        assert_eq!(types, expected);
+        assert!(types.contains(&ArticleChangeType::Added));
+        assert!(result.structure_changes.is_some() && result.old_meta.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_compare_git_meta_matches_structure() {
+        let request = || -> CompareRequest {
+            serde_json::from_value(serde_json::json!({
+                "old_text": "中华人民共和国网络安全法\n第一章 总则\n第一条 为了保障网络安全，制定本法。",
+                "new_text": "中华人民共和国网络安全法\n第一章 总则\n第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第二条 本法自公布之日起施行。",
+                "options": { "clean_pdf_artifacts": true },
+            })).unwrap()
+        };
+        let Json(git) = compare_git(Json(request())).await.unwrap();
+        let Json(structure) = compare_structure(Json(request())).await.unwrap();
+        let meta = |result: &DiffResult| serde_json::to_value((&result.old_meta, &result.new_meta)).unwrap();
+        assert!(git.new_meta.is_some());
+        assert_eq!(meta(&git), meta(&structure));
+    }
+}
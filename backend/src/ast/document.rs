@@ -0,0 +1,112 @@
+use super::{check_toc, parse_article_with_options, validate_numbering};
+use crate::models::{ArticleNode, Diagnostic, NodeType, ParsedDocument, ParserOptions};
+use serde::Serialize;
+
+/// Share of control or replacement characters above which input is treated as binary
+const MAX_INVALID_CHAR_RATIO: f32 = 0.05;
+
+/// Diagnostic code for an article with neither text nor sub-units
+pub const EMPTY_ARTICLE_DIAGNOSTIC: &str = "empty-article";
+/// Diagnostic code for a gap, duplicate or out-of-order number
+pub const NUMBERING_DIAGNOSTIC: &str = "numbering";
+/// Diagnostic code for a heading that disagrees with the 目录
+pub const TOC_MISMATCH_DIAGNOSTIC: &str = "toc-mismatch";
+
+/// Input the parser cannot turn into a meaningful tree
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ParseError {
+    #[error("input is empty")]
+    Empty,
+    #[error("input does not look like text: {invalid_chars} control or replacement characters")]
+    #[serde(rename_all = "camelCase")]
+    NotText { invalid_chars: usize },
+    #[error("no 编/章/节/条 structure found in {lines} line(s) of text")]
+    NoStructure { lines: usize },
+}
+
+/// 422 body of the parse endpoints for input the parser rejected
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseErrorBody {
+    pub error: ParseError,
+    pub message: String,
+    pub document: Option<usize>, // Index of the failing document in /api/parse/documents
+}
+
+impl ParseErrorBody {
+    pub fn new(error: ParseError, document: Option<usize>) -> Self {
+        Self { message: error.to_string(), error, document }
+    }
+}
+
+/// Parse a document, rejecting input that yields no structure and collecting recoverable
+/// problems (empty articles, numbering breaks, 目录 mismatches) as diagnostics.
+/// `parse_article_with_options` stays the lenient variant that always returns a tree.
+pub fn parse_document(text: &str, options: &ParserOptions) -> Result<ParsedDocument, ParseError> {
+    if text.trim().is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let invalid_chars = text.chars()
+        .filter(|c| *c == '\u{FFFD}' || (c.is_control() && !c.is_whitespace()))
+        .count();
+    if invalid_chars as f32 > text.chars().count() as f32 * MAX_INVALID_CHAR_RATIO {
+        return Err(ParseError::NotText { invalid_chars });
+    }
+
+    let ast = parse_article_with_options(text, options);
+    if ast.children.iter().all(|c| matches!(c.node_type, NodeType::Preamble | NodeType::Toc)) {
+        return Err(ParseError::NoStructure { lines: text.lines().filter(|l| !l.trim().is_empty()).count() });
+    }
+
+    let mut diagnostics = Vec::new();
+    collect_empty_articles(&ast, &mut diagnostics);
+    diagnostics.extend(validate_numbering(&ast).into_iter().map(|f| Diagnostic {
+        code: NUMBERING_DIAGNOSTIC.into(),
+        message: f.message,
+        line: Some(f.start_line),
+    }));
+    diagnostics.extend(check_toc(&ast).into_iter().map(|f| Diagnostic {
+        code: TOC_MISMATCH_DIAGNOSTIC.into(),
+        message: f.message,
+        line: Some(f.start_line),
+    }));
+    diagnostics.sort_by_key(|d| d.line);
+    Ok(ParsedDocument { ast, diagnostics })
+}
+
+fn collect_empty_articles(node: &ArticleNode, out: &mut Vec<Diagnostic>) {
+    for child in &node.children {
+        if child.node_type == NodeType::Article && child.content.trim().is_empty() && child.children.is_empty() {
+            out.push(Diagnostic {
+                code: EMPTY_ARTICLE_DIAGNOSTIC.into(),
+                message: format!("{} has no content", super::article_label(&child.number)),
+                line: Some(child.start_line),
+            });
+        }
+        collect_empty_articles(child, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document_errors() {
+        let options = ParserOptions::default();
+        assert_eq!(parse_document(" \n ", &options).unwrap_err(), ParseError::Empty);
+        assert_eq!(parse_document("这是一段普通文字。\n没有任何条文。", &options).unwrap_err(), ParseError::NoStructure { lines: 2 });
+        assert!(matches!(parse_document("\u{0}\u{1}\u{2}第一条\u{FFFD}", &options), Err(ParseError::NotText { invalid_chars: 4 })));
+
+        let error = serde_json::to_value(ParseError::NotText { invalid_chars: 4 }).unwrap();
+        assert_eq!(error, serde_json::json!({ "kind": "notText", "invalidChars": 4 }));
+    }
+
+    #[test]
+    fn test_parse_document_diagnostics() {
+        let parsed = parse_document("第一条 内容。\n第二条\n第四条 内容。", &ParserOptions::default()).unwrap();
+        assert_eq!(parsed.ast.children.len(), 3);
+        let codes: Vec<(&str, Option<usize>)> = parsed.diagnostics.iter().map(|d| (d.code.as_ref(), d.line)).collect();
+        assert_eq!(codes, vec![(EMPTY_ARTICLE_DIAGNOSTIC, Some(2)), (NUMBERING_DIAGNOSTIC, Some(3))]);
+    }
+}
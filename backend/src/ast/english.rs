@@ -0,0 +1,327 @@
+use super::*;
+use regex::Captures;
+
+static EN_PART_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_CHAPTER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_SUBCHAPTER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_SECTION_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_SUBSECTION_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_PARAGRAPH_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_HEADING_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EN_REFERENCE_TAIL_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_en_part_pattern() -> &'static Regex {
+    EN_PART_PATTERN.get_or_init(|| Regex::new(r"^(?i:part|title)\s+([IVXLC]+|\d+)\b[.:\s—-]*(.*)$").unwrap())
+}
+
+fn get_en_chapter_pattern() -> &'static Regex {
+    // "Article II" with a roman numeral is a container (constitutions, bylaws)
+    EN_CHAPTER_PATTERN.get_or_init(|| Regex::new(r"^(?:(?i:chapter)\s+([IVXLC]+|\d+)|(?i:article)\s+([IVXLC]+))\b[.:\s—-]*(.*)$").unwrap())
+}
+
+fn get_en_subchapter_pattern() -> &'static Regex {
+    EN_SUBCHAPTER_PATTERN.get_or_init(|| Regex::new(r"^(?i:subchapter|subpart)\s+([IVXLC]+|[A-Z]|\d+)\b[.:\s—-]*(.*)$").unwrap())
+}
+
+fn get_en_section_pattern() -> &'static Regex {
+    // The aligned unit: "Section 1.", "Sec. 2", "§ 3", or "Article 5" with an arabic number
+    EN_SECTION_PATTERN.get_or_init(|| Regex::new(r"^(?:(?i:section|sec\.)|§|(?i:article))\s*(\d+[A-Za-z]?(?:\.\d+)*)\.?(?:\s+(.*))?$").unwrap())
+}
+
+fn get_en_subsection_pattern() -> &'static Regex {
+    EN_SUBSECTION_PATTERN.get_or_init(|| Regex::new(r"^\(([a-z])\)").unwrap())
+}
+
+fn get_en_paragraph_pattern() -> &'static Regex {
+    EN_PARAGRAPH_PATTERN.get_or_init(|| Regex::new(r"^\((\d+|[ivx]+|[A-Z])\)").unwrap())
+}
+
+fn get_en_heading_pattern() -> &'static Regex {
+    // "Short title." or "Short title. This Act may be cited as…"; see `split_heading`
+    EN_HEADING_PATTERN.get_or_init(|| Regex::new(r"^([A-Z][^.;:]{0,60})\.(?:\s+(.*))?$").unwrap())
+}
+
+fn get_en_reference_tail_pattern() -> &'static Regex {
+    EN_REFERENCE_TAIL_PATTERN.get_or_init(|| Regex::new(r"^(?:\s+[a-z]|\s*[,;)])").unwrap())
+}
+
+/// Whether the text right after a Part/Chapter/Section number goes on as a sentence
+/// ("Section 3 applies to…", "Chapter 5 of this title", "Title 18, United States Code")
+/// rather than a heading, which follows the number with a separator or a capital
+fn is_en_reference_tail(after_number: &str) -> bool {
+    get_en_reference_tail_pattern().is_match(after_number)
+}
+
+/// English statutes and contracts: "Section 1." articles under Part/Chapter headings, "(a)"
+/// clauses and "(1)" items. The tree comes from `parse_english`, so the Chinese line patterns
+/// this grammar inherits only matter to the article stream, which ignores it.
+pub struct EnglishStatute;
+
+impl StructureGrammar for EnglishStatute {
+    fn clause_marker<'a>(&self, line: &'a str, _styles: &[MarkerStyle]) -> Option<(&'a str, String)> {
+        let caps = get_en_subsection_pattern().captures(line)?;
+        Some((caps.get(0).unwrap().as_str(), caps[1].to_string()))
+    }
+
+    fn item_marker<'a>(&self, line: &'a str, _styles: &[MarkerStyle]) -> Option<(&'a str, String)> {
+        let caps = get_en_paragraph_pattern().captures(line)?;
+        Some((caps.get(0).unwrap().as_str(), caps[1].to_string()))
+    }
+
+    fn parse_document(&self, text: &str, options: &ParserOptions) -> Option<ArticleNode> {
+        Some(parse_english(text, options))
+    }
+}
+
+/// Optional capture group, empty when it did not participate
+fn group<'h>(caps: &Captures<'h>, i: usize) -> &'h str {
+    caps.get(i).map_or("", |m| m.as_str())
+}
+
+/// Longest run of words still read as a section heading rather than a sentence
+const MAX_HEADING_WORDS: usize = 6;
+
+/// Split "Short title. This Act…" into heading and body. A lone sentence only counts as a
+/// heading when it is short ("Definitions."), so "Penalties are set by regulation." stays content.
+fn split_heading(rest: &str) -> (Option<&str>, &str) {
+    match get_en_heading_pattern().captures(rest) {
+        Some(caps) => {
+            let heading = caps.get(1).unwrap().as_str();
+            let body = group(&caps, 2);
+            let words = heading.split_whitespace().count();
+            if words <= MAX_HEADING_WORDS && (!body.is_empty() || words <= MAX_HEADING_WORDS / 2) {
+                (Some(heading), body)
+            } else {
+                (None, rest)
+            }
+        }
+        None => (None, rest),
+    }
+}
+
+/// Close the open clause and article into their containers
+fn close_article(
+    root: &mut ArticleNode,
+    part: &mut Option<ArticleNode>,
+    chapter: &mut Option<ArticleNode>,
+    section: &mut Option<ArticleNode>,
+    article: &mut Option<ArticleNode>,
+    clause: &mut Option<ArticleNode>,
+) {
+    if let Some(c) = clause.take() {
+        if let Some(a) = article.as_mut() { a.children.push(c); }
+    }
+    if let Some(a) = article.take() {
+        if let Some(s) = section.as_mut() { s.children.push(a); }
+        else if let Some(c) = chapter.as_mut() { c.children.push(a); }
+        else if let Some(p) = part.as_mut() { p.children.push(a); }
+        else { root.children.push(a); }
+    }
+}
+
+/// Close the open clause/article/section, keeping the chapter and part open
+fn close_below_chapter(
+    root: &mut ArticleNode,
+    part: &mut Option<ArticleNode>,
+    chapter: &mut Option<ArticleNode>,
+    section: &mut Option<ArticleNode>,
+    article: &mut Option<ArticleNode>,
+    clause: &mut Option<ArticleNode>,
+) {
+    close_article(root, part, chapter, section, article, clause);
+    if let Some(s) = section.take() {
+        if let Some(c) = chapter.as_mut() { c.children.push(s); }
+        else if let Some(p) = part.as_mut() { p.children.push(s); }
+        else { root.children.push(s); }
+    }
+}
+
+/// Parse an English-language statute or contract into the same tree the Chinese grammar
+/// produces: Part/Title -> Part, Chapter or roman "Article II" -> Chapter, Subchapter -> Section,
+/// "Section 1." / "Article 5" -> Article, "(a)" -> Clause and "(1)" / "(i)" -> Item.
+fn parse_english(text: &str, options: &ParserOptions) -> ArticleNode {
+    let spans = line_spans(text);
+    let mut root = ArticleNode {
+        node_type: NodeType::Article,
+        number: "root".into(),
+        title: Some("Document Root".into()),
+        content: "".into(),
+        children: Vec::new(),
+        start_line: 0,
+        start_offset: 0,
+        end_offset: text.chars().count(),
+        indent: None,
+    };
+
+    let mut part: Option<ArticleNode> = None;
+    let mut chapter: Option<ArticleNode> = None;
+    let mut section: Option<ArticleNode> = None;
+    let mut article: Option<ArticleNode> = None;
+    let mut clause: Option<ArticleNode> = None;
+    let mut preamble: Option<ArticleNode> = None;
+
+    for (line_idx, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let node = |node_type: NodeType, number: &str, title: Option<&str>, content: &str| ArticleNode {
+            node_type,
+            number: number.into(),
+            title: title.map(str::trim).filter(|t| !t.is_empty()).map(|t| t.into()),
+            content: content.trim().into(),
+            children: Vec::new(),
+            start_line: line_idx + 1,
+            start_offset: spans[line_idx].0,
+            end_offset: spans[line_idx].1,
+            indent: None,
+        };
+
+        // Chapter numbers are in group 1 or 2, every other number in group 1
+        let marker = |pattern: &'static Regex| pattern.captures(trimmed).filter(|caps| {
+            let number = caps.get(1).or_else(|| caps.get(2)).unwrap();
+            !is_en_reference_tail(&trimmed[number.end()..])
+        });
+
+        if let Some(caps) = marker(get_en_part_pattern()) {
+            close_open_nodes(&mut root, &mut part, &mut None, &mut chapter, &mut section, &mut article, &mut clause, true);
+            part = Some(node(NodeType::Part, &caps[1], Some(group(&caps, 2)), ""));
+        } else if let Some(caps) = marker(get_en_subchapter_pattern()) {
+            close_below_chapter(&mut root, &mut part, &mut chapter, &mut section, &mut article, &mut clause);
+            section = Some(node(NodeType::Section, &caps[1], Some(group(&caps, 2)), ""));
+        } else if let Some(caps) = marker(get_en_chapter_pattern()) {
+            close_open_nodes(&mut root, &mut part, &mut None, &mut chapter, &mut section, &mut article, &mut clause, false);
+            let number = caps.get(1).or(caps.get(2)).unwrap().as_str();
+            chapter = Some(node(NodeType::Chapter, number, Some(group(&caps, 3)), ""));
+        } else if let Some(caps) = marker(get_en_section_pattern()) {
+            close_article(&mut root, &mut part, &mut chapter, &mut section, &mut article, &mut clause);
+            let rest = group(&caps, 2);
+            let (title, mut body) = split_heading(rest);
+            if options.keep_title_in_content {
+                body = rest;
+            }
+            article = Some(node(NodeType::Article, &caps[1], title, body));
+        } else if let (Some((_, number)), Some(a)) = (EnglishStatute.clause_marker(trimmed, &[]), article.as_mut()) {
+            if let Some(c) = clause.take() {
+                a.children.push(c);
+            }
+            clause = Some(node(NodeType::Clause, &number, None, trimmed));
+        } else if let (Some((_, number)), true) = (EnglishStatute.item_marker(trimmed, &[]), article.is_some()) {
+            let item = node(NodeType::Item, &number, None, trimmed);
+            match clause.as_mut() {
+                Some(c) => c.children.push(item),
+                None => article.as_mut().unwrap().children.push(item),
+            }
+        } else {
+            // Continuation text, or the preamble before the first structural marker
+            let target = if let Some(c) = clause.as_mut() {
+                Some(c)
+            } else if let Some(a) = article.as_mut() {
+                Some(a)
+            } else if part.is_none() && chapter.is_none() && section.is_none() && root.children.is_empty() {
+                Some(preamble.get_or_insert_with(|| {
+                    let mut p = node(NodeType::Preamble, "0", Some("Preamble"), "");
+                    p.start_line = 1;
+                    p
+                }))
+            } else {
+                chapter.as_mut().or(part.as_mut())
+            };
+            if let Some(target) = target {
+                target.content = if target.content.is_empty() {
+                    trimmed.into()
+                } else {
+                    format!("{}\n{}", target.content, trimmed).into()
+                };
+                target.end_offset = spans[line_idx].1;
+            }
+        }
+    }
+
+    close_open_nodes(&mut root, &mut part, &mut None, &mut chapter, &mut section, &mut article, &mut clause, true);
+    if let Some(p) = preamble {
+        if options.capture_preamble {
+            root.children.insert(0, p);
+        }
+    }
+    for child in &mut root.children {
+        extend_end_offsets(child);
+    }
+    attach_indentation(&mut root, &text.chars().collect::<Vec<_>>());
+    if options.prune_empty {
+        prune_empty_nodes(&mut root);
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles_with_options;
+    use crate::models::{ArticleChangeType, CompareOptions, Language};
+
+    fn english() -> ParserOptions {
+        ParserOptions { language: Language::English, ..Default::default() }
+    }
+
+    #[test]
+    fn test_parse_english_statute() {
+        let text = "An Act to promote data security.\n\
+                    ARTICLE I GENERAL PROVISIONS\n\
+                    Section 1. Short title.\nThis Act may be cited as the Data Security Act.\n\
+                    Section 2. Definitions. In this Act:\n\
+                    (a) \"operator\" means any person operating a network;\n\
+                    (1) including contractors;\n\
+                    (b) \"data\" means any record.\n\
+                    Article II Enforcement\n\
+                    Sec. 3 Penalties for violations are set by regulation.";
+        let ast = parse_article_with_options(text, &english());
+
+        assert_eq!(ast.children[0].node_type, NodeType::Preamble);
+        let chapter = &ast.children[1];
+        assert_eq!((chapter.node_type.clone(), chapter.number.as_ref(), chapter.title.as_deref()),
+                   (NodeType::Chapter, "I", Some("GENERAL PROVISIONS")));
+
+        let s1 = &chapter.children[0];
+        assert_eq!((s1.number.as_ref(), s1.title.as_deref(), s1.content.as_ref()),
+                   ("1", Some("Short title"), "This Act may be cited as the Data Security Act."));
+        let s2 = &chapter.children[1];
+        assert_eq!((s2.title.as_deref(), s2.content.as_ref()), (Some("Definitions"), "In this Act:"));
+        let clauses: Vec<&str> = s2.children.iter().map(|c| c.number.as_ref()).collect();
+        assert_eq!(clauses, vec!["a", "b"]);
+        assert_eq!(s2.children[0].children[0].node_type, NodeType::Item);
+
+        let s3 = &ast.children[2].children[0];
+        assert_eq!((s3.number.as_ref(), s3.title.as_deref()), ("3", None));
+    }
+
+    #[test]
+    fn test_english_reference_lines() {
+        let text = "Section 4. Scope.\nThe duties of operators under\n\
+                    Section 3 apply to contractors, and\n\
+                    Chapter II of this Act governs appeals under\n\
+                    Title 18, United States Code.\n\
+                    Section 5 Penalties.";
+        let ast = parse_article_with_options(text, &english());
+
+        // Lines opening with a reference continue Section 4
+        let numbers: Vec<&str> = ast.children.iter().map(|c| c.number.as_ref()).collect();
+        assert_eq!(numbers, vec!["4", "5"]);
+        assert!(ast.children.iter().all(|c| c.node_type == NodeType::Article));
+        assert!(ast.children[0].content.ends_with("Title 18, United States Code."));
+    }
+
+    #[test]
+    fn test_english_alignment_pipeline() {
+        let old = "Section 1. Short title.\nThis Act may be cited as the Data Act.\nSection 2. Operators shall keep logs for three months.";
+        let new = "Section 1. Short title.\nThis Act may be cited as the Data Act.\nSection 2. Operators shall keep logs for six months.";
+        let options = CompareOptions { parser: english(), ..Default::default() };
+        let changes = align_articles_with_options(old, new, &options).changes;
+
+        let types: Vec<(&str, ArticleChangeType)> = changes.iter()
+            .map(|c| (c.new_articles.as_ref().unwrap()[0].number.as_ref(), c.change_type.clone()))
+            .collect();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[1], ("2", ArticleChangeType::Modified));
+    }
+}
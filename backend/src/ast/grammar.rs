@@ -0,0 +1,221 @@
+use super::english::EnglishStatute;
+use super::{
+    clause_marker, get_annex_pattern, get_article_pattern, get_chapter_pattern, get_part_pattern,
+    get_section_pattern, get_sub_part_pattern, get_supplementary_pattern, item_marker,
+};
+use crate::models::{ArticleNode, DocumentType, Language, MarkerStyle, ParserOptions};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Names the built-in grammars are registered under
+pub const CHINESE_STATUTE: &str = "chinese-statute";
+pub const JUDICIAL_INTERPRETATION: &str = "judicial-interpretation";
+pub const ENGLISH_STATUTE: &str = "english-statute";
+
+static INTERPRETATION_HEADING_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// Line recognizer behind the tree and stream parsers. Every method defaults to the Chinese
+/// statute rules, so a grammar for local regulations, party rules or judicial interpretations
+/// only overrides the markers that differ.
+///
+/// Patterns are matched against trimmed lines and must keep the built-in capture groups:
+/// the number in group 1, and for articles the bis suffix in group 2 and the text after the
+/// marker in group 4. The annex pattern captures its number in 1 and title in 2.
+pub trait StructureGrammar: Send + Sync {
+    fn part_pattern(&self) -> &Regex {
+        get_part_pattern()
+    }
+
+    fn sub_part_pattern(&self) -> &Regex {
+        get_sub_part_pattern()
+    }
+
+    fn chapter_pattern(&self) -> &Regex {
+        get_chapter_pattern()
+    }
+
+    fn section_pattern(&self) -> &Regex {
+        get_section_pattern()
+    }
+
+    fn article_pattern(&self) -> &Regex {
+        get_article_pattern()
+    }
+
+    fn supplementary_pattern(&self) -> &Regex {
+        get_supplementary_pattern()
+    }
+
+    fn annex_pattern(&self) -> &Regex {
+        get_annex_pattern()
+    }
+
+    /// Clause (项) marker at the start of a line: (full marker, Chinese-numeral number)
+    fn clause_marker<'a>(&self, line: &'a str, styles: &[MarkerStyle]) -> Option<(&'a str, String)> {
+        clause_marker(line, styles)
+    }
+
+    /// Item (目) marker at the start of a line: (full marker, arabic number)
+    fn item_marker<'a>(&self, line: &'a str, styles: &[MarkerStyle]) -> Option<(&'a str, String)> {
+        item_marker(line, styles)
+    }
+
+    /// The whole tree, for a grammar whose structure the line rules above cannot express;
+    /// None leaves the document to the shared tree parser
+    fn parse_document(&self, _text: &str, _options: &ParserOptions) -> Option<ArticleNode> {
+        None
+    }
+}
+
+/// 第X编/分编/章/节/条, （一） clauses and 1. items, honouring any `patterns.toml` overrides
+pub struct ChineseStatute;
+
+impl StructureGrammar for ChineseStatute {}
+
+/// Judicial interpretations number their provisions 第X条 but group them under 一、二、
+/// headings instead of 第X章
+pub struct JudicialInterpretation;
+
+impl StructureGrammar for JudicialInterpretation {
+    fn chapter_pattern(&self) -> &Regex {
+        INTERPRETATION_HEADING_PATTERN.get_or_init(|| Regex::new(r"^([一二三四五六七八九十]+)、").unwrap())
+    }
+}
+
+static GRAMMARS: OnceLock<RwLock<HashMap<String, Arc<dyn StructureGrammar>>>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<HashMap<String, Arc<dyn StructureGrammar>>> {
+    GRAMMARS.get_or_init(|| {
+        let mut grammars: HashMap<String, Arc<dyn StructureGrammar>> = HashMap::new();
+        grammars.insert(CHINESE_STATUTE.to_string(), Arc::new(ChineseStatute));
+        grammars.insert(JUDICIAL_INTERPRETATION.to_string(), Arc::new(JudicialInterpretation));
+        grammars.insert(ENGLISH_STATUTE.to_string(), Arc::new(EnglishStatute));
+        RwLock::new(grammars)
+    })
+}
+
+/// Make a grammar selectable through `ParserOptions::grammar`; replaces any grammar
+/// already registered under `name`
+pub fn register_grammar(name: &str, grammar: Arc<dyn StructureGrammar>) {
+    registry().write().unwrap().insert(name.to_string(), grammar);
+}
+
+/// Names of all registered grammars, sorted
+pub fn grammar_names() -> Vec<String> {
+    let mut names: Vec<String> = registry().read().unwrap().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// The grammar `options` selects: the one named by `grammar`, else the statute grammar of
+/// `language`; unknown names fall back to the Chinese statute grammar
+pub fn grammar_for(options: &ParserOptions) -> Arc<dyn StructureGrammar> {
+    let name = options.grammar.as_deref().unwrap_or(match options.language {
+        Language::Chinese => CHINESE_STATUTE,
+        Language::English => ENGLISH_STATUTE,
+    });
+    if let Some(grammar) = registry().read().unwrap().get(name) {
+        return grammar.clone();
+    }
+    tracing::warn!("Unknown structure grammar {:?}, using {}", name, CHINESE_STATUTE);
+    Arc::new(ChineseStatute)
+}
+
+/// The grammar for a document of `document_type`: a grammar named in `options` wins,
+/// otherwise Chinese judicial interpretations get `JudicialInterpretation`
+pub fn grammar_for_document(document_type: DocumentType, options: &ParserOptions) -> Arc<dyn StructureGrammar> {
+    match (document_type, &options.grammar, options.language) {
+        (DocumentType::JudicialInterpretation, None, Language::Chinese) => Arc::new(JudicialInterpretation),
+        _ => grammar_for(options),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_article_with_options, parse_clauses, stream_articles};
+    use crate::models::{DocumentType, NodeType};
+
+    /// Some implementing rules group their articles under 第一部分, 第二部分 instead of 第X章
+    struct PartitionedRules;
+
+    static PORTION_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+    impl StructureGrammar for PartitionedRules {
+        fn chapter_pattern(&self) -> &Regex {
+            PORTION_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十]+)部分").unwrap())
+        }
+    }
+
+    #[test]
+    fn test_registered_grammar() {
+        register_grammar("partitioned-rules", Arc::new(PartitionedRules));
+        assert!(grammar_names().contains(&"partitioned-rules".to_string()));
+
+        let text = "第一部分 总体要求\n第一条 各部门应当执行。\n第二部分 主要任务\n第二条 各部门应当落实。";
+        let options = ParserOptions { grammar: Some("partitioned-rules".into()), ..Default::default() };
+        let ast = parse_article_with_options(text, &options);
+
+        assert_eq!(ast.children.len(), 2);
+        assert_eq!(ast.children[1].node_type, NodeType::Chapter);
+        assert_eq!((ast.children[1].number.as_ref(), ast.children[1].title.as_deref()), ("二", Some("主要任务")));
+        assert_eq!(ast.children[1].children[0].number.as_ref(), "二");
+
+        let streamed: Vec<_> = stream_articles(text.as_bytes(), &options).collect();
+        assert_eq!(streamed[1].parents, vec![Arc::from("二 主要任务")]);
+
+        // The default grammar leaves 第X部分 lines as article text
+        let ast = parse_article_with_options(text, &ParserOptions::default());
+        assert!(ast.children.iter().all(|c| c.node_type != NodeType::Chapter));
+    }
+
+    #[test]
+    fn test_judicial_interpretation() {
+        let text = "最高人民法院关于审理案件若干问题的解释\n法释〔2021〕3号\n为正确审理案件，制定本解释。\n一、关于受理\n第一条 人民法院应当受理。\n二、关于审理\n第二条 人民法院应当审理。";
+        let ast = parse_article_with_options(text, &ParserOptions::default());
+
+        assert_eq!(ast.children[0].node_type, NodeType::Preamble);
+        assert!(ast.children[0].content.ends_with("制定本解释。"));
+        let chapters: Vec<Option<&str>> = ast.children[1..].iter().map(|c| c.title.as_deref()).collect();
+        assert_eq!(chapters, vec![Some("关于受理"), Some("关于审理")]);
+
+        // Selected explicitly for a stream, which cannot look ahead at the header
+        let options = ParserOptions { document_type: Some(DocumentType::JudicialInterpretation), ..Default::default() };
+        let streamed: Vec<_> = stream_articles(text.as_bytes(), &options).collect();
+        assert_eq!(streamed.last().unwrap().parents, vec![Arc::from("二 关于审理")]);
+
+        // A 批复 answering with 一、二、 and no 第X条 keeps them as text
+        let reply = "最高人民法院关于某问题的批复\n法释〔2019〕5号\n一、经研究，答复如下。\n二、此复。";
+        let ast = parse_article_with_options(reply, &ParserOptions::default());
+        assert!(ast.children.iter().all(|c| c.node_type == NodeType::Preamble));
+    }
+
+    #[test]
+    fn test_english_grammar() {
+        assert!(grammar_names().contains(&ENGLISH_STATUTE.to_string()));
+        let text = "Section 1. Definitions. In this Act:\n(a) \"data\" means any record;\n(1) including copies;\n(b) \"operator\" means any person.";
+
+        // Selected by name or by language, to the same tree
+        let by_name = parse_article_with_options(text, &ParserOptions { grammar: Some(ENGLISH_STATUTE.into()), ..Default::default() });
+        let by_language = parse_article_with_options(text, &ParserOptions { language: Language::English, ..Default::default() });
+        assert_eq!(by_name.children[0].children.len(), 2);
+        assert_eq!(format!("{:?}", by_name), format!("{:?}", by_language));
+
+        // Clause splitting goes through the grammar's markers
+        let content = &by_name.children[0].content;
+        let body = text.split_once("In this Act:").unwrap().1;
+        assert!(parse_clauses(&format!("{}{}", content, body), &ChineseStatute, &[]).is_empty());
+        let clauses = parse_clauses(&format!("{}{}", content, body), &EnglishStatute, &[]);
+        let numbers: Vec<&str> = clauses.iter().map(|c| c.number.as_ref()).collect();
+        assert_eq!(numbers, vec!["a", "b"]);
+        assert!(clauses[0].content.ends_with("(1) including copies;"));
+    }
+
+    #[test]
+    fn test_unknown_grammar_falls_back() {
+        let options = ParserOptions { grammar: Some("no-such-grammar".into()), ..Default::default() };
+        let ast = parse_article_with_options("第一条 内容。", &options);
+        assert_eq!(ast.children[0].node_type, NodeType::Article);
+    }
+}
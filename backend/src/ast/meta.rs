@@ -0,0 +1,259 @@
+use regex::Regex;
+use std::sync::{Arc, OnceLock};
+use crate::models::{ArticleNode, DocumentMeta, DocumentType, NodeType, PromulgationOrder};
+
+static NOTE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ADOPTION_PATTERN: OnceLock<Regex> = OnceLock::new();
+static PROMULGATION_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EFFECTIVE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static SESSION_PATTERN: OnceLock<Regex> = OnceLock::new();
+static DOCUMENT_NUMBER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ORDER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ORDER_NUMBER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static SIGNATORY_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// How many non-empty lines from the top are searched for the 文号
+const HEADER_LINES: usize = 10;
+
+const DATE: &str = r"\d{4}年\d{1,2}月\d{1,2}日";
+
+/// Parenthesised legislative history note under the title
+fn get_note_pattern() -> &'static Regex {
+    NOTE_PATTERN.get_or_init(|| Regex::new(r"[（(]([^（）()]*\d{4}年[^（）()]*)[）)]").unwrap())
+}
+
+fn get_adoption_pattern() -> &'static Regex {
+    ADOPTION_PATTERN.get_or_init(|| Regex::new(&format!(r"({})(.*?)通过", DATE)).unwrap())
+}
+
+fn get_promulgation_pattern() -> &'static Regex {
+    PROMULGATION_PATTERN.get_or_init(|| Regex::new(&format!(r"({})[^，。\s]*?(?:公布|发布)", DATE)).unwrap())
+}
+
+fn get_effective_pattern() -> &'static Regex {
+    EFFECTIVE_PATTERN.get_or_init(|| Regex::new(&format!(r"自({})起施行", DATE)).unwrap())
+}
+
+/// "第十二届" prefix and "第二十四次会议" suffix around the issuing body
+fn get_session_pattern() -> &'static Regex {
+    SESSION_PATTERN.get_or_init(|| Regex::new(r"^第[^届]+届|第[^次]+次(?:全体)?会议$").unwrap())
+}
+
+/// 文号 on a line of its own: "法释〔2021〕1号", "国发[2020]5号"
+fn get_document_number_pattern() -> &'static Regex {
+    DOCUMENT_NUMBER_PATTERN.get_or_init(|| Regex::new(r"^\p{Han}{1,10}\s*[〔\[［【]\s*\d{4}\s*[〕\]］】]\s*\d+\s*号$").unwrap())
+}
+
+/// "国务院令", "中华人民共和国主席令（第五十三号）", "交通运输部令2019年第1号"
+fn get_order_pattern() -> &'static Regex {
+    ORDER_PATTERN.get_or_init(|| {
+        Regex::new(r"^(\p{Han}{1,18}令)\s*(?:[（(]?\s*((?:\d{4}\s*年\s*)?第\s*[一二三四五六七八九十百千零两〇\d]+\s*号)\s*[）)]?)?$").unwrap()
+    })
+}
+
+/// The 第X号 line under an order heading printed on its own
+fn get_order_number_pattern() -> &'static Regex {
+    ORDER_NUMBER_PATTERN.get_or_init(|| {
+        Regex::new(r"^[（(]?\s*((?:\d{4}\s*年\s*)?第\s*[一二三四五六七八九十百千零两〇\d]+\s*号)\s*[）)]?$").unwrap()
+    })
+}
+
+/// "总理　李克强", "中华人民共和国主席　习近平", "部长　某某"
+fn get_signatory_pattern() -> &'static Regex {
+    SIGNATORY_PATTERN.get_or_init(|| {
+        Regex::new(r"^\p{Han}{0,16}(?:总理|主席|委员长|部长|主任|署长|局长|行长|院长|检察长|会长)\s+\p{Han}{2,4}$").unwrap()
+    })
+}
+
+/// The promulgation order opening `lines` (trimmed, non-empty) and how many lines it spans:
+/// the order heading and number, then the announcement, signature and date in any order
+pub fn promulgation_order(lines: &[&str]) -> Option<(PromulgationOrder, usize)> {
+    let caps = get_order_pattern().captures(lines.first()?)?;
+    let mut consumed = 1;
+    let number = match caps.get(2) {
+        Some(number) => number.as_str(),
+        None => {
+            let number = get_order_number_pattern().captures(lines.get(1)?)?.get(1).unwrap().as_str();
+            consumed = 2;
+            number
+        }
+    };
+    let mut order = PromulgationOrder {
+        issuer: caps[1].into(),
+        number: number.chars().filter(|c| !c.is_whitespace()).collect::<String>().into(),
+        announcement: None,
+        signatory: None,
+        signed: None,
+    };
+
+    let date = Regex::new(&format!("^{}$", DATE)).unwrap();
+    for line in &lines[consumed..] {
+        if order.announcement.is_none() && (line.starts_with('《') || line.contains("现予公布") || line.contains("现予发布")) {
+            order.announcement = Some((*line).into());
+        } else if order.signatory.is_none() && get_signatory_pattern().is_match(line) {
+            order.signatory = Some((*line).into());
+        } else if order.signed.is_none() && date.is_match(line) {
+            order.signed = Some((*line).into());
+        } else {
+            break;
+        }
+        consumed += 1;
+    }
+    Some((order, consumed))
+}
+
+/// The 文号 in the header of `text`, if any
+pub fn document_number(text: &str) -> Option<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(HEADER_LINES)
+        .find(|l| get_document_number_pattern().is_match(l))
+}
+
+/// Judicial interpretations carry a 法释 number (高检发释字 for the procuratorate's)
+pub fn detect_document_type(text: &str) -> DocumentType {
+    match document_number(text) {
+        Some(number) if number.starts_with("法释") || number.starts_with("高检发释字") => DocumentType::JudicialInterpretation,
+        _ => DocumentType::Statute,
+    }
+}
+
+/// Extract statute metadata from a parsed document: title and legislative history
+/// from the preamble, effective date from the articles (usually 附则).
+pub fn extract_document_meta(root: &ArticleNode) -> DocumentMeta {
+    let mut meta = DocumentMeta::default();
+
+    let preamble = root.children.iter()
+        .find(|n| n.node_type == NodeType::Preamble)
+        .map(|n| n.content.as_ref())
+        .unwrap_or("");
+
+    meta.document_number = document_number(preamble).map(Arc::from);
+    meta.document_type = detect_document_type(preamble);
+
+    // The decree block above a regulation's title is metadata, not the title
+    let header: Vec<&str> = preamble.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let order_lines = match promulgation_order(&header) {
+        Some((order, consumed)) => {
+            meta.promulgation_order = Some(order);
+            consumed
+        }
+        None => 0,
+    };
+    let mut title_lines = header[order_lines..].iter()
+        .copied()
+        .filter(|l| !l.starts_with(['（', '(']) && !l.contains("目录") && !get_document_number_pattern().is_match(l));
+    meta.title = match meta.document_type {
+        // A 公告 may precede the interpretation; its title is the "最高人民法院关于…" line
+        DocumentType::JudicialInterpretation => preamble.lines()
+            .map(str::trim)
+            .find(|l| l.starts_with("最高人民") && l.contains("关于"))
+            .or_else(|| title_lines.next()),
+        DocumentType::Statute => title_lines.next(),
+    }.map(Arc::from);
+
+    if let Some(note) = get_note_pattern().captures(preamble).map(|c| c.get(1).unwrap().as_str()) {
+        if let Some(caps) = get_adoption_pattern().captures(note) {
+            meta.adopted = Some(caps[1].into());
+            let authority = get_session_pattern().replace_all(caps[2].trim(), "");
+            if !authority.is_empty() {
+                meta.issuing_authority = Some(authority.as_ref().into());
+            }
+        }
+        meta.amendments = note.split(|c: char| c.is_whitespace() || c == '；' || c == ';')
+            .map(str::trim)
+            .filter(|segment| segment.contains("修正") || segment.contains("修订"))
+            .map(Arc::from)
+            .collect();
+    }
+
+    meta.promulgated = get_promulgation_pattern().captures(preamble).map(|c| c[1].into())
+        .or_else(|| meta.promulgation_order.as_ref().and_then(|order| order.signed.clone()));
+    meta.effective = find_effective_date(root);
+    meta
+}
+
+fn find_effective_date(node: &ArticleNode) -> Option<Arc<str>> {
+    if let Some(caps) = get_effective_pattern().captures(&node.content) {
+        return Some(caps[1].into());
+    }
+    node.children.iter().find_map(find_effective_date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article;
+    use crate::nlp::formatter::normalize_legal_text;
+
+    #[test]
+    fn test_extract_document_meta() {
+        let text = "中华人民共和国网络安全法\n\
+                    （2016年11月7日第十二届全国人民代表大会常务委员会第二十四次会议通过　根据2021年6月10日第十三届全国人民代表大会常务委员会第二十九次会议修正）\n\
+                    第一条 为了保障网络安全，制定本法。\n\
+                    第二条 本法自2017年6月1日起施行。";
+        let ast = parse_article(&normalize_legal_text(text));
+        let meta = extract_document_meta(&ast);
+
+        assert_eq!(meta.title.as_deref(), Some("中华人民共和国网络安全法"));
+        assert_eq!(meta.adopted.as_deref(), Some("2016年11月7日"));
+        assert_eq!(meta.issuing_authority.as_deref(), Some("全国人民代表大会常务委员会"));
+        assert_eq!(meta.effective.as_deref(), Some("2017年6月1日"));
+        assert_eq!(meta.amendments.len(), 1);
+        assert!(meta.amendments[0].starts_with("根据2021年6月10日"));
+        assert!(meta.promulgated.is_none());
+        assert!(meta.document_number.is_none());
+        assert_eq!(meta.document_type, DocumentType::Statute);
+    }
+
+    #[test]
+    fn test_judicial_interpretation_meta() {
+        let text = "最高人民法院公告\n\
+                    法释〔2020〕15号\n\
+                    最高人民法院关于适用《中华人民共和国民法典》时间效力的若干规定\n\
+                    （2020年12月14日最高人民法院审判委员会第1821次会议通过，自2021年1月1日起施行）\n\
+                    一、一般规定\n\
+                    第一条 民法典施行后的法律事实引起的民事纠纷案件，适用民法典的规定。";
+        let ast = parse_article(text);
+        let meta = extract_document_meta(&ast);
+
+        assert_eq!(meta.document_type, DocumentType::JudicialInterpretation);
+        assert_eq!(meta.document_number.as_deref(), Some("法释〔2020〕15号"));
+        assert_eq!(meta.title.as_deref(), Some("最高人民法院关于适用《中华人民共和国民法典》时间效力的若干规定"));
+        assert_eq!(meta.issuing_authority.as_deref(), Some("最高人民法院审判委员会"));
+        assert_eq!(meta.effective.as_deref(), Some("2021年1月1日"));
+        assert_eq!(ast.children[1].title.as_deref(), Some("一般规定"));
+    }
+
+    #[test]
+    fn test_meta_without_document_type() {
+        let json = r#"{"title":"中华人民共和国网络安全法","adopted":null,"promulgated":null,"effective":null,"issuingAuthority":null,"amendments":[],"documentNumber":null}"#;
+        let meta: DocumentMeta = serde_json::from_str(json).unwrap();
+        assert_eq!(meta.document_type, DocumentType::Statute);
+    }
+
+    #[test]
+    fn test_promulgation_order_meta() {
+        let text = "中华人民共和国国务院令\n\
+                    第704号\n\
+                    《行政区划管理条例》已经2018年10月10日国务院第28次常务会议通过，现予公布，自2019年1月1日起施行。\n\
+                    总理　李克强\n\
+                    2018年10月20日\n\
+                    行政区划管理条例\n\
+                    第一条 为了加强行政区划管理，制定本条例。";
+        let meta = extract_document_meta(&parse_article(&normalize_legal_text(text)));
+
+        let order = meta.promulgation_order.unwrap();
+        assert_eq!((order.issuer.as_ref(), order.number.as_ref()), ("中华人民共和国国务院令", "第704号"));
+        assert!(order.announcement.unwrap().starts_with("《行政区划管理条例》"));
+        assert_eq!(order.signatory.as_deref(), Some("总理  李克强"));
+        assert_eq!(meta.title.as_deref(), Some("行政区划管理条例"));
+        assert_eq!(meta.promulgated.as_deref(), Some("2018年10月20日"));
+        assert_eq!(meta.effective.as_deref(), Some("2019年1月1日"));
+
+        let (order, consumed) = promulgation_order(&["中华人民共和国主席令（第五十三号）", "中华人民共和国网络安全法"]).unwrap();
+        assert_eq!((order.number.as_ref(), consumed), ("第五十三号", 1));
+        assert!(promulgation_order(&["中华人民共和国网络安全法", "第一条 内容。"]).is_none());
+    }
+}
@@ -1,30 +1,72 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use regex::{Captures, Regex};
+use std::sync::{Arc, OnceLock};
 use std::collections::HashSet;
-use crate::models::{ArticleNode, NodeType};
+use crate::models::{ArticleNode, DocumentType, MarkerStyle, NodeType, ParserOptions};
+use crate::nlp::formatter::{is_reference_tail, normalize_traditional_markers};
+
+mod document;
+mod english;
+mod grammar;
+mod meta;
+mod patterns;
+mod render;
+mod stream;
+mod table;
+mod validate;
+pub use document::{parse_document, ParseError, ParseErrorBody, EMPTY_ARTICLE_DIAGNOSTIC, NUMBERING_DIAGNOSTIC, TOC_MISMATCH_DIAGNOSTIC};
+pub use grammar::{
+    grammar_for, grammar_for_document, grammar_names, register_grammar, ChineseStatute, JudicialInterpretation,
+    StructureGrammar, CHINESE_STATUTE, ENGLISH_STATUTE, JUDICIAL_INTERPRETATION,
+};
+pub use english::EnglishStatute;
+pub use meta::{detect_document_type, document_number, extract_document_meta};
+pub use patterns::{install_patterns, install_patterns_from_env, PatternOverrides, PATTERNS_FILE_ENV};
+pub use render::{render_text, render_text_with_indent};
+pub use stream::{stream_articles, ArticleStream};
+pub use table::{parse_tables, table_text, Table};
+pub use validate::{check_toc, validate_numbering};
 
 static PART_PATTERN: OnceLock<Regex> = OnceLock::new();
+static SUB_PART_PATTERN: OnceLock<Regex> = OnceLock::new();
 static CHAPTER_PATTERN: OnceLock<Regex> = OnceLock::new();
 static SECTION_PATTERN: OnceLock<Regex> = OnceLock::new();
 static ARTICLE_PATTERN: OnceLock<Regex> = OnceLock::new();
 static CLAUSE_PATTERN: OnceLock<Regex> = OnceLock::new();
 static ITEM_PATTERN: OnceLock<Regex> = OnceLock::new();
+static CLOSING_PAREN_PATTERN: OnceLock<Regex> = OnceLock::new();
+static CIRCLED_PATTERN: OnceLock<Regex> = OnceLock::new();
+static STEM_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ORDINAL_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EXPLICIT_CLAUSE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static EXPLICIT_ITEM_PATTERN: OnceLock<Regex> = OnceLock::new();
+static COMMA_ITEM_PATTERN: OnceLock<Regex> = OnceLock::new();
+static PAREN_TITLE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static BRACKET_TITLE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static SUPPLEMENTARY_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ANNEX_PATTERN: OnceLock<Regex> = OnceLock::new();
+static INLINE_CLAUSE_PATTERN: OnceLock<Regex> = OnceLock::new();
 
 fn get_part_pattern() -> &'static Regex {
-    PART_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十百千万零两\d]+)编").unwrap())
+    PART_PATTERN.get_or_init(|| Regex::new(r"^第[ 　]*([一二三四五六七八九十百千万零两\d]+)[ 　]*编").unwrap())
+}
+
+fn get_sub_part_pattern() -> &'static Regex {
+    // 分编 under a 编, as in the Civil Code's 第二编 物权 / 第一分编 通则
+    SUB_PART_PATTERN.get_or_init(|| Regex::new(r"^第[ 　]*([一二三四五六七八九十百千万零两\d]+)[ 　]*分编").unwrap())
 }
 
 fn get_chapter_pattern() -> &'static Regex {
-    CHAPTER_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十百千万零两\d]+)章").unwrap())
+    CHAPTER_PATTERN.get_or_init(|| Regex::new(r"^第[ 　]*([一二三四五六七八九十百千万零两\d]+)[ 　]*章").unwrap())
 }
 
 fn get_section_pattern() -> &'static Regex {
-    SECTION_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十百千万零两\d]+)节").unwrap())
+    SECTION_PATTERN.get_or_init(|| Regex::new(r"^第[ 　]*([一二三四五六七八九十百千万零两\d]+)[ 　]*节").unwrap())
 }
 
 fn get_article_pattern() -> &'static Regex {
     // Capture both number and optional title/content starting with space or bracket
-    ARTICLE_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十百千万零两\d]+)条([\s　]*)(.*)").unwrap())
+    // Group 2 is an optional bis suffix: 第二十九条之一
+    ARTICLE_PATTERN.get_or_init(|| Regex::new(r"^第[ 　]*([一二三四五六七八九十百千万零两\d]+)[ 　]*条(之[一二三四五六七八九十]+)?([\s　]*)(.*)").unwrap())
 }
 
 fn get_clause_pattern() -> &'static Regex {
@@ -35,9 +77,429 @@ fn get_item_pattern() -> &'static Regex {
     ITEM_PATTERN.get_or_init(|| Regex::new(r"^(\d+)\.").unwrap())
 }
 
+fn get_closing_paren_pattern() -> &'static Regex {
+    // "一)" / "1）" without the opening parenthesis
+    CLOSING_PAREN_PATTERN.get_or_init(|| Regex::new(r"^([一二三四五六七八九十]+|\d+)[)）]").unwrap())
+}
+
+fn get_circled_pattern() -> &'static Regex {
+    CIRCLED_PATTERN.get_or_init(|| Regex::new(r"^[①-⑳]").unwrap())
+}
+
+fn get_comma_item_pattern() -> &'static Regex {
+    COMMA_ITEM_PATTERN.get_or_init(|| Regex::new(r"^(\d+)、").unwrap())
+}
+
+/// The ten heavenly stems, used as an enumeration in place of 一二三…十
+const STEMS: [char; 10] = ['甲', '乙', '丙', '丁', '戊', '己', '庚', '辛', '壬', '癸'];
+
+fn get_stem_pattern() -> &'static Regex {
+    STEM_PATTERN.get_or_init(|| Regex::new(r"^([甲乙丙丁戊己庚辛壬癸])[、．.]").unwrap())
+}
+
+fn get_ordinal_pattern() -> &'static Regex {
+    // "其一，" but not "其一般" or "其余"
+    ORDINAL_PATTERN.get_or_init(|| Regex::new(r"^其([一二三四五六七八九十]+)[，,、：:；]").unwrap())
+}
+
+fn get_explicit_clause_pattern() -> &'static Regex {
+    EXPLICIT_CLAUSE_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十]+)款[ 　：:]*").unwrap())
+}
+
+fn get_explicit_item_pattern() -> &'static Regex {
+    EXPLICIT_ITEM_PATTERN.get_or_init(|| Regex::new(r"^第([一二三四五六七八九十]+)项[ 　：:]*").unwrap())
+}
+
+/// Explicit 第X款/第X项 marker matched by `pattern`, unless the line goes on as a reference
+/// ("第二款规定的…")
+fn explicit_marker<'a>(pattern: &Regex, line: &'a str) -> Option<(&'a str, String)> {
+    let caps = pattern.captures(line)?;
+    let full = caps.get(0).unwrap();
+    (!is_reference_tail(&line[full.end()..])).then(|| (full.as_str(), caps[1].to_string()))
+}
+
+/// Clause marker at the start of a line: (full marker, clause number in Chinese numerals)
+fn clause_marker<'a>(line: &'a str, styles: &[MarkerStyle]) -> Option<(&'a str, String)> {
+    let marker = |caps: regex::Captures<'a>| (caps.get(0).unwrap().as_str(), caps[1].to_string());
+    if styles.contains(&MarkerStyle::Paren) {
+        if let Some(caps) = get_clause_pattern().captures(line) {
+            return Some(marker(caps));
+        }
+    }
+    if styles.contains(&MarkerStyle::ClosingParen) {
+        if let Some(caps) = get_closing_paren_pattern().captures(line).filter(|c| !c[1].starts_with(|c: char| c.is_ascii_digit())) {
+            return Some(marker(caps));
+        }
+    }
+    if styles.contains(&MarkerStyle::Stem) {
+        if let Some(caps) = get_stem_pattern().captures(line) {
+            let stem = caps[1].chars().next().unwrap();
+            let position = STEMS.iter().position(|s| *s == stem).unwrap();
+            return Some((caps.get(0).unwrap().as_str(), crate::nlp::numerals::int_to_chinese(position + 1)));
+        }
+    }
+    if styles.contains(&MarkerStyle::Ordinal) {
+        if let Some(caps) = get_ordinal_pattern().captures(line) {
+            return Some(marker(caps));
+        }
+    }
+    if styles.contains(&MarkerStyle::Explicit) {
+        if let Some(found) = explicit_marker(get_explicit_clause_pattern(), line) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Item marker at the start of a line: (full marker, item number)
+fn item_marker<'a>(line: &'a str, styles: &[MarkerStyle]) -> Option<(&'a str, String)> {
+    let arabic = |caps: regex::Captures<'a>| (caps.get(0).unwrap().as_str(), caps[1].to_string());
+    if styles.contains(&MarkerStyle::Dot) {
+        if let Some(caps) = get_item_pattern().captures(line) {
+            return Some(arabic(caps));
+        }
+    }
+    if styles.contains(&MarkerStyle::Comma) {
+        if let Some(caps) = get_comma_item_pattern().captures(line) {
+            return Some(arabic(caps));
+        }
+    }
+    if styles.contains(&MarkerStyle::ClosingParen) {
+        if let Some(caps) = get_closing_paren_pattern().captures(line).filter(|c| c[1].starts_with(|c: char| c.is_ascii_digit())) {
+            return Some(arabic(caps));
+        }
+    }
+    if styles.contains(&MarkerStyle::Circled) {
+        if let Some(m) = get_circled_pattern().find(line) {
+            let c = m.as_str().chars().next().unwrap();
+            return Some((m.as_str(), (c as u32 - '①' as u32 + 1).to_string()));
+        }
+    }
+    if styles.contains(&MarkerStyle::Explicit) {
+        if let Some(found) = explicit_marker(get_explicit_item_pattern(), line) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn get_supplementary_pattern() -> &'static Regex {
+    SUPPLEMENTARY_PATTERN.get_or_init(|| Regex::new(r"^附\s*则$").unwrap())
+}
+
+fn get_annex_pattern() -> &'static Regex {
+    // "附件", "附件一", "附件2：收费标准"
+    ANNEX_PATTERN.get_or_init(|| Regex::new(r"^附件([一二三四五六七八九十\d]*)(?:[：:\s　]+(.*))?$").unwrap())
+}
+
+fn get_inline_clause_pattern() -> &'static Regex {
+    // A clause marker right after list-introducing or separating punctuation
+    INLINE_CLAUSE_PATTERN.get_or_init(|| Regex::new(r"([：；。:;])[ \t　]*([（(][一二三四五六七八九十]+[)）])").unwrap())
+}
+
+fn get_paren_title_pattern() -> &'static Regex {
+    // Titles are short runs of Chinese words; digits (full-width included) or other text in
+    // the paren mean a clause marker or an aside, not a title
+    PAREN_TITLE_PATTERN.get_or_init(|| Regex::new(r"^[（(]([\p{Han}、·]{2,20})[)）]").unwrap())
+}
+
+fn get_bracket_title_pattern() -> &'static Regex {
+    BRACKET_TITLE_PATTERN.get_or_init(|| Regex::new(r"^【([^【】]+)】").unwrap())
+}
+
+/// Split a leading title (e.g. "【立法目的】" or "（立法目的）") off the article content.
+/// A paren holding only numerals (e.g. "（一）") is a clause marker, not a title.
+fn split_article_title(text: &str) -> (Option<&str>, &str) {
+    if let Some(caps) = get_bracket_title_pattern().captures(text) {
+        let rest = &text[caps.get(0).unwrap().end()..];
+        return (Some(caps.get(1).unwrap().as_str().trim()), rest.trim());
+    }
+    if let Some(caps) = get_paren_title_pattern().captures(text) {
+        let inner = caps.get(1).unwrap().as_str();
+        let is_numeric = inner.chars().all(|c| "一二三四五六七八九十百千万零两".contains(c) || c.is_ascii_digit());
+        if !is_numeric {
+            let rest = &text[caps.get(0).unwrap().end()..];
+            return (Some(inner.trim()), rest.trim());
+        }
+    }
+    (None, text)
+}
+
+/// Heading text after a 编/分编/章/节 marker: "第一章 总则" -> "总则", "第二节：一般规定" -> "一般规定"
+fn heading_title(line: &str, caps: &Captures) -> Option<Arc<str>> {
+    let rest = line.get(caps.get(0).unwrap().end()..).unwrap_or("");
+    let title = rest.trim_start_matches(|c: char| c.is_whitespace() || "：:、".contains(c)).trim_end();
+    if title.is_empty() { None } else { Some(title.into()) }
+}
+
+/// Close the open clause/article/section/chapter (and optionally sub-part and part) into their parents
+#[allow(clippy::too_many_arguments)]
+fn close_open_nodes(
+    root: &mut ArticleNode,
+    part: &mut Option<ArticleNode>,
+    sub_part: &mut Option<ArticleNode>,
+    chapter: &mut Option<ArticleNode>,
+    section: &mut Option<ArticleNode>,
+    article: &mut Option<ArticleNode>,
+    clause: &mut Option<ArticleNode>,
+    close_part: bool,
+) {
+    if let Some(c) = clause.take() {
+        if let Some(a) = article.as_mut() { a.children.push(c); }
+    }
+    if let Some(a) = article.take() {
+        if let Some(s) = section.as_mut() { s.children.push(a); }
+        else if let Some(c) = chapter.as_mut() { c.children.push(a); }
+        else if let Some(sp) = sub_part.as_mut() { sp.children.push(a); }
+        else if let Some(p) = part.as_mut() { p.children.push(a); }
+        else { root.children.push(a); }
+    }
+    if let Some(s) = section.take() {
+        if let Some(c) = chapter.as_mut() { c.children.push(s); }
+        else if let Some(sp) = sub_part.as_mut() { sp.children.push(s); }
+        else if let Some(p) = part.as_mut() { p.children.push(s); }
+        else { root.children.push(s); }
+    }
+    if let Some(c) = chapter.take() {
+        if let Some(sp) = sub_part.as_mut() { sp.children.push(c); }
+        else if let Some(p) = part.as_mut() { p.children.push(c); }
+        else { root.children.push(c); }
+    }
+    if close_part {
+        if let Some(sp) = sub_part.take() {
+            if let Some(p) = part.as_mut() { p.children.push(sp); }
+            else { root.children.push(sp); }
+        }
+        if let Some(p) = part.take() { root.children.push(p); }
+    }
+}
+
+/// Display label for an article number: "五" -> "第五条", "二十九之一" -> "第二十九条之一"
+pub fn article_label(number: &str) -> String {
+    match number.split_once('之') {
+        Some((base, sub)) => format!("第{}条之{}", base, sub),
+        None => format!("第{}条", number),
+    }
+}
+
+/// An article title as written before its text: "（立法目的）", or "【Purpose 1】" when the
+/// parentheses would not parse back as a title (not all Han, or over 20 characters)
+pub fn article_title_markup(title: &str) -> String {
+    let paren = format!("（{}）", title);
+    match split_article_title(&paren) {
+        (Some(parsed), "") if parsed == title => paren,
+        _ => format!("【{}】", title),
+    }
+}
+
+/// Heading a TocEntry stands for: "第一章 总则", "第二十九条之一"
+pub fn toc_entry_label(entry: &ArticleNode) -> String {
+    let label = match entry.content.as_ref() {
+        "条" => article_label(&entry.number),
+        level => format!("第{}{}", entry.number, level),
+    };
+    match &entry.title {
+        Some(title) => format!("{} {}", label, title),
+        None => label,
+    }
+}
+
+/// Text a Toc node stands for: its heading, each entry's label in order, then any other
+/// 目录 lines, one per line
+pub fn toc_text(toc: &ArticleNode) -> String {
+    fn labels(entries: &[ArticleNode], lines: &mut Vec<String>) {
+        for entry in entries {
+            lines.push(toc_entry_label(entry));
+            labels(&entry.children, lines);
+        }
+    }
+    let mut lines = vec![toc.number.to_string()];
+    labels(&toc.children, &mut lines);
+    if !toc.content.is_empty() {
+        lines.push(toc.content.to_string());
+    }
+    lines.join("\n")
+}
+
+/// Split article content into its clause nodes, as `grammar` marks them with `styles`
+/// (（一）/（二）… in Chinese statutes). Lead-in text before the first clause is skipped;
+/// continuation and item lines stay with their clause.
+pub fn parse_clauses(content: &str, grammar: &dyn StructureGrammar, styles: &[MarkerStyle]) -> Vec<ArticleNode> {
+    let spans = line_spans(content);
+    let mut clauses: Vec<ArticleNode> = Vec::new();
+    for (line_idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_matches(|c: char| c.is_whitespace() || c == '\u{3000}');
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some((_, number)) = grammar.clause_marker(trimmed, styles) {
+            clauses.push(ArticleNode {
+                node_type: NodeType::Clause,
+                number: number.into(),
+                title: None,
+                content: trimmed.into(),
+                children: Vec::new(),
+                start_line: line_idx + 1,
+                start_offset: spans[line_idx].0,
+                end_offset: spans[line_idx].1,
+                indent: None,
+            });
+        } else if let Some(clause) = clauses.last_mut() {
+            clause.content = format!("{}\n{}", clause.content, trimmed).into();
+            clause.end_offset = spans[line_idx].1;
+        }
+    }
+    clauses
+}
+
+/// Whether a line in the table of contents region looks like a TOC entry rather than body text
+fn is_likely_toc_entry(text: &str, grammar: &dyn StructureGrammar) -> bool {
+    let t = text.trim();
+    if t.is_empty() { return false; }
+
+    // Classic markers: dots, ellipsis, trailing page numbers
+    if t.contains("...") || t.contains("···") || t.contains("..") ||
+       t.chars().last().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+        return true;
+    }
+
+    // Heuristic: Indented structural elements in the preamble are almost always TOC entries
+    let is_indented = text.starts_with(' ') || text.starts_with('\u{3000}') || text.starts_with('\t');
+    let is_structural = grammar.chapter_pattern().is_match(t) ||
+                       grammar.section_pattern().is_match(t) ||
+                       grammar.part_pattern().is_match(t) ||
+                       grammar.sub_part_pattern().is_match(t) ||
+                       grammar.article_pattern().is_match(t);
+
+    if is_indented && is_structural {
+        return true;
+    }
+
+    // High-level structural markers (non-article) that are short and appear right after "目录"
+    // Articles are usually not in TOC unless they have dots/page numbers or are indented.
+    let is_high_structural = grammar.chapter_pattern().is_match(t) ||
+                            grammar.section_pattern().is_match(t) ||
+                            grammar.part_pattern().is_match(t) ||
+                            grammar.sub_part_pattern().is_match(t);
+
+    if is_high_structural && t.chars().count() < 30 {
+        return true;
+    }
+
+    false
+}
+
+/// Parse one 目录 line ("第一章 总则……3") into a TocEntry, dropping leader dots and page numbers
+fn toc_entry(line: &str, line_idx: usize, span: (usize, usize), grammar: &dyn StructureGrammar) -> Option<ArticleNode> {
+    let cleaned = line.trim().trim_end_matches(|c: char| c.is_ascii_digit() || c.is_whitespace() || ".·…．-—".contains(c));
+    let (number, level, rest) = if let Some(caps) = grammar.article_pattern().captures(cleaned) {
+        let number = match caps.get(2) {
+            Some(bis) => format!("{}{}", &caps[1], bis.as_str()),
+            None => caps[1].to_string(),
+        };
+        (number, "条", caps.get(4).map_or("", |m| m.as_str()))
+    } else {
+        [(grammar.part_pattern(), "编"), (grammar.sub_part_pattern(), "分编"), (grammar.chapter_pattern(), "章"), (grammar.section_pattern(), "节")]
+            .into_iter()
+            .find_map(|(pattern, level)| {
+                pattern.captures(cleaned).map(|caps| (caps[1].to_string(), level, &cleaned[caps.get(0).unwrap().end()..]))
+            })?
+    };
+    let title = rest.trim();
+    Some(ArticleNode {
+        node_type: NodeType::TocEntry,
+        number: number.into(),
+        title: if title.is_empty() { None } else { Some(title.into()) },
+        content: level.into(),
+        children: Vec::new(),
+        start_line: line_idx + 1,
+        start_offset: span.0,
+        end_offset: span.1,
+        indent: None,
+    })
+}
+
+/// Nest flat TOC entries by level: sections under their chapter, chapters under their
+/// sub-part or part
+fn nest_toc_entries(entries: Vec<ArticleNode>) -> Vec<ArticleNode> {
+    let rank = |entry: &ArticleNode| ["编", "分编", "章", "节", "条"].iter().position(|l| *l == entry.content.as_ref()).unwrap_or(4);
+    let mut nested: Vec<ArticleNode> = Vec::new();
+    let mut stack: Vec<ArticleNode> = Vec::new();
+    let close = |stack: &mut Vec<ArticleNode>, nested: &mut Vec<ArticleNode>| {
+        let entry = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(entry),
+            None => nested.push(entry),
+        }
+    };
+    for entry in entries {
+        while stack.last().is_some_and(|open| rank(open) >= rank(&entry)) {
+            close(&mut stack, &mut nested);
+        }
+        stack.push(entry);
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut nested);
+    }
+    nested
+}
+
+/// The document type whose headings `text` is parsed with. A judicial interpretation with no
+/// 第X条 (a 批复 answering 一、二、) keeps the statute grammar: its 一、 paragraphs are the
+/// provisions themselves, not headings.
+fn structure_document_type(text: &str, options: &ParserOptions) -> DocumentType {
+    match options.document_type.unwrap_or_else(|| detect_document_type(text)) {
+        DocumentType::JudicialInterpretation if !text.lines().any(|l| get_article_pattern().is_match(l.trim())) => DocumentType::Statute,
+        document_type => document_type,
+    }
+}
+
+/// Split a text holding several documents (e.g. a law plus its implementing regulation)
+/// on the configured delimiter lines and parse each one; offsets are per document.
+pub fn parse_documents(text: &str, options: &ParserOptions) -> Vec<ArticleNode> {
+    split_documents(text, options).iter()
+        .map(|doc| parse_article_with_options(doc, options))
+        .collect()
+}
+
+/// The non-empty documents between `options.document_delimiters` lines
+pub fn split_documents(text: &str, options: &ParserOptions) -> Vec<String> {
+    let mut documents = vec![String::new()];
+    for line in text.lines() {
+        if options.document_delimiters.iter().any(|d| d.trim() == line.trim()) {
+            documents.push(String::new());
+        } else {
+            let current = documents.last_mut().unwrap();
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    documents.retain(|doc| !doc.trim().is_empty());
+    documents
+}
+
 /// Parse legal article text into AST structure
 pub fn parse_article(text: &str) -> ArticleNode {
+    parse_article_with_options(text, &ParserOptions::default())
+}
+
+/// Parse legal article text into AST structure with explicit parser options
+/// With `split_inline_clauses`, offsets refer to the text after clause splitting.
+pub fn parse_article_with_options(text: &str, options: &ParserOptions) -> ArticleNode {
+    let grammar = grammar_for_document(structure_document_type(text, options), options);
+    if let Some(root) = grammar.parse_document(text, options) {
+        return root;
+    }
+    let grammar = grammar.as_ref();
+    // Traditional markers (第一條) map 1:1 onto simplified ones, so offsets are unaffected
+    let mut text = normalize_traditional_markers(text);
+    if options.split_inline_clauses {
+        text = get_inline_clause_pattern().replace_all(&text, "$1\n$2").into_owned();
+    }
+    let text = text.as_str();
+
     let lines: Vec<&str> = text.lines().collect();
+    let spans = line_spans(text);
 
     let mut root = ArticleNode {
         node_type: NodeType::Article,
@@ -46,53 +508,28 @@ pub fn parse_article(text: &str) -> ArticleNode {
         content: "".into(),
         children: Vec::new(),
         start_line: 0,
+        start_offset: 0,
+        end_offset: text.chars().count(),
+        indent: None,
     };
 
     let mut current_part: Option<ArticleNode> = None;
+    let mut current_sub_part: Option<ArticleNode> = None;
     let mut current_chapter: Option<ArticleNode> = None;
     let mut current_section: Option<ArticleNode> = None;
     let mut current_article: Option<ArticleNode> = None;
     let mut current_clause: Option<ArticleNode> = None;
+    let mut current_annex: Option<ArticleNode> = None;
 
     let mut preamble_buffer: Vec<String> = Vec::new();
+    let mut preamble_span: (usize, usize) = (0, 0);
     let mut structure_started = false;
     let mut in_toc = false;
+    let mut paragraph_count = 1;
+    let mut toc: Option<ArticleNode> = None;
+    let mut toc_entries: Vec<ArticleNode> = Vec::new();
     let mut seen_markers = HashSet::new();
 
-    let is_likely_toc_entry = |text: &str| -> bool {
-        let t = text.trim();
-        if t.is_empty() { return false; }
-
-        // Classic markers: dots, ellipsis, trailing page numbers
-        if t.contains("...") || t.contains("···") || t.contains("..") ||
-           t.chars().last().map(|c| c.is_ascii_digit()).unwrap_or(false) {
-            return true;
-        }
-
-        // Heuristic: Indented structural elements in the preamble are almost always TOC entries
-        let is_indented = text.starts_with(' ') || text.starts_with('\u{3000}') || text.starts_with('\t');
-        let is_structural = get_chapter_pattern().is_match(t) ||
-                           get_section_pattern().is_match(t) ||
-                           get_part_pattern().is_match(t) ||
-                           get_article_pattern().is_match(t);
-
-        if is_indented && is_structural {
-            return true;
-        }
-
-        // High-level structural markers (non-article) that are short and appear right after "目录"
-        // Articles are usually not in TOC unless they have dots/page numbers or are indented.
-        let is_high_structural = get_chapter_pattern().is_match(t) ||
-                                get_section_pattern().is_match(t) ||
-                                get_part_pattern().is_match(t);
-
-        if is_high_structural && t.chars().count() < 30 {
-            return true;
-        }
-
-        false
-    };
-
     for (line_idx, line) in lines.iter().enumerate() {
         let trimmed = line.trim();
         if trimmed.is_empty() {
@@ -100,15 +537,65 @@ pub fn parse_article(text: &str) -> ArticleNode {
         }
 
         // TOC Detection
-        if !structure_started && (trimmed.contains("目录") || trimmed == "目 录") {
+        if options.detect_toc && !structure_started && (trimmed.contains("目录") || trimmed == "目 录") {
             in_toc = true;
         }
 
-        if let Some(caps) = get_article_pattern().captures(trimmed) {
-            let after_marker = caps.get(3).map(|m| m.as_str()).unwrap_or("");
-            if !after_marker.starts_with("规定") && !after_marker.starts_with("之") {
+        if structure_started {
+            // Annex (附件): everything up to the next annex header belongs to it
+            if let Some(caps) = grammar.annex_pattern().captures(trimmed) {
+                close_open_nodes(&mut root, &mut current_part, &mut current_sub_part, &mut current_chapter, &mut current_section,
+                                 &mut current_article, &mut current_clause, true);
+                if let Some(annex) = current_annex.take() {
+                    root.children.push(annex);
+                }
+                current_annex = Some(ArticleNode {
+                    node_type: NodeType::Annex,
+                    number: format!("附件{}", &caps[1]).into(),
+                    title: caps.get(2).map(|m| m.as_str().trim()).filter(|t| !t.is_empty()).map(|t| t.into()),
+                    content: "".into(),
+                    children: Vec::new(),
+                    start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
+                });
+                continue;
+            }
+            if let Some(ref mut annex) = current_annex {
+                annex.content = if annex.content.is_empty() {
+                    trimmed.into()
+                } else {
+                    format!("{}\n{}", annex.content, trimmed).into()
+                };
+                annex.end_offset = spans[line_idx].1;
+                continue;
+            }
+
+            // Supplementary provisions (附则): holds the closing articles like a chapter
+            if grammar.supplementary_pattern().is_match(trimmed) {
+                close_open_nodes(&mut root, &mut current_part, &mut current_sub_part, &mut current_chapter, &mut current_section,
+                                 &mut current_article, &mut current_clause, false);
+                current_chapter = Some(ArticleNode {
+                    node_type: NodeType::Supplementary,
+                    number: "附则".into(),
+                    title: None,
+                    content: "".into(),
+                    children: Vec::new(),
+                    start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
+                });
+                continue;
+            }
+        }
+
+        if let Some(caps) = grammar.article_pattern().captures(trimmed) {
+            let after_marker = caps.get(4).map(|m| m.as_str()).unwrap_or("");
+            if !is_reference_tail(after_marker) {
                 // If we are in TOC, only breakout if this isn't a likely TOC entry
-                let should_breakout = if in_toc { !is_likely_toc_entry(line) } else { true };
+                let should_breakout = if in_toc { !is_likely_toc_entry(line, grammar) } else { true };
 
                 if should_breakout {
                     // Inline check_preamble
@@ -120,6 +607,9 @@ pub fn parse_article(text: &str) -> ArticleNode {
                             content: preamble_buffer.join("\n").into(),
                             children: Vec::new(),
                             start_line: 1,
+                            start_offset: preamble_span.0,
+                            end_offset: preamble_span.1,
+                            indent: None,
                         });
                         preamble_buffer.clear();
                     }
@@ -132,19 +622,31 @@ pub fn parse_article(text: &str) -> ArticleNode {
                     if let Some(article) = current_article.take() {
                         if let Some(ref mut section) = current_section { section.children.push(article); }
                         else if let Some(ref mut chapter) = current_chapter { chapter.children.push(article); }
+                        else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(article); }
                         else if let Some(ref mut part) = current_part { part.children.push(article); }
                         else { root.children.push(article); }
                     }
 
+                    let (title, mut body) = split_article_title(after_marker.trim());
+                    if options.keep_title_in_content {
+                        body = after_marker.trim();
+                    }
                     current_article = Some(ArticleNode {
                         node_type: NodeType::Article,
-                        number: caps.get(1).unwrap().as_str().into(),
-                        title: None,
-                        content: after_marker.trim().into(),
+                        number: match caps.get(2) {
+                            Some(bis) => format!("{}{}", &caps[1], bis.as_str()).into(),
+                            None => caps.get(1).unwrap().as_str().into(),
+                        },
+                        title: title.map(|t| t.into()),
+                        content: body.into(),
                         children: Vec::new(),
                         start_line: line_idx + 1,
+                        start_offset: spans[line_idx].0,
+                        end_offset: spans[line_idx].1,
+                        indent: None,
                     });
                     current_clause = None;
+                    paragraph_count = 1;
                     continue;
                 }
             }
@@ -152,24 +654,27 @@ pub fn parse_article(text: &str) -> ArticleNode {
 
         // Structural breakout check for TOC
         if in_toc {
-            let is_structural = get_chapter_pattern().is_match(trimmed) ||
-                               get_section_pattern().is_match(trimmed) ||
-                               get_part_pattern().is_match(trimmed);
+            let is_structural = grammar.chapter_pattern().is_match(trimmed) ||
+                               grammar.section_pattern().is_match(trimmed) ||
+                               grammar.part_pattern().is_match(trimmed) ||
+                               grammar.sub_part_pattern().is_match(trimmed);
             if is_structural {
-                let marker = if let Some(caps) = get_chapter_pattern().captures(trimmed) {
+                let marker = if let Some(caps) = grammar.chapter_pattern().captures(trimmed) {
                     format!("CH_{}", caps.get(1).unwrap().as_str())
-                } else if let Some(caps) = get_section_pattern().captures(trimmed) {
+                } else if let Some(caps) = grammar.section_pattern().captures(trimmed) {
                     format!("SEC_{}", caps.get(1).unwrap().as_str())
-                } else if let Some(caps) = get_part_pattern().captures(trimmed) {
+                } else if let Some(caps) = grammar.part_pattern().captures(trimmed) {
                     format!("PART_{}", caps.get(1).unwrap().as_str())
+                } else if let Some(caps) = grammar.sub_part_pattern().captures(trimmed) {
+                    format!("SUBPART_{}", caps.get(1).unwrap().as_str())
                 } else { String::new() };
 
                 if !marker.is_empty() {
                     // Break out of TOC if we see a repeat of a high-level marker (Chapter/Part)
                     // OR if it's clearly not a TOC line (e.g. has body content or lacks TOC characteristics)
-                    let is_high_level = marker.starts_with("CH_") || marker.starts_with("PART_");
+                    let is_high_level = marker.starts_with("CH_") || marker.starts_with("PART_") || marker.starts_with("SUBPART_");
                     let is_repeat = is_high_level && seen_markers.contains(&marker);
-                    let clearly_not_toc = !is_likely_toc_entry(line);
+                    let clearly_not_toc = !is_likely_toc_entry(line, grammar);
 
                     if is_repeat || clearly_not_toc {
                         in_toc = false;
@@ -183,7 +688,7 @@ pub fn parse_article(text: &str) -> ArticleNode {
         // 2. High-level Structural Elements (Part, Chapter, Section) - Ignored in TOC
         if !in_toc {
             // Check for Part (编)
-            if let Some(caps) = get_part_pattern().captures(trimmed) {
+            if let Some(caps) = grammar.part_pattern().captures(trimmed) {
                 if !structure_started && !preamble_buffer.is_empty() {
                     root.children.push(ArticleNode {
                         node_type: NodeType::Preamble,
@@ -192,6 +697,9 @@ pub fn parse_article(text: &str) -> ArticleNode {
                         content: preamble_buffer.join("\n").into(),
                         children: Vec::new(),
                         start_line: 1,
+                        start_offset: preamble_span.0,
+                        end_offset: preamble_span.1,
+                        indent: None,
                     });
                     preamble_buffer.clear();
                 }
@@ -203,24 +711,39 @@ pub fn parse_article(text: &str) -> ArticleNode {
                 if let Some(article) = current_article.take() {
                     if let Some(ref mut section) = current_section { section.children.push(article); }
                     else if let Some(ref mut chapter) = current_chapter { chapter.children.push(article); }
+                    else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(article); }
+                    else if let Some(ref mut part) = current_part { part.children.push(article); }
                     else { root.children.push(article); }
                 }
                 if let Some(section) = current_section.take() {
                     if let Some(ref mut chapter) = current_chapter { chapter.children.push(section); }
+                    else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(section); }
+                    else if let Some(ref mut part) = current_part { part.children.push(section); }
                     else { root.children.push(section); }
                 }
                 if let Some(chapter) = current_chapter.take() {
-                    if let Some(ref mut part) = current_part { part.children.push(chapter); }
+                    if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(chapter); }
+                    else if let Some(ref mut part) = current_part { part.children.push(chapter); }
                     else { root.children.push(chapter); }
                 }
+                if let Some(sub_part) = current_sub_part.take() {
+                    if let Some(ref mut part) = current_part { part.children.push(sub_part); }
+                    else { root.children.push(sub_part); }
+                }
+                if let Some(part) = current_part.take() {
+                    root.children.push(part);
+                }
 
                 current_part = Some(ArticleNode {
                     node_type: NodeType::Part,
                     number: caps.get(1).unwrap().as_str().into(),
-                    title: caps.get(2).map(|m| m.as_str().into()),
+                    title: heading_title(trimmed, &caps),
                     content: "".into(),
                     children: Vec::new(),
                     start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
                 });
                 current_chapter = None;
                 current_section = None;
@@ -229,10 +752,49 @@ pub fn parse_article(text: &str) -> ArticleNode {
                 continue;
             }
 
+            // Check for Sub-part (分编)
+            if let Some(caps) = grammar.sub_part_pattern().captures(trimmed) {
+                if !structure_started && !preamble_buffer.is_empty() {
+                    root.children.push(ArticleNode {
+                        node_type: NodeType::Preamble,
+                        number: "0".into(),
+                        title: Some("序言/目录".into()),
+                        content: preamble_buffer.join("\n").into(),
+                        children: Vec::new(),
+                        start_line: 1,
+                        start_offset: preamble_span.0,
+                        end_offset: preamble_span.1,
+                        indent: None,
+                    });
+                    preamble_buffer.clear();
+                }
+                structure_started = true;
+                in_toc = false;
+                close_open_nodes(&mut root, &mut current_part, &mut current_sub_part, &mut current_chapter, &mut current_section,
+                                 &mut current_article, &mut current_clause, false);
+                if let Some(sub_part) = current_sub_part.take() {
+                    if let Some(ref mut part) = current_part { part.children.push(sub_part); }
+                    else { root.children.push(sub_part); }
+                }
+
+                current_sub_part = Some(ArticleNode {
+                    node_type: NodeType::SubPart,
+                    number: caps.get(1).unwrap().as_str().into(),
+                    title: heading_title(trimmed, &caps),
+                    content: "".into(),
+                    children: Vec::new(),
+                    start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
+                });
+                continue;
+            }
+
             // Check for Chapter (章)
-            if let Some(caps) = get_chapter_pattern().captures(trimmed) {
+            if let Some(caps) = grammar.chapter_pattern().captures(trimmed) {
                 let after_marker = trimmed.get(caps.get(0).unwrap().end()..).unwrap_or("");
-                if !after_marker.starts_with("规定") && !after_marker.starts_with("之") {
+                if !is_reference_tail(after_marker) {
                     if !structure_started && !preamble_buffer.is_empty() {
                     root.children.push(ArticleNode {
                         node_type: NodeType::Preamble,
@@ -241,6 +803,9 @@ pub fn parse_article(text: &str) -> ArticleNode {
                         content: preamble_buffer.join("\n").into(),
                         children: Vec::new(),
                         start_line: 1,
+                        start_offset: preamble_span.0,
+                        end_offset: preamble_span.1,
+                        indent: None,
                     });
                     preamble_buffer.clear();
                 }
@@ -252,24 +817,32 @@ pub fn parse_article(text: &str) -> ArticleNode {
                     if let Some(article) = current_article.take() {
                         if let Some(ref mut section) = current_section { section.children.push(article); }
                         else if let Some(ref mut chapter) = current_chapter { chapter.children.push(article); }
+                        else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(article); }
+                        else if let Some(ref mut part) = current_part { part.children.push(article); }
                         else { root.children.push(article); }
                     }
                     if let Some(section) = current_section.take() {
                         if let Some(ref mut chapter) = current_chapter { chapter.children.push(section); }
+                        else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(section); }
+                        else if let Some(ref mut part) = current_part { part.children.push(section); }
                         else { root.children.push(section); }
                     }
                     if let Some(chapter) = current_chapter.take() {
-                         if let Some(ref mut part) = current_part { part.children.push(chapter); }
+                         if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(chapter); }
+                         else if let Some(ref mut part) = current_part { part.children.push(chapter); }
                          else { root.children.push(chapter); }
                     }
 
                     current_chapter = Some(ArticleNode {
                         node_type: NodeType::Chapter,
                         number: caps.get(1).unwrap().as_str().into(),
-                        title: if after_marker.is_empty() { None } else { Some(after_marker.trim().into()) },
+                        title: heading_title(trimmed, &caps),
                         content: "".into(),
                         children: Vec::new(),
                         start_line: line_idx + 1,
+                        start_offset: spans[line_idx].0,
+                        end_offset: spans[line_idx].1,
+                        indent: None,
                     });
                     current_section = None;
                     current_article = None;
@@ -279,7 +852,7 @@ pub fn parse_article(text: &str) -> ArticleNode {
             }
 
             // Check for Section (节)
-            if let Some(caps) = get_section_pattern().captures(trimmed) {
+            if let Some(caps) = grammar.section_pattern().captures(trimmed) {
                 if !structure_started && !preamble_buffer.is_empty() {
                     root.children.push(ArticleNode {
                         node_type: NodeType::Preamble,
@@ -288,6 +861,9 @@ pub fn parse_article(text: &str) -> ArticleNode {
                         content: preamble_buffer.join("\n").into(),
                         children: Vec::new(),
                         start_line: 1,
+                        start_offset: preamble_span.0,
+                        end_offset: preamble_span.1,
+                        indent: None,
                     });
                     preamble_buffer.clear();
                 }
@@ -299,19 +875,27 @@ pub fn parse_article(text: &str) -> ArticleNode {
                 if let Some(article) = current_article.take() {
                     if let Some(ref mut section) = current_section { section.children.push(article); }
                     else if let Some(ref mut chapter) = current_chapter { chapter.children.push(article); }
+                    else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(article); }
+                    else if let Some(ref mut part) = current_part { part.children.push(article); }
                     else { root.children.push(article); }
                 }
                 if let Some(section) = current_section.take() {
                     if let Some(ref mut chapter) = current_chapter { chapter.children.push(section); }
+                    else if let Some(ref mut sub_part) = current_sub_part { sub_part.children.push(section); }
+                    else if let Some(ref mut part) = current_part { part.children.push(section); }
+                    else { root.children.push(section); }
                 }
 
                 current_section = Some(ArticleNode {
                     node_type: NodeType::Section,
                     number: caps.get(1).unwrap().as_str().into(),
-                    title: caps.get(2).map(|m| m.as_str().into()),
+                    title: heading_title(trimmed, &caps),
                     content: "".into(),
                     children: Vec::new(),
                     start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
                 });
                 current_article = None;
                 current_clause = None;
@@ -321,10 +905,29 @@ pub fn parse_article(text: &str) -> ArticleNode {
 
         if !in_toc {
             // 3. Clause (款)
-        if let Some(caps) = get_clause_pattern().captures(trimmed) {
-            let full_marker = caps.get(0).unwrap().as_str();
+        if let Some((full_marker, number)) = grammar.clause_marker(trimmed, &options.marker_styles) {
             let after_marker = trimmed.get(full_marker.len()..).unwrap_or("");
             if !after_marker.starts_with("规定") && !after_marker.starts_with("之") {
+                // With paragraphs as implicit 款, a （一） marker opens a 项 of the current paragraph
+                let paragraph_item = options.implicit_clauses && !get_explicit_clause_pattern().is_match(full_marker);
+                if let (true, Some(article)) = (paragraph_item, current_article.as_mut()) {
+                    let item = ArticleNode {
+                        node_type: NodeType::Item,
+                        number: number.into(),
+                        title: None,
+                        content: format!("{}{}", full_marker, after_marker.trim()).into(),
+                        children: Vec::new(),
+                        start_line: line_idx + 1,
+                        start_offset: spans[line_idx].0,
+                        end_offset: spans[line_idx].1,
+                        indent: None,
+                    };
+                    match current_clause.as_mut() {
+                        Some(clause) => clause.children.push(item),
+                        None => article.children.push(item),
+                    }
+                    continue;
+                }
                 if !structure_started && !preamble_buffer.is_empty() {
                     root.children.push(ArticleNode {
                         node_type: NodeType::Preamble,
@@ -333,6 +936,9 @@ pub fn parse_article(text: &str) -> ArticleNode {
                         content: preamble_buffer.join("\n").into(),
                         children: Vec::new(),
                         start_line: 1,
+                        start_offset: preamble_span.0,
+                        end_offset: preamble_span.1,
+                        indent: None,
                     });
                     preamble_buffer.clear();
                 }
@@ -343,11 +949,14 @@ pub fn parse_article(text: &str) -> ArticleNode {
                 }
                 current_clause = Some(ArticleNode {
                     node_type: NodeType::Clause,
-                    number: caps.get(1).unwrap().as_str().into(),
+                    number: number.into(),
                     title: None,
                     content: format!("{}{}", full_marker, after_marker.trim()).into(),
                     children: Vec::new(),
                     start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
                 });
                 continue;
             }
@@ -355,16 +964,18 @@ pub fn parse_article(text: &str) -> ArticleNode {
 
         if !in_toc {
             // 4. Item (项)
-        if let Some(caps) = get_item_pattern().captures(trimmed) {
-            let full_marker = caps.get(0).unwrap().as_str();
+        if let Some((full_marker, number)) = grammar.item_marker(trimmed, &options.marker_styles) {
             let after_marker = trimmed.get(full_marker.len()..).unwrap_or("");
             let item = ArticleNode {
                 node_type: NodeType::Item,
-                number: caps.get(1).unwrap().as_str().into(),
+                number: number.into(),
                 title: None,
                 content: format!("{}{}", full_marker, after_marker.trim()).into(),
                 children: Vec::new(),
                 start_line: line_idx + 1,
+                start_offset: spans[line_idx].0,
+                end_offset: spans[line_idx].1,
+                indent: None,
             };
             if let Some(ref mut clause) = current_clause { clause.children.push(item); }
             else if let Some(ref mut article) = current_article { article.children.push(item); }
@@ -372,26 +983,79 @@ pub fn parse_article(text: &str) -> ArticleNode {
         } }
 
         // 5. Fallback: Content continuation
-        if !structure_started {
+        if !structure_started && in_toc {
+            // 目录 block: entries become TocEntry nodes, anything else stays as TOC text
+            let block = toc.get_or_insert_with(|| ArticleNode {
+                node_type: NodeType::Toc,
+                number: "目录".into(),
+                title: None,
+                content: "".into(),
+                children: Vec::new(),
+                start_line: line_idx + 1,
+                start_offset: spans[line_idx].0,
+                end_offset: spans[line_idx].1,
+                indent: None,
+            });
+            block.end_offset = spans[line_idx].1;
+            if let Some(entry) = toc_entry(trimmed, line_idx, spans[line_idx], grammar) {
+                toc_entries.push(entry);
+            } else if !(trimmed.contains("目录") || trimmed == "目 录") {
+                block.content = if block.content.is_empty() {
+                    trimmed.into()
+                } else {
+                    format!("{}\n{}", block.content, trimmed).into()
+                };
+            }
+        } else if !structure_started {
+            if preamble_buffer.is_empty() {
+                preamble_span.0 = spans[line_idx].0;
+            }
+            preamble_span.1 = spans[line_idx].1;
             preamble_buffer.push(trimmed.to_string());
         } else {
             // To append to Arc<str>, we must convert back to String, append, then convert again.
             // This is slightly inefficient but only happens for continuation lines.
-            if let Some(ref mut clause) = current_clause {
+            if let (true, Some(article)) = (options.implicit_clauses, current_article.as_mut()) {
+                // Every paragraph of an article is a 款, numbered after the article's first paragraph
+                if let Some(clause) = current_clause.take() {
+                    article.children.push(clause);
+                }
+                paragraph_count += 1;
+                current_clause = Some(ArticleNode {
+                    node_type: NodeType::Clause,
+                    number: paragraph_count.to_string().into(),
+                    title: None,
+                    content: trimmed.into(),
+                    children: Vec::new(),
+                    start_line: line_idx + 1,
+                    start_offset: spans[line_idx].0,
+                    end_offset: spans[line_idx].1,
+                    indent: None,
+                });
+            } else if let Some(ref mut clause) = current_clause {
                 let mut content = clause.content.to_string();
-                content.push('\n');
+                if !content.is_empty() {
+                    content.push('\n');
+                }
                 content.push_str(trimmed);
                 clause.content = content.into();
+                clause.end_offset = spans[line_idx].1;
             } else if let Some(ref mut article) = current_article {
                 let mut content = article.content.to_string();
-                content.push('\n');
+                if !content.is_empty() {
+                    content.push('\n');
+                }
                 content.push_str(trimmed);
                 article.content = content.into();
+                article.end_offset = spans[line_idx].1;
             } else if let Some(ref mut chapter) = current_chapter {
                 let mut content = chapter.content.to_string();
-                content.push('\n');
+                if !content.is_empty() {
+                    content.push('\n');
+                }
                 content.push_str(trimmed);
                 chapter.content = content.into();
+                chapter.end_offset = spans[line_idx].1;
             }
         }
     }
@@ -408,6 +1072,8 @@ pub fn parse_article(text: &str) -> ArticleNode {
             section.children.push(article);
         } else if let Some(ref mut chapter) = current_chapter {
             chapter.children.push(article);
+        } else if let Some(ref mut sub_part) = current_sub_part {
+            sub_part.children.push(article);
         } else if let Some(ref mut part) = current_part {
             part.children.push(article);
         } else {
@@ -418,23 +1084,41 @@ pub fn parse_article(text: &str) -> ArticleNode {
     if let Some(section) = current_section {
         if let Some(ref mut chapter) = current_chapter {
             chapter.children.push(section);
+        } else if let Some(ref mut sub_part) = current_sub_part {
+            sub_part.children.push(section);
+        } else if let Some(ref mut part) = current_part {
+            part.children.push(section);
         } else {
             root.children.push(section);
         }
     }
 
     if let Some(chapter) = current_chapter {
-        if let Some(ref mut part) = current_part {
+        if let Some(ref mut sub_part) = current_sub_part {
+            sub_part.children.push(chapter);
+        } else if let Some(ref mut part) = current_part {
             part.children.push(chapter);
         } else {
             root.children.push(chapter);
         }
     }
 
+    if let Some(sub_part) = current_sub_part {
+        if let Some(ref mut part) = current_part {
+            part.children.push(sub_part);
+        } else {
+            root.children.push(sub_part);
+        }
+    }
+
     if let Some(part) = current_part {
         root.children.push(part);
     }
 
+    if let Some(annex) = current_annex {
+        root.children.push(annex);
+    }
+
     // If we finished and still have preamble content that was never flushed
     if !preamble_buffer.is_empty() {
         root.children.insert(0, ArticleNode {
@@ -444,13 +1128,78 @@ pub fn parse_article(text: &str) -> ArticleNode {
             content: preamble_buffer.join("\n").into(),
             children: Vec::new(),
             start_line: 1,
+            start_offset: preamble_span.0,
+            end_offset: preamble_span.1,
+            indent: None,
         });
     }
 
-    prune_empty_nodes(&mut root);
+    if let Some(mut block) = toc {
+        block.children = nest_toc_entries(toc_entries);
+        let after_preamble = root.children.first().is_some_and(|c| c.node_type == NodeType::Preamble) as usize;
+        root.children.insert(after_preamble, block);
+    }
+    if options.detect_tables {
+        table::extract_tables(&mut root, &lines, &spans);
+    }
+
+    for child in &mut root.children {
+        extend_end_offsets(child);
+    }
+    attach_indentation(&mut root, &text.chars().collect::<Vec<_>>());
+    if !options.capture_preamble {
+        root.children.retain(|child| !matches!(child.node_type, NodeType::Preamble | NodeType::Toc));
+    }
+    if options.prune_empty {
+        prune_empty_nodes(&mut root);
+    }
     root
 }
 
+/// Record the whitespace each node's first line opens with in `chars`, the text its
+/// offsets refer to
+fn attach_indentation(node: &mut ArticleNode, chars: &[char]) {
+    for child in &mut node.children {
+        child.indent = leading_whitespace(chars, child.start_offset).and_then(to_indent);
+        attach_indentation(child, chars);
+    }
+}
+
+/// The characters between the start of the line holding `offset` and `offset`, if they
+/// are all whitespace (None for a node that starts mid-line)
+fn leading_whitespace(chars: &[char], offset: usize) -> Option<&[char]> {
+    let offset = offset.min(chars.len());
+    let line_start = chars[..offset].iter().rposition(|c| *c == '\n').map_or(0, |p| p + 1);
+    let lead = &chars[line_start..offset];
+    lead.iter().all(|c| c.is_whitespace()).then_some(lead)
+}
+
+fn to_indent(lead: &[char]) -> Option<Arc<str>> {
+    (!lead.is_empty()).then(|| lead.iter().collect::<String>().into())
+}
+
+/// Char offsets (start, end) of each line's trimmed text, indexed like `str::lines`
+fn line_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut base = 0;
+    for segment in text.split('\n') {
+        let segment_len = segment.chars().count();
+        let lead = segment.chars().take_while(|c| c.is_whitespace()).count();
+        let trimmed_len = segment.trim().chars().count();
+        spans.push((base + lead, base + lead + trimmed_len));
+        base += segment_len + 1;
+    }
+    spans
+}
+
+/// Grow each node's end offset to cover its children
+fn extend_end_offsets(node: &mut ArticleNode) {
+    for child in &mut node.children {
+        extend_end_offsets(child);
+        node.end_offset = node.end_offset.max(child.end_offset);
+    }
+}
+
 /// Recursively remove structural nodes that have no content and no children.
 /// This is primarily to remove "Table of Contents" entries that are parsed as structural nodes
 /// but contain no actual legal text or articles.
@@ -467,7 +1216,7 @@ fn prune_empty_nodes(node: &mut ArticleNode) {
     node.children.retain(|child| {
         let is_structural = matches!(
             child.node_type,
-            NodeType::Part | NodeType::Chapter | NodeType::Section
+            NodeType::Part | NodeType::SubPart | NodeType::Chapter | NodeType::Section | NodeType::Supplementary
         );
 
         if is_structural {
@@ -511,6 +1260,203 @@ mod tests {
         assert_eq!(article.children[1].node_type, NodeType::Clause);
     }
 
+    #[test]
+    fn test_parse_article_bracket_title() {
+        let text = "第一条　【立法目的】为了保障网络安全，制定本法。";
+        let ast = parse_article(text);
+        let article = &ast.children[0];
+        assert_eq!(article.title.as_deref(), Some("立法目的"));
+        assert_eq!(article.content.as_ref(), "为了保障网络安全，制定本法。");
+
+        let options = ParserOptions { keep_title_in_content: true, ..Default::default() };
+        let ast = parse_article_with_options(text, &options);
+        assert_eq!(ast.children[0].title.as_deref(), Some("立法目的"));
+        assert_eq!(ast.children[0].content.as_ref(), "【立法目的】为了保障网络安全，制定本法。");
+    }
+
+    #[test]
+    fn test_parse_bis_article() {
+        let text = "第二十九条 国家支持研究开发。\n第二十九条之一 国家鼓励开发网络数据安全保护技术。\n第三十条 内容。";
+        let ast = parse_article(text);
+        let numbers: Vec<&str> = ast.children.iter().map(|n| n.number.as_ref()).collect();
+        assert_eq!(numbers, vec!["二十九", "二十九之一", "三十"]);
+        assert_eq!(ast.children[1].content.as_ref(), "国家鼓励开发网络数据安全保护技术。");
+        assert_eq!(article_label(&ast.children[1].number), "第二十九条之一");
+    }
+
+    #[test]
+    fn test_parse_supplementary_and_annex() {
+        let text = "第一条 为了规范管理，制定本办法。\n附则\n本办法所称以上，包括本数。\n第二条 本办法自公布之日起施行。\n附件一：收费标准\n一、登记费每件五十元\n二、查询费每次十元\n附件二\n申请表格式";
+        let ast = parse_article(text);
+        let types: Vec<&NodeType> = ast.children.iter().map(|n| &n.node_type).collect();
+        assert_eq!(types, vec![&NodeType::Article, &NodeType::Supplementary, &NodeType::Annex, &NodeType::Annex]);
+
+        let supplementary = &ast.children[1];
+        assert_eq!(supplementary.content.trim(), "本办法所称以上，包括本数。");
+        assert_eq!(supplementary.children[0].number.as_ref(), "二");
+        assert_eq!(supplementary.children[0].content.as_ref(), "本办法自公布之日起施行。");
+
+        let annex = &ast.children[2];
+        assert_eq!(annex.number.as_ref(), "附件一");
+        assert_eq!(annex.title.as_deref(), Some("收费标准"));
+        assert_eq!(annex.content.as_ref(), "一、登记费每件五十元\n二、查询费每次十元");
+        assert_eq!(ast.children[3].number.as_ref(), "附件二");
+    }
+
+    #[test]
+    fn test_parse_traditional_statute() {
+        let text = "第一章 總則\n第 1 條 為規範公司之組織及行為，特制定本法。\n第 2 條 本法所稱公司，謂以營利為目的之社團法人。";
+        let ast = parse_article(text);
+        let chapter = &ast.children[0];
+        assert_eq!(chapter.node_type, NodeType::Chapter);
+        let numbers: Vec<&str> = chapter.children.iter().map(|n| n.number.as_ref()).collect();
+        assert_eq!(numbers, vec!["1", "2"]);
+        assert_eq!(chapter.children[0].content.as_ref(), "為規範公司之組織及行為，特制定本法。");
+    }
+
+    #[test]
+    fn test_parse_documents_splits_on_delimiters() {
+        let text = "中华人民共和国示例法\n第一条 本法目的。\n---\n示例法实施条例\n第一条 根据示例法，制定本条例。\n第二条 本条例自公布之日起施行。\n---\n";
+        let options = ParserOptions { document_delimiters: vec!["---".to_string()], ..Default::default() };
+        let roots = parse_documents(text, &options);
+
+        assert_eq!(roots.len(), 2);
+        assert_eq!(extract_document_meta(&roots[1]).title.as_deref(), Some("示例法实施条例"));
+        let articles: Vec<_> = roots[1].children.iter().filter(|n| n.node_type == NodeType::Article).collect();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].start_offset, "示例法实施条例\n".chars().count());
+
+        // Without delimiters the whole text is one document
+        assert_eq!(parse_documents(text, &ParserOptions::default()).len(), 1);
+    }
+
+    #[test]
+    fn test_alternative_marker_styles() {
+        let text = "第一条 有下列情形之一的，予以处罚：\n一）未经许可的；\n1) 情节较轻的\n① 初次违法的\n2、情节严重的\n二) 超出范围的。";
+        let article = &parse_article(text).children[0];
+        let clauses: Vec<(&NodeType, &str)> = article.children.iter().map(|n| (&n.node_type, n.number.as_ref())).collect();
+        assert_eq!(clauses, vec![(&NodeType::Clause, "一"), (&NodeType::Clause, "二")]);
+        let items: Vec<&str> = article.children[0].children.iter().map(|n| n.number.as_ref()).collect();
+        assert_eq!(items, vec!["1", "1", "2"]);
+
+        // Restricting the marker set leaves the other styles as continuation text
+        let options = ParserOptions { marker_styles: vec![MarkerStyle::Paren, MarkerStyle::Dot], ..Default::default() };
+        let article = &parse_article_with_options(text, &options).children[0];
+        assert!(article.children.is_empty());
+        assert!(article.content.contains("① 初次违法的"));
+    }
+
+    #[test]
+    fn test_reference_lines_and_explicit_markers() {
+        // A line opening with a reference continues the article instead of starting 第三十条
+        let text = "第三十一条 有下列情形的，依法处理：\n第三十条第二款规定的情形；\n第二十九条、第三十条规定的其他情形。";
+        let ast = parse_article(text);
+        assert_eq!(ast.children.len(), 1);
+        assert!(ast.children[0].content.ends_with("第二十九条、第三十条规定的其他情形。"));
+
+        let text = "第五条\n第一款 国家保护网络安全。\n第二款 有下列情形之一的，从重处罚：\n第一项 拒不改正的；\n第二款规定的罚款由有关部门收缴。";
+        let options = ParserOptions { marker_styles: vec![MarkerStyle::Paren, MarkerStyle::Explicit], ..Default::default() };
+        let article = &parse_article_with_options(text, &options).children[0];
+        let clauses: Vec<(&str, &str)> = article.children.iter().map(|c| (c.number.as_ref(), c.content.as_ref())).collect();
+        assert_eq!(clauses, vec![
+            ("一", "第一款 国家保护网络安全。"),
+            ("二", "第二款 有下列情形之一的，从重处罚：\n第二款规定的罚款由有关部门收缴。"),
+        ]);
+        // 第X项 is an item of the open 款, not another clause
+        let item = &article.children[1].children[0];
+        assert_eq!((&item.node_type, item.number.as_ref(), item.content.as_ref()), (&NodeType::Item, "一", "第一项 拒不改正的；"));
+    }
+
+    #[test]
+    fn test_stem_and_ordinal_markers() {
+        let text = "第一条 下列船舶应当登记：\n甲、客船；\n乙、货船。\n第二条 登记分为两类：\n其一，初始登记；\n其二，变更登记。";
+        let options = ParserOptions {
+            marker_styles: vec![MarkerStyle::Paren, MarkerStyle::Stem, MarkerStyle::Ordinal],
+            ..Default::default()
+        };
+        let ast = parse_article_with_options(&normalize_legal_text(text), &options);
+        let clauses: Vec<Vec<(&str, &str)>> = ast.children.iter()
+            .map(|a| a.children.iter().map(|c| (c.number.as_ref(), c.content.as_ref())).collect())
+            .collect();
+        assert_eq!(clauses[0], vec![("一", "甲、客船；"), ("二", "乙、货船。")]);
+        assert_eq!(clauses[1], vec![("一", "其一，初始登记；"), ("二", "其二，变更登记。")]);
+
+        // Off by default: 甲、 lines stay in the article text
+        let article = &parse_article(text).children[0];
+        assert!(article.children.is_empty());
+        assert!(article.content.contains("乙、货船。"));
+    }
+
+    #[test]
+    fn test_implicit_clauses() {
+        let text = "第一条 网络运营者应当履行安全保护义务。\n网络运营者不得泄露个人信息。\n有下列情形之一的，从重处罚：\n（一）拒不改正的；\n违反前款规定的，依法处理。";
+        let options = ParserOptions { implicit_clauses: true, ..Default::default() };
+        let article = &parse_article_with_options(text, &options).children[0];
+
+        assert_eq!(article.content.as_ref(), "网络运营者应当履行安全保护义务。");
+        let clauses: Vec<(&str, &str)> = article.children.iter().map(|n| (n.number.as_ref(), n.content.as_ref())).collect();
+        assert_eq!(clauses, vec![
+            ("2", "网络运营者不得泄露个人信息。"),
+            ("3", "有下列情形之一的，从重处罚："),
+            ("4", "违反前款规定的，依法处理。"),
+        ]);
+        // A marked 项 stays with the paragraph that introduces it
+        let item = &article.children[1].children[0];
+        assert_eq!((&item.node_type, item.number.as_ref(), item.content.as_ref()), (&NodeType::Item, "一", "（一）拒不改正的；"));
+
+        // Off by default: paragraphs stay in the article content
+        let article = &parse_article(text).children[0];
+        assert!(article.content.contains("网络运营者不得泄露个人信息。"));
+    }
+
+    #[test]
+    fn test_parser_options() {
+        let text = "某某条例\n第一章 总则\n第一条 应当履行下列义务：（一）义务一；（二）义务二。\n第二章 附录";
+
+        let ast = parse_article(text);
+        assert_eq!(ast.children[0].node_type, NodeType::Preamble);
+        assert_eq!(ast.children.len(), 2, "empty chapter is pruned");
+        assert!(ast.children[1].children[0].children.is_empty(), "inline clauses stay inline");
+
+        let options = ParserOptions {
+            capture_preamble: false,
+            prune_empty: false,
+            split_inline_clauses: true,
+            ..Default::default()
+        };
+        let ast = parse_article_with_options(text, &options);
+        let types: Vec<&NodeType> = ast.children.iter().map(|n| &n.node_type).collect();
+        assert_eq!(types, vec![&NodeType::Chapter, &NodeType::Chapter]);
+
+        let article = &ast.children[0].children[0];
+        assert_eq!(article.content.as_ref(), "应当履行下列义务：");
+        let clauses: Vec<&str> = article.children.iter().map(|c| c.number.as_ref()).collect();
+        assert_eq!(clauses, vec!["一", "二"]);
+    }
+
+    #[test]
+    fn test_node_offsets() {
+        let text = "序言\n第一条 应当履行下列义务：\n（一）建立制度；\n第二条 内容。";
+        let ast = parse_article(text);
+        let chars: Vec<char> = text.chars().collect();
+        let slice = |node: &ArticleNode| chars[node.start_offset..node.end_offset].iter().collect::<String>();
+
+        assert_eq!(slice(&ast.children[0]), "序言");
+        assert_eq!(slice(&ast.children[1]), "第一条 应当履行下列义务：\n（一）建立制度；");
+        assert_eq!(slice(&ast.children[1].children[0]), "（一）建立制度；");
+        assert_eq!(slice(&ast.children[2]), "第二条 内容。");
+    }
+
+    #[test]
+    fn test_parse_clauses() {
+        let content = "应当履行下列义务：\n　　（一）建立管理制度；\n1.制定规程\n　　（二）采取技术措施；";
+        let clauses = parse_clauses(content, &ChineseStatute, &ParserOptions::default().marker_styles);
+        assert_eq!(clauses.len(), 2);
+        assert_eq!(clauses[0].number.as_ref(), "一");
+        assert_eq!(clauses[0].content.as_ref(), "（一）建立管理制度；\n1.制定规程");
+        assert_eq!(clauses[1].number.as_ref(), "二");
+    }
+
     #[test]
     fn test_repro_user_issue_chapter_detection() {
         // User provided raw text with full-width spaces
@@ -553,7 +1499,8 @@ mod tests {
         let article = &chapter.children[0];
         assert_eq!(article.node_type, NodeType::Article);
         assert_eq!(article.number.as_ref(), "一");
-        assert!(article.content.contains("【立法目的】"), "Content should contain title");
+        assert_eq!(article.title.as_deref(), Some("立法目的"), "Bracket title should be extracted");
+        assert!(!article.content.contains("【立法目的】"), "Title should be stripped from content");
     }
     #[test]
     fn test_parse_inline_structure_preserved() {
@@ -572,6 +1519,26 @@ mod tests {
         assert!(article.content.contains("（一）义务一"), "Content should be preserved inline");
     }
 
+    #[test]
+    fn test_parse_article_paren_title() {
+        let text = "第一条 （立法目的）为了规范管理，制定本法。\n第二条 （一）不是标题。";
+        let ast = parse_article(text);
+        assert_eq!(ast.children.len(), 2);
+
+        let art1 = &ast.children[0];
+        assert_eq!(art1.title.as_deref(), Some("立法目的"), "Paren title should be extracted");
+        assert_eq!(art1.content.as_ref(), "为了规范管理，制定本法。");
+        assert!(art1.children.is_empty(), "Title must not become a clause");
+
+        let art2 = &ast.children[1];
+        assert_eq!(art2.title, None, "Numeric paren is a clause marker, not a title");
+        assert!(art2.content.starts_with("（一）"));
+
+        for text in ["第三条 （１）全角数字不是标题。", "第三条 （12）编号不是标题。", "第三条 （见附件1）不是标题。"] {
+            assert_eq!(parse_article(text).children[0].title, None, "{}", text);
+        }
+    }
+
     #[test]
     fn test_parse_article_with_tail_clauses() {
         // Test that clauses followed by a new article are not lost
@@ -615,6 +1582,59 @@ mod tests {
         assert_eq!(match_old2.new_articles.as_ref().unwrap()[0].number.as_ref(), "三", "Old 2 should match New 3 due to similarity");
     }
 
+    #[test]
+    fn test_parse_multiple_parts() {
+        let text = "第一编 总则\n第一条 总则内容\n第二编 分则\n第一章 通则\n第二条 分则内容";
+        let ast = parse_article(text);
+
+        assert_eq!(ast.children.len(), 2, "Earlier parts must not be overwritten");
+        assert_eq!(ast.children[0].node_type, NodeType::Part);
+        assert_eq!(ast.children[0].children[0].number.as_ref(), "一", "Article directly under a part stays in it");
+        assert_eq!(ast.children[1].number.as_ref(), "二");
+        assert_eq!(ast.children[1].children[0].node_type, NodeType::Chapter);
+    }
+
+    #[test]
+    fn test_heading_titles() {
+        let text = "第一编 总则\n第一章　基本规定\n第一节：适用范围\n第一条 内容。\n第二节\n第二条 内容。";
+        let ast = parse_article(text);
+
+        let part = &ast.children[0];
+        let chapter = &part.children[0];
+        let titles: Vec<(&str, Option<&str>)> = [part, chapter, &chapter.children[0], &chapter.children[1]].iter()
+            .map(|n| (n.number.as_ref(), n.title.as_deref()))
+            .collect();
+        assert_eq!(titles, vec![("一", Some("总则")), ("一", Some("基本规定")), ("一", Some("适用范围")), ("二", None)]);
+
+        let articles = crate::diff::aligner::flatten_articles(&ast);
+        assert_eq!(articles[0].parents, vec![Arc::from("一 总则"), Arc::from("一 基本规定"), Arc::from("一 适用范围")]);
+        assert_eq!(articles[1].parents.last().map(|p| p.as_ref()), Some("二"));
+    }
+
+    #[test]
+    fn test_sub_parts() {
+        let text = "第一编 合同\n第一分编 通则\n第一章 一般规定\n第一条 内容。\n第二分编 典型合同\n第二章 买卖合同\n第二条 内容。\n第二编 人格权\n第一章 一般规定\n第三条 内容。";
+        let ast = parse_article(text);
+
+        assert_eq!(ast.children.len(), 2);
+        let part = &ast.children[0];
+        let sub_parts: Vec<(&NodeType, &str)> = part.children.iter().map(|n| (&n.node_type, n.number.as_ref())).collect();
+        assert_eq!(sub_parts, vec![(&NodeType::SubPart, "一"), (&NodeType::SubPart, "二")]);
+        assert_eq!(part.children[1].title.as_deref(), Some("典型合同"));
+        assert_eq!(part.children[1].children[0].children[0].number.as_ref(), "二");
+        // A new 编 closes the open 分编
+        assert_eq!(ast.children[1].children[0].node_type, NodeType::Chapter);
+
+        let parents = |articles: Vec<crate::models::ArticleInfo>| articles.into_iter().map(|a| a.parents).collect::<Vec<_>>();
+        let flattened = parents(crate::diff::aligner::flatten_articles(&ast));
+        assert_eq!(flattened[1], vec![Arc::from("一 合同"), Arc::from("二 典型合同"), Arc::from("二 买卖合同")]);
+        assert_eq!(flattened[2], vec![Arc::from("二 人格权"), Arc::from("一 一般规定")]);
+        assert_eq!(parents(stream_articles(text.as_bytes(), &ParserOptions::default()).collect()), flattened);
+
+        // Chapters run on across the 分编 of a 编
+        assert!(validate_numbering(&ast).is_empty());
+    }
+
     #[test]
     fn test_parse_articles_with_zero() {
         let text = r#"第二百条 内容
@@ -635,16 +1655,35 @@ mod tests {
 第二章 细则
 第一条 正式内容"#;
         let ast = parse_article(text);
-        // Expect Preamble then Article 1
+        // Expect the TOC then Article 1
         assert_eq!(ast.children.len(), 2);
-        assert_eq!(ast.children[0].node_type, NodeType::Preamble);
-        assert!(ast.children[0].content.contains("第一章"));
-        assert!(ast.children[0].content.contains("第二章"));
-        assert!(ast.children[0].content.contains("（一）"));
+        let toc = &ast.children[0];
+        assert_eq!(toc.node_type, NodeType::Toc);
+        let entries: Vec<(&str, Option<&str>, &str)> = toc.children.iter()
+            .map(|e| (e.number.as_ref(), e.title.as_deref(), e.content.as_ref()))
+            .collect();
+        assert_eq!(entries, vec![("一", Some("总则"), "章"), ("二", Some("细则"), "章")]);
+        assert_eq!(toc.content.as_ref(), "（一）第一款");
         assert_eq!(ast.children[1].node_type, NodeType::Article);
         assert_eq!(ast.children[1].number.as_ref(), "一");
     }
 
+    #[test]
+    fn test_toc_entries_nest_by_level() {
+        let text = "示例法\n目录\n第一编 总则……1\n  第一章 一般规定……1\n    第一节 适用范围……2\n  第二章 附则……5\n第一编 总则\n第一章 一般规定\n第一条 正式内容";
+        let ast = parse_article(text);
+
+        assert_eq!(ast.children[0].node_type, NodeType::Preamble);
+        assert_eq!(ast.children[0].content.as_ref(), "示例法");
+        let toc = &ast.children[1];
+        assert_eq!(toc.node_type, NodeType::Toc);
+        let part = &toc.children[0];
+        assert_eq!((part.number.as_ref(), part.title.as_deref()), ("一", Some("总则")));
+        let chapters: Vec<&str> = part.children.iter().map(|c| c.title.as_deref().unwrap()).collect();
+        assert_eq!(chapters, vec!["一般规定", "附则"]);
+        assert_eq!(part.children[0].children[0].content.as_ref(), "节");
+    }
+
     #[test]
     fn test_toc_breakout_repetition() {
         let text = r#"目 录
@@ -653,10 +1692,9 @@ mod tests {
 第一章 总则
 第一条 正式内容"#;
         let ast = parse_article(text);
-        // Expect Preamble (TOC), then Chapter 1, which contains Article 1
-        // Children: Preamble, Chapter 1
-        assert_eq!(ast.children.len(), 2, "Should have Preamble and Chapter 1");
-        assert_eq!(ast.children[0].node_type, NodeType::Preamble);
+        // Expect the TOC, then Chapter 1, which contains Article 1
+        assert_eq!(ast.children.len(), 2, "Should have TOC and Chapter 1");
+        assert_eq!(ast.children[0].node_type, NodeType::Toc);
         assert_eq!(ast.children[1].node_type, NodeType::Chapter);
         assert_eq!(ast.children[1].number.as_ref(), "一");
         assert_eq!(ast.children[1].children.len(), 1);
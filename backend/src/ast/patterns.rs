@@ -0,0 +1,162 @@
+use super::{ARTICLE_PATTERN, CHAPTER_PATTERN, CLAUSE_PATTERN, ITEM_PATTERN, PART_PATTERN, SECTION_PATTERN, SUB_PART_PATTERN};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Environment variable naming the pattern file; `patterns.toml` in the working directory
+/// is used when it is unset
+pub const PATTERNS_FILE_ENV: &str = "LAW_DIFF_PATTERNS";
+const DEFAULT_PATTERNS_FILE: &str = "patterns.toml";
+
+/// Overridable patterns: config key, static, and the capture groups the parser reads
+/// (number in group 1; articles also read the bis suffix in 2 and the text after the marker in 4)
+static PATTERNS: [(&str, &OnceLock<Regex>, usize); 7] = [
+    ("part", &PART_PATTERN, 1),
+    ("sub_part", &SUB_PART_PATTERN, 1),
+    ("chapter", &CHAPTER_PATTERN, 1),
+    ("section", &SECTION_PATTERN, 1),
+    ("article", &ARTICLE_PATTERN, 4),
+    ("clause", &CLAUSE_PATTERN, 1),
+    ("item", &ITEM_PATTERN, 1),
+];
+
+/// Operator-supplied replacements for the structural regexes, keyed like `PATTERNS`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PatternOverrides {
+    pub patterns: Vec<(String, String)>,
+}
+
+/// The keys a pattern file may set, at the top level or under `[patterns]`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternTable {
+    part: Option<String>,
+    sub_part: Option<String>,
+    chapter: Option<String>,
+    section: Option<String>,
+    article: Option<String>,
+    clause: Option<String>,
+    item: Option<String>,
+}
+
+impl PatternOverrides {
+    /// Read a TOML table of `name = 'regex'` keys, optionally under a `[patterns]` header.
+    /// Literal strings keep backslashes as written.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let mut table: toml::Table = toml::from_str(text)?;
+        if let (1, Some(toml::Value::Table(nested))) = (table.len(), table.get_mut("patterns")) {
+            table = std::mem::take(nested);
+        }
+        let table: PatternTable = table.try_into()?;
+
+        let mut overrides = Self::default();
+        let keys = [
+            ("part", table.part), ("sub_part", table.sub_part), ("chapter", table.chapter), ("section", table.section),
+            ("article", table.article), ("clause", table.clause), ("item", table.item),
+        ];
+        for (name, value) in keys {
+            if let Some(value) = value {
+                overrides.set(name, value)?;
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Load the pattern file, then apply `LAW_DIFF_<NAME>_PATTERN` variables on top
+    pub fn from_env() -> Result<Self> {
+        let mut overrides = match std::env::var(PATTERNS_FILE_ENV) {
+            Ok(path) => Self::from_file(Path::new(&path))?,
+            Err(_) if Path::new(DEFAULT_PATTERNS_FILE).exists() => Self::from_file(Path::new(DEFAULT_PATTERNS_FILE))?,
+            Err(_) => Self::default(),
+        };
+        for (name, _, _) in &PATTERNS {
+            if let Ok(value) = std::env::var(format!("LAW_DIFF_{}_PATTERN", name.to_uppercase())) {
+                overrides.set(name, value)?;
+            }
+        }
+        Ok(overrides)
+    }
+
+    fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::from_toml(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    fn set(&mut self, name: &str, value: String) -> Result<()> {
+        if !PATTERNS.iter().any(|(key, _, _)| *key == name) {
+            bail!("unknown pattern `{}` (expected one of part, sub_part, chapter, section, article, clause, item)", name);
+        }
+        self.patterns.retain(|(key, _)| key != name);
+        self.patterns.push((name.to_string(), value));
+        Ok(())
+    }
+
+    /// Compile every override and check it has the capture groups the parser reads
+    pub fn compile(&self) -> Result<Vec<(&'static OnceLock<Regex>, Regex)>> {
+        self.patterns.iter().map(|(name, source)| {
+            let Some((_, slot, groups)) = PATTERNS.iter().find(|(key, _, _)| key == name) else {
+                bail!("unknown pattern `{}`", name);
+            };
+            let regex = Regex::new(source).with_context(|| format!("invalid `{}` pattern", name))?;
+            if regex.captures_len() - 1 < *groups {
+                bail!("`{}` pattern needs at least {} capture group(s), found {}", name, groups, regex.captures_len() - 1);
+            }
+            Ok((*slot, regex))
+        }).collect()
+    }
+}
+
+/// Replace the built-in structural patterns. Must run before the first parse: a pattern
+/// that is already in use cannot be swapped out.
+pub fn install_patterns(overrides: &PatternOverrides) -> Result<usize> {
+    let compiled = overrides.compile()?;
+    let count = compiled.len();
+    for ((name, _), (slot, regex)) in overrides.patterns.iter().zip(compiled) {
+        if slot.set(regex).is_err() {
+            bail!("`{}` pattern was used before the override was installed", name);
+        }
+    }
+    Ok(count)
+}
+
+/// Install overrides from `LAW_DIFF_PATTERNS`/`patterns.toml` and the per-pattern variables;
+/// returns how many patterns were replaced
+pub fn install_patterns_from_env() -> Result<usize> {
+    install_patterns(&PatternOverrides::from_env()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_overrides_from_toml() {
+        let text = "# local regulations number articles as 第X条款\n[patterns]\narticle = '^第([一二三四五六七八九十百\\d]+)条款?(之[一二三四五六七八九十]+)?(\\s*)(.*)'\nitem = \"^(\\\\d+)\\\\)\"\n";
+        let overrides = PatternOverrides::from_toml(text).unwrap();
+        assert_eq!(overrides.patterns.len(), 2);
+        assert_eq!(overrides.patterns[1], ("item".to_string(), r"^(\d+)\)".to_string()));
+
+        let compiled = overrides.compile().unwrap();
+        let caps = compiled[0].1.captures("第十二条款 本办法自发布之日起施行。").unwrap();
+        assert_eq!((&caps[1], &caps[4]), ("十二", "本办法自发布之日起施行。"));
+
+        // Without the header, with any TOML string form
+        let overrides = PatternOverrides::from_toml("chapter = '''^第(.+)章'''  # multi-line literal\nsection = \"^\\u7B2C(.+)节\"").unwrap();
+        assert_eq!(overrides.patterns, vec![("chapter".to_string(), "^第(.+)章".to_string()), ("section".to_string(), "^第(.+)节".to_string())]);
+    }
+
+    #[test]
+    fn test_invalid_pattern_overrides() {
+        assert!(PatternOverrides::from_toml("paragraph = '^x'").is_err());
+        assert!(PatternOverrides::from_toml("article = ^x").is_err());
+        assert!(PatternOverrides::from_toml("article = 5").is_err());
+        assert!(PatternOverrides::from_toml("[patterns]\nparagraph = '^x'").unwrap_err().to_string().contains("paragraph"));
+
+        // Too few capture groups for what the parser reads, or not a regex at all
+        let overrides = PatternOverrides::from_toml("article = '^第(.+)条'").unwrap();
+        assert!(overrides.compile().unwrap_err().to_string().contains("capture group"));
+        assert!(PatternOverrides::from_toml("chapter = '^第(.+章'").unwrap().compile().is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use super::{article_label, article_title_markup, table_text, toc_entry_label};
+use crate::models::{ArticleNode, NodeType};
+
+/// Indentation for clause and item lines, as in the flattened article content
+const INDENT: &str = "\u{3000}\u{3000}";
+
+/// Serialize an AST back into normalized legal text: one heading or paragraph per line,
+/// clauses and items indented under their article. Parsing the result yields the same tree
+/// (offsets aside).
+pub fn render_text(root: &ArticleNode) -> String {
+    render(root, false)
+}
+
+/// `render_text`, except that each node's first line opens with the indentation recorded
+/// in `indent`, reproducing the layout of the submitted text
+pub fn render_text_with_indent(root: &ArticleNode) -> String {
+    render(root, true)
+}
+
+fn render(root: &ArticleNode, keep_indent: bool) -> String {
+    let mut out = String::new();
+    if root.number.as_ref() == "root" {
+        for child in &root.children {
+            render_node(child, keep_indent, &mut out);
+        }
+    } else {
+        render_node(root, keep_indent, &mut out);
+    }
+    out
+}
+
+fn render_node(node: &ArticleNode, keep_indent: bool, out: &mut String) {
+    let mut recorded = node.indent.as_deref().filter(|_| keep_indent);
+    let mut indent = |default: &'static str| recorded.take().unwrap_or(default);
+    let heading = |marker: &str| match &node.title {
+        Some(title) => format!("第{}{} {}", node.number, marker, title),
+        None => format!("第{}{}", node.number, marker),
+    };
+    let mut lines = node.content.lines().filter(|l| !l.trim().is_empty());
+    match node.node_type {
+        NodeType::Preamble => {}
+        NodeType::Part => push_line(out, indent(""), &heading("编")),
+        NodeType::SubPart => push_line(out, indent(""), &heading("分编")),
+        NodeType::Chapter => push_line(out, indent(""), &heading("章")),
+        NodeType::Section => push_line(out, indent(""), &heading("节")),
+        NodeType::Supplementary => push_line(out, indent(""), "附则"),
+        NodeType::Annex => match &node.title {
+            Some(title) => push_line(out, indent(""), &format!("{}：{}", node.number, title)),
+            None => push_line(out, indent(""), &node.number),
+        },
+        NodeType::Article => {
+            let title = node.title.as_deref().map(article_title_markup).unwrap_or_default();
+            let first = lines.next().unwrap_or("");
+            push_line(out, indent(""), &format!("{} {}{}", article_label(&node.number), title, first));
+        }
+        NodeType::Clause | NodeType::Item => {
+            for line in lines.by_ref() {
+                push_line(out, indent(INDENT), line);
+            }
+        }
+        NodeType::Toc => push_line(out, indent(""), "目录"),
+        // The label already holds the entry's text
+        NodeType::TocEntry => {
+            push_line(out, indent(INDENT), &toc_entry_label(node));
+            return;
+        }
+        NodeType::Table => {
+            for row in table_text(node).lines().chain(lines) {
+                push_line(out, indent(""), row);
+            }
+            return;
+        }
+        NodeType::TableRow | NodeType::TableCell => return,
+    }
+    for line in lines {
+        push_line(out, indent(""), line);
+    }
+    for child in &node.children {
+        render_node(child, keep_indent, out);
+    }
+}
+
+fn push_line(out: &mut String, indent: &str, line: &str) {
+    let line = line.trim();
+    if !line.is_empty() {
+        out.push_str(indent);
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article;
+
+    /// Node structure without positions
+    fn shape(node: &ArticleNode) -> String {
+        let children: Vec<String> = node.children.iter().map(shape).collect();
+        format!("{:?}|{}|{:?}|{}[{}]", node.node_type, node.number, node.title, node.content, children.join(","))
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let text = "中华人民共和国示例法\n第一章 总则\n第一条 【立法目的】为了规范管理，制定本法。\n第二条 有下列情形之一的，予以处罚：\n  （一）未经许可的；\n  （二）超出范围的。\n1. 情节轻微的\n第二章 法律责任\n第二十九条之一 本法所称主管部门，\n是指国务院有关部门。\n附则\n第三十条 本法自公布之日起施行。\n附件一：收费标准\n一、登记费十元。";
+        let ast = parse_article(text);
+        let rendered = render_text(&ast);
+
+        assert_eq!(shape(&parse_article(&rendered)), shape(&ast));
+        assert_eq!(render_text(&parse_article(&rendered)), rendered);
+        assert!(rendered.contains("第一条 （立法目的）为了规范管理，制定本法。\n"));
+        assert!(rendered.contains("\n\u{3000}\u{3000}（一）未经许可的；\n"));
+        assert!(rendered.contains("第二十九条之一 本法所称主管部门，\n是指国务院有关部门。\n"));
+
+        // Titles the parentheses cannot carry keep brackets
+        let text = "第一条 【Purpose 1】为了规范管理，制定本法。\n第二条 【关于网络运营者安全保护义务和法律责任的一般规定】内容。";
+        let ast = parse_article(text);
+        let rendered = render_text(&ast);
+        assert_eq!(shape(&parse_article(&rendered)), shape(&ast));
+        assert!(rendered.starts_with("第一条 【Purpose 1】为了规范管理"));
+    }
+
+    #[test]
+    fn test_render_toc_round_trip() {
+        let text = "中华人民共和国示例法\n目录\n第一章 总则\n第二章 附则\n第一章 总则\n第一条 为了规范管理，制定本法。\n第二章 附则\n第二条 本法自公布之日起施行。";
+        let ast = parse_article(text);
+        let rendered = render_text(&ast);
+
+        assert!(rendered.contains("目录\n\u{3000}\u{3000}第一章 总则\n\u{3000}\u{3000}第二章 附则\n第一章 总则\n"));
+        assert_eq!(shape(&parse_article(&rendered)), shape(&ast));
+    }
+
+    #[test]
+    fn test_render_with_indent() {
+        let text = "示例法\n\u{3000}\u{3000}第一条 为了规范管理，制定本法。\n第二条 有下列情形之一的，予以处罚：\n\u{3000}\u{3000}\u{3000}\u{3000}（一）未经许可的。";
+        let ast = parse_article(text);
+        assert_eq!(ast.children[1].indent.as_deref(), Some("\u{3000}\u{3000}"));
+        assert_eq!(ast.children[2].indent, None);
+        assert_eq!(render_text_with_indent(&ast), format!("示例法\n{}", &text["示例法\n".len()..]) + "\n");
+    }
+}
@@ -0,0 +1,466 @@
+use super::*;
+use crate::diff::simhash::fingerprint;
+use crate::models::{ArticleInfo, Language};
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::sync::Arc;
+
+/// Incremental parser that consumes a document line by line and yields each article as soon
+/// as the next structural marker closes it.
+///
+/// Only the article being built and the current 编/分编/章/节 labels are held in memory, so very
+/// large codes can be processed without materializing the whole line vector or the tree.
+/// The yielded units match `flatten_articles(&parse_article_with_options(..))`, except that
+/// a 附则 unit's end offset covers only its own text, not the articles under it, and its
+/// content is only the text ahead of its first article.
+/// Only Chinese grammars are streamed; `ParserOptions::language` is ignored here, and
+/// tables stay in article content as text whatever `detect_tables` says. The stream cannot
+/// look ahead for a 法释 header, so judicial interpretations need `document_type` set.
+pub struct ArticleStream<I> {
+    lines: I,
+    options: ParserOptions,
+    grammar: Arc<dyn StructureGrammar>,
+    split_lines: VecDeque<String>,
+    line_idx: usize,
+    offset: usize,
+
+    part: Option<Arc<str>>,
+    sub_part: Option<Arc<str>>,
+    chapter: Option<Arc<str>>,
+    section: Option<Arc<str>>,
+    article: Option<ArticleInfo>,
+    // Content of `article`, moved into its `Arc<str>` once the article closes
+    article_text: String,
+    clause_open: bool,
+    supplementary: Option<ArticleInfo>,
+    annex: Option<ArticleInfo>,
+    preamble: Option<ArticleInfo>,
+    // 目录 entry labels, its other lines, and its span; merged into the preamble when emitted
+    toc_entries: Vec<String>,
+    toc_lines: Vec<String>,
+    toc_span: Option<(usize, usize)>,
+
+    structure_started: bool,
+    in_toc: bool,
+    seen_markers: HashSet<String>,
+    ready: VecDeque<ArticleInfo>,
+    finished: bool,
+}
+
+/// Parent label as `flatten_articles` builds it: "一 总则", or the bare number without a title
+fn heading_label(number: &str, title: Option<Arc<str>>) -> Arc<str> {
+    match title {
+        Some(title) => format!("{} {}", number, title).into(),
+        None => number.into(),
+    }
+}
+
+/// Stream the articles of a document read from `reader`. Lines are split on `\n` alone, so
+/// the `\r` of CRLF input stays on its line and counts toward offsets as in the tree parser.
+pub fn stream_articles<R: BufRead>(reader: R, options: &ParserOptions) -> ArticleStream<impl Iterator<Item = String>> {
+    let lines = reader.split(b'\n').map_while(|line| String::from_utf8(line.ok()?).ok());
+    ArticleStream::new(lines, options)
+}
+
+impl<I: Iterator<Item = String>> ArticleStream<I> {
+    pub fn new(lines: I, options: &ParserOptions) -> Self {
+        Self {
+            lines,
+            options: options.clone(),
+            // A grammar with a parser of its own (English) needs the whole document
+            grammar: grammar_for_document(options.document_type.unwrap_or_default(), &ParserOptions { language: Language::Chinese, ..options.clone() }),
+            split_lines: VecDeque::new(),
+            line_idx: 0,
+            offset: 0,
+            part: None,
+            sub_part: None,
+            chapter: None,
+            section: None,
+            article: None,
+            article_text: String::new(),
+            clause_open: false,
+            supplementary: None,
+            annex: None,
+            preamble: None,
+            toc_entries: Vec::new(),
+            toc_lines: Vec::new(),
+            toc_span: None,
+            structure_started: false,
+            in_toc: false,
+            seen_markers: HashSet::new(),
+            ready: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Next physical line, after the same character normalization as the tree parser
+    fn next_line(&mut self) -> Option<String> {
+        if let Some(line) = self.split_lines.pop_front() {
+            return Some(line);
+        }
+        let line = normalize_traditional_markers(&self.lines.next()?);
+        if self.options.split_inline_clauses {
+            let split = get_inline_clause_pattern().replace_all(&line, "$1\n$2");
+            self.split_lines.extend(split.split('\n').map(str::to_string));
+            return self.split_lines.pop_front();
+        }
+        Some(line)
+    }
+
+    fn parents(&self) -> Vec<Arc<str>> {
+        [&self.part, &self.sub_part, &self.chapter, &self.section].into_iter().flatten().cloned().collect()
+    }
+
+    fn start_structure(&mut self) {
+        if !self.structure_started {
+            self.emit_preamble();
+        }
+        self.structure_started = true;
+        self.in_toc = false;
+    }
+
+    /// Queue the preamble with the 目录 text folded in, as `flatten_articles` builds it
+    fn emit_preamble(&mut self) {
+        let mut preamble = self.preamble.take();
+        if let Some((start, end)) = self.toc_span.take() {
+            let mut lines = vec!["目录".to_string()];
+            lines.append(&mut self.toc_entries);
+            lines.append(&mut self.toc_lines);
+            let text = lines.join("\n");
+            let unit = preamble.get_or_insert_with(|| ArticleInfo {
+                number: "0".into(),
+                content: "".into(),
+                title: Some("序言/目录".into()),
+                start_line: 1,
+                start_offset: start,
+                end_offset: end,
+                node_type: NodeType::Preamble,
+                parents: Vec::new(),
+                source: None,
+                fingerprint: 0,
+            });
+            unit.content = if unit.content.is_empty() { text.into() } else { format!("{}\n{}", unit.content, text).into() };
+            unit.start_offset = unit.start_offset.min(start);
+            unit.end_offset = unit.end_offset.max(end);
+        }
+        if let Some(preamble) = preamble.filter(|_| self.options.capture_preamble) {
+            self.ready.push_back(preamble);
+        }
+    }
+
+    fn close_article(&mut self) {
+        self.clause_open = false;
+        if let Some(mut article) = self.article.take() {
+            article.content = std::mem::take(&mut self.article_text).into();
+            self.ready.push_back(article);
+        }
+    }
+
+    fn close_supplementary(&mut self) {
+        if let Some(mut unit) = self.supplementary.take() {
+            if !unit.content.trim().is_empty() {
+                unit.content = unit.content.trim().into();
+                self.ready.push_back(unit);
+            }
+        }
+    }
+
+    fn append_to_article(&mut self, text: &str, indent: bool, end: usize) {
+        if let Some(article) = self.article.as_mut() {
+            let content = &mut self.article_text;
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            if indent {
+                content.push_str("\u{3000}\u{3000}");
+            }
+            content.push_str(text);
+            article.end_offset = end;
+        }
+    }
+
+    fn unit(&self, node_type: NodeType, number: Arc<str>, title: Option<Arc<str>>, span: (usize, usize)) -> ArticleInfo {
+        ArticleInfo {
+            number,
+            content: "".into(),
+            title,
+            start_line: self.line_idx,
+            start_offset: span.0,
+            end_offset: span.1,
+            node_type,
+            parents: self.parents(),
+            source: None,
+            fingerprint: 0,
+        }
+    }
+
+    fn process_line(&mut self, line: &str) {
+        let grammar = Arc::clone(&self.grammar);
+        self.line_idx += 1;
+        let lead = line.chars().take_while(|c| c.is_whitespace()).count();
+        let span = (self.offset + lead, self.offset + lead + line.trim().chars().count());
+        self.offset += line.chars().count() + 1;
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return;
+        }
+
+        if self.options.detect_toc && !self.structure_started && (trimmed.contains("目录") || trimmed == "目 录") {
+            self.in_toc = true;
+        }
+
+        if self.structure_started {
+            if let Some(caps) = grammar.annex_pattern().captures(trimmed) {
+                self.close_article();
+                self.close_supplementary();
+                if let Some(annex) = self.annex.take() {
+                    self.ready.push_back(annex);
+                }
+                self.part = None;
+                self.sub_part = None;
+                self.chapter = None;
+                self.section = None;
+                let title = caps.get(2).map(|m| m.as_str().trim()).filter(|t| !t.is_empty()).map(|t| t.into());
+                self.annex = Some(self.unit(NodeType::Annex, format!("附件{}", &caps[1]).into(), title, span));
+                return;
+            }
+            if let Some(annex) = self.annex.as_mut() {
+                annex.content = if annex.content.is_empty() {
+                    trimmed.into()
+                } else {
+                    format!("{}\n{}", annex.content, trimmed).into()
+                };
+                annex.end_offset = span.1;
+                return;
+            }
+
+            if grammar.supplementary_pattern().is_match(trimmed) {
+                self.close_article();
+                self.close_supplementary();
+                self.chapter = None;
+                self.section = None;
+                self.supplementary = Some(self.unit(NodeType::Supplementary, "附则".into(), None, span));
+                self.chapter = Some("附则".into());
+                return;
+            }
+        }
+
+        if let Some(caps) = grammar.article_pattern().captures(trimmed) {
+            let after_marker = caps.get(4).map(|m| m.as_str()).unwrap_or("");
+            let should_breakout = !self.in_toc || !is_likely_toc_entry(line, grammar.as_ref());
+            if !is_reference_tail(after_marker) && should_breakout {
+                self.start_structure();
+                self.close_article();
+                // 附则 text ahead of its first article is complete once an article starts
+                self.close_supplementary();
+
+                let (title, mut body) = split_article_title(after_marker.trim());
+                if self.options.keep_title_in_content {
+                    body = after_marker.trim();
+                }
+                let number: Arc<str> = match caps.get(2) {
+                    Some(bis) => format!("{}{}", &caps[1], bis.as_str()).into(),
+                    None => caps[1].into(),
+                };
+                let article = self.unit(NodeType::Article, number, title.map(|t| t.into()), span);
+                self.article_text = body.to_string();
+                self.article = Some(article);
+                return;
+            }
+        }
+
+        if self.in_toc {
+            let marker = if let Some(caps) = grammar.chapter_pattern().captures(trimmed) {
+                format!("CH_{}", &caps[1])
+            } else if let Some(caps) = grammar.section_pattern().captures(trimmed) {
+                format!("SEC_{}", &caps[1])
+            } else if let Some(caps) = grammar.part_pattern().captures(trimmed) {
+                format!("PART_{}", &caps[1])
+            } else if let Some(caps) = grammar.sub_part_pattern().captures(trimmed) {
+                format!("SUBPART_{}", &caps[1])
+            } else {
+                String::new()
+            };
+            if !marker.is_empty() {
+                let is_high_level = marker.starts_with("CH_") || marker.starts_with("PART_") || marker.starts_with("SUBPART_");
+                if (is_high_level && self.seen_markers.contains(&marker)) || !is_likely_toc_entry(line, grammar.as_ref()) {
+                    self.in_toc = false;
+                } else {
+                    self.seen_markers.insert(marker);
+                }
+            }
+        }
+
+        if !self.in_toc {
+            if let Some(caps) = grammar.part_pattern().captures(trimmed) {
+                self.start_structure();
+                self.close_article();
+                self.close_supplementary();
+                self.part = Some(heading_label(&caps[1], heading_title(trimmed, &caps)));
+                self.sub_part = None;
+                self.chapter = None;
+                self.section = None;
+                return;
+            }
+
+            if let Some(caps) = grammar.sub_part_pattern().captures(trimmed) {
+                self.start_structure();
+                self.close_article();
+                self.close_supplementary();
+                self.sub_part = Some(heading_label(&caps[1], heading_title(trimmed, &caps)));
+                self.chapter = None;
+                self.section = None;
+                return;
+            }
+
+            if let Some(caps) = grammar.chapter_pattern().captures(trimmed) {
+                let after_marker = &trimmed[caps.get(0).unwrap().end()..];
+                if !is_reference_tail(after_marker) {
+                    self.start_structure();
+                    self.close_article();
+                    self.close_supplementary();
+                    self.chapter = Some(heading_label(&caps[1], heading_title(trimmed, &caps)));
+                    self.section = None;
+                    return;
+                }
+            }
+
+            if let Some(caps) = grammar.section_pattern().captures(trimmed) {
+                self.start_structure();
+                self.close_article();
+                self.section = Some(heading_label(&caps[1], heading_title(trimmed, &caps)));
+                return;
+            }
+
+            if let Some((full_marker, _)) = grammar.clause_marker(trimmed, &self.options.marker_styles) {
+                let after_marker = &trimmed[full_marker.len()..];
+                if !after_marker.starts_with("规定") && !after_marker.starts_with("之") {
+                    self.start_structure();
+                    self.clause_open = true;
+                    self.append_to_article(&format!("{}{}", full_marker, after_marker.trim()), true, span.1);
+                    return;
+                }
+            }
+
+            if let Some((full_marker, _)) = grammar.item_marker(trimmed, &self.options.marker_styles) {
+                let after_marker = &trimmed[full_marker.len()..];
+                self.append_to_article(&format!("{}{}", full_marker, after_marker.trim()), true, span.1);
+                return;
+            }
+        }
+
+        // Content continuation; 目录 lines are held apart as the tree's Toc node is
+        if !self.structure_started && self.in_toc {
+            let toc_span = self.toc_span.get_or_insert(span);
+            toc_span.1 = span.1;
+            if let Some(entry) = toc_entry(trimmed, self.line_idx - 1, span, grammar.as_ref()) {
+                self.toc_entries.push(toc_entry_label(&entry));
+            } else if !(trimmed.contains("目录") || trimmed == "目 录") {
+                self.toc_lines.push(trimmed.to_string());
+            }
+        } else if !self.structure_started {
+            let preamble = self.preamble.get_or_insert_with(|| ArticleInfo {
+                number: "0".into(),
+                content: "".into(),
+                title: Some("序言/目录".into()),
+                start_line: 1,
+                start_offset: span.0,
+                end_offset: span.1,
+                node_type: NodeType::Preamble,
+                parents: Vec::new(),
+                source: None,
+                fingerprint: 0,
+            });
+            preamble.content = if preamble.content.is_empty() {
+                trimmed.into()
+            } else {
+                format!("{}\n{}", preamble.content, trimmed).into()
+            };
+            preamble.end_offset = span.1;
+        } else if self.article.is_some() {
+            // Implicit 款 are Clause children, which the flattened content indents
+            self.clause_open |= self.options.implicit_clauses;
+            self.append_to_article(trimmed, self.options.implicit_clauses, span.1);
+        } else if let Some(unit) = self.supplementary.as_mut().filter(|_| !self.clause_open) {
+            unit.content = format!("{}\n{}", unit.content, trimmed).into();
+            unit.end_offset = span.1;
+        }
+    }
+
+    fn finish(&mut self) {
+        self.close_article();
+        self.close_supplementary();
+        if let Some(annex) = self.annex.take() {
+            self.ready.push_back(annex);
+        }
+        if !self.structure_started {
+            self.emit_preamble();
+        }
+        self.finished = true;
+    }
+}
+
+impl<I: Iterator<Item = String>> Iterator for ArticleStream<I> {
+    type Item = ArticleInfo;
+
+    fn next(&mut self) -> Option<ArticleInfo> {
+        loop {
+            if let Some(mut unit) = self.ready.pop_front() {
+                // Content is complete only once the unit is closed
+                unit.fingerprint = fingerprint(&unit.content);
+                return Some(unit);
+            }
+            if self.finished {
+                return None;
+            }
+            match self.next_line() {
+                Some(line) => self.process_line(&line),
+                None => self.finish(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::flatten_articles;
+
+    fn assert_matches_tree(text: &str, options: &ParserOptions) {
+        let streamed: Vec<ArticleInfo> = stream_articles(text.as_bytes(), options).collect();
+        let flattened = flatten_articles(&parse_article_with_options(text, options));
+        assert_eq!(streamed.len(), flattened.len());
+        for (s, f) in streamed.iter().zip(&flattened) {
+            assert_eq!(
+                (&s.number, &s.content, &s.title, s.start_line, s.start_offset, s.end_offset, &s.node_type, &s.parents, s.fingerprint),
+                (&f.number, &f.content, &f.title, f.start_line, f.start_offset, f.end_offset, &f.node_type, &f.parents, f.fingerprint),
+            );
+        }
+    }
+
+    #[test]
+    fn test_stream_matches_tree_parser() {
+        let text = "中华人民共和国示例法\n目录\n  第一章 总则\n  第二章 附则\n第一章 总则\n第一节 一般规定\n第一条 【立法目的】为了规范管理，制定本法。\n第二条 有下列情形之一的，予以处罚：\n（一）未经许可的；\n（二）超出范围的。\n1. 情节轻微的\n第二节 特别规定\n第三条 本法所称主管部门，\n是指国务院有关部门。\n第二章 附则\n第四条 本法自公布之日起施行。\n附件一：收费标准\n一、登记费十元。";
+        assert_matches_tree(text, &ParserOptions::default());
+        assert_matches_tree(text, &ParserOptions { capture_preamble: false, keep_title_in_content: true, ..Default::default() });
+        assert_matches_tree(text, &ParserOptions { implicit_clauses: true, ..Default::default() });
+        assert_matches_tree("第一条 下列情形：（一）甲；（二）乙。\n第二条 其他。", &ParserOptions { split_inline_clauses: true, ..Default::default() });
+
+        // Bare headings, and an article whose text starts on the line after its heading
+        let text = "第一章\n总则\n第一条\n为了规范管理，制定本法。\n第一节\n第二条 乙。\n第二章\n第三条 丙。";
+        assert_matches_tree(text, &ParserOptions::default());
+        assert_matches_tree(text, &ParserOptions { implicit_clauses: true, ..Default::default() });
+        // CRLF line endings
+        let text = "中华人民共和国示例法\n目录\n  第一章 总则\n第一章 总则\n第一条 甲。\n第二条 乙：\n（一）丙。";
+        assert_matches_tree(&text.replace('\n', "\r\n"), &ParserOptions::default());
+    }
+
+    #[test]
+    fn test_stream_yields_before_input_is_exhausted() {
+        let lines = vec!["第一条 甲。".to_string(), "第二条 乙。".to_string()].into_iter()
+            .chain(std::iter::once_with(|| panic!("read past the second article")));
+        let mut stream = ArticleStream::new(lines, &ParserOptions::default());
+        assert_eq!(stream.next().unwrap().number.as_ref(), "一");
+    }
+}
@@ -0,0 +1,242 @@
+use crate::models::{ArticleNode, NodeType};
+use regex::Regex;
+use std::sync::{Arc, OnceLock};
+
+/// Widest cell accepted in a whitespace-aligned table; longer runs are prose with double spaces
+const MAX_ALIGNED_CELL_CHARS: usize = 20;
+
+static ALIGNED_COLUMN_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_aligned_column_pattern() -> &'static Regex {
+    ALIGNED_COLUMN_PATTERN.get_or_init(|| Regex::new(r"\t+|[ \u{3000}]{2,}").unwrap())
+}
+
+/// A table found in a block of text: its rows of cells and the line range it occupied
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+    pub first_line: usize, // Index into the scanned lines
+    pub line_count: usize, // Lines covered, separator rows included
+    pub row_lines: Vec<usize>, // Index into the scanned lines of each row
+}
+
+enum TableLine {
+    Row(Vec<String>, bool), // Cells; whether they came from ｜/| separators
+    Separator,              // Markdown-style |---|---| rule
+}
+
+fn classify(line: &str) -> Option<TableLine> {
+    let trimmed = line.trim();
+    if trimmed.contains(['｜', '|']) {
+        let mut cells: Vec<&str> = trimmed.split(['｜', '|']).map(str::trim).collect();
+        // "| a | b |" has empty cells outside the outer bars
+        if cells.first() == Some(&"") {
+            cells.remove(0);
+        }
+        if cells.last() == Some(&"") {
+            cells.pop();
+        }
+        if cells.len() < 2 {
+            return None;
+        }
+        if cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| "-:=—－".contains(ch))) {
+            return Some(TableLine::Separator);
+        }
+        return Some(TableLine::Row(cells.into_iter().map(String::from).collect(), true));
+    }
+    let cells: Vec<&str> = get_aligned_column_pattern().split(trimmed).collect();
+    if cells.len() >= 2 && cells.iter().all(|c| c.chars().count() <= MAX_ALIGNED_CELL_CHARS) {
+        return Some(TableLine::Row(cells.into_iter().map(String::from).collect(), false));
+    }
+    None
+}
+
+/// Find tables in a block of text: runs of at least two consecutive rows split by ｜ or |,
+/// or aligned into the same number of columns by tabs or runs of spaces.
+pub fn parse_tables(content: &str) -> Vec<Table> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tables = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(TableLine::Row(first, piped)) = classify(lines[i]) else {
+            i += 1;
+            continue;
+        };
+        let mut rows = vec![first];
+        let mut row_lines = vec![i];
+        let mut end = i + 1;
+        while end < lines.len() {
+            match classify(lines[end]) {
+                Some(TableLine::Separator) if piped => {}
+                // Aligned columns only hold together while the column count does
+                Some(TableLine::Row(cells, row_piped)) if row_piped == piped && (piped || cells.len() == rows[0].len()) => {
+                    rows.push(cells);
+                    row_lines.push(end);
+                }
+                _ => break,
+            }
+            end += 1;
+        }
+        if rows.len() >= 2 {
+            tables.push(Table { rows, first_line: i, line_count: end - i, row_lines });
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    tables
+}
+
+/// One table row per line, cells separated by ｜
+pub fn table_text(table: &ArticleNode) -> String {
+    table.children.iter()
+        .map(|row| row.children.iter().map(|cell| cell.content.as_ref()).collect::<Vec<_>>().join("｜"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Move tables out of article, clause, item and annex content into Table child nodes
+/// (rows, then cells), placed ahead of the node's other children. The text up to the first
+/// table stays the node's content; the text following a table becomes that table's content,
+/// so the blocks keep their document order. Rows and cells are located in `lines`, the text
+/// given to the parser, whose `spans` give their char offsets.
+pub(super) fn extract_tables(node: &mut ArticleNode, lines: &[&str], spans: &[(usize, usize)]) {
+    for child in &mut node.children {
+        extract_tables(child, lines, spans);
+    }
+    if !matches!(node.node_type, NodeType::Article | NodeType::Clause | NodeType::Item | NodeType::Annex) {
+        return;
+    }
+    let tables = parse_tables(&node.content);
+    if tables.is_empty() {
+        return;
+    }
+
+    let content_lines: Vec<&str> = node.content.lines().collect();
+    let block = |from: usize, to: usize| -> Arc<str> { content_lines[from..to].join("\n").trim_end().into() };
+    let mut nodes = Vec::new();
+    // Source lines are searched from the node's own first line onwards
+    let mut source_idx = node.start_line.saturating_sub(1);
+    for (table_idx, table) in tables.iter().enumerate() {
+        let rows: Vec<ArticleNode> = table.rows.iter().zip(&table.row_lines).enumerate().map(|(row_idx, (cells, &row_line))| {
+            let row_text = content_lines[row_line].trim();
+            let found = (source_idx..lines.len()).find(|&i| lines[i].trim() == row_text);
+            if let Some(i) = found {
+                source_idx = i + 1;
+            }
+            let line_idx = found.unwrap_or(node.start_line.saturating_sub(1));
+            let span = found.and_then(|i| spans.get(i).copied()).unwrap_or((node.start_offset, node.end_offset));
+            let line = found.map_or("", |i| lines[i].trim());
+
+            let cells = cells.iter().zip(cell_spans(line, cells, span)).enumerate()
+                .map(|(cell_idx, (cell, cell_span))| table_node(NodeType::TableCell, cell_idx, cell.as_str().into(), Vec::new(), line_idx, cell_span))
+                .collect();
+            table_node(NodeType::TableRow, row_idx, "".into(), cells, line_idx, span)
+        }).collect();
+
+        let next = tables.get(table_idx + 1).map_or(content_lines.len(), |t| t.first_line);
+        let trailing = block(table.first_line + table.line_count, next);
+        let (first, last) = (&rows[0], &rows[rows.len() - 1]);
+        let (line_idx, span) = (first.start_line - 1, (first.start_offset, last.end_offset));
+        nodes.push(table_node(NodeType::Table, table_idx, trailing, rows, line_idx, span));
+    }
+
+    node.content = block(0, tables[0].first_line);
+    node.children.splice(0..0, nodes);
+}
+
+/// Char offsets of each cell within its row's source line, `line` trimmed and starting at
+/// `span.0`; a cell not found there gets the whole line
+fn cell_spans(line: &str, cells: &[String], span: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut cursor = 0;
+    cells.iter().map(|cell| match line[cursor..].find(cell.as_str()) {
+        Some(pos) => {
+            let start = span.0 + line[..cursor + pos].chars().count();
+            cursor += pos + cell.len();
+            (start, start + cell.chars().count())
+        }
+        None => span,
+    }).collect()
+}
+
+fn table_node(node_type: NodeType, idx: usize, content: Arc<str>, children: Vec<ArticleNode>, line_idx: usize, span: (usize, usize)) -> ArticleNode {
+    ArticleNode {
+        node_type,
+        number: (idx + 1).to_string().into(),
+        title: None,
+        content,
+        children,
+        start_line: line_idx + 1,
+        start_offset: span.0,
+        end_offset: span.1,
+        indent: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article_with_options;
+    use crate::models::ParserOptions;
+
+    #[test]
+    fn test_parse_tables() {
+        let content = "罚款标准如下：\n| 违法行为 | 罚款 |\n|---|---|\n| 未登记 | 一万元 |\n| 逾期 | 五千元 |\n其他情形另行规定。";
+        let tables = parse_tables(content);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].rows, vec![vec!["违法行为", "罚款"], vec!["未登记", "一万元"], vec!["逾期", "五千元"]]);
+        assert_eq!((tables[0].first_line, tables[0].line_count), (1, 4));
+        assert_eq!(tables[0].row_lines, vec![1, 3, 4]);
+
+        let aligned = parse_tables("等级    罚款\n一级    一万元\n二级    五千元");
+        assert_eq!(aligned[0].rows.len(), 3);
+
+        // A single row, or prose with a double space, is not a table
+        assert!(parse_tables("违法行为｜罚款\n正文内容。").is_empty());
+        assert!(parse_tables("本法所称网络运营者，是指网络的所有者、管理者和网络服务提供者。  其他规定。\n第二行").is_empty());
+    }
+
+    #[test]
+    fn test_table_nodes() {
+        let text = "第一条 违反本法的，按下表处罚：\n违法行为｜罚款\n未登记｜一万元\n逾期｜五千元\n第二条 本法自公布之日起施行。";
+        let options = ParserOptions { detect_tables: true, ..Default::default() };
+        let ast = parse_article_with_options(text, &options);
+
+        let article = &ast.children[0];
+        assert_eq!(article.content.as_ref(), "违反本法的，按下表处罚：");
+        let table = &article.children[0];
+        assert_eq!(table.node_type, NodeType::Table);
+        assert_eq!(table.children.len(), 3);
+        assert_eq!(table.children[1].children[1].content.as_ref(), "一万元");
+        assert_eq!(table.start_line, 2);
+        assert_eq!(table_text(table), "违法行为｜罚款\n未登记｜一万元\n逾期｜五千元");
+        assert_eq!(&text.chars().skip(table.start_offset).take(table.end_offset - table.start_offset).collect::<String>(),
+                   "违法行为｜罚款\n未登记｜一万元\n逾期｜五千元");
+
+        // Off by default
+        assert!(crate::ast::parse_article(text).children[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_table_keeps_document_order() {
+        let text = "第一条 违反本法的，按下表处罚：\n| 违法行为 | 罚款 |\n|---|---|\n| 未登记 | 一万元 |\n情节严重的，加倍处罚。\n第二条 本法自公布之日起施行。";
+        let options = ParserOptions { detect_tables: true, ..Default::default() };
+        let ast = parse_article_with_options(text, &options);
+
+        // The text after the table follows it rather than joining the text before it
+        let article = &ast.children[0];
+        assert_eq!(article.content.as_ref(), "违反本法的，按下表处罚：");
+        let table = &article.children[0];
+        assert_eq!(table.content.as_ref(), "情节严重的，加倍处罚。");
+        assert!(crate::ast::render_text(&ast).contains("未登记｜一万元\n情节严重的，加倍处罚。\n"));
+
+        // Each row and cell points at its own text
+        let source = |node: &ArticleNode| text.chars().skip(node.start_offset).take(node.end_offset - node.start_offset).collect::<String>();
+        let row = &table.children[1];
+        assert_eq!(row.start_line, 4);
+        assert_eq!(source(row), "| 未登记 | 一万元 |");
+        assert_eq!(source(&row.children[0]), "未登记");
+        assert_eq!(source(&row.children[1]), "一万元");
+        assert_eq!(source(&table.children[0].children[1]), "罚款");
+    }
+}
@@ -0,0 +1,259 @@
+use super::article_label;
+use crate::models::{ArticleNode, NodeType, NumberingFinding, NumberingIssue, TocFinding, TocIssue};
+use crate::nlp::numerals::{article_sort_key, int_to_chinese};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Check that articles run 第一条, 第二条, … through the whole document and that 编, 分编, 章 and 节
+/// count up from 一 within their parent, reporting gaps, duplicates and out-of-order numbers.
+/// Bis articles (第五条之一) must follow their base article in sequence.
+pub fn validate_numbering(root: &ArticleNode) -> Vec<NumberingFinding> {
+    let mut findings = Vec::new();
+    let mut articles = Vec::new();
+    collect_articles(root, &mut articles);
+    check_sequence(&articles, &mut findings);
+    check_headings(root, &mut findings);
+    findings.sort_by_key(|f| f.start_line);
+    findings
+}
+
+fn collect_articles<'a>(node: &'a ArticleNode, out: &mut Vec<&'a ArticleNode>) {
+    for child in &node.children {
+        if child.node_type == NodeType::Article {
+            out.push(child);
+        } else {
+            collect_articles(child, out);
+        }
+    }
+}
+
+/// Chapters within each part (or the document), sections within each chapter, parts overall.
+/// 分编 count up within their part, while chapters run on across the 分编 of a part.
+fn check_headings(node: &ArticleNode, findings: &mut Vec<NumberingFinding>) {
+    for level in [NodeType::Part, NodeType::SubPart, NodeType::Chapter, NodeType::Section] {
+        if node.node_type == NodeType::SubPart && level == NodeType::Chapter {
+            continue;
+        }
+        let siblings: Vec<&ArticleNode> = node.children.iter()
+            .flat_map(|c| match c.node_type {
+                NodeType::SubPart if level == NodeType::Chapter => c.children.iter().filter(|g| g.node_type == level).collect(),
+                _ if c.node_type == level => vec![c],
+                _ => Vec::new(),
+            })
+            .collect();
+        check_sequence(&siblings, findings);
+    }
+    for child in &node.children {
+        if matches!(child.node_type, NodeType::Part | NodeType::SubPart | NodeType::Chapter | NodeType::Section | NodeType::Supplementary) {
+            check_headings(child, findings);
+        }
+    }
+}
+
+fn check_sequence(nodes: &[&ArticleNode], findings: &mut Vec<NumberingFinding>) {
+    let mut seen = Vec::new();
+    let mut prev: (usize, usize) = (0, 0);
+    for node in nodes {
+        let key = article_sort_key(&node.number);
+        let finding = |kind: NumberingIssue, expected: Option<(usize, usize)>| {
+            let label = label(&node.node_type, &node.number);
+            let expected_label = expected.map(|e| label_like(node, e));
+            let message = match (&kind, &expected_label) {
+                (NumberingIssue::Gap, Some(expected)) => format!("{} follows a gap: expected {}", label, expected),
+                (NumberingIssue::Duplicate, _) => format!("{} appears more than once", label),
+                (_, Some(expected)) => format!("{} is out of order: expected {}", label, expected),
+                (_, None) => format!("{} is out of order", label),
+            };
+            NumberingFinding {
+                kind,
+                node_type: node.node_type.clone(),
+                number: node.number.clone(),
+                expected: expected_label.map(Into::into),
+                start_line: node.start_line,
+                message,
+            }
+        };
+
+        let next_base = (prev.0 + 1, 0);
+        if seen.contains(&key) {
+            findings.push(finding(NumberingIssue::Duplicate, None));
+            continue;
+        }
+        if key <= prev {
+            findings.push(finding(NumberingIssue::OutOfOrder, Some(if prev.1 > 0 { (prev.0, prev.1 + 1) } else { next_base })));
+        } else if key.1 == 0 && key.0 > next_base.0 {
+            findings.push(finding(NumberingIssue::Gap, Some(next_base)));
+        } else if key.1 > 0 && key.0 != prev.0 {
+            // A bis article without its base article right before it
+            findings.push(finding(NumberingIssue::Gap, Some((key.0, 0))));
+        } else if key.1 > prev.1 + 1 {
+            findings.push(finding(NumberingIssue::Gap, Some((key.0, prev.1 + 1))));
+        }
+        seen.push(key);
+        prev = prev.max(key);
+    }
+}
+
+fn label(node_type: &NodeType, number: &str) -> String {
+    match node_type {
+        NodeType::Article => article_label(number),
+        NodeType::Part => format!("第{}编", number),
+        NodeType::SubPart => format!("第{}分编", number),
+        NodeType::Chapter => format!("第{}章", number),
+        _ => format!("第{}节", number),
+    }
+}
+
+/// Label for `key` written with the same numerals (Chinese or arabic) as `node`
+fn label_like(node: &ArticleNode, key: (usize, usize)) -> String {
+    let numeral = |n: usize| if node.number.starts_with(|c: char| c.is_ascii_digit()) { n.to_string() } else { int_to_chinese(n) };
+    let number = match key {
+        (base, 0) => numeral(base),
+        (base, sub) => format!("{}之{}", numeral(base), int_to_chinese(sub)),
+    };
+    label(&node.node_type, &number)
+}
+
+/// A 编/分编/章/节 heading keyed by its numbers from the top down, at the levels the 目录 lists
+struct Heading<'a> {
+    path: Vec<(&'static str, (usize, usize))>,
+    level: &'static str,
+    node: &'a ArticleNode,
+}
+
+fn heading_level(node: &ArticleNode) -> Option<&'static str> {
+    match (&node.node_type, node.content.as_ref()) {
+        (NodeType::Part, _) | (NodeType::TocEntry, "编") => Some("编"),
+        (NodeType::SubPart, _) | (NodeType::TocEntry, "分编") => Some("分编"),
+        (NodeType::Chapter, _) | (NodeType::TocEntry, "章") => Some("章"),
+        (NodeType::Section, _) | (NodeType::TocEntry, "节") => Some("节"),
+        _ => None,
+    }
+}
+
+fn collect_headings<'a>(
+    nodes: &'a [ArticleNode],
+    levels: &HashSet<&str>,
+    path: &[(&'static str, (usize, usize))],
+    out: &mut Vec<Heading<'a>>,
+) {
+    for node in nodes {
+        let Some(level) = heading_level(node) else {
+            continue;
+        };
+        let mut path = path.to_vec();
+        if levels.contains(level) {
+            path.push((level, article_sort_key(&node.number)));
+            out.push(Heading { path: path.clone(), level, node });
+        }
+        collect_headings(&node.children, levels, &path, out);
+    }
+}
+
+/// Cross-check the 目录 against the parsed 编/分编/章/节 headings: entries with no heading in the
+/// body, body headings the 目录 leaves out, and titles that differ. Only the levels the 目录
+/// lists are compared, so a 目录 of chapters alone ignores sections.
+pub fn check_toc(root: &ArticleNode) -> Vec<TocFinding> {
+    let Some(toc) = root.children.iter().find(|n| n.node_type == NodeType::Toc) else {
+        return Vec::new();
+    };
+    let all_levels: HashSet<&str> = ["编", "分编", "章", "节"].into_iter().collect();
+    let mut listed = Vec::new();
+    collect_headings(&toc.children, &all_levels, &[], &mut listed);
+    let levels: HashSet<&str> = listed.iter().map(|h| h.level).collect();
+    let mut body = Vec::new();
+    collect_headings(&root.children, &levels, &[], &mut body);
+
+    let title = |node: &ArticleNode| node.title.as_ref().map(|t| t.chars().filter(|c| !c.is_whitespace()).collect::<String>());
+    let finding = |kind: TocIssue, heading: &Heading, toc_title: Option<Arc<str>>, body_title: Option<Arc<str>>| {
+        let label = format!("第{}{}", heading.node.number, heading.level);
+        let message = match kind {
+            TocIssue::Missing => format!("目录 lists {} but the body has no such heading", label),
+            TocIssue::Unlisted => format!("{} is missing from the 目录", label),
+            TocIssue::TitleMismatch => format!(
+                "{} is titled \"{}\" in the 目录 but \"{}\" in the body",
+                label, toc_title.as_deref().unwrap_or(""), body_title.as_deref().unwrap_or("")
+            ),
+        };
+        TocFinding {
+            kind,
+            level: heading.level.into(),
+            number: heading.node.number.clone(),
+            toc_title,
+            body_title,
+            start_line: heading.node.start_line,
+            message,
+        }
+    };
+
+    let mut findings = Vec::new();
+    for entry in &listed {
+        match body.iter().find(|h| h.path == entry.path) {
+            None => findings.push(finding(TocIssue::Missing, entry, entry.node.title.clone(), None)),
+            Some(heading) if title(heading.node) != title(entry.node) => findings.push(finding(
+                TocIssue::TitleMismatch, heading, entry.node.title.clone(), heading.node.title.clone(),
+            )),
+            Some(_) => {}
+        }
+    }
+    for heading in body.iter().filter(|h| !listed.iter().any(|entry| entry.path == h.path)) {
+        findings.push(finding(TocIssue::Unlisted, heading, None, heading.node.title.clone()));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article;
+
+    fn kinds(text: &str) -> Vec<(NumberingIssue, String, Option<String>)> {
+        validate_numbering(&parse_article(text)).into_iter()
+            .map(|f| (f.kind, f.number.to_string(), f.expected.map(|e| e.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_numbering() {
+        let text = "第一章 总则\n第一条 内容。\n第二条 内容。\n第二条之一 内容。\n第二章 附则\n第一节 一般规定\n第三条 内容。";
+        assert!(validate_numbering(&parse_article(text)).is_empty());
+    }
+
+    #[test]
+    fn test_numbering_findings() {
+        let text = "第一章 总则\n第一条 内容。\n第十二条 内容。\n第十四条 内容。\n第十四条 重复。\n第十三条 内容。\n第三章 附则\n第十五条之二 内容。";
+        assert_eq!(kinds(text), vec![
+            (NumberingIssue::Gap, "十二".into(), Some("第二条".into())),
+            (NumberingIssue::Gap, "十四".into(), Some("第十三条".into())),
+            (NumberingIssue::Duplicate, "十四".into(), None),
+            (NumberingIssue::OutOfOrder, "十三".into(), Some("第十五条".into())),
+            (NumberingIssue::Gap, "三".into(), Some("第二章".into())),
+            (NumberingIssue::Gap, "十五之二".into(), Some("第十五条".into())),
+        ]);
+
+        let findings = validate_numbering(&parse_article("第1条 内容。\n第3条 内容。"));
+        assert_eq!(findings[0].message, "第3条 follows a gap: expected 第2条");
+        assert_eq!(findings[0].start_line, 2);
+    }
+
+    #[test]
+    fn test_check_toc() {
+        let text = "目录\n第一章 总则……1\n第一节 适用范围……1\n第二章 法律责任……3\n第三章 附则……5\n\
+                    第一章 总则\n第一节 适用范围\n第一条 内容。\n第二节 定义\n第二条 内容。\n第二章 罚则\n第三条 内容。";
+        let findings = check_toc(&parse_article(text));
+        let summary: Vec<(TocIssue, &str, &str)> = findings.iter()
+            .map(|f| (f.kind, f.level.as_ref(), f.number.as_ref()))
+            .collect();
+        assert_eq!(summary, vec![
+            (TocIssue::TitleMismatch, "章", "二"),
+            (TocIssue::Missing, "章", "三"),
+            (TocIssue::Unlisted, "节", "二"),
+        ]);
+        assert_eq!(findings[0].message, "第二章 is titled \"法律责任\" in the 目录 but \"罚则\" in the body");
+        assert_eq!(findings[2].start_line, 9);
+
+        // A 目录 that matches, or none at all
+        assert!(check_toc(&parse_article("目录\n第一章 总则……1\n第一章 总则\n第一条 内容。")).is_empty());
+        assert!(check_toc(&parse_article("第一章 总则\n第一条 内容。")).is_empty());
+    }
+}
@@ -1,48 +1,69 @@
-use crate::ast::parse_article;
-use crate::diff::similarity::calculate_composite_similarity;
-use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, ArticleNode, NodeType, SimilarityScore};
+use crate::ast::{article_label, check_toc, grammar_for, parse_article_with_options, parse_clauses, table_text, toc_text, StructureGrammar};
+use crate::diff::assignment::max_weight_assignment;
+use crate::diff::budget::Budget;
+use crate::diff::cache::{article_key, config_key, CachedMatrix, MatrixCache, CACHE_MIN_CELLS};
+use crate::diff::conditions::annotate_scope_changes;
+use crate::diff::ids::assign_article_change_ids;
+use crate::diff::minhash::{candidate_pairs, signature};
+use crate::diff::moves::{annotate_moves, position_of};
+use crate::diff::references::annotate_reference_shifts;
+use crate::diff::structure::diff_structure;
+use crate::diff::tables::annotate_table_changes;
+use crate::diff::unit_diff;
+use crate::diff::values::annotate_value_changes;
+use crate::diff::simhash::{clearly_unrelated, fingerprint};
+use crate::diff::similarity::{calculate_char_similarity, calculate_composite_similarity_with, SimilarityConfig, Terms};
+use crate::models::{
+    AlignmentProgress, AlignmentQuality, AlignmentStrategy, ArticleAnchor, ArticleChange, ArticleChangeType, ArticleInfo, ArticleNode, ChangeOrder, ChapterStats, CompareOptions, DiffAlgorithm, DiffStats, Granularity, MarkerStyle, NodeType, PartResult, PreambleMode, ProgressSink,
+    RunnerUp, RunnerUpSide, SimilarityPair, SimilarityScore, SourcePosition, StructureChange,
+};
 use crate::nlp::tokenizer::tokenize_to_set;
-use crate::nlp::formatter::normalize_legal_text;
+use crate::nlp::formatter::{attach_deferred_article_content, clean_pdf_artifacts, normalize_with_stages, source_line_map, strip_cosmetic, strip_format};
+use crate::nlp::opencc::convert_script;
+use crate::nlp::numerals::article_sort_key;
 use rayon::prelude::*;
-use std::collections::HashSet;
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 // Base thresholds - will be adjusted by user input
 const EXACT_MATCH_THRESHOLD: f32 = 1.0;
 const MEDIUM_SIMILARITY_THRESHOLD: f32 = 0.4;
-
-fn chinese_to_int(s: &str) -> usize {
-    if s == "root" { return 0; }
-    if s == "0" || s.is_empty() { return 0; }
-
-    let mut result = 0;
-    let mut temp = 0;
-
-    let mut mapping = std::collections::HashMap::new();
-    mapping.insert('零', 0); mapping.insert('一', 1); mapping.insert('二', 2); mapping.insert('两', 2);
-    mapping.insert('三', 3); mapping.insert('四', 4); mapping.insert('五', 5); mapping.insert('六', 6);
-    mapping.insert('七', 7); mapping.insert('八', 8); mapping.insert('九', 9); mapping.insert('十', 10);
-    mapping.insert('百', 100); mapping.insert('千', 1000); mapping.insert('万', 10000);
-
-    for c in s.chars() {
-        if let Some(&v) = mapping.get(&c) {
-            if v >= 10 {
-                if temp == 0 { temp = 1; }
-                if v == 10000 {
-                    result = (result + temp) * 10000;
-                    temp = 0;
-                } else {
-                    result += temp * v;
-                    temp = 0;
-                }
-            } else {
-                temp = temp * 10 + v;
-            }
-        } else if let Some(d) = c.to_digit(10) {
-            temp = temp * 10 + d as usize;
-        }
-    }
-    result + temp
+/// Share of the source's tokens the fragments must cover jointly for a split or merge
+const FRAGMENT_COVERAGE_THRESHOLD: f32 = 0.5;
+/// Share of the source's tokens a fragment must newly cover to join the set
+const FRAGMENT_MIN_GAIN: f32 = 0.05;
+/// Containment at which a short fragment is a candidate despite a low composite score
+const FRAGMENT_CONTAINMENT_THRESHOLD: f32 = 0.8;
+/// Score margin (over the threshold, or over the runner-up) below which a match needs review
+const REVIEW_MARGIN: f32 = 0.1;
+/// Closest counterparts suggested for each Added or Deleted article
+const NEAREST_CANDIDATES: usize = 2;
+/// Share of `align_threshold` two provisions of different laws must score to correspond
+const CROSS_LAW_THRESHOLD_SCALE: f32 = 0.5;
+
+/// Warning code emitted when alignment fell back to number-only matching
+pub const DEGRADED_ALIGNMENT_WARNING: &str = "degraded-alignment";
+/// Warning code emitted for articles that parsed with no content
+pub const EMPTY_ARTICLE_WARNING: &str = "empty-article";
+/// Warning code emitted when a 目录 lists headings the body does not have, or vice versa
+pub const TOC_MISMATCH_WARNING: &str = "toc-mismatch";
+/// Warning code emitted for a manual anchor naming an article missing from either text
+pub const UNMATCHED_ANCHOR_WARNING: &str = "unmatched-anchor";
+/// Warning code emitted when the time budget ran out or the request was cancelled
+pub const TRUNCATED_ALIGNMENT_WARNING: &str = "truncated-alignment";
+
+/// Article changes plus non-fatal warnings raised while aligning
+#[derive(Debug, Default)]
+pub struct AlignmentResult {
+    pub changes: Vec<ArticleChange>,
+    pub warnings: Vec<String>,
+    pub structure_changes: Vec<StructureChange>, // Heading-level tree diff
+    pub chapter_stats: Vec<ChapterStats>,        // Article changes per 章
+    pub truncated: bool,                         // Stages were skipped for lack of time; changes are partial
+    pub preamble_changes: Vec<ArticleChange>,    // Preambles aligned apart (`PreambleMode::Separate`)
+    pub quality: AlignmentQuality,
 }
 
 /// Main function to perform intelligent structural alignment of legal articles
@@ -52,85 +73,691 @@ pub fn align_articles(
     threshold: f32,
     format_text: bool
 ) -> Vec<ArticleChange> {
+    let options = CompareOptions {
+        align_threshold: threshold,
+        format_text,
+        ..Default::default()
+    };
+    align_articles_with_options(old_text, new_text, &options).changes
+}
+
+/// Normalize text for parsing, applying the optional content-recovery heuristics
+fn prepare_text(text: &str, options: &CompareOptions) -> String {
+    let text = convert_script(text, options);
+    let normalized = if options.clean_pdf_artifacts {
+        normalize_with_stages(&clean_pdf_artifacts(&text), &options.normalize_stages)
+    } else {
+        normalize_with_stages(&text, &options.normalize_stages)
+    };
+    if options.attach_deferred_content {
+        attach_deferred_article_content(&normalized)
+    } else {
+        normalized
+    }
+}
+
+/// A compared text prepared and parsed once, with its lines located in the submitted text
+pub struct PreparedText {
+    pub ast: ArticleNode,
+    source_map: Vec<SourcePosition>,
+}
+
+/// Prepare and parse `text` as the aligner does, for callers that reuse the tree
+/// (`align_prepared`, `align_parts`, metadata extraction)
+pub fn prepare_document(text: &str, options: &CompareOptions) -> PreparedText {
     // Always normalize for AST parsing robustness
-    let processed_old = normalize_legal_text(old_text);
-    let processed_new = normalize_legal_text(new_text);
+    let processed = prepare_text(text, options);
+    PreparedText {
+        ast: parse_article_with_options(&processed, &options.parser),
+        // Script conversion is one character for one, so the converted text lines up with the submitted one
+        source_map: source_line_map(&convert_script(text, options), &processed),
+    }
+}
+
+/// The tree `prepare_document` builds, without locating its lines; for callers that only
+/// read the structure (metadata)
+pub fn parse_prepared(text: &str, options: &CompareOptions) -> ArticleNode {
+    parse_article_with_options(&prepare_text(text, options), &options.parser)
+}
+
+/// Structural alignment driven by the full set of request options
+pub fn align_articles_with_options(
+    old_text: &str,
+    new_text: &str,
+    options: &CompareOptions,
+) -> AlignmentResult {
+    align_prepared(&prepare_document(old_text, options), &prepare_document(new_text, options), options, &Budget::start(options))
+}
+
+/// Structural alignment of two texts from `prepare_document`, within `budget`
+pub fn align_prepared(old: &PreparedText, new: &PreparedText, options: &CompareOptions, budget: &Budget) -> AlignmentResult {
+    align_trees(&old.ast, &new.ast, &old.source_map, &new.source_map, options, budget)
+}
+
+/// Structural alignment of two already parsed documents, e.g. the `ast` of /api/parse
+/// results. Without the submitted texts, articles carry no source positions.
+pub fn align_parsed(
+    old_ast: &ArticleNode,
+    new_ast: &ArticleNode,
+    options: &CompareOptions,
+) -> AlignmentResult {
+    align_trees(old_ast, new_ast, &[], &[], options, &Budget::start(options))
+}
+
+/// Align the articles of two parsed documents, `old_map`/`new_map` locating their lines in
+/// the submitted texts
+fn align_trees(
+    old_ast: &ArticleNode,
+    new_ast: &ArticleNode,
+    old_map: &[SourcePosition],
+    new_map: &[SourcePosition],
+    options: &CompareOptions,
+    budget: &Budget,
+) -> AlignmentResult {
+    let mut old_articles = flatten_articles(old_ast);
+    let mut new_articles = flatten_articles(new_ast);
+    attach_source_positions(&mut old_articles, old_map);
+    attach_source_positions(&mut new_articles, new_map);
+    let (old_articles, old_preambles) = split_preambles(old_articles, options.preamble);
+    let (new_articles, new_preambles) = split_preambles(new_articles, options.preamble);
+    options.progress.report(AlignmentProgress::Parsed { old_articles: old_articles.len(), new_articles: new_articles.len() });
+
+    let mut result = align_flattened(&old_articles, &new_articles, options, budget, &options.progress);
+    // Only the article alignment feeds the progress events; a second matrix would restart them
+    if options.preamble == PreambleMode::Separate {
+        result.preamble_changes = align_flattened(&old_preambles, &new_preambles, options, budget, &ProgressSink::default()).changes;
+    }
+    result.structure_changes = diff_structure(old_ast, new_ast, &result.changes);
+    result.chapter_stats = chapter_stats(&result.changes, old_ast, new_ast);
+    for (side, ast) in [("old", old_ast), ("new", new_ast)] {
+        result.warnings.extend(check_toc(ast).into_iter().map(|f| format!("{}: {} in the {} text", TOC_MISMATCH_WARNING, f.message, side)));
+    }
+    result
+}
+
+/// Take the preambles out of `articles` unless they align with the rest: returns the
+/// remaining articles and, for `PreambleMode::Separate`, the preambles
+fn split_preambles(articles: Vec<ArticleInfo>, mode: PreambleMode) -> (Vec<ArticleInfo>, Vec<ArticleInfo>) {
+    if mode == PreambleMode::Align {
+        return (articles, Vec::new());
+    }
+    let (preambles, rest): (Vec<_>, Vec<_>) = articles.into_iter().partition(|a| a.node_type == NodeType::Preamble);
+    (rest, if mode == PreambleMode::Separate { preambles } else { Vec::new() })
+}
+
+/// Record where each article starts in the submitted text; `start_line` counts lines of
+/// the prepared text the articles were parsed from
+fn attach_source_positions(articles: &mut [ArticleInfo], map: &[SourcePosition]) {
+    for article in articles {
+        article.source = article.start_line.checked_sub(1).and_then(|idx| map.get(idx)).copied();
+    }
+}
+
+/// Every article pair whose composite similarity reaches `options.similarity_floor`,
+/// with all score components, strongest first. Scores come from the same matrix the
+/// aligner uses, so they include the hierarchy boost.
+pub fn similarity_pairs(
+    old_text: &str,
+    new_text: &str,
+    options: &CompareOptions,
+) -> Vec<SimilarityPair> {
+    let old_articles = flatten_articles(&parse_article_with_options(&prepare_text(old_text, options), &options.parser));
+    let new_articles = flatten_articles(&parse_article_with_options(&prepare_text(new_text, options), &options.parser));
+
+    let config = SimilarityConfig::from_options(options);
+    let matrix = build_similarity_matrix(&old_articles, &new_articles, &config, options.lsh_min_cells, &Budget::default(), &ProgressSink::default());
+
+    let mut pairs: Vec<SimilarityPair> = Vec::new();
+    for (old_idx, row) in matrix.iter().enumerate() {
+        for (new_idx, score) in row.iter().enumerate() {
+            if score.composite >= options.similarity_floor {
+                pairs.push(SimilarityPair {
+                    old_number: old_articles[old_idx].number.clone(),
+                    new_number: new_articles[new_idx].number.clone(),
+                    score: score.clone(),
+                });
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.score.composite.total_cmp(&a.score.composite));
+    pairs
+}
+
+/// Compare each top-level Part (编) independently, e.g. 总则 against 总则 and 分则 against 分则.
+/// Parts are paired by number; a Part present on only one side is reported as fully added/deleted.
+/// Progress is not reported: the events describe the whole-document alignment.
+pub fn align_parts(
+    old: &PreparedText,
+    new: &PreparedText,
+    options: &CompareOptions,
+    budget: &Budget,
+) -> Vec<PartResult> {
+    let top_parts = |root: &ArticleNode| -> Vec<ArticleNode> {
+        root.children.iter().filter(|n| n.node_type == NodeType::Part).cloned().collect()
+    };
+    let old_parts = top_parts(&old.ast);
+    let new_parts = top_parts(&new.ast);
+
+    // New-document order first, then parts that only exist in the old document
+    let mut part_numbers: Vec<Arc<str>> = new_parts.iter().map(|p| p.number.clone()).collect();
+    for part in &old_parts {
+        if !part_numbers.contains(&part.number) {
+            part_numbers.push(part.number.clone());
+        }
+    }
+
+    part_numbers.into_iter().map(|number| {
+        let old_part = old_parts.iter().find(|p| p.number == number);
+        let new_part = new_parts.iter().find(|p| p.number == number);
+
+        let mut old_articles = old_part.map(flatten_articles).unwrap_or_default();
+        let mut new_articles = new_part.map(flatten_articles).unwrap_or_default();
+        attach_source_positions(&mut old_articles, &old.source_map);
+        attach_source_positions(&mut new_articles, &new.source_map);
+        let article_changes = align_flattened(&old_articles, &new_articles, options, budget, &ProgressSink::default()).changes;
+
+        PartResult {
+            title: new_part.or(old_part).and_then(|p| p.title.clone()),
+            number,
+            similarity: average_similarity(&article_changes),
+            stats: article_change_stats(&article_changes),
+            article_changes,
+        }
+    }).collect()
+}
+
+/// Average similarity over all article changes (unmatched articles count as 0)
+pub fn average_similarity(changes: &[ArticleChange]) -> f32 {
+    if changes.is_empty() {
+        return 0.0;
+    }
+    let total_sim: f32 = changes.iter().map(|c| c.similarity.unwrap_or(0.0)).sum();
+    total_sim / changes.len() as f32
+}
+
+/// Number of article changes of each type
+pub fn count_by_type(changes: &[ArticleChange]) -> BTreeMap<ArticleChangeType, usize> {
+    let mut counts = BTreeMap::new();
+    for change in changes {
+        *counts.entry(change.change_type.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Record the chapter ("第三章 法律责任") of every article by its start line
+fn collect_chapters(node: &ArticleNode, chapter: Option<&Arc<str>>, out: &mut HashMap<usize, Arc<str>>) {
+    for child in &node.children {
+        match child.node_type {
+            NodeType::Chapter => {
+                let label: Arc<str> = match &child.title {
+                    Some(title) => format!("第{}章 {}", child.number, title).into(),
+                    None => format!("第{}章", child.number).into(),
+                };
+                collect_chapters(child, Some(&label), out);
+            }
+            NodeType::Article => {
+                if let Some(chapter) = chapter {
+                    out.insert(child.start_line, chapter.clone());
+                }
+            }
+            _ => collect_chapters(child, chapter, out),
+        }
+    }
+}
 
-    // 1. Parse and flatten articles
-    let old_ast = parse_article(&processed_old);
-    let new_ast = parse_article(&processed_new);
+/// Count article changes per chapter, in the order chapters first appear among the changes.
+/// A change belongs to its new article's chapter, a deletion to the old one's; articles
+/// outside any chapter are not counted.
+pub fn chapter_stats(changes: &[ArticleChange], old_ast: &ArticleNode, new_ast: &ArticleNode) -> Vec<ChapterStats> {
+    let (mut old_chapters, mut new_chapters) = (HashMap::new(), HashMap::new());
+    collect_chapters(old_ast, None, &mut old_chapters);
+    collect_chapters(new_ast, None, &mut new_chapters);
+
+    let mut stats: Vec<ChapterStats> = Vec::new();
+    for change in changes {
+        let chapter = match change.new_articles.as_ref().and_then(|list| list.first()) {
+            Some(new_art) => new_chapters.get(&new_art.start_line),
+            None => change.old_article.as_ref().and_then(|old_art| old_chapters.get(&old_art.start_line)),
+        };
+        let Some(chapter) = chapter else {
+            continue;
+        };
+        let entry = match stats.iter().position(|s| &s.chapter == chapter) {
+            Some(idx) => &mut stats[idx],
+            None => {
+                stats.push(ChapterStats { chapter: chapter.clone(), counts: BTreeMap::new() });
+                stats.last_mut().unwrap()
+            }
+        };
+        *entry.counts.entry(change.change_type.clone()).or_insert(0) += 1;
+    }
+    stats
+}
 
-    let old_articles = flatten_articles(&old_ast);
-    let new_articles = flatten_articles(&new_ast);
+/// Summarize article changes into added/deleted/modified/unchanged counts
+pub fn article_change_stats(changes: &[ArticleChange]) -> DiffStats {
+    let mut stats = DiffStats::default();
+    for change in changes {
+        match change.change_type {
+            ArticleChangeType::Added => stats.additions += 1,
+            ArticleChangeType::Deleted => stats.deletions += 1,
+            ArticleChangeType::Unchanged => stats.unchanged += 1,
+            ArticleChangeType::Preamble => {}
+            _ => stats.modifications += 1,
+        }
+    }
+    stats.articles_by_type = count_by_type(changes);
+    stats
+}
 
+/// Run the multi-stage alignment over already flattened article lists, reporting matrix and
+/// stage progress to `progress`. Once `budget` runs out, the remaining stages and the
+/// per-change annotations are skipped and the result is flagged truncated.
+fn align_flattened(
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    options: &CompareOptions,
+    budget: &Budget,
+    progress: &ProgressSink,
+) -> AlignmentResult {
+    let mut result = AlignmentResult::default();
     if old_articles.is_empty() && new_articles.is_empty() {
-        return Vec::new();
+        return result;
+    }
+
+    // Empty articles score 0 against everything and end up as Deleted/Added pairs
+    for (side, articles) in [("old", old_articles), ("new", new_articles)] {
+        for article in articles.iter().filter(|a| a.node_type == NodeType::Article && a.content.trim().is_empty()) {
+            result.warnings.push(format!(
+                "{}: {} in the {} text has no content",
+                EMPTY_ARTICLE_WARNING, article_label(&article.number), side
+            ));
+        }
+    }
+
+    for anchor in &options.anchors {
+        let (old_idx, new_idx) = (find_anchored(old_articles, &anchor.old_number), find_anchored(new_articles, &anchor.new_number));
+        if old_idx.is_none() || new_idx.is_none() {
+            result.warnings.push(format!(
+                "{}: anchor {} -> {} names an article missing from the {} text",
+                UNMATCHED_ANCHOR_WARNING, anchor.old_number, anchor.new_number, if old_idx.is_none() { "old" } else { "new" }
+            ));
+        }
+    }
+
+    // Guard against quadratic blow-up: fall back to number-only alignment for huge inputs
+    let cells = old_articles.len().saturating_mul(new_articles.len());
+    if cells > options.max_matrix_cells {
+        tracing::warn!(
+            "Similarity matrix {}x{} exceeds {} cells, degrading to number-only alignment",
+            old_articles.len(), new_articles.len(), options.max_matrix_cells
+        );
+        result.warnings.push(format!(
+            "{}: {}x{} article pairs exceed the {} cell limit; articles were aligned by number only",
+            DEGRADED_ALIGNMENT_WARNING, old_articles.len(), new_articles.len(), options.max_matrix_cells
+        ));
+        result.changes = align_by_number(old_articles, new_articles);
+    } else {
+        (result.changes, result.quality) = align_by_similarity(old_articles, new_articles, options, budget, progress);
+    }
+    measure_matches(&mut result.quality, &result.changes, old_articles.len() + new_articles.len());
+
+    // 5. Sort by document order
+    sort_changes(&mut result.changes);
+
+    annotate_reference_shifts(&mut result.changes, old_articles, new_articles);
+    annotate_scope_changes(&mut result.changes);
+    annotate_value_changes(&mut result.changes);
+    annotate_cosmetic_changes(&mut result.changes, options);
+    annotate_moves(&mut result.changes, old_articles, new_articles);
+    if !budget.exhausted() {
+        annotate_details(&mut result.changes, options.granularity, options.algorithm);
+
+        if options.compute_churn {
+            annotate_churn(&mut result.changes);
+        }
+
+        if options.clause_alignment {
+            annotate_clause_changes(&mut result.changes, options, budget);
+        }
+    }
+
+    if options.parser.detect_tables {
+        annotate_table_changes(&mut result.changes);
+    }
+
+    if options.simplify_change_types {
+        simplify_change_types(&mut result.changes);
+    }
+
+    assign_article_change_ids(&mut result.changes);
+    order_changes(&mut result.changes, options.sort_by);
+
+    if budget.tripped() {
+        result.truncated = true;
+        result.warnings.push(format!(
+            "{}: alignment stopped early ({}); unmatched articles are reported as added or deleted",
+            TRUNCATED_ALIGNMENT_WARNING,
+            if options.cancel.is_cancelled() { "cancelled".to_string() } else { format!("time budget of {} ms", options.time_budget_ms.unwrap_or_default()) }
+        ));
+    }
+    result
+}
+
+/// Fill in the matched ratio and average match similarity of `changes` over `total_articles`
+/// old and new articles
+fn measure_matches(quality: &mut AlignmentQuality, changes: &[ArticleChange], total_articles: usize) {
+    let matched: Vec<&ArticleChange> = changes.iter()
+        .filter(|c| !matches!(c.change_type, ArticleChangeType::Added | ArticleChangeType::Deleted))
+        .collect();
+
+    // Split and merge fragments appear in several changes; count each article once
+    let (mut old_lines, mut new_lines) = (HashSet::new(), HashSet::new());
+    for change in &matched {
+        old_lines.extend(change.old_article.iter().chain(change.merged_from.iter().flatten()).map(|a| a.start_line));
+        new_lines.extend(change.new_articles.iter().flatten().map(|a| a.start_line));
+    }
+    if total_articles > 0 {
+        quality.matched_ratio = (old_lines.len() + new_lines.len()) as f32 / total_articles as f32;
     }
 
+    let scores: Vec<f32> = matched.iter().filter_map(|c| c.similarity).collect();
+    if !scores.is_empty() {
+        quality.average_match_similarity = scores.iter().sum::<f32>() / scores.len() as f32;
+    }
+}
+
+/// Multi-stage similarity alignment (matrix, sequential matches, splits/merges, leftovers),
+/// reporting matrix progress and the changes found after each stage to `progress`. Also
+/// returns the matrix sparsity and how many number matches fell below the threshold.
+fn align_by_similarity(
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    options: &CompareOptions,
+    budget: &Budget,
+    progress: &ProgressSink,
+) -> (Vec<ArticleChange>, AlignmentQuality) {
+    let threshold = options.align_threshold;
+    let mut quality = AlignmentQuality::default();
+
     // 2. Build similarity matrix
-    let similarity_matrix = build_similarity_matrix(&old_articles, &new_articles);
+    let config = SimilarityConfig::from_options(options);
+    let mut similarity_matrix = build_similarity_matrix(old_articles, new_articles, &config, options.lsh_min_cells, budget, progress);
+    let cells = old_articles.len() * new_articles.len();
+    if cells > 0 {
+        let weak = similarity_matrix.iter().flatten().filter(|s| s.composite < MEDIUM_SIMILARITY_THRESHOLD).count();
+        quality.matrix_sparsity = Some(weak as f32 / cells as f32);
+    }
 
     // 3. Perform multi-stage alignment
     let mut changes = Vec::new();
     let mut used_old = vec![false; old_articles.len()];
     let mut used_new = vec![false; new_articles.len()];
 
+    // Stage 0: Lock the matches the user asserted, on a copy when the cache shares the matrix
+    if !options.anchors.is_empty() {
+        lock_anchors(
+            old_articles,
+            new_articles,
+            &options.anchors,
+            Arc::make_mut(&mut similarity_matrix).as_mut_slice(),
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+        );
+    }
+    if options.cross_law {
+        for change in &mut changes {
+            change.change_type = ArticleChangeType::Corresponding;
+            change.tags = vec!["corresponding".to_string(), "anchored".to_string()];
+        }
+    }
+
     // Stage 1: Find high-confidence 1:1 matches (Similarity takes precedence for renumbering)
-    find_one_to_one_matches(
-        &old_articles,
-        &new_articles,
-        &similarity_matrix,
-        &mut used_old,
-        &mut used_new,
-        &mut changes,
-        threshold,
-    );
+    let stage_one_threshold = if options.cross_law { threshold * CROSS_LAW_THRESHOLD_SCALE } else { threshold };
+    match options.alignment_strategy {
+        _ if budget.exhausted() => {}
+        _ if options.cross_law => find_corresponding_provisions(
+            old_articles,
+            new_articles,
+            &similarity_matrix,
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+            stage_one_threshold,
+        ),
+        AlignmentStrategy::Heuristic => find_one_to_one_matches(
+            old_articles,
+            new_articles,
+            &similarity_matrix,
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+            threshold,
+        ),
+        AlignmentStrategy::Optimal => find_optimal_matches(
+            old_articles,
+            new_articles,
+            &similarity_matrix,
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+            threshold,
+        ),
+    }
+    progress.report(AlignmentProgress::Stage { name: "one-to-one", matches: changes.len() });
+
+    // Stages 2 and 3 assume one law amended into the next, which does not hold across laws
+    let amendment = !options.cross_law;
 
     // Stage 2: Perfect number matches (as fallback for items similarity didn't catch)
-    find_number_matches(
-        &old_articles,
-        &new_articles,
-        &similarity_matrix,
-        &mut used_old,
-        &mut used_new,
-        &mut changes,
-    );
+    if amendment && !budget.exhausted() {
+        let before = changes.len();
+        find_number_matches(
+            old_articles,
+            new_articles,
+            &similarity_matrix,
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+        );
+        quality.forced_number_matches = changes[before..].iter().filter(|c| c.similarity.is_some_and(|s| s < threshold)).count();
+    }
+    progress.report(AlignmentProgress::Stage { name: "numbers", matches: changes.len() });
 
     // Stage 2: Detect split patterns (1:N)
-    detect_splits(
-        &old_articles,
-        &new_articles,
-        &similarity_matrix,
-        &mut used_old,
-        &mut used_new,
-        &mut changes,
-    );
+    if amendment && !budget.exhausted() {
+        detect_splits(
+            old_articles,
+            new_articles,
+            &similarity_matrix,
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+        );
+    }
+    progress.report(AlignmentProgress::Stage { name: "splits", matches: changes.len() });
 
     // Stage 3: Detect merge patterns (N:1)
-    detect_merges(
-        &old_articles,
-        &new_articles,
-        &similarity_matrix,
-        &mut used_old,
-        &mut used_new,
-        &mut changes,
-    );
+    if amendment && !budget.exhausted() {
+        detect_merges(
+            old_articles,
+            new_articles,
+            &similarity_matrix,
+            &mut used_old,
+            &mut used_new,
+            &mut changes,
+            options.consolidate_merges,
+        );
+    }
+    progress.report(AlignmentProgress::Stage { name: "merges", matches: changes.len() });
 
     // Stage 4: Handle remaining articles
     handle_remaining_articles(
-        &old_articles,
-        &new_articles,
+        old_articles,
+        new_articles,
         &used_old,
         &used_new,
         &mut changes,
     );
 
-    // 5. Sort by document order
+    // Runner-ups and nearest counterparts from a truncated matrix would be guesses
+    if !budget.tripped() {
+        flag_ambiguous_matches(&mut changes, old_articles, new_articles, &similarity_matrix, stage_one_threshold);
+        suggest_nearest(&mut changes, old_articles, new_articles, &similarity_matrix);
+    }
+    (changes, quality)
+}
+
+/// Attach to each Added or Deleted article the `NEAREST_CANDIDATES` best-scoring articles of
+/// the other text, matched or not, so a reviewer spotting a heavy rewrite can anchor the pair
+fn suggest_nearest(
+    changes: &mut [ArticleChange],
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    similarity_matrix: &[Vec<SimilarityScore>],
+) {
+    for change in changes.iter_mut() {
+        // (side, index on that side, score) of every counterpart
+        let mut candidates: Vec<(RunnerUpSide, usize, f32)> = match change.change_type {
+            ArticleChangeType::Deleted => {
+                let Some(old_idx) = change.old_article.as_ref().and_then(|a| position_of(old_articles, a)) else { continue };
+                similarity_matrix[old_idx].iter().enumerate().map(|(j, score)| (RunnerUpSide::New, j, score.composite)).collect()
+            }
+            ArticleChangeType::Added => {
+                let Some(new_idx) = change.new_articles.as_ref().and_then(|list| list.first()).and_then(|a| position_of(new_articles, a)) else { continue };
+                similarity_matrix.iter().enumerate().map(|(i, row)| (RunnerUpSide::Old, i, row[new_idx].composite)).collect()
+            }
+            _ => continue,
+        };
+        candidates.retain(|&(_, _, s)| s > 0.0);
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        candidates.truncate(NEAREST_CANDIDATES);
+        if candidates.is_empty() {
+            continue;
+        }
+
+        change.nearest = Some(candidates.into_iter().map(|(side, idx, similarity)| RunnerUp {
+            side,
+            article: match side {
+                RunnerUpSide::Old => old_articles[idx].clone(),
+                RunnerUpSide::New => new_articles[idx].clone(),
+            },
+            similarity,
+        }).collect());
+    }
+}
+
+/// Mark 1:1 matches that scored within `REVIEW_MARGIN` of the threshold or of the best
+/// competing pair sharing one of their articles, with that competitor as the runner-up.
+/// Anchored matches were asserted by the user and are left alone.
+fn flag_ambiguous_matches(
+    changes: &mut [ArticleChange],
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    similarity_matrix: &[Vec<SimilarityScore>],
+    threshold: f32,
+) {
+    for change in changes.iter_mut() {
+        if change.change_type == ArticleChangeType::Preamble || change.tags.iter().any(|t| t == "anchored") {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().filter(|list| list.len() == 1).and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+        let (Some(old_idx), Some(new_idx)) = (position_of(old_articles, old_art), position_of(new_articles, new_art)) else {
+            continue;
+        };
+
+        let score = similarity_matrix[old_idx][new_idx].composite;
+        let other_new = (0..new_articles.len()).filter(|&j| j != new_idx)
+            .map(|j| (RunnerUpSide::New, j, similarity_matrix[old_idx][j].composite));
+        let other_old = (0..old_articles.len()).filter(|&i| i != old_idx)
+            .map(|i| (RunnerUpSide::Old, i, similarity_matrix[i][new_idx].composite));
+        let runner_up = other_new.chain(other_old)
+            .filter(|&(_, _, s)| s > 0.0)
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        let close_call = runner_up.is_some_and(|(_, _, s)| score - s < REVIEW_MARGIN);
+        if score >= threshold + REVIEW_MARGIN && !close_call {
+            continue;
+        }
+
+        change.needs_review = true;
+        change.runner_up = runner_up.map(|(side, idx, similarity)| RunnerUp {
+            side,
+            article: match side {
+                RunnerUpSide::Old => old_articles[idx].clone(),
+                RunnerUpSide::New => new_articles[idx].clone(),
+            },
+            similarity,
+        });
+    }
+}
+
+/// Identity of an article number: numeric for 第X条 (so 第5条 == 第五条), verbatim otherwise
+type NumberKey = ((usize, usize), Option<Arc<str>>);
+
+fn number_key(article: &ArticleInfo) -> NumberKey {
+    if article.node_type == NodeType::Article {
+        (article_sort_key(&article.number), None)
+    } else {
+        ((0, 0), Some(article.number.clone()))
+    }
+}
+
+/// Degraded alignment: pair articles purely by their parsed number, without a similarity matrix
+fn align_by_number(old_articles: &[ArticleInfo], new_articles: &[ArticleInfo]) -> Vec<ArticleChange> {
+    let mut changes = Vec::new();
+    let mut used_old = vec![false; old_articles.len()];
+    let mut used_new = vec![false; new_articles.len()];
+
+    let new_index: HashMap<NumberKey, usize> = new_articles.iter().enumerate()
+        .map(|(idx, art)| (number_key(art), idx))
+        .collect();
+
+    for (old_idx, old_art) in old_articles.iter().enumerate() {
+        let Some(&new_idx) = new_index.get(&number_key(old_art)) else { continue };
+        if used_new[new_idx] {
+            continue;
+        }
+        let new_art = &new_articles[new_idx];
+
+        let (change_type, similarity, tags) = if old_art.node_type == NodeType::Preamble {
+            (ArticleChangeType::Preamble, None, vec!["preamble".to_string()])
+        } else if old_art.content == new_art.content {
+            (ArticleChangeType::Unchanged, Some(1.0), Vec::new())
+        } else {
+            let score = calculate_char_similarity(&old_art.content, &new_art.content);
+            (ArticleChangeType::Modified, Some(score), vec!["modified".to_string()])
+        };
+
+        changes.push(ArticleChange {
+            change_type,
+            old_article: Some(old_art.clone()),
+            new_articles: Some(vec![new_art.clone()]),
+            similarity,
+            tags,
+            ..Default::default()
+        });
+        used_old[old_idx] = true;
+        used_new[new_idx] = true;
+    }
+
+    handle_remaining_articles(old_articles, new_articles, &used_old, &used_new, &mut changes);
+    changes
+}
+
+/// Order changes by document position: preamble first, then article number, then line
+fn sort_changes(changes: &mut [ArticleChange]) {
     changes.sort_by(|a, b| {
         let is_preamble = |c: &ArticleChange| {
             c.change_type == ArticleChangeType::Preamble ||
-            c.new_articles.as_ref().map_or(false, |list| list.iter().any(|a| a.node_type == NodeType::Preamble)) ||
-            c.old_article.as_ref().map_or(false, |a| a.node_type == NodeType::Preamble)
+            c.new_articles.as_ref().is_some_and(|list| list.iter().any(|a| a.node_type == NodeType::Preamble)) ||
+            c.old_article.as_ref().is_some_and(|a| a.node_type == NodeType::Preamble)
         };
 
         // 1. Preamble always first
@@ -140,23 +767,29 @@ pub fn align_articles(
             return if pa { std::cmp::Ordering::Less } else { std::cmp::Ordering::Greater };
         }
 
+        // Annexes close the document; 附则 text, 款 and 项 fall back to line order
+        let sort_key = |a: &ArticleInfo| match a.node_type {
+            NodeType::Annex => (usize::MAX - 1, 0),
+            NodeType::Supplementary | NodeType::Clause | NodeType::Item => (0, 0),
+            _ => article_sort_key(&a.number),
+        };
         let get_sort_info = |c: &ArticleChange| {
             if let Some(new_list) = &c.new_articles {
                 if let Some(first) = new_list.first() {
-                    return (chinese_to_int(&first.number), first.start_line, 0);
+                    return (sort_key(first), first.start_line, 0);
                 }
             }
             if let Some(old) = &c.old_article {
-                return (chinese_to_int(&old.number), old.start_line, 1);
+                return (sort_key(old), old.start_line, 1);
             }
-            (usize::MAX, usize::MAX, 2)
+            ((usize::MAX, 0), usize::MAX, 2)
         };
 
         let (num_a, line_a, src_a) = get_sort_info(a);
         let (num_b, line_b, src_b) = get_sort_info(b);
 
         // 2. Sort by Article Number primarily (if both have numbers)
-        if num_a != num_b && num_a != 0 && num_b != 0 {
+        if num_a != num_b && num_a.0 != 0 && num_b.0 != 0 {
             return num_a.cmp(&num_b);
         }
 
@@ -166,37 +799,294 @@ pub fn align_articles(
             other => other
         }
     });
+}
 
-    changes
+/// Rank of each change type for `ChangeOrder::Type`
+fn type_rank(change_type: &ArticleChangeType) -> usize {
+    match change_type {
+        ArticleChangeType::Added => 0,
+        ArticleChangeType::Deleted => 1,
+        ArticleChangeType::Modified | ArticleChangeType::Changed => 2,
+        ArticleChangeType::Renumbered => 3,
+        ArticleChangeType::Split => 4,
+        ArticleChangeType::Merged => 5,
+        ArticleChangeType::Moved => 6,
+        ArticleChangeType::Replaced => 7,
+        ArticleChangeType::Corresponding => 8,
+        ArticleChangeType::Preamble => 9,
+        ArticleChangeType::Unchanged => 10,
+    }
+}
+
+/// Reorder changes already in new-document order (`sort_changes`) as requested. Every
+/// order is stable, so ties keep new-document order.
+fn order_changes(changes: &mut Vec<ArticleChange>, order: ChangeOrder) {
+    match order {
+        ChangeOrder::New => {}
+        ChangeOrder::Old => {
+            // Added articles have no old position: keep them after the matched article before them
+            let mut last_old_line = 0;
+            let mut keyed: Vec<((usize, bool), ArticleChange)> = changes.drain(..).map(|change| {
+                let key = match &change.old_article {
+                    Some(old) => {
+                        if change.new_articles.is_some() {
+                            last_old_line = old.start_line;
+                        }
+                        (old.start_line, false)
+                    }
+                    None => (last_old_line, true),
+                };
+                (key, change)
+            }).collect();
+            keyed.sort_by_key(|(key, _)| *key);
+            changes.extend(keyed.into_iter().map(|(_, change)| change));
+        }
+        ChangeOrder::Type => changes.sort_by_key(|c| type_rank(&c.change_type)),
+        ChangeOrder::Similarity => changes.sort_by(|a, b| a.similarity.unwrap_or(0.0).total_cmp(&b.similarity.unwrap_or(0.0))),
+    }
+}
+
+/// Tag matched articles that differ only in whitespace/punctuation as "cosmetic-only".
+/// Same-numbered cosmetic edits are reported as Unchanged with `cosmetic_as_unchanged`, or
+/// when they only touch what `ignore_punctuation`/`ignore_whitespace` strips. The options
+/// combine with OR: `cosmetic_as_unchanged` is the broader one and is never narrowed by the
+/// `ignore_*` flags.
+fn annotate_cosmetic_changes(changes: &mut [ArticleChange], options: &CompareOptions) {
+    let (punctuation, whitespace) = (options.ignore_punctuation, options.ignore_whitespace);
+    for change in changes.iter_mut() {
+        if matches!(change.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Preamble) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().filter(|list| list.len() == 1).and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+        if old_art.content == new_art.content || strip_cosmetic(&old_art.content) != strip_cosmetic(&new_art.content) {
+            continue;
+        }
+
+        change.tags.push("cosmetic-only".to_string());
+        let ignored = (punctuation || whitespace)
+            && strip_format(&old_art.content, punctuation, whitespace) == strip_format(&new_art.content, punctuation, whitespace);
+        if (options.cosmetic_as_unchanged || ignored) && old_art.number == new_art.number {
+            change.change_type = ArticleChangeType::Unchanged;
+            change.tags.retain(|t| t != "modified");
+        }
+    }
+}
+
+/// Collapse Modified/Renumbered/Replaced into Changed, keeping the specific kind as a tag
+fn simplify_change_types(changes: &mut [ArticleChange]) {
+    for change in changes.iter_mut() {
+        // Clause changes of any article change, including splits and merges
+        if let Some(clause_changes) = change.clause_changes.as_mut() {
+            simplify_change_types(clause_changes);
+        }
+
+        let tag = match change.change_type {
+            ArticleChangeType::Modified => "modified",
+            ArticleChangeType::Renumbered => "renumbered",
+            ArticleChangeType::Replaced => "replaced",
+            _ => continue,
+        };
+        if !change.tags.iter().any(|t| t == tag) {
+            change.tags.push(tag.to_string());
+        }
+        change.change_type = ArticleChangeType::Changed;
+    }
+}
+
+/// Run the multi-stage alignment over the 款/项 of each matched, non-identical article pair.
+/// Articles that are a single paragraph on both sides are left alone.
+fn annotate_clause_changes(changes: &mut [ArticleChange], options: &CompareOptions, budget: &Budget) {
+    let grammar = grammar_for(&options.parser);
+    for change in changes.iter_mut() {
+        if matches!(change.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Preamble) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().filter(|list| list.len() == 1).and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+
+        let old_clauses = clause_infos(old_art, grammar.as_ref(), &options.parser.marker_styles);
+        let new_clauses = clause_infos(new_art, grammar.as_ref(), &options.parser.marker_styles);
+        if old_clauses.len() <= 1 && new_clauses.len() <= 1 {
+            continue;
+        }
+
+        if budget.exhausted() {
+            break;
+        }
+        let (mut clause_changes, _) = align_by_similarity(&old_clauses, &new_clauses, options, budget, &ProgressSink::default());
+        sort_changes(&mut clause_changes);
+        annotate_details(&mut clause_changes, options.granularity, options.algorithm);
+        change.clause_changes = Some(clause_changes);
+    }
+}
+
+/// 款 and 项 of an article as alignable units: each paragraph before the first （一） item is
+/// a 款 numbered "1", "2"… (as with `implicit_clauses`), followed by the items (content
+/// without the （一） marker, later paragraphs staying with the item before them). Items are
+/// marked as `grammar` marks clauses.
+fn clause_infos(article: &ArticleInfo, grammar: &dyn StructureGrammar, styles: &[MarkerStyle]) -> Vec<ArticleInfo> {
+    let items = parse_clauses(&article.content, grammar, styles);
+    let first_item_line = items.first().map_or(usize::MAX, |item| item.start_line);
+    let unit = |number: Arc<str>, content: &str, start_line: usize, node_type: NodeType| ArticleInfo {
+        number,
+        content: content.trim().into(),
+        title: None,
+        start_line,
+        // Unit spans are relative to the rebuilt article content; report the article's span
+        start_offset: article.start_offset,
+        end_offset: article.end_offset,
+        node_type,
+        parents: Vec::new(),
+        source: article.source,
+        fingerprint: fingerprint(content.trim()),
+    };
+
+    let paragraphs = article.content.lines().enumerate()
+        .take_while(|(idx, _)| idx + 1 < first_item_line)
+        .map(|(idx, line)| (idx, line.trim_matches(|c: char| c.is_whitespace() || c == '\u{3000}')))
+        .filter(|(_, line)| !line.is_empty())
+        .enumerate()
+        .map(|(n, (idx, line))| unit((n + 1).to_string().into(), line, idx + 1, NodeType::Clause));
+    let items = items.iter().map(|item| {
+        let body = item.content.split_once(['）', ')']).map(|(_, rest)| rest).unwrap_or(&item.content);
+        unit(item.number.clone(), body, item.start_line, NodeType::Item)
+    });
+    paragraphs.chain(items).collect()
+}
+
+/// Diff of each matched article's content in units of `granularity`, for inline highlighting
+fn annotate_details(changes: &mut [ArticleChange], granularity: Granularity, algorithm: DiffAlgorithm) {
+    for change in changes.iter_mut() {
+        if !matches!(change.change_type, ArticleChangeType::Modified | ArticleChangeType::Renumbered | ArticleChangeType::Moved | ArticleChangeType::Replaced | ArticleChangeType::Corresponding) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+        change.details = Some(unit_diff(&old_art.content, &new_art.content, granularity, algorithm));
+    }
+}
+
+/// Record inserted/removed character counts for matched articles (churn metric)
+fn annotate_churn(changes: &mut [ArticleChange]) {
+    for change in changes.iter_mut() {
+        if !matches!(change.change_type, ArticleChangeType::Modified | ArticleChangeType::Renumbered | ArticleChangeType::Moved | ArticleChangeType::Corresponding) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+
+        let diff = TextDiff::from_chars(old_art.content.as_ref(), new_art.content.as_ref());
+        let mut added = 0;
+        let mut removed = 0;
+        for c in diff.iter_all_changes() {
+            match c.tag() {
+                ChangeTag::Insert => added += c.value().chars().count(),
+                ChangeTag::Delete => removed += c.value().chars().count(),
+                ChangeTag::Equal => {}
+            }
+        }
+        change.chars_added = Some(added);
+        change.chars_removed = Some(removed);
+    }
 }
 
 /// Build a comprehensive similarity matrix between all old and new articles.
-/// Optimized with parallel processing and pre-tokenization.
+/// Optimized with parallel processing and pre-tokenization. Above `lsh_min_cells` pairs,
+/// only pairs MinHash/LSH flags as likely similar and whose SimHash fingerprints are not
+/// clearly unrelated (plus same-numbered ones) are scored; the rest score 0. Scores of articles unchanged since a recent run of
+/// `CACHE_MIN_CELLS` pairs or more come from the `MatrixCache`. Rows left when `budget`
+/// runs out score 0, and such a matrix is not cached. Every tenth of the rows scored is
+/// reported to `progress`.
 fn build_similarity_matrix(
     old_articles: &[ArticleInfo],
     new_articles: &[ArticleInfo],
-) -> Vec<Vec<SimilarityScore>> {
+    config: &SimilarityConfig,
+    lsh_min_cells: usize,
+    budget: &Budget,
+    progress: &ProgressSink,
+) -> Arc<Vec<Vec<SimilarityScore>>> {
+    let cells = old_articles.len().saturating_mul(new_articles.len());
+    let pruned = cells > lsh_min_cells;
+    let cacheable = cells >= CACHE_MIN_CELLS;
+
+    let config_hash = config_key(config, pruned);
+    let (old_keys, new_keys): (Vec<u64>, Vec<u64>) = if cacheable {
+        (old_articles.iter().map(article_key).collect(), new_articles.iter().map(article_key).collect())
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let cached = if cacheable {
+        MatrixCache::shared().lock().unwrap_or_else(|e| e.into_inner()).lookup(config_hash, &old_keys, &new_keys)
+    } else {
+        None
+    };
     // 1. Pre-tokenize everything once
-    let old_tokens: Vec<HashSet<std::sync::Arc<str>>> = old_articles.par_iter()
-        .map(|art| tokenize_to_set(&art.content))
-        .collect();
-
-    let new_tokens: Vec<HashSet<std::sync::Arc<str>>> = new_articles.par_iter()
-        .map(|art| tokenize_to_set(&art.content))
-        .collect();
+    let old_tokens: Vec<Terms> = old_articles.par_iter().map(|art| Terms::of(&art.content)).collect();
+    let new_tokens: Vec<Terms> = new_articles.par_iter().map(|art| Terms::of(&art.content)).collect();
+
+    // A complete hit (same documents, e.g. a new threshold) scores nothing
+    let complete = cached.as_ref().is_some_and(|hit| hit.is_complete());
+    let candidates = (pruned && !complete).then(|| {
+        let old_signatures: Vec<Vec<u64>> = old_tokens.par_iter().map(|t| signature(&t.set)).collect();
+        let new_signatures: Vec<Vec<u64>> = new_tokens.par_iter().map(|t| signature(&t.set)).collect();
+        candidate_pairs(&old_signatures, &new_signatures)
+    });
+    let char_counts = |articles: &[ArticleInfo]| -> Vec<usize> {
+        if pruned { articles.iter().map(|a| a.content.chars().count()).collect() } else { Vec::new() }
+    };
+    let (old_lens, new_lens) = (char_counts(old_articles), char_counts(new_articles));
 
     // 2. Build matrix in parallel
-    old_articles.par_iter().enumerate().map(|(i, old_art)| {
+    let rows_done = AtomicUsize::new(0);
+    let report_row = || {
+        let (done, total) = (rows_done.fetch_add(1, Ordering::Relaxed) + 1, old_articles.len());
+        if progress.is_active() && done * 10 / total != (done - 1) * 10 / total {
+            progress.report(AlignmentProgress::Matrix { percent: done * 100 / total });
+        }
+    };
+    let matrix: Vec<Vec<SimilarityScore>> = old_articles.par_iter().enumerate().map(|(i, old_art)| {
+        if budget.exhausted() {
+            return vec![SimilarityScore::new(0.0, 0.0, 0.0, 0.0, 0.0); new_articles.len()];
+        }
         let mut row = Vec::with_capacity(new_articles.len());
         let tokens_a = &old_tokens[i];
 
         for (j, new_art) in new_articles.iter().enumerate() {
+            if let Some(score) = cached.as_ref().and_then(|hit| hit.score(i, j)) {
+                row.push(score.clone());
+                continue;
+            }
+            let pruned = candidates.as_ref().is_some_and(|c| {
+                clearly_unrelated(old_art.fingerprint, old_lens[i], new_art.fingerprint, new_lens[j]) || !c[i].contains(&j)
+            });
+            if pruned && old_art.number != new_art.number {
+                row.push(SimilarityScore::new(0.0, 0.0, 0.0, 0.0, 0.0));
+                continue;
+            }
             let tokens_b = &new_tokens[j];
-            let mut score_wrapper = calculate_composite_similarity(
+            let mut score_wrapper = calculate_composite_similarity_with(
                 &old_art.content,
                 &new_art.content,
                 tokens_a,
                 tokens_b,
+                config,
             );
 
             // Boost score if hierarchy context matches
@@ -218,8 +1108,61 @@ fn build_similarity_matrix(
 
             row.push(score_wrapper);
         }
+        report_row();
         row
-    }).collect()
+    }).collect();
+
+    let matrix = Arc::new(matrix);
+    if cacheable && !budget.tripped() {
+        let entry = CachedMatrix { config: config_hash, old_keys, new_keys, matrix: Arc::clone(&matrix) };
+        MatrixCache::shared().lock().unwrap_or_else(|e| e.into_inner()).store(entry);
+    }
+    matrix
+}
+
+/// Index of the article a manual anchor names; "第五条", "五" and "5" all name 第五条
+fn find_anchored(articles: &[ArticleInfo], number: &str) -> Option<usize> {
+    let key = article_sort_key(number.trim().trim_start_matches('第').trim_end_matches('条'));
+    articles.iter().position(|a| a.node_type == NodeType::Article && article_sort_key(&a.number) == key)
+}
+
+/// Stage 0: Pair the articles of each manual anchor regardless of their similarity, tagged
+/// "anchored". Their rows and columns are cleared in the matrix and the anchor cells outweigh
+/// any sequence of other matches, so the order-preserving stage aligns the rest around them.
+fn lock_anchors(
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    anchors: &[ArticleAnchor],
+    similarity_matrix: &mut [Vec<SimilarityScore>],
+    used_old: &mut [bool],
+    used_new: &mut [bool],
+    changes: &mut Vec<ArticleChange>,
+) {
+    let anchor_weight = (old_articles.len() + new_articles.len()) as f32;
+    for anchor in anchors {
+        let (Some(old_idx), Some(new_idx)) = (find_anchored(old_articles, &anchor.old_number), find_anchored(new_articles, &anchor.new_number)) else {
+            continue;
+        };
+        if used_old[old_idx] || used_new[new_idx] {
+            continue;
+        }
+
+        let mut change = matched_change(&old_articles[old_idx], &new_articles[new_idx], &similarity_matrix[old_idx][new_idx]);
+        change.tags.push("anchored".to_string());
+        changes.push(change);
+        used_old[old_idx] = true;
+        used_new[new_idx] = true;
+
+        for (i, row) in similarity_matrix.iter_mut().enumerate() {
+            for (j, score) in row.iter_mut().enumerate() {
+                if i == old_idx && j == new_idx {
+                    score.composite = anchor_weight;
+                } else if i == old_idx || j == new_idx {
+                    score.composite = 0.0;
+                }
+            }
+        }
+    }
 }
 
 /// Stage 0: Match articles with identical numbers as primary signal
@@ -267,8 +1210,10 @@ fn find_number_matches(
                     old_article: Some(old_art.clone()),
                     new_articles: Some(vec![new_art.clone()]),
                     similarity: Some(score),
+                    score_detail: Some(similarity_matrix[old_idx][new_idx].clone()),
                     details: None,
                     tags,
+                    ..Default::default()
                 });
 
                 used_old[old_idx] = true;
@@ -336,43 +1281,7 @@ fn find_one_to_one_matches(
             let new_idx = curr_j - 1;
 
             if !used_old[old_idx] && !used_new[new_idx] {
-                let old_art = &old_articles[old_idx];
-                let new_art = &new_articles[new_idx];
-                let score = similarity_matrix[old_idx][new_idx].composite;
-
-                let change_type = if old_art.node_type == NodeType::Preamble || new_art.node_type == NodeType::Preamble {
-                    ArticleChangeType::Preamble
-                } else if score >= EXACT_MATCH_THRESHOLD && old_art.number == new_art.number {
-                    ArticleChangeType::Unchanged
-                } else if old_art.number == new_art.number {
-                    ArticleChangeType::Modified
-                } else {
-                    // Content matches significantly but number differs
-                    ArticleChangeType::Renumbered
-                };
-
-                let mut tags = Vec::new();
-                if change_type == ArticleChangeType::Preamble {
-                    tags.push("preamble".to_string());
-                } else {
-                    if old_art.number != new_art.number {
-                        tags.push("renumbered".to_string());
-                    }
-                    // Use a very high threshold to detect even minor modifications
-                    if score < 0.999 {
-                        tags.push("modified".to_string());
-                    }
-                }
-
-                changes.push(ArticleChange {
-                    change_type,
-                    old_article: Some(old_art.clone()),
-                    new_articles: Some(vec![new_art.clone()]),
-                    similarity: Some(score),
-                    details: None,
-                    tags,
-                });
-
+                changes.push(matched_change(&old_articles[old_idx], &new_articles[new_idx], &similarity_matrix[old_idx][new_idx]));
                 used_old[old_idx] = true;
                 used_new[new_idx] = true;
             }
@@ -418,8 +1327,10 @@ fn find_one_to_one_matches(
                 old_article: Some(old_art.clone()),
                 new_articles: Some(vec![new_art.clone()]),
                 similarity: Some(best_score),
+                score_detail: Some(similarity_matrix[old_idx][new_idx].clone()),
                 details: None,
                 tags,
+                ..Default::default()
             });
             used_old[old_idx] = true;
             used_new[new_idx] = true;
@@ -427,68 +1338,217 @@ fn find_one_to_one_matches(
     }
 }
 
-/// Detect split patterns: one old article → multiple new articles
-fn detect_splits(
+/// A 1:1 match of the first stage, typed by number and similarity
+fn matched_change(old_art: &ArticleInfo, new_art: &ArticleInfo, score_detail: &SimilarityScore) -> ArticleChange {
+    let score = score_detail.composite;
+    let change_type = if old_art.node_type == NodeType::Preamble || new_art.node_type == NodeType::Preamble {
+        ArticleChangeType::Preamble
+    } else if score >= EXACT_MATCH_THRESHOLD && old_art.number == new_art.number {
+        ArticleChangeType::Unchanged
+    } else if old_art.number == new_art.number {
+        ArticleChangeType::Modified
+    } else {
+        // Content matches significantly but number differs
+        ArticleChangeType::Renumbered
+    };
+
+    let mut tags = Vec::new();
+    if change_type == ArticleChangeType::Preamble {
+        tags.push("preamble".to_string());
+    } else {
+        if old_art.number != new_art.number {
+            tags.push("renumbered".to_string());
+        }
+        // Use a very high threshold to detect even minor modifications
+        if score < 0.999 {
+            tags.push("modified".to_string());
+        }
+    }
+
+    ArticleChange {
+        change_type,
+        old_article: Some(old_art.clone()),
+        new_articles: Some(vec![new_art.clone()]),
+        similarity: Some(score),
+        score_detail: Some(score_detail.clone()),
+        details: None,
+        tags,
+        ..Default::default()
+    }
+}
+
+/// Stage 1 (cross-law): pair provisions that are each other's best-scoring counterpart at
+/// `threshold` or above, as Corresponding. Pairing repeats over the unpaired articles, so a
+/// provision whose best counterpart was taken can still pair with its next best.
+fn find_corresponding_provisions(
     old_articles: &[ArticleInfo],
     new_articles: &[ArticleInfo],
     similarity_matrix: &[Vec<SimilarityScore>],
     used_old: &mut [bool],
     used_new: &mut [bool],
     changes: &mut Vec<ArticleChange>,
+    threshold: f32,
 ) {
-    for (old_idx, old_art) in old_articles.iter().enumerate() {
-        if used_old[old_idx] {
+    // Best unpaired counterpart scoring at least `threshold`: (index, score)
+    let best = |scores: Vec<(usize, f32)>| scores.into_iter().filter(|&(_, s)| s >= threshold).max_by(|a, b| a.1.total_cmp(&b.1));
+    loop {
+        let mut paired = false;
+        for old_idx in 0..old_articles.len() {
+            if used_old[old_idx] {
+                continue;
+            }
+            let forward = (0..new_articles.len()).filter(|&j| !used_new[j]).map(|j| (j, similarity_matrix[old_idx][j].composite));
+            let Some((new_idx, score)) = best(forward.collect()) else {
+                continue;
+            };
+            let backward = (0..old_articles.len()).filter(|&i| !used_old[i]).map(|i| (i, similarity_matrix[i][new_idx].composite));
+            if best(backward.collect()).map(|(i, _)| i) != Some(old_idx) {
+                continue;
+            }
+
+            let (old_art, new_art) = (&old_articles[old_idx], &new_articles[new_idx]);
+            changes.push(ArticleChange {
+                change_type: ArticleChangeType::Corresponding,
+                old_article: Some(old_art.clone()),
+                new_articles: Some(vec![new_art.clone()]),
+                similarity: Some(score),
+                score_detail: Some(similarity_matrix[old_idx][new_idx].clone()),
+                tags: vec!["corresponding".to_string()],
+                ..Default::default()
+            });
+            used_old[old_idx] = true;
+            used_new[new_idx] = true;
+            paired = true;
+        }
+        if !paired {
+            break;
+        }
+    }
+}
+
+/// Stage 1 (optimal strategy): pair articles by a global assignment maximizing the total
+/// similarity over pairs scoring at least the sequential floor of `find_one_to_one_matches`.
+/// Among equal scores, pairs closer to the diagonal win, so identical articles keep their order.
+fn find_optimal_matches(
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    similarity_matrix: &[Vec<SimilarityScore>],
+    used_old: &mut [bool],
+    used_new: &mut [bool],
+    changes: &mut Vec<ArticleChange>,
+    threshold: f32,
+) {
+    let (n, m) = (old_articles.len(), new_articles.len());
+    let floor = (threshold * 0.7).max(0.3);
+    let weights: Vec<Vec<f32>> = (0..n).map(|i| (0..m).map(|j| {
+        let score = similarity_matrix[i][j].composite;
+        if score < floor {
+            return 0.0;
+        }
+        let displacement = (i as f32 / n as f32 - j as f32 / m as f32).abs();
+        score - 1e-4 * displacement
+    }).collect()).collect();
+
+    for (old_idx, new_idx) in max_weight_assignment(&weights) {
+        if used_old[old_idx] || used_new[new_idx] {
             continue;
         }
+        changes.push(matched_change(&old_articles[old_idx], &new_articles[new_idx], &similarity_matrix[old_idx][new_idx]));
+        used_old[old_idx] = true;
+        used_new[new_idx] = true;
+    }
+}
 
-        // Find all new articles with medium+ similarity
-        let mut candidates: Vec<(usize, f32)> = new_articles
-            .iter()
-            .enumerate()
-            .filter(|(new_idx, _)| !used_new[*new_idx])
-            .map(|(new_idx, _)| {
-                let score = similarity_matrix[old_idx][new_idx].composite;
-                (new_idx, score)
-            })
-            .filter(|(_, score)| *score >= MEDIUM_SIMILARITY_THRESHOLD)
-            .collect();
+/// Whether `score` makes an article a candidate fragment of a split or merge
+fn is_fragment_candidate(score: &SimilarityScore) -> bool {
+    score.composite >= MEDIUM_SIMILARITY_THRESHOLD || score.containment_similarity >= FRAGMENT_CONTAINMENT_THRESHOLD
+}
 
-        // Check if this looks like a split (multiple good matches)
-        if candidates.len() >= 2 {
-            candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+/// Greedily collect fragments (best score first) while each covers new tokens of `source`.
+/// Returns the fragment indices in document order, or `None` unless at least two of them
+/// jointly cover `FRAGMENT_COVERAGE_THRESHOLD` of the source or their scores sum to 1.0
+/// (the source gained text of its own).
+fn accumulate_fragments(
+    source: &HashSet<Arc<str>>,
+    candidates: &mut [(usize, f32)],
+    fragment_tokens: impl Fn(usize) -> HashSet<Arc<str>>,
+) -> Option<Vec<usize>> {
+    if candidates.len() < 2 || source.is_empty() {
+        return None;
+    }
+    candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    let min_gain = ((source.len() as f32 * FRAGMENT_MIN_GAIN).ceil() as usize).max(1);
+    let mut covered: HashSet<&Arc<str>> = HashSet::new();
+    let mut fragments = Vec::new();
+    let mut total_score = 0.0;
+    for &(idx, score) in candidates.iter() {
+        let tokens = fragment_tokens(idx);
+        let gain: Vec<&Arc<str>> = source.iter().filter(|t| tokens.contains(*t) && !covered.contains(t)).collect();
+        if gain.len() >= min_gain {
+            covered.extend(gain);
+            fragments.push(idx);
+            total_score += score;
+        }
+    }
 
-            // Take top matches that sum to reasonable coverage
-            let total_score: f32 = candidates.iter().take(3).map(|(_, s)| s).sum();
+    let coverage = covered.len() as f32 / source.len() as f32;
+    if fragments.len() < 2 || (coverage < FRAGMENT_COVERAGE_THRESHOLD && total_score < 1.0) {
+        return None;
+    }
+    fragments.sort_unstable();
+    Some(fragments)
+}
 
-            if total_score >= 1.0 {
-                // This looks like a split!
-                let split_indices: Vec<usize> = candidates
-                    .iter()
-                    .take(3)
-                    .map(|(idx, _)| *idx)
-                    .collect();
+/// Average composite score of the fragments against the source
+fn fragment_score(candidates: &[(usize, f32)], fragments: &[usize]) -> f32 {
+    let total: f32 = candidates.iter().filter(|(idx, _)| fragments.contains(idx)).map(|(_, s)| s).sum();
+    total / fragments.len() as f32
+}
 
-                let split_articles: Vec<ArticleInfo> = split_indices
-                    .iter()
-                    .map(|idx| new_articles[*idx].clone())
-                    .collect();
+/// Detect split patterns: one old article → multiple new articles
+fn detect_splits(
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+    similarity_matrix: &[Vec<SimilarityScore>],
+    used_old: &mut [bool],
+    used_new: &mut [bool],
+    changes: &mut Vec<ArticleChange>,
+) {
+    for (old_idx, old_art) in old_articles.iter().enumerate() {
+        if used_old[old_idx] {
+            continue;
+        }
 
-                let avg_score = total_score / split_indices.len() as f32;
+        // Find all unused new articles that may hold part of this one
+        let mut candidates: Vec<(usize, f32)> = (0..new_articles.len())
+            .filter(|&new_idx| !used_new[new_idx] && is_fragment_candidate(&similarity_matrix[old_idx][new_idx]))
+            .map(|new_idx| (new_idx, similarity_matrix[old_idx][new_idx].composite))
+            .collect();
+        if candidates.len() < 2 {
+            continue;
+        }
 
-                changes.push(ArticleChange {
-                    change_type: ArticleChangeType::Split,
-                    old_article: Some(old_art.clone()),
-                    new_articles: Some(split_articles),
-                    similarity: Some(avg_score),
-                    details: None,
-                    tags: vec!["split".to_string()],
-                });
+        // Split when the fragments together cover enough of the old article
+        let source = tokenize_to_set(&old_art.content);
+        let Some(split_indices) = accumulate_fragments(&source, &mut candidates, |idx| tokenize_to_set(&new_articles[idx].content)) else {
+            continue;
+        };
 
-                used_old[old_idx] = true;
-                for idx in split_indices {
-                    used_new[idx] = true;
-                }
-            }
+        let split_articles: Vec<ArticleInfo> = split_indices.iter().map(|&idx| new_articles[idx].clone()).collect();
+        changes.push(ArticleChange {
+            change_type: ArticleChangeType::Split,
+            old_article: Some(old_art.clone()),
+            new_articles: Some(split_articles),
+            similarity: Some(fragment_score(&candidates, &split_indices)),
+            details: None,
+            tags: vec!["split".to_string()],
+            ..Default::default()
+        });
+
+        used_old[old_idx] = true;
+        for idx in split_indices {
+            used_new[idx] = true;
         }
     }
 }
@@ -501,63 +1561,61 @@ fn detect_merges(
     used_old: &mut [bool],
     used_new: &mut [bool],
     changes: &mut Vec<ArticleChange>,
+    consolidate: bool,
 ) {
     for (new_idx, new_art) in new_articles.iter().enumerate() {
         if used_new[new_idx] {
             continue;
         }
 
-        // Find all old articles with medium+ similarity to this new article
-        let mut candidates: Vec<(usize, f32)> = old_articles
-            .iter()
-            .enumerate()
-            .filter(|(old_idx, _)| !used_old[*old_idx])
-            .map(|(old_idx, _)| {
-                let score = similarity_matrix[old_idx][new_idx].composite;
-                (old_idx, score)
-            })
-            .filter(|(_, score)| *score >= MEDIUM_SIMILARITY_THRESHOLD)
+        // Find all unused old articles that may have been folded into this one
+        let mut candidates: Vec<(usize, f32)> = (0..old_articles.len())
+            .filter(|&old_idx| !used_old[old_idx] && is_fragment_candidate(&similarity_matrix[old_idx][new_idx]))
+            .map(|old_idx| (old_idx, similarity_matrix[old_idx][new_idx].composite))
             .collect();
+        if candidates.len() < 2 {
+            continue;
+        }
 
-        // Check if this looks like a merge (multiple old → one new)
-        if candidates.len() >= 2 {
-            candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-            let total_score: f32 = candidates.iter().take(3).map(|(_, s)| s).sum();
-
-            if total_score >= 1.0 {
-                // This looks like a merge!
-                let merge_indices: Vec<usize> = candidates
-                    .iter()
-                    .take(3)
-                    .map(|(idx, _)| *idx)
-                    .collect();
-
-                // For merge, we store the first old article as the main one
-                // (or we could create multiple ArticleChange entries)
-                let merged_old_articles: Vec<ArticleInfo> = merge_indices
-                    .iter()
-                    .map(|idx| old_articles[*idx].clone())
-                    .collect();
-
-                let avg_score = total_score / merge_indices.len() as f32;
-
-                // Create one change per merged old article for clarity
-                for (i, old_idx) in merge_indices.iter().enumerate() {
-                    changes.push(ArticleChange {
-                        change_type: ArticleChangeType::Merged,
-                        old_article: Some(old_articles[*old_idx].clone()),
-                        new_articles: Some(vec![new_art.clone()]),
-                        similarity: Some(avg_score),
-                        details: None,
-                        tags: vec!["merged".to_string()],
-                    });
-                    used_old[*old_idx] = true;
-                }
+        // Merge when the old articles together cover enough of the new one
+        let target = tokenize_to_set(&new_art.content);
+        let Some(merge_indices) = accumulate_fragments(&target, &mut candidates, |idx| tokenize_to_set(&old_articles[idx].content)) else {
+            continue;
+        };
+        let avg_score = fragment_score(&candidates, &merge_indices);
 
-                used_new[new_idx] = true;
+        if consolidate {
+            // Single change listing every source article in document order
+            let merged_from: Vec<ArticleInfo> = merge_indices.iter().map(|&idx| old_articles[idx].clone()).collect();
+            changes.push(ArticleChange {
+                change_type: ArticleChangeType::Merged,
+                old_article: merged_from.first().cloned(),
+                new_articles: Some(vec![new_art.clone()]),
+                similarity: Some(avg_score),
+                details: None,
+                tags: vec!["merged".to_string()],
+                merged_from: Some(merged_from),
+                ..Default::default()
+            });
+        } else {
+            // Create one change per merged old article for clarity
+            for &old_idx in &merge_indices {
+                changes.push(ArticleChange {
+                    change_type: ArticleChangeType::Merged,
+                    old_article: Some(old_articles[old_idx].clone()),
+                    new_articles: Some(vec![new_art.clone()]),
+                    similarity: Some(avg_score),
+                    details: None,
+                    tags: vec!["merged".to_string()],
+                    ..Default::default()
+                });
             }
         }
+
+        for old_idx in merge_indices {
+            used_old[old_idx] = true;
+        }
+        used_new[new_idx] = true;
     }
 }
 
@@ -583,6 +1641,7 @@ fn handle_remaining_articles(
                 similarity: None,
                 details: None,
                 tags,
+                ..Default::default()
             });
         }
     }
@@ -601,31 +1660,88 @@ fn handle_remaining_articles(
                 similarity: None,
                 details: None,
                 tags,
+                ..Default::default()
             });
         }
     }
 }
 
 /// Helper to flatten AST into a list of articles with hierarchy context
-fn flatten_articles(node: &ArticleNode) -> Vec<ArticleInfo> {
+pub fn flatten_articles(node: &ArticleNode) -> Vec<ArticleInfo> {
     let mut articles = Vec::new();
     let parent_stack = Vec::new();
     collect_articles_recursive(node, &mut articles, &parent_stack);
+    // 目录 text aligns with the preamble, so edits to it still surface as a change
+    if let Some(toc) = node.children.iter().find(|c| c.node_type == NodeType::Toc) {
+        merge_into_preamble(&mut articles, &toc_text(toc), toc.start_offset, toc.end_offset);
+    }
     articles
 }
 
+/// Append `text` to the preamble unit, opening one ahead of the articles if there is none
+fn merge_into_preamble(articles: &mut Vec<ArticleInfo>, text: &str, start_offset: usize, end_offset: usize) {
+    match articles.first_mut().filter(|a| a.node_type == NodeType::Preamble) {
+        Some(preamble) => {
+            preamble.content = format!("{}\n{}", preamble.content, text).into();
+            preamble.start_offset = preamble.start_offset.min(start_offset);
+            preamble.end_offset = preamble.end_offset.max(end_offset);
+            preamble.fingerprint = fingerprint(&preamble.content);
+        }
+        None => articles.insert(0, ArticleInfo {
+            number: "0".into(),
+            fingerprint: fingerprint(text),
+            content: text.into(),
+            title: Some("序言/目录".into()),
+            start_line: 1,
+            start_offset,
+            end_offset,
+            node_type: NodeType::Preamble,
+            parents: Vec::new(),
+            source: None,
+        }),
+    }
+}
+
 fn collect_articles_recursive(node: &ArticleNode, list: &mut Vec<ArticleInfo>, parent_stack: &[Arc<str>]) {
+    // 附则 text outside its articles and whole 附件 blocks align as units of their own
+    let standalone = match node.node_type {
+        NodeType::Supplementary => !node.content.trim().is_empty(),
+        NodeType::Annex => true,
+        _ => false,
+    };
+    if standalone {
+        // Annex tables live in child nodes; 附则 children are articles of their own
+        let content: Arc<str> = if node.node_type == NodeType::Annex { get_all_content(node).trim().into() } else { node.content.trim().into() };
+        list.push(ArticleInfo {
+            number: node.number.clone(),
+            fingerprint: fingerprint(&content),
+            content,
+            title: node.title.clone(),
+            start_line: node.start_line,
+            start_offset: node.start_offset,
+            end_offset: node.end_offset,
+            node_type: node.node_type.clone(),
+            parents: parent_stack.to_vec(),
+            source: None,
+        });
+    }
+
     // If this node is an article or preamble, add it to the list
     if matches!(node.node_type, NodeType::Article | NodeType::Preamble) {
         // Skip technical root node
         if node.number.as_ref() != "root" {
+            let content: Arc<str> = get_all_content(node).into();
             list.push(ArticleInfo {
                 number: node.number.clone(),
-                content: get_all_content(node).into(),
+                fingerprint: fingerprint(&content),
+                content,
                 title: node.title.clone(),
                 start_line: node.start_line,
+                start_offset: node.start_offset,
+                end_offset: node.end_offset,
                 node_type: node.node_type.clone(),
                 parents: parent_stack.to_vec(),
+                source: None,
             });
         }
     }
@@ -633,7 +1749,7 @@ fn collect_articles_recursive(node: &ArticleNode, list: &mut Vec<ArticleInfo>, p
     // Determine if this node contributes to the parent stack for its children
     let mut current_stack = parent_stack.to_vec();
     match node.node_type {
-        NodeType::Part | NodeType::Chapter | NodeType::Section => {
+        NodeType::Part | NodeType::SubPart | NodeType::Chapter | NodeType::Section | NodeType::Supplementary => {
             let label: Arc<str> = if let Some(title) = &node.title {
                 format!("{} {}", node.number, title).into()
             } else {
@@ -656,7 +1772,12 @@ fn get_all_content(node: &ArticleNode) -> String {
 
     // For articles, we want to maintain some separation if content exists
     for child in &node.children {
-        let child_content = get_all_content(child);
+        let child_content = match child.node_type {
+            // Rows first, then the text following the table
+            NodeType::Table if !child.content.trim().is_empty() => format!("{}\n{}", table_text(child), child.content),
+            NodeType::Table => table_text(child),
+            _ => get_all_content(child),
+        };
         if !child_content.is_empty() {
             if !result.is_empty() && !result.ends_with('\n') {
                 result.push('\n');
@@ -1,5 +1,6 @@
-use crate::diff::aligner::align_articles;
-use crate::models::ArticleChangeType;
+use crate::diff::aligner::{align_articles, align_articles_with_options, align_parts, average_similarity, count_by_type, prepare_document, similarity_pairs, DEGRADED_ALIGNMENT_WARNING, EMPTY_ARTICLE_WARNING, TOC_MISMATCH_WARNING, TRUNCATED_ALIGNMENT_WARNING, UNMATCHED_ANCHOR_WARNING};
+use crate::diff::budget::Budget;
+use crate::models::{AlignmentStrategy, ArticleAnchor, ArticleChangeType, ChangeOrder, CompareOptions, NodeType, PreambleMode, RunnerUpSide};
 
 #[cfg(test)]
 mod alignment_tests {
@@ -30,7 +31,7 @@ mod alignment_tests {
 
         let changes = align_articles(old_text, new_text, 0.6, true);
         let has_high_sim = changes.iter().any(|c| {
-            c.similarity.map_or(false, |s| s > 0.8)
+            c.similarity.is_some_and(|s| s > 0.8)
         });
         assert!(has_high_sim, "Renumbered should have high similarity");
     }
@@ -62,7 +63,7 @@ mod alignment_tests {
 
         // Should have at least one change with very high similarity
         let has_high_similarity = changes.iter().any(|c| {
-            c.similarity.map_or(false, |s| s >= 0.95)
+            c.similarity.is_some_and(|s| s >= 0.95)
         });
         assert!(has_high_similarity, "Unchanged text should have high similarity");
     }
@@ -81,4 +82,692 @@ mod alignment_tests {
         let changes = align_articles(old_text, new_text, 0.6, true);
         assert!(changes.len() >= 3, "Should detect multiple changes");
     }
+
+    #[test]
+    fn test_churn_counts() {
+        let old_text = "第一条 网络运营者应当建立管理制度，并定期检查。";
+        let new_text = "第一条 网络运营者应当建立健全安全的管理制度，并检查。";
+
+        let options = CompareOptions { compute_churn: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+
+        let modified = changes.iter()
+            .find(|c| c.change_type == ArticleChangeType::Modified)
+            .expect("Article should be matched as modified");
+        assert_eq!(modified.chars_added, Some(5), "Gained 健全安全的");
+        assert_eq!(modified.chars_removed, Some(2), "Lost 定期");
+
+        // Churn is opt-in
+        let plain = align_articles(old_text, new_text, 0.6, false);
+        assert!(plain.iter().all(|c| c.chars_added.is_none() && c.chars_removed.is_none()));
+    }
+
+    #[test]
+    fn test_partition_by_part() {
+        let old_text = r#"第一编 总则
+第一章 基本规定
+第一条 为了保护民事主体的合法权益，调整民事关系，制定本法。
+第二条 民法调整平等主体之间的人身关系和财产关系。
+第二编 物权
+第二章 通则
+第三条 因物的归属和利用产生的民事关系，适用本编。
+第四条 国家坚持和完善社会主义基本经济制度。"#;
+        let new_text = r#"第一编 总则
+第一章 基本规定
+第一条 为了保护民事主体的合法权益，调整民事关系，制定本法。
+第二条 民法调整平等主体之间的人身关系和财产关系。
+第二编 物权
+第二章 通则
+第三条 因物的归属和利用产生的民事关系，适用本编。
+第四条 国家坚持和完善社会主义基本经济制度，实行公有制为主体的制度。
+第五条 国家实行社会主义市场经济，保障一切市场主体的平等法律地位。"#;
+
+        let options = CompareOptions { partition_by_part: true, ..Default::default() };
+        let parts = align_parts(&prepare_document(old_text, &options), &prepare_document(new_text, &options), &options, &Budget::start(&options));
+
+        assert_eq!(parts.len(), 2, "Each 编 should get its own result block");
+        assert_eq!(parts[0].number.as_ref(), "一");
+        assert_eq!(parts[1].number.as_ref(), "二");
+
+        // Part one is untouched, part two has an edit and an addition
+        assert_eq!(parts[0].article_changes.len(), 2);
+        assert_eq!(parts[0].stats.additions, 0);
+        assert_eq!(parts[1].stats.additions, 1);
+        assert!(parts[1].article_changes.iter().all(|c| {
+            c.old_article.as_ref().is_none_or(|a| ["三", "四"].contains(&a.number.as_ref()))
+        }), "Part two must only see its own articles");
+        assert!(parts[0].similarity > parts[1].similarity, "Similarity is computed per part");
+    }
+
+    #[test]
+    fn test_degraded_alignment_for_huge_inputs() {
+        // 2100 x 2100 pairs exceeds the default 4M-cell matrix cap
+        let build = |edited: usize| -> String {
+            (1..=2100)
+                .map(|n| if n == edited {
+                    format!("第{}条 本条内容已经修改。", n)
+                } else {
+                    format!("第{}条 原始内容，编号{}。", n, n)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let old_text = build(0);
+        let new_text = build(7);
+
+        let result = align_articles_with_options(&old_text, &new_text, &CompareOptions::default());
+
+        assert!(result.warnings.iter().any(|w| w.starts_with(DEGRADED_ALIGNMENT_WARNING)),
+            "Oversized input should report degraded alignment");
+        assert_eq!(result.changes.len(), 2100, "Every article should still be paired by number");
+
+        let modified: Vec<_> = result.changes.iter()
+            .filter(|c| c.change_type == ArticleChangeType::Modified)
+            .collect();
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0].old_article.as_ref().unwrap().number.as_ref(), "7");
+    }
+
+    #[test]
+    fn test_cosmetic_only_change() {
+        let old_text = "第一条 网络运营者应当建立管理制度，采取技术措施。";
+        let new_text = "第一条 网络运营者应当建立管理制度、采取技术措施；";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].tags.contains(&"cosmetic-only".to_string()));
+        assert_ne!(changes[0].change_type, ArticleChangeType::Unchanged);
+
+        let options = CompareOptions { cosmetic_as_unchanged: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes[0].change_type, ArticleChangeType::Unchanged);
+        assert!(changes[0].tags.contains(&"cosmetic-only".to_string()));
+
+        // A real wording change is not cosmetic
+        let changes = align_articles(old_text, "第一条 网络运营者应当建立安全管理制度，采取技术措施。", 0.6, false);
+        assert!(!changes[0].tags.contains(&"cosmetic-only".to_string()));
+    }
+
+    #[test]
+    fn test_clause_alignment() {
+        let old_text = "第三条 网络运营者应当履行下列安全保护义务：\n\
+                        （一）制定内部安全管理制度和操作规程，确定网络安全负责人；\n\
+                        （二）采取防范计算机病毒和网络攻击、网络侵入等危害网络安全行为的技术措施；\n\
+                        （三）采取监测、记录网络运行状态、网络安全事件的技术措施，并留存网络日志不少于三个月。";
+        let new_text = "第三条 网络运营者应当履行下列安全保护义务：\n\
+                        （一）采取防范计算机病毒和网络攻击、网络侵入等危害网络安全行为的技术措施；\n\
+                        （二）制定内部安全管理制度和操作规程，确定网络安全负责人；\n\
+                        （三）采取监测、记录网络运行状态、网络安全事件的技术措施，并留存网络日志不少于六个月。";
+
+        let options = CompareOptions { clause_alignment: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes.len(), 1);
+
+        let clauses = changes[0].clause_changes.as_ref().expect("clause changes");
+        assert_eq!(clauses.len(), 4);
+        assert_eq!(clauses[0].change_type, ArticleChangeType::Unchanged);
+        assert_eq!(clauses[0].old_article.as_ref().unwrap().node_type, NodeType::Clause);
+        let find = |old_num: &str| clauses.iter()
+            .find(|c| c.old_article.as_ref().is_some_and(|a| a.number.as_ref() == old_num))
+            .unwrap();
+
+        let first = find("一");
+        assert_eq!(first.change_type, ArticleChangeType::Renumbered);
+        assert_eq!(first.new_articles.as_ref().unwrap()[0].number.as_ref(), "二");
+        let second = find("二");
+        assert_eq!(second.change_type, ArticleChangeType::Renumbered);
+        assert_eq!(second.new_articles.as_ref().unwrap()[0].number.as_ref(), "一");
+        assert_eq!(find("三").change_type, ArticleChangeType::Modified);
+
+        // Off by default
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        assert!(changes[0].clause_changes.is_none());
+
+        // Paragraphs (款) of an article without items align too
+        let old_text = "第十条 网络运营者应当建立管理制度。\n违反前款规定的，由主管部门责令改正。";
+        let new_text = "第十条 网络运营者应当建立管理制度。\n网络运营者应当定期开展演练。\n违反前两款规定的，由主管部门责令改正。";
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        let clauses = changes[0].clause_changes.as_ref().expect("paragraph changes");
+        let types: Vec<_> = clauses.iter().map(|c| c.change_type.clone()).collect();
+        assert_eq!(types, vec![ArticleChangeType::Unchanged, ArticleChangeType::Added, ArticleChangeType::Renumbered]);
+        assert!(clauses[2].details.is_some());
+    }
+
+    #[test]
+    fn test_consolidated_merge() {
+        let old_text = "第二十条 网络运营者应当制定网络安全事件应急预案。\n\
+                        第二十一条 网络运营者应当定期组织应急演练。";
+        let new_text = "第二十二条 网络运营者应当制定网络安全事件应急预案，定期组织应急演练，并向主管部门报告演练情况，演练记录保存不少于三年，发现问题及时整改完善预案内容，具体办法由国务院规定。";
+
+        // Strict threshold so neither source is taken as a plain 1:1 match first
+        let per_source = align_articles(old_text, new_text, 1.0, false);
+        let merged: Vec<_> = per_source.iter().filter(|c| c.change_type == ArticleChangeType::Merged).collect();
+        assert_eq!(merged.len(), 2);
+
+        let options = CompareOptions { align_threshold: 1.0, consolidate_merges: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change_type, ArticleChangeType::Merged);
+
+        let sources: Vec<&str> = changes[0].merged_from.as_ref().unwrap().iter().map(|a| a.number.as_ref()).collect();
+        assert_eq!(sources, vec!["二十", "二十一"]);
+        assert_eq!(changes[0].old_article.as_ref().unwrap().number.as_ref(), "二十");
+    }
+
+    #[test]
+    fn test_split_into_many_fragments() {
+        let old_text = "第九条 网络运营者须制定网络安全事件应急预案，定期组织应急演练，\
+                        及时处置系统漏洞和计算机病毒，留存网络日志不少于六个月，\
+                        并对重要数据进行备份和加密。";
+        let new_text = "第十条 网络运营者须制定网络安全事件应急预案。\n\
+                        第十一条 网络运营者须定期组织应急演练。\n\
+                        第十二条 网络运营者须及时处置系统漏洞和计算机病毒。\n\
+                        第十三条 网络运营者须留存网络日志不少于六个月。\n\
+                        第十四条 网络运营者须对重要数据进行备份和加密。";
+
+        let changes = align_articles(old_text, new_text, 1.0, false);
+        let split = changes.iter().find(|c| c.change_type == ArticleChangeType::Split).expect("split detected");
+        let numbers: Vec<&str> = split.new_articles.as_ref().unwrap().iter().map(|a| a.number.as_ref()).collect();
+        assert_eq!(numbers, vec!["十", "十一", "十二", "十三", "十四"]);
+    }
+
+    #[test]
+    fn test_table_changes() {
+        let old_text = "第一条 违反本法的，按下表处罚：\n违法行为｜罚款\n未登记｜一万元\n逾期｜五千元\n第二条 本法自公布之日起施行。";
+        let new_text = "第一条 违反本法的，按下表处罚：\n违法行为｜罚款\n未登记｜两万元\n逾期｜五千元\n第二条 本法自公布之日起施行。";
+        let mut options = CompareOptions::default();
+        options.parser.detect_tables = true;
+
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        let first = changes.iter().find(|c| c.change_type == ArticleChangeType::Modified).expect("modified article");
+        let cells = first.table_changes.as_ref().expect("table changes");
+        assert_eq!(cells.len(), 1);
+        assert_eq!((cells[0].table, cells[0].new_row, cells[0].column), (1, Some(2), 2));
+        assert_eq!(cells[0].new_value.as_deref(), Some("两万元"));
+        assert!(first.new_articles.as_ref().unwrap()[0].content.contains("未登记｜两万元"));
+
+        // Without table detection the change is only visible as text
+        let changes = align_articles_with_options(old_text, new_text, &CompareOptions::default()).changes;
+        assert!(changes.iter().all(|c| c.table_changes.is_none()));
+    }
+
+    #[test]
+    fn test_toc_mismatch_warning() {
+        let old_text = "目录\n第一章 总则……1\n第二章 附则……2\n第一章 总则\n第一条 内容。\n第二章 附则\n第二条 施行。";
+        let new_text = "目录\n第一章 总则……1\n第二章 法律责任……2\n第一章 总则\n第一条 内容。\n第二章 附则\n第二条 施行。";
+
+        let result = align_articles_with_options(old_text, new_text, &CompareOptions::default());
+        let toc_warnings: Vec<&String> = result.warnings.iter().filter(|w| w.starts_with(TOC_MISMATCH_WARNING)).collect();
+        assert_eq!(toc_warnings.len(), 1, "{:?}", toc_warnings);
+        assert!(toc_warnings[0].ends_with("in the new text"));
+        assert!(toc_warnings[0].contains("\"法律责任\" in the 目录 but \"附则\" in the body"));
+
+        // The edited 目录 entry still surfaces through the preamble unit
+        let preamble = result.changes.iter().find(|c| c.change_type == ArticleChangeType::Preamble).unwrap();
+        assert_eq!(preamble.old_article.as_ref().unwrap().content.as_ref(), "目录\n第一章 总则\n第二章 附则");
+        assert!(preamble.new_articles.as_ref().unwrap()[0].content.contains("第二章 法律责任"));
+    }
+
+    #[test]
+    fn test_deferred_article_content() {
+        let old_text = "第五条\n　　国家支持网络安全技术研究开发。\n第六条 网络运营者应当制定应急预案。";
+        let new_text = "第五条 国家支持网络安全技术研究开发。\n第六条\n第七条 网络运营者应当制定应急预案。";
+
+        let result = align_articles_with_options(old_text, new_text, &CompareOptions::default());
+        assert!(result.warnings.iter().any(|w| w.starts_with(EMPTY_ARTICLE_WARNING) && w.contains("第六条")));
+
+        let options = CompareOptions { attach_deferred_content: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        let fifth = changes.iter()
+            .find(|c| c.old_article.as_ref().is_some_and(|a| a.number.as_ref() == "五"))
+            .unwrap();
+        assert_eq!(fifth.old_article.as_ref().unwrap().content.as_ref(), "国家支持网络安全技术研究开发。");
+        assert_eq!(fifth.change_type, ArticleChangeType::Unchanged);
+    }
+
+    #[test]
+    fn test_similarity_pairs() {
+        let old_text = "第一条 网络运营者应当制定网络安全事件应急预案。\n第二条 国家支持网络安全技术研究开发。";
+        let new_text = "第一条 国家支持网络安全技术研究开发。\n第二条 网络运营者应当制定网络安全事件应急预案，并定期演练。";
+
+        let pairs = similarity_pairs(old_text, new_text, &CompareOptions::default());
+        let find = |old: &str, new: &str| pairs.iter()
+            .find(|p| p.old_number.as_ref() == old && p.new_number.as_ref() == new);
+
+        let moved = find("二", "一").expect("identical pair listed");
+        assert!(moved.score.composite > 0.95);
+        assert!(moved.score.char_similarity > 0.99);
+        assert!(moved.score.jaccard_similarity > 0.99);
+
+        let edited = find("一", "二").expect("edited pair listed");
+        assert!(edited.score.containment_similarity > edited.score.char_similarity);
+        assert!(pairs.windows(2).all(|w| w[0].score.composite >= w[1].score.composite));
+
+        // A floor of 1.0 keeps only identical pairs
+        let options = CompareOptions { similarity_floor: 1.0, ..Default::default() };
+        assert!(similarity_pairs(old_text, new_text, &options).len() <= 1);
+    }
+
+    #[test]
+    fn test_simplify_change_types() {
+        let old_text = "第一条 为了保障网络安全，制定本法。\n第二条 网络运营者应当制定网络安全事件应急预案。";
+        let new_text = "第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第三条 网络运营者应当制定网络安全事件应急预案。";
+
+        let detailed = align_articles(old_text, new_text, 0.6, false);
+        assert!(detailed.iter().any(|c| c.change_type == ArticleChangeType::Modified));
+        assert!(detailed.iter().any(|c| c.change_type == ArticleChangeType::Renumbered));
+
+        let options = CompareOptions { simplify_change_types: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|c| c.change_type == ArticleChangeType::Changed));
+        assert!(changes[0].tags.contains(&"modified".to_string()));
+        assert!(changes[1].tags.contains(&"renumbered".to_string()));
+
+        // Clause changes under a moved article are simplified as well
+        let old_text = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n第二条 网络运营者应当履行下列义务：\n（一）制定内部安全管理制度和操作规程；\n（二）采取防范网络攻击的技术措施。\n\
+                        第二章 网络安全支持与促进\n第三条 国家建立和完善网络安全标准体系。\n第四条 国家支持网络安全技术研究开发。";
+        let new_text = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n\
+                        第二章 网络安全支持与促进\n第二条 网络运营者应当履行下列义务：\n（一）制定内部安全管理制度、操作规程和应急预案；\n（二）采取防范网络攻击的技术措施。\n第三条 国家建立和完善网络安全标准体系。\n第四条 国家支持网络安全技术研究开发。";
+        let options = CompareOptions { clause_alignment: true, ..options };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        let moved = changes.iter().find(|c| c.change_type == ArticleChangeType::Moved).unwrap();
+        let clause_changes = moved.clause_changes.as_ref().unwrap();
+        assert!(clause_changes.iter().any(|c| c.change_type == ArticleChangeType::Changed));
+        assert!(!clause_changes.iter().any(|c| matches!(c.change_type, ArticleChangeType::Modified | ArticleChangeType::Renumbered)));
+    }
+
+    #[test]
+    fn test_bis_article_sorted_between_neighbors() {
+        let old_text = "第二十九条 国家支持网络安全技术研究开发。\n第三十条 网络运营者应当制定应急预案。";
+        let new_text = "第二十九条 国家支持网络安全技术研究开发。第二十九条之一 国家鼓励开发网络数据安全保护和利用技术。\n第三十条 网络运营者应当制定应急预案。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        let order: Vec<&str> = changes.iter()
+            .map(|c| c.new_articles.as_ref().unwrap()[0].number.as_ref())
+            .collect();
+        assert_eq!(order, vec!["二十九", "二十九之一", "三十"]);
+        assert_eq!(changes[1].change_type, ArticleChangeType::Added);
+    }
+
+    #[test]
+    fn test_annex_aligned_as_own_unit() {
+        let old_text = "第一条 登记机关应当公示收费项目。\n第二条 本办法自公布之日起施行。\n附件一：收费标准\n一、登记费每件五十元";
+        let new_text = "第一条 登记机关应当公示收费项目。\n第二条 本办法自公布之日起施行。\n附件一：收费标准\n一、登记费每件八十元";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        assert_eq!(changes.len(), 3);
+        let annex = changes.last().unwrap();
+        assert_eq!(annex.old_article.as_ref().unwrap().node_type, NodeType::Annex);
+        assert_eq!(annex.new_articles.as_ref().unwrap()[0].number.as_ref(), "附件一");
+        assert_eq!(annex.change_type, ArticleChangeType::Modified);
+        // The last article no longer absorbs the annex text
+        assert_eq!(changes[1].old_article.as_ref().unwrap().content.as_ref(), "本办法自公布之日起施行。");
+    }
+
+    #[test]
+    fn test_articles_carry_source_positions() {
+        let old_text = "示例法\n\n第一条 内容。第二条 旧内容。";
+        let new_text = "示例法\n第一条 内容。\n\n第二条 新内容。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        let modified = changes.iter().find(|c| c.change_type == ArticleChangeType::Modified).unwrap();
+        // The old second article was glued onto line 3 of the submitted text
+        let old_source = modified.old_article.as_ref().unwrap().source.unwrap();
+        assert_eq!((old_source.line, old_source.offset), (3, 12));
+        let new_source = modified.new_articles.as_ref().unwrap()[0].source.unwrap();
+        assert_eq!(new_source.line, 4);
+    }
+
+    #[cfg(feature = "opencc")]
+    #[test]
+    fn test_traditional_text_compared_as_simplified() {
+        let old_text = "第一条 网络运营者应当履行安全保护义务。";
+        let new_text = "第一條 網絡運營者應當履行安全保護義務。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        assert_ne!(changes[0].change_type, ArticleChangeType::Unchanged);
+
+        let options = CompareOptions { to_simplified: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change_type, ArticleChangeType::Unchanged);
+    }
+
+    #[test]
+    fn test_modified_articles_carry_word_details() {
+        let changes = align_articles("第一条 违反本法规定的，处一万元以下罚款。\n第二条 内容。", "第一条 违反本法规定的，处五万元以下罚款。\n第二条 内容。", 0.6, false);
+        let modified = changes.iter().find(|c| c.change_type == ArticleChangeType::Modified).unwrap();
+        let details = modified.details.as_ref().unwrap();
+        assert!(details.iter().any(|d| d.change_type == crate::models::ChangeType::Modify));
+        assert!(changes.iter().filter(|c| c.change_type == ArticleChangeType::Unchanged).all(|c| c.details.is_none()));
+    }
+
+    #[test]
+    fn test_sentence_details_for_long_articles() {
+        let old_text = "第一条 网络运营者应当履行下列义务：制定内部安全管理制度；采取防范计算机病毒的技术措施；按照规定留存网络日志。";
+        let new_text = "第一条 网络运营者应当履行下列义务：制定内部安全管理制度；采取监测、记录网络运行状态的技术措施；按照规定留存网络日志。";
+        let options = CompareOptions { granularity: crate::models::Granularity::Sentence, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+
+        let details = changes[0].details.as_ref().unwrap();
+        let modified: Vec<_> = details.iter().filter(|d| d.change_type != crate::models::ChangeType::Unchanged).collect();
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0].old_content.as_deref(), Some("采取防范计算机病毒的技术措施；"));
+    }
+
+    #[test]
+    fn test_format_only_changes() {
+        let old_text = "第一条 网络运营者，网络服务提供者应当遵守本法。\n第二条 国家支持网络安全技术研究。";
+        let new_text = "第一条 网络运营者、网络服务提供者应当遵守本法。\n第二条 国家鼓励网络安全技术研究。";
+
+        let options = CompareOptions { ignore_punctuation: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes[0].change_type, ArticleChangeType::Unchanged);
+        assert_eq!(changes[0].tags, vec!["cosmetic-only".to_string()]);
+        assert_eq!(changes[1].change_type, ArticleChangeType::Modified);
+
+        // Whitespace alone is not enough for a punctuation swap
+        let options = CompareOptions { ignore_whitespace: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_ne!(changes[0].change_type, ArticleChangeType::Unchanged);
+
+        // cosmetic_as_unchanged wins over the narrower flag
+        let options = CompareOptions { ignore_whitespace: true, cosmetic_as_unchanged: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, new_text, &options).changes;
+        assert_eq!(changes[0].change_type, ArticleChangeType::Unchanged);
+        assert_eq!(changes[1].change_type, ArticleChangeType::Modified);
+
+        // An edit touching both classes needs both flags
+        let spaced = "第一条 网络运营者、网络服务提供者 应当遵守本法。\n第二条 国家鼓励网络安全技术研究。";
+        let options = CompareOptions { ignore_punctuation: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, spaced, &options).changes;
+        assert_ne!(changes[0].change_type, ArticleChangeType::Unchanged);
+        let options = CompareOptions { ignore_punctuation: true, ignore_whitespace: true, ..Default::default() };
+        let changes = align_articles_with_options(old_text, spaced, &options).changes;
+        assert_eq!(changes[0].change_type, ArticleChangeType::Unchanged);
+    }
+
+    #[test]
+    fn test_chapter_stats() {
+        let old_text = "第一章 总则\n第一条 为了规范管理，制定本法。\n第二章 法律责任\n第二条 违反本法规定的，处一万元以下罚款。\n第三条 情节严重的，吊销许可证。";
+        let new_text = "第一章 总则\n第一条 为了规范管理，制定本法。\n第二章 法律责任\n第二条 违反本法规定的，处五万元以下罚款。\n第三条 情节严重的，吊销许可证。\n第四条 拒不改正的，责令停业整顿。";
+
+        let options = CompareOptions::default();
+        let result = align_articles_with_options(old_text, new_text, &options);
+        let labels: Vec<&str> = result.chapter_stats.iter().map(|s| s.chapter.as_ref()).collect();
+        assert_eq!(labels, vec!["第一章 总则", "第二章 法律责任"]);
+        assert_eq!(result.chapter_stats[0].counts.values().sum::<usize>(), 1);
+        let second = &result.chapter_stats[1].counts;
+        assert_eq!((second.values().sum::<usize>(), second.get(&ArticleChangeType::Added)), (3, Some(&1)));
+
+        let counts = count_by_type(&result.changes);
+        assert_eq!(counts.values().sum::<usize>(), 4);
+        let json = serde_json::to_value(&counts).unwrap();
+        assert_eq!(json["added"], 1);
+    }
+
+    #[test]
+    fn test_optimal_alignment_strategy() {
+        // Two near-identical articles swap places; the order-preserving pass pairs them
+        // with each other's successor
+        let old = "第一条 县级以上人民政府应当加强对食品安全工作的领导，建立健全食品安全监督管理工作机制。\n第二条 县级以上人民政府应当加强对药品安全工作的领导，建立健全药品安全监督管理工作机制。";
+        let new = "第一条 县级以上人民政府应当加强对药品安全工作的统一领导，建立健全药品安全监督管理工作机制。\n第二条 县级以上人民政府应当加强对食品安全工作的统一领导，建立健全食品安全监督管理工作机制。";
+        let pairs = |strategy: AlignmentStrategy| -> Vec<(String, String, ArticleChangeType)> {
+            let options = CompareOptions { alignment_strategy: strategy, ..Default::default() };
+            align_articles_with_options(old, new, &options).changes.iter()
+                .map(|c| (
+                    c.old_article.as_ref().map(|a| a.number.to_string()).unwrap_or_default(),
+                    c.new_articles.as_ref().map(|l| l[0].number.to_string()).unwrap_or_default(),
+                    c.change_type.clone(),
+                ))
+                .collect()
+        };
+        let pair = |old: &str, new: &str, change_type| (old.to_string(), new.to_string(), change_type);
+        assert_eq!(pairs(AlignmentStrategy::Heuristic).len(), 2);
+        // The swap leaves one of the two out of order
+        assert_eq!(pairs(AlignmentStrategy::Optimal), vec![
+            pair("二", "一", ArticleChangeType::Renumbered),
+            pair("一", "二", ArticleChangeType::Moved),
+        ]);
+    }
+
+    #[test]
+    fn test_manual_anchors() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。\n第三条 国家支持网络安全技术研究开发。";
+        let new = "第一条 为了保障网络安全，制定本法。\n第二条 国家支持网络安全技术研究开发。\n第三条 国家建立和完善网络安全标准体系。";
+        let anchor = |old: &str, new: &str| ArticleAnchor { old_number: old.to_string(), new_number: new.to_string() };
+        let options = CompareOptions {
+            anchors: vec![anchor("第二条", "2"), anchor("第九条", "三")],
+            ..Default::default()
+        };
+        let result = align_articles_with_options(old, new, &options);
+
+        let anchored: Vec<_> = result.changes.iter().filter(|c| c.tags.contains(&"anchored".to_string())).collect();
+        assert_eq!(anchored.len(), 1);
+        assert_eq!(anchored[0].old_article.as_ref().unwrap().number.as_ref(), "二");
+        assert_eq!(anchored[0].new_articles.as_ref().unwrap()[0].number.as_ref(), "二");
+
+        // The rest aligns around the anchor: 第一条 still pairs with 第一条
+        let first = result.changes.iter().find(|c| c.old_article.as_ref().is_some_and(|a| a.number.as_ref() == "一")).unwrap();
+        assert_eq!(first.change_type, ArticleChangeType::Unchanged);
+
+        let warnings: Vec<_> = result.warnings.iter().filter(|w| w.starts_with(UNMATCHED_ANCHOR_WARNING)).collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("第九条"));
+    }
+
+    #[test]
+    fn test_score_detail() {
+        let old = "第一条 网络运营者应当建立安全管理制度。\n第二条 本法自公布之日起施行。";
+        let new = "第一条 网络运营者应当建立健全安全管理制度。";
+        let changes = align_articles(old, new, 0.6, false);
+
+        let modified = changes.iter().find(|c| c.change_type == ArticleChangeType::Modified).unwrap();
+        let detail = modified.score_detail.as_ref().expect("1:1 matches carry the score breakdown");
+        assert_eq!(Some(detail.composite), modified.similarity);
+        assert!(detail.containment_similarity > detail.jaccard_similarity);
+
+        let deleted = changes.iter().find(|c| c.change_type == ArticleChangeType::Deleted).unwrap();
+        assert!(deleted.score_detail.is_none());
+    }
+
+    #[test]
+    fn test_lsh_pruned_alignment_matches_full() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。\n\
+                   第三条 国家支持网络安全技术研究开发。\n第四条 网络运营者应当建立健全用户信息保护制度。";
+        let new = "第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第二条 国家支持网络安全技术研究开发。\n\
+                   第三条 国家建立和完善网络安全标准体系。\n第四条 关键信息基础设施的运营者应当进行国家安全审查。";
+        let summary = |options: &CompareOptions| -> Vec<(ArticleChangeType, Option<String>, Option<String>)> {
+            align_articles_with_options(old, new, options).changes.iter()
+                .map(|c| (
+                    c.change_type.clone(),
+                    c.old_article.as_ref().map(|a| a.number.to_string()),
+                    c.new_articles.as_ref().map(|l| l[0].number.to_string()),
+                ))
+                .collect()
+        };
+
+        let pruned = CompareOptions { lsh_min_cells: 0, ..Default::default() };
+        assert_eq!(summary(&pruned), summary(&CompareOptions::default()));
+
+        // SimHash skips unrelated pairs of the bundled example laws without changing the result
+        let (old, new) = (include_str!("../../examples/origin.txt"), include_str!("../../examples/now.txt"));
+        let summary = |options: &CompareOptions| -> Vec<(ArticleChangeType, Option<String>)> {
+            align_articles_with_options(old, new, options).changes.iter()
+                .map(|c| (c.change_type.clone(), c.old_article.as_ref().map(|a| a.number.to_string())))
+                .collect()
+        };
+        assert_eq!(summary(&pruned), summary(&CompareOptions::default()));
+    }
+
+    #[test]
+    fn test_change_order() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。\n第三条 本法自公布之日起施行。";
+        let new = "第一条 国家建立和完善网络安全标准体系。\n第二条 网络运营者应当建立健全用户信息保护制度。\n第三条 本法自公布之日起施行。";
+        let order = |sort_by: ChangeOrder| -> Vec<ArticleChangeType> {
+            let options = CompareOptions { sort_by, ..Default::default() };
+            align_articles_with_options(old, new, &options).changes.into_iter().map(|c| c.change_type).collect()
+        };
+
+        use ArticleChangeType::*;
+        assert_eq!(order(ChangeOrder::New), vec![Renumbered, Deleted, Added, Unchanged]);
+        assert_eq!(order(ChangeOrder::Old), vec![Deleted, Renumbered, Added, Unchanged]);
+        assert_eq!(order(ChangeOrder::Type), vec![Added, Deleted, Renumbered, Unchanged]);
+        assert_eq!(order(ChangeOrder::Similarity), vec![Deleted, Added, Renumbered, Unchanged]);
+    }
+
+    #[test]
+    fn test_needs_review() {
+        let old = "第一条 网络运营者应当建立健全用户信息保护制度，加强对用户个人信息的保护。\n第二条 国家建立和完善网络安全标准体系。";
+        let new = "第一条 网络运营者应当建立健全用户信息保护制度，加强对用户个人信息安全的保护。\n第二条 网络运营者应当建立健全用户信息保护制度，加强对用户个人信息隐私的保护。\n第三条 国家建立和完善网络安全标准体系。";
+        let changes = align_articles_with_options(old, new, &CompareOptions::default()).changes;
+
+        let first = changes.iter().find(|c| c.old_article.as_ref().is_some_and(|a| a.number.as_ref() == "一")).unwrap();
+        assert!(first.needs_review);
+        let runner_up = first.runner_up.as_ref().unwrap();
+        assert_eq!(runner_up.side, RunnerUpSide::New);
+        assert_eq!(runner_up.article.number.as_ref(), "一");
+
+        // An exact match with no close competitor is not flagged
+        let second = changes.iter().find(|c| c.old_article.as_ref().is_some_and(|a| a.number.as_ref() == "二")).unwrap();
+        assert!(!second.needs_review && second.runner_up.is_none());
+    }
+
+    #[test]
+    fn test_cached_realignment() {
+        let subjects = ["网络运营者", "电信业务经营者", "关键信息基础设施的运营者", "国家网信部门", "有关主管部门"];
+        let duties = ["建立健全用户信息保护制度", "制定网络安全事件应急预案", "对重要系统和数据库进行容灾备份", "加强对公共通信和信息服务的保护", "按照规定留存相关的网络日志"];
+        let old: String = (1..=25).map(|i| format!("第{}条 {}应当{}，第{}项规定的除外。\n", i, subjects[i % 5], duties[(i / 5) % 5], i)).collect();
+        let new = old.replace("第7条", "第7条 新增内容。\n第7条之一").replace("应当建立健全用户信息保护制度，第3项", "应当建立用户信息保护制度，第3项");
+
+        let summary = |old: &str, new: &str, options: &CompareOptions| -> Vec<(ArticleChangeType, Option<String>, Option<f32>)> {
+            align_articles_with_options(old, new, options).changes.into_iter()
+                .map(|c| (c.change_type, c.old_article.map(|a| a.number.to_string()), c.similarity))
+                .collect()
+        };
+
+        // Prime the cache, then re-run with a new threshold and after an edit
+        let hits = || crate::diff::cache::LOOKUP_HITS.with(|hits| hits.get());
+        let options = CompareOptions::default();
+        summary(&old, &old, &options);
+        let primed = hits();
+        let strict = CompareOptions { align_threshold: 0.9, ..Default::default() };
+        let cached_strict = summary(&old, &new, &strict);
+        let cached = summary(&old, &new, &options);
+        assert!(hits() >= primed + 2, "re-runs did not hit the cache");
+
+        // A keyword absent from both texts scores the same under a config nothing cached
+        let uncached = |options: CompareOptions| CompareOptions { similarity_keywords: Some(vec!["不存在的关键词".to_string()]), ..options };
+        assert_eq!(cached, summary(&old, &new, &uncached(CompareOptions::default())));
+        assert_eq!(cached_strict, summary(&old, &new, &uncached(strict)));
+    }
+
+    #[test]
+    fn test_time_budget_truncates() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。";
+        let new = "第一条 为了保障网络安全，维护网络空间主权，制定本法。\n第二条 国家建立和完善网络安全标准体系。";
+
+        let expired = CompareOptions { time_budget_ms: Some(0), ..Default::default() };
+        let result = align_articles_with_options(old, new, &expired);
+        assert!(result.truncated);
+        assert!(result.warnings.iter().any(|w| w.starts_with(TRUNCATED_ALIGNMENT_WARNING)));
+        assert!(result.changes.iter().all(|c| matches!(c.change_type, ArticleChangeType::Added | ArticleChangeType::Deleted)));
+
+        let cancelled = CompareOptions::default();
+        cancelled.cancel.cancel();
+        assert!(align_articles_with_options(old, new, &cancelled).truncated);
+
+        let generous = CompareOptions { time_budget_ms: Some(60_000), ..Default::default() };
+        let result = align_articles_with_options(old, new, &generous);
+        assert!(!result.truncated && result.warnings.is_empty());
+        assert_eq!(result.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_nearest_candidates() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 网络运营者应当按照网络安全等级保护制度的要求，履行安全保护义务。\n第三条 本法自公布之日起施行。";
+        let new = "第一条 为了保障网络安全，制定本法。\n第三条 运营者须依照等级保护的规定，落实保护责任，防范网络攻击。\n第四条 本法自公布之日起施行。";
+        let changes = align_articles_with_options(old, new, &CompareOptions::default()).changes;
+
+        let deleted = changes.iter().find(|c| c.change_type == ArticleChangeType::Deleted).unwrap();
+        let nearest = deleted.nearest.as_ref().unwrap();
+        assert!(!nearest.is_empty() && nearest.len() <= 2);
+        assert_eq!(nearest[0].side, RunnerUpSide::New);
+        assert_eq!(nearest[0].article.number.as_ref(), "三");
+        assert!(nearest.windows(2).all(|w| w[0].similarity >= w[1].similarity));
+
+        let added = changes.iter().find(|c| c.change_type == ArticleChangeType::Added).unwrap();
+        assert_eq!(added.nearest.as_ref().unwrap()[0].article.number.as_ref(), "二");
+        assert!(changes.iter().filter(|c| c.change_type == ArticleChangeType::Unchanged).all(|c| c.nearest.is_none()));
+    }
+
+    #[test]
+    fn test_preamble_modes() {
+        let old = "中华人民共和国网络安全法\n（2016年11月7日通过）\n第一条 为了保障网络安全，制定本法。\n第二条 本法自公布之日起施行。";
+        let new = "中华人民共和国网络安全法\n（2025年10月28日修正）\n第一条 为了保障网络安全，制定本法。\n第二条 本法自公布之日起施行。";
+        let is_preamble = |c: &crate::models::ArticleChange| c.change_type == ArticleChangeType::Preamble;
+
+        let aligned = align_articles_with_options(old, new, &CompareOptions::default());
+        assert!(aligned.changes.iter().any(is_preamble));
+        assert!(aligned.preamble_changes.is_empty());
+
+        let skipped = align_articles_with_options(old, new, &CompareOptions { preamble: PreambleMode::Skip, ..Default::default() });
+        assert_eq!(skipped.changes.len(), 2);
+        assert!(!skipped.changes.iter().any(is_preamble) && skipped.preamble_changes.is_empty());
+
+        let separate = align_articles_with_options(old, new, &CompareOptions { preamble: PreambleMode::Separate, ..Default::default() });
+        assert_eq!(separate.changes.len(), 2);
+        assert_eq!(separate.preamble_changes.len(), 1);
+        assert!(is_preamble(&separate.preamble_changes[0]));
+        assert_eq!(average_similarity(&separate.changes), 1.0);
+    }
+
+    #[test]
+    fn test_cross_law_mapping() {
+        let cybersecurity = "第一条 为了保障网络安全，维护网络空间主权和国家安全，制定本法。\n\
+                             第二条 在中华人民共和国境内建设、运营、维护和使用网络，适用本法。\n\
+                             第三条 国家建立和完善网络安全标准体系。\n\
+                             第四条 本法自2017年6月1日起施行。";
+        let data_security = "第一条 为了规范数据处理活动，保障数据安全，维护国家主权、安全和发展利益，制定本法。\n\
+                             第二条 国家建立数据分类分级保护制度。\n\
+                             第三条 在中华人民共和国境内开展数据处理活动，适用本法。\n\
+                             第四条 国家支持数据安全标准体系建设。\n\
+                             第五条 本法自2021年9月1日起施行。";
+        let options = CompareOptions { cross_law: true, ..Default::default() };
+        let changes = align_articles_with_options(cybersecurity, data_security, &options).changes;
+
+        let pairs: Vec<(&str, &str)> = changes.iter()
+            .filter(|c| c.change_type == ArticleChangeType::Corresponding)
+            .map(|c| (c.old_article.as_ref().unwrap().number.as_ref(), c.new_articles.as_ref().unwrap()[0].number.as_ref()))
+            .collect();
+        assert!(pairs.contains(&("二", "三")) && pairs.contains(&("四", "五")));
+        assert!(changes.iter().all(|c| matches!(c.change_type, ArticleChangeType::Corresponding | ArticleChangeType::Added | ArticleChangeType::Deleted)));
+        assert!(changes.iter().filter(|c| c.change_type == ArticleChangeType::Corresponding).all(|c| c.tags.contains(&"corresponding".to_string())));
+        // Figures and 前条 references of another law are not edits of these
+        assert!(changes.iter().all(|c| c.value_changes.is_none() && !c.tags.iter().any(|t| t.starts_with("value-") || t.starts_with("reference-"))));
+    }
+
+    #[test]
+    fn test_alignment_quality() {
+        let old = "第一条 为了保障网络安全，维护网络空间主权和国家安全，制定本法。\n\
+                   第二条 国家建立和完善网络安全标准体系。\n\
+                   第三条 网络运营者应当履行网络安全保护义务。";
+        let new = "第一条 为了保障网络安全，维护网络空间主权和国家安全，制定本法。\n\
+                   第二条 国家建立和完善网络安全标准体系。\n\
+                   第三条 本法自二〇一七年六月一日起施行。\n\
+                   第四条 县级以上地方人民政府有关部门负责本行政区域内的监督管理工作。";
+
+        let quality = align_articles_with_options(old, old, &CompareOptions::default()).quality;
+        assert_eq!(quality.matched_ratio, 1.0);
+        assert_eq!(quality.average_match_similarity, 1.0);
+        assert_eq!(quality.forced_number_matches, 0);
+
+        // 第三条 is kept by number despite its rewrite; 第四条 is new
+        let quality = align_articles_with_options(old, new, &CompareOptions::default()).quality;
+        assert_eq!(quality.forced_number_matches, 1);
+        assert!((quality.matched_ratio - 6.0 / 7.0).abs() < 1e-6);
+        assert!(quality.average_match_similarity < 1.0);
+        assert!(quality.matrix_sparsity.is_some_and(|s| s > 0.0 && s < 1.0));
+    }
 }
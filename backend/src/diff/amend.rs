@@ -0,0 +1,279 @@
+use crate::ast::{article_label, parse_article};
+use crate::diff::aligner::flatten_articles;
+use crate::models::{AmendmentInstruction, NodeType};
+use crate::nlp::article_sort_key;
+use crate::nlp::formatter::normalize_legal_text;
+use regex::Regex;
+use std::sync::{Arc, OnceLock};
+
+pub const UNRECOGNIZED_AMENDMENT_WARNING: &str = "unrecognized-amendment";
+pub const UNAPPLIED_AMENDMENT_WARNING: &str = "unapplied-amendment";
+
+static ITEM_PATTERN: OnceLock<Regex> = OnceLock::new();
+static REPLACE_WORDS_PATTERN: OnceLock<Regex> = OnceLock::new();
+static DELETE_WORDS_PATTERN: OnceLock<Regex> = OnceLock::new();
+static REPLACE_PATTERN: OnceLock<Regex> = OnceLock::new();
+static INSERT_PATTERN: OnceLock<Regex> = OnceLock::new();
+static DELETE_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_item_pattern() -> &'static Regex {
+    // Numbered decision items: "一、" at the start of a line
+    ITEM_PATTERN.get_or_init(|| Regex::new(r"(?m)^[ \t　]*[一二三四五六七八九十百]+、").unwrap())
+}
+
+fn get_replace_words_pattern() -> &'static Regex {
+    REPLACE_WORDS_PATTERN.get_or_init(|| {
+        Regex::new(r"将(?:第([一二三四五六七八九十百千零两\d]+(?:之[一二三四五六七八九十]+)?)条)?(?:第[一二三四五六七八九十]+[款项])*中的“([^”]+)”(?:修改|改)为“([^”]*)”").unwrap()
+    })
+}
+
+fn get_delete_words_pattern() -> &'static Regex {
+    DELETE_WORDS_PATTERN.get_or_init(|| {
+        Regex::new(r"删去(?:第([一二三四五六七八九十百千零两\d]+(?:之[一二三四五六七八九十]+)?)条)?(?:第[一二三四五六七八九十]+[款项])*中的“([^”]+)”").unwrap()
+    })
+}
+
+fn get_replace_pattern() -> &'static Regex {
+    REPLACE_PATTERN.get_or_init(|| {
+        Regex::new(r"(?s)^将第([一二三四五六七八九十百千零两\d]+(?:之[一二三四五六七八九十]+)?)条修改为[：:]?\s*“(.*)”").unwrap()
+    })
+}
+
+fn get_insert_pattern() -> &'static Regex {
+    INSERT_PATTERN.get_or_init(|| {
+        Regex::new(r"(?s)^(?:在第([一二三四五六七八九十百千零两\d]+(?:之[一二三四五六七八九十]+)?)条后)?增加一条[，,]?(?:作为第[一二三四五六七八九十百千零两\d]+(?:之[一二三四五六七八九十]+)?条)?[：:]?\s*“(.*)”").unwrap()
+    })
+}
+
+fn get_delete_pattern() -> &'static Regex {
+    DELETE_PATTERN.get_or_init(|| {
+        Regex::new(r"^(?:删去|删除)第([一二三四五六七八九十百千零两\d]+(?:之[一二三四五六七八九十]+)?)条[。；;]?$").unwrap()
+    })
+}
+
+/// Parse an amendment decision (修改决定) into editing instructions.
+///
+/// Each numbered item ("一、…") becomes one instruction. Items that match none of the
+/// supported forms are returned as warnings instead.
+pub fn parse_amendments(decision: &str) -> (Vec<AmendmentInstruction>, Vec<String>) {
+    let starts: Vec<usize> = get_item_pattern().find_iter(decision).map(|m| m.start()).collect();
+    let items: Vec<&str> = if starts.is_empty() {
+        vec![decision]
+    } else {
+        starts.iter().enumerate()
+            .map(|(i, &start)| &decision[start..starts.get(i + 1).copied().unwrap_or(decision.len())])
+            .collect()
+    };
+
+    let mut instructions = Vec::new();
+    let mut warnings = Vec::new();
+    for item in items {
+        let body = get_item_pattern().replace(item, "");
+        let body = body.trim();
+        if body.is_empty() {
+            continue;
+        }
+        match parse_instruction(body) {
+            Some(instruction) => instructions.push(instruction),
+            None => warnings.push(format!("{}: {}", UNRECOGNIZED_AMENDMENT_WARNING, body.lines().next().unwrap_or(body))),
+        }
+    }
+    (instructions, warnings)
+}
+
+fn parse_instruction(body: &str) -> Option<AmendmentInstruction> {
+    if let Some(caps) = get_replace_words_pattern().captures(body) {
+        return Some(AmendmentInstruction::ReplaceWords {
+            article: caps.get(1).map(|m| m.as_str().into()),
+            from: caps[2].into(),
+            to: caps[3].into(),
+        });
+    }
+    if let Some(caps) = get_delete_words_pattern().captures(body) {
+        return Some(AmendmentInstruction::ReplaceWords {
+            article: caps.get(1).map(|m| m.as_str().into()),
+            from: caps[2].into(),
+            to: "".into(),
+        });
+    }
+    if let Some(caps) = get_replace_pattern().captures(body) {
+        return Some(AmendmentInstruction::Replace {
+            article: caps[1].into(),
+            text: unquote(&caps[2]).into(),
+        });
+    }
+    if let Some(caps) = get_insert_pattern().captures(body) {
+        return Some(AmendmentInstruction::Insert {
+            after: caps.get(1).map(|m| m.as_str().into()),
+            text: unquote(&caps[2]).into(),
+        });
+    }
+    get_delete_pattern().captures(body).map(|caps| AmendmentInstruction::Delete { article: caps[1].into() })
+}
+
+/// Quoted article text spans several paragraphs, each wrapped in its own “…”
+fn unquote(text: &str) -> String {
+    text.lines()
+        .map(|l| l.trim().trim_start_matches('“').trim_end_matches('”'))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A stretch of the base text: an article, or whatever lies between articles (headings, preamble)
+enum Segment {
+    Text(String),
+    Article { number: Option<Arc<str>>, text: String },
+}
+
+/// Apply amendment instructions to a base law text, returning the consolidated text and
+/// warnings for instructions that could not be applied.
+///
+/// Article references are resolved against the base numbering, as amendment decisions do;
+/// inserted articles keep the number given in their text and the remaining articles are
+/// not renumbered ("条文顺序作相应调整" is left to the reader).
+pub fn apply_amendments(base_text: &str, instructions: &[AmendmentInstruction]) -> (String, Vec<String>) {
+    let normalized = normalize_legal_text(base_text);
+    let chars: Vec<char> = normalized.chars().collect();
+    let mut articles: Vec<_> = flatten_articles(&parse_article(&normalized))
+        .into_iter()
+        .filter(|a| a.node_type == NodeType::Article)
+        .collect();
+    articles.sort_by_key(|a| a.start_offset);
+
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for article in &articles {
+        let (start, end) = (article.start_offset.max(pos), article.end_offset.min(chars.len()));
+        if start > pos {
+            segments.push(Segment::Text(chars[pos..start].iter().collect()));
+        }
+        segments.push(Segment::Article {
+            number: Some(article.number.clone()),
+            text: chars[start..end.max(start)].iter().collect(),
+        });
+        pos = end.max(start);
+    }
+    if pos < chars.len() {
+        segments.push(Segment::Text(chars[pos..].iter().collect()));
+    }
+
+    let find = |segments: &[Segment], number: &str| {
+        let key = article_sort_key(number);
+        segments.iter().position(|s| {
+            matches!(s, Segment::Article { number: Some(n), .. } if article_sort_key(n) == key)
+        })
+    };
+
+    let mut warnings = Vec::new();
+    for instruction in instructions {
+        let applied = match instruction {
+            AmendmentInstruction::Replace { article, text } => match find(&segments, article) {
+                Some(idx) => {
+                    segments[idx] = Segment::Article { number: Some(article.clone()), text: with_label(article, text) };
+                    true
+                }
+                None => false,
+            },
+            AmendmentInstruction::Delete { article } => match find(&segments, article) {
+                Some(idx) => {
+                    segments.remove(idx);
+                    true
+                }
+                None => false,
+            },
+            AmendmentInstruction::Insert { after, text } => {
+                let idx = match after {
+                    Some(after) => find(&segments, after).map(|idx| idx + 1),
+                    None => Some(segments.len()),
+                };
+                if let Some(idx) = idx {
+                    // Inserted articles are not addressable by later instructions
+                    segments.insert(idx, Segment::Article { number: None, text: text.to_string() });
+                }
+                idx.is_some()
+            }
+            AmendmentInstruction::ReplaceWords { article, from, to } => {
+                let targets: Vec<usize> = match article {
+                    Some(article) => find(&segments, article).into_iter().collect(),
+                    None => (0..segments.len()).collect(),
+                };
+                let mut replaced = false;
+                for idx in targets {
+                    let (Segment::Text(text) | Segment::Article { text, .. }) = &mut segments[idx];
+                    if text.contains(from.as_ref()) {
+                        *text = text.replace(from.as_ref(), to);
+                        replaced = true;
+                    }
+                }
+                replaced
+            }
+        };
+        if !applied {
+            warnings.push(format!("{}: {:?}", UNAPPLIED_AMENDMENT_WARNING, instruction));
+        }
+    }
+
+    let mut result = String::new();
+    for segment in &segments {
+        let (Segment::Text(text) | Segment::Article { text, .. }) = segment;
+        for line in text.lines() {
+            let trimmed = line.trim_end();
+            if !trimmed.is_empty() {
+                result.push_str(trimmed);
+                result.push('\n');
+            }
+        }
+    }
+    (result, warnings)
+}
+
+/// Replacement text may omit the "第X条" heading; it keeps the number it replaces
+fn with_label(number: &str, text: &str) -> String {
+    if text.starts_with('第') {
+        text.to_string()
+    } else {
+        format!("{} {}", article_label(number), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "第一章 总则\n第一条 为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立管理制度。\n第三条 本条将被删除。\n第二章 法律责任\n第四条 违反本法规定的，由有关主管部门处五千元以下罚款。";
+
+    const DECISION: &str = "全国人民代表大会常务委员会决定对《网络安全法》作如下修改：\n一、将第二条修改为：“第二条 网络运营者应当建立健全安全管理制度。”\n二、删去第三条。\n三、在第二条后增加一条，作为第三条：“第三条 国家支持网络安全技术研究。”\n四、将第四条中的“五千元”修改为“一万元”。\n本决定自公布之日起施行。";
+
+    #[test]
+    fn test_parse_amendments() {
+        let (instructions, warnings) = parse_amendments(DECISION);
+        assert_eq!(instructions, vec![
+            AmendmentInstruction::Replace { article: "二".into(), text: "第二条 网络运营者应当建立健全安全管理制度。".into() },
+            AmendmentInstruction::Delete { article: "三".into() },
+            AmendmentInstruction::Insert { after: Some("二".into()), text: "第三条 国家支持网络安全技术研究。".into() },
+            AmendmentInstruction::ReplaceWords { article: Some("四".into()), from: "五千元".into(), to: "一万元".into() },
+        ]);
+        // The trailing sentence belongs to the last item and is ignored there; the preface is not an item
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_apply_amendments_consolidates_text() {
+        let (instructions, _) = parse_amendments(DECISION);
+        let (text, warnings) = apply_amendments(BASE, &instructions);
+
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(text, "第一章 总则\n第一条 为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立健全安全管理制度。\n第三条 国家支持网络安全技术研究。\n第二章 法律责任\n第四条 违反本法规定的，由有关主管部门处一万元以下罚款。\n");
+    }
+
+    #[test]
+    fn test_unapplied_and_unrecognized_instructions_warn() {
+        let (instructions, warnings) = parse_amendments("一、删去第九条。\n二、本法第五章的章名修改为“监督检查”。");
+        assert_eq!(instructions.len(), 1);
+        assert!(warnings[0].starts_with(UNRECOGNIZED_AMENDMENT_WARNING));
+
+        let (_, warnings) = apply_amendments(BASE, &instructions);
+        assert!(warnings[0].starts_with(UNAPPLIED_AMENDMENT_WARNING));
+    }
+}
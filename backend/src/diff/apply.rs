@@ -0,0 +1,137 @@
+use crate::ast::{article_label, article_title_markup, parse_article};
+use crate::diff::aligner::flatten_articles;
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, NodeType};
+use crate::nlp::formatter::normalize_legal_text;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Rebuild the amended document by applying article changes to a base text.
+///
+/// Changes are applied in their given order (the aligner emits them in new-document
+/// order): deleted articles are dropped, unchanged ones are taken from the base text
+/// and everything else is replaced by its new version. Merged articles are emitted once.
+/// Structural headings (编/分编/章/节) are rebuilt from each article's `parents` wherever they
+/// change; their level is inferred from how deep the hierarchy goes.
+pub fn apply_changes(base_text: &str, changes: &[ArticleChange]) -> String {
+    let base_ast = parse_article(&normalize_legal_text(base_text));
+    let base_articles = flatten_articles(&base_ast);
+
+    let mut emitted: HashSet<(Arc<str>, usize)> = HashSet::new();
+    let mut articles: Vec<&ArticleInfo> = Vec::new();
+
+    for change in changes {
+        match change.change_type {
+            ArticleChangeType::Deleted => continue,
+            ArticleChangeType::Unchanged => {
+                let from_base = change.old_article.as_ref().and_then(|old| {
+                    base_articles.iter().find(|a| a.number == old.number && a.node_type == old.node_type)
+                });
+                if let Some(article) = from_base.or_else(|| change.new_articles.as_ref().and_then(|l| l.first())) {
+                    if emitted.insert((article.number.clone(), article.start_line)) {
+                        articles.push(article);
+                    }
+                }
+            }
+            _ => {
+                for article in change.new_articles.iter().flatten() {
+                    if emitted.insert((article.number.clone(), article.start_line)) {
+                        articles.push(article);
+                    }
+                }
+            }
+        }
+    }
+
+    let depth = articles.iter().map(|a| a.parents.len()).max().unwrap_or(0);
+    let mut open: Vec<Arc<str>> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    for article in articles {
+        let common = open.iter().zip(&article.parents).take_while(|(a, b)| a == b).count();
+        for (level, label) in article.parents.iter().enumerate().skip(common) {
+            lines.push(heading_line(label, heading_marker(level, depth)));
+        }
+        lines.push(render_article(article));
+        open = article.parents.clone();
+        // A 附则 unit prints its own heading, which the articles after it share
+        if article.node_type == NodeType::Supplementary {
+            open.push("附则".into());
+        }
+    }
+
+    let mut result = String::new();
+    for line in lines.iter().flat_map(|block| block.lines()) {
+        let trimmed = line.trim_end();
+        if !trimmed.is_empty() {
+            result.push_str(trimmed);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// Heading marker of the parent at `level` in a hierarchy `depth` levels deep: 章 alone, 章/节,
+/// 编/章/节, or 编/分编/章/节
+fn heading_marker(level: usize, depth: usize) -> &'static str {
+    let markers: &[&str] = match depth {
+        0 | 1 => &["章"],
+        2 => &["章", "节"],
+        3 => &["编", "章", "节"],
+        _ => &["编", "分编", "章", "节"],
+    };
+    markers.get(level).copied().unwrap_or("节")
+}
+
+/// Heading line for a parent label as `flatten_articles` builds it ("一 总则", "二", "附则")
+fn heading_line(label: &str, marker: &str) -> String {
+    if label == "附则" {
+        return label.to_string();
+    }
+    match label.split_once(' ') {
+        Some((number, title)) => format!("第{}{} {}", number, marker, title),
+        None => format!("第{}{}", label, marker),
+    }
+}
+
+/// Render a flattened article back to "第X条 …" text
+pub fn render_article(article: &ArticleInfo) -> String {
+    match article.node_type {
+        NodeType::Preamble => return article.content.to_string(),
+        NodeType::Supplementary => return format!("附则\n{}", article.content),
+        NodeType::Annex => {
+            let heading = match &article.title {
+                Some(title) => format!("{}：{}", article.number, title),
+                None => article.number.to_string(),
+            };
+            return format!("{}\n{}", heading, article.content);
+        }
+        _ => {}
+    }
+    let title = article.title.as_deref().map(article_title_markup).unwrap_or_default();
+    format!("{} {}{}", article_label(&article.number), title, article.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_apply_reproduces_new_text() {
+        let old = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立管理制度。\n第三条 本条将被删除。\n第四条 违反本法规定的，依法处罚。";
+        let new = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立健全安全管理制度。\n第三条 违反本法规定的，依法处罚。\n第四条 本法自公布之日起施行。";
+
+        let changes = align_articles(old, new, 0.6, false);
+        let applied = apply_changes(old, &changes);
+
+        assert_eq!(applied, normalize_legal_text(new));
+    }
+
+    #[test]
+    fn test_apply_keeps_headings_and_titles() {
+        let old = "第一章 总则\n第一条 【Purpose 1】为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立管理制度。\n第二章 网络运行安全\n第一节 一般规定\n第三条 国家实行网络安全等级保护制度。\n第二节 关键信息基础设施\n第四条 国家对关键信息基础设施实行重点保护。\n附则\n第五条 本法自公布之日起施行。";
+        let new = "第一章 总则\n第一条 【Purpose 1】为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立健全安全管理制度。\n第二章 网络运行安全\n第一节 一般规定\n第三条 国家实行网络安全等级保护制度。\n第四条 国家鼓励开发网络安全保护技术。\n第二节 关键信息基础设施\n第五条 国家对关键信息基础设施实行重点保护。\n附则\n本法下列用语的含义以国家标准为准。\n第六条 本法自公布之日起施行。";
+
+        let changes = align_articles(old, new, 0.6, false);
+        assert_eq!(apply_changes(old, &changes), normalize_legal_text(new));
+    }
+}
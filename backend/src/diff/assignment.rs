@@ -0,0 +1,95 @@
+/// Pairing of rows to columns with the highest total weight (Hungarian algorithm, O(n²m)
+/// for n rows and m columns, n <= m). Each row gets at most one column and each column at
+/// most one row; pairs of weight zero or less are left out of the result, so a weight of 0
+/// marks a pair that must not be made.
+pub fn max_weight_assignment(weights: &[Vec<f32>]) -> Vec<(usize, usize)> {
+    let rows = weights.len();
+    let cols = weights.first().map_or(0, Vec::len);
+    if rows == 0 || cols == 0 {
+        return Vec::new();
+    }
+    // The algorithm needs no more rows than columns; solve the transpose otherwise
+    if rows > cols {
+        let transposed: Vec<Vec<f32>> = (0..cols).map(|j| (0..rows).map(|i| weights[i][j]).collect()).collect();
+        let mut pairs: Vec<(usize, usize)> = max_weight_assignment(&transposed).into_iter().map(|(j, i)| (i, j)).collect();
+        pairs.sort_unstable();
+        return pairs;
+    }
+
+    // Minimize the negated weights; potentials u (rows) and v (columns), 1-based with a
+    // virtual column 0 holding the row being inserted
+    let cost = |i: usize, j: usize| -(weights[i - 1][j - 1].max(0.0) as f64);
+    let (mut u, mut v) = (vec![0.0f64; rows + 1], vec![0.0f64; cols + 1]);
+    let mut owner = vec![0usize; cols + 1];
+    let mut way = vec![0usize; cols + 1];
+    for row in 1..=rows {
+        owner[0] = row;
+        let mut col = 0;
+        let mut min_slack = vec![f64::INFINITY; cols + 1];
+        let mut visited = vec![false; cols + 1];
+        loop {
+            visited[col] = true;
+            let current = owner[col];
+            let (mut delta, mut next) = (f64::INFINITY, 0);
+            for j in 1..=cols {
+                if visited[j] {
+                    continue;
+                }
+                let slack = cost(current, j) - u[current] - v[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    way[j] = col;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    next = j;
+                }
+            }
+            for j in 0..=cols {
+                if visited[j] {
+                    u[owner[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            col = next;
+            if owner[col] == 0 {
+                break;
+            }
+        }
+        // Flip the augmenting path back to the virtual column
+        while col != 0 {
+            let previous = way[col];
+            owner[col] = owner[previous];
+            col = previous;
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (1..=cols)
+        .filter(|&j| owner[j] != 0 && weights[owner[j] - 1][j - 1] > 0.0)
+        .map(|j| (owner[j] - 1, j - 1))
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_weight_assignment() {
+        // Taking the best pair first (0, 0) would leave row 1 with a useless partner
+        let weights = vec![vec![0.9, 0.8], vec![0.85, 0.0]];
+        assert_eq!(max_weight_assignment(&weights), vec![(0, 1), (1, 0)]);
+
+        // Rectangular either way round; zero-weight pairs stay unassigned
+        let wide = vec![vec![0.0, 0.7, 0.0], vec![0.0, 0.9, 0.6]];
+        assert_eq!(max_weight_assignment(&wide), vec![(0, 1), (1, 2)]);
+        let tall = vec![vec![0.0, 0.0], vec![0.7, 0.9], vec![0.0, 0.6]];
+        assert_eq!(max_weight_assignment(&tall), vec![(1, 0), (2, 1)]);
+        assert!(max_weight_assignment(&[vec![0.0]]).is_empty());
+        assert!(max_weight_assignment(&[]).is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use crate::models::{CancelToken, CompareOptions};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Time budget and cancellation of one alignment, checked between stages and matrix rows
+#[derive(Debug, Default)]
+pub struct Budget {
+    deadline: Option<Instant>,
+    cancel: CancelToken,
+    tripped: AtomicBool,
+}
+
+impl Budget {
+    /// Start the clock on `options.time_budget_ms`, watching `options.cancel`
+    pub fn start(options: &CompareOptions) -> Self {
+        Self {
+            deadline: options.time_budget_ms.map(|ms| Instant::now() + Duration::from_millis(ms)),
+            cancel: options.cancel.clone(),
+            tripped: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the work should stop here: the deadline passed or the token was cancelled
+    pub fn exhausted(&self) -> bool {
+        if self.tripped.load(Ordering::Relaxed) {
+            return true;
+        }
+        let exhausted = self.cancel.is_cancelled() || self.deadline.is_some_and(|d| Instant::now() >= d);
+        if exhausted {
+            self.tripped.store(true, Ordering::Relaxed);
+        }
+        exhausted
+    }
+
+    /// Whether any check found the budget exhausted, i.e. some work was skipped
+    pub fn tripped(&self) -> bool {
+        self.tripped.load(Ordering::Relaxed)
+    }
+}
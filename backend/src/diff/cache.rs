@@ -0,0 +1,195 @@
+use crate::diff::similarity::SimilarityConfig;
+use crate::models::{ArticleInfo, SimilarityScore};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Cells kept across all cached matrices, about 96 MB of scores: one matrix at the default
+/// `max_matrix_cells`, or several of a typical law. The oldest entries go first. Entries hold
+/// no document text, only hashes and scores, so this bounds the cache's memory.
+const CACHE_MAX_CELLS: usize = 4_000_000;
+/// Smaller matrices (clauses, short texts) are cheaper to rebuild than to keep
+pub const CACHE_MIN_CELLS: usize = 400;
+
+static MATRIX_CACHE: OnceLock<Mutex<MatrixCache>> = OnceLock::new();
+
+#[cfg(test)]
+thread_local! {
+    // Lookups that found an entry, per thread so parallel tests do not see each other's
+    pub static LOOKUP_HITS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Similarity matrix of one comparison, keyed by content hashes
+#[derive(Debug)]
+pub struct CachedMatrix {
+    pub config: u64,
+    pub old_keys: Vec<u64>,
+    pub new_keys: Vec<u64>,
+    pub matrix: Arc<Vec<Vec<SimilarityScore>>>, // Shared with the aligner that built it
+}
+
+impl CachedMatrix {
+    fn cells(&self) -> usize {
+        self.old_keys.len() * self.new_keys.len()
+    }
+}
+
+/// Which cached row (old article) and column (new article) each current article can reuse
+pub struct CacheHit {
+    pub entry: Arc<CachedMatrix>,
+    pub old_rows: Vec<Option<usize>>,
+    pub new_cols: Vec<Option<usize>>,
+}
+
+impl CacheHit {
+    /// Cached score of a pair whose articles are both unchanged
+    pub fn score(&self, old_idx: usize, new_idx: usize) -> Option<&SimilarityScore> {
+        Some(&self.entry.matrix[self.old_rows[old_idx]?][self.new_cols[new_idx]?])
+    }
+
+    /// Whether every pair is cached, as when only the threshold changed
+    pub fn is_complete(&self) -> bool {
+        self.old_rows.iter().chain(&self.new_cols).all(Option::is_some)
+    }
+}
+
+/// Recently built similarity matrices. A score depends only on the two articles and the
+/// scoring config, so a re-run with a new threshold reuses the whole matrix and a re-run
+/// after editing a few articles reuses every pair neither side of which changed.
+#[derive(Debug, Default)]
+pub struct MatrixCache {
+    entries: Vec<Arc<CachedMatrix>>,
+}
+
+impl MatrixCache {
+    /// The process-wide cache the aligner uses
+    pub fn shared() -> &'static Mutex<MatrixCache> {
+        MATRIX_CACHE.get_or_init(|| Mutex::new(MatrixCache::default()))
+    }
+
+    /// The entry with the same config sharing the most pairs with `old_keys` x `new_keys`
+    pub fn lookup(&self, config: u64, old_keys: &[u64], new_keys: &[u64]) -> Option<CacheHit> {
+        self.entries.iter()
+            .filter(|entry| entry.config == config)
+            .map(|entry| {
+                let index = |keys: &[u64]| -> HashMap<u64, usize> { keys.iter().enumerate().map(|(i, &k)| (k, i)).collect() };
+                let (old_index, new_index) = (index(&entry.old_keys), index(&entry.new_keys));
+                CacheHit {
+                    entry: entry.clone(),
+                    old_rows: old_keys.iter().map(|k| old_index.get(k).copied()).collect(),
+                    new_cols: new_keys.iter().map(|k| new_index.get(k).copied()).collect(),
+                }
+            })
+            .max_by_key(|hit| {
+                let count = |slots: &[Option<usize>]| slots.iter().filter(|s| s.is_some()).count();
+                count(&hit.old_rows) * count(&hit.new_cols)
+            })
+            .filter(|hit| hit.old_rows.iter().any(Option::is_some) && hit.new_cols.iter().any(Option::is_some))
+            .inspect(|_| {
+                #[cfg(test)]
+                LOOKUP_HITS.with(|hits| hits.set(hits.get() + 1));
+            })
+    }
+
+    /// Keep `entry` as the most recent, replacing any entry for the same documents and
+    /// evicting the oldest beyond `CACHE_MAX_CELLS`; a matrix larger than that is not kept
+    pub fn store(&mut self, entry: CachedMatrix) {
+        if entry.cells() > CACHE_MAX_CELLS {
+            return;
+        }
+        self.entries.retain(|e| e.config != entry.config || e.old_keys != entry.old_keys || e.new_keys != entry.new_keys);
+        self.entries.insert(0, Arc::new(entry));
+        let mut total = 0;
+        let kept = self.entries.iter().take_while(|e| {
+            total += e.cells();
+            total <= CACHE_MAX_CELLS
+        }).count();
+        self.entries.truncate(kept);
+    }
+}
+
+/// Hash of everything an article's scores depend on: number (same-numbered pairs are
+/// never pruned), content and hierarchy (same-heading pairs get a boost)
+pub fn article_key(article: &ArticleInfo) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    article.number.hash(&mut hasher);
+    article.content.hash(&mut hasher);
+    article.parents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of the scoring config, and of whether LSH pruning applies at this matrix size
+pub fn config_key(config: &SimilarityConfig, pruned: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.keywords.hash(&mut hasher);
+    let weights = &config.weights;
+    for weight in [weights.char, weights.jaccard, weights.containment, weights.keyword, weights.cosine] {
+        weight.to_bits().hash(&mut hasher);
+    }
+    pruned.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(config: u64, old_keys: Vec<u64>, new_keys: Vec<u64>) -> CachedMatrix {
+        let matrix = old_keys.iter().map(|&o| new_keys.iter().map(|&n| SimilarityScore::new(o as f32, n as f32, 0.0, 0.0, 0.0)).collect()).collect();
+        CachedMatrix {
+            config,
+            old_keys,
+            new_keys,
+            matrix: Arc::new(matrix),
+        }
+    }
+
+    #[test]
+    fn test_matrix_cache_reuse() {
+        let mut cache = MatrixCache::default();
+        cache.store(entry(1, vec![10, 11, 12], vec![20, 21]));
+
+        // Same documents: every pair is reused
+        let hit = cache.lookup(1, &[10, 11, 12], &[20, 21]).unwrap();
+        assert!(hit.is_complete());
+
+        // One edited old article and one inserted new article
+        let hit = cache.lookup(1, &[10, 99, 12], &[20, 98, 21]).unwrap();
+        assert!(!hit.is_complete());
+        assert_eq!(hit.score(2, 2).map(|s| s.char_similarity), Some(12.0));
+        assert_eq!(hit.score(2, 2).map(|s| s.jaccard_similarity), Some(21.0));
+        assert!(hit.score(1, 0).is_none() && hit.score(0, 1).is_none());
+
+        // Another config or unrelated documents miss
+        assert!(cache.lookup(2, &[10, 11, 12], &[20, 21]).is_none());
+        assert!(cache.lookup(1, &[30], &[40]).is_none());
+
+    }
+
+    #[test]
+    fn test_matrix_cache_capped_by_cells() {
+        // Only the key counts size an entry, so the scores can stay empty
+        let sized = |config: u64, rows: u64, cols: u64| CachedMatrix {
+            config,
+            old_keys: (0..rows).collect(),
+            new_keys: (0..cols).collect(),
+            matrix: Arc::default(),
+        };
+        let mut cache = MatrixCache::default();
+        cache.store(entry(1, vec![10, 11, 12], vec![20, 21]));
+        cache.store(sized(2, 2000, 1500));
+        assert!(cache.lookup(1, &[10], &[20]).is_some());
+        assert!(cache.lookup(2, &[0], &[0]).is_some());
+
+        // 2M more cells push out both older entries
+        cache.store(sized(3, 2000, 1000));
+        assert!(cache.lookup(1, &[10], &[20]).is_none());
+        assert!(cache.lookup(2, &[0], &[0]).is_none());
+        assert!(cache.lookup(3, &[0], &[0]).is_some());
+
+        // A matrix over the cap is never kept
+        cache.store(sized(4, 2001, 2000));
+        assert!(cache.lookup(4, &[0], &[0]).is_none());
+        assert!(cache.lookup(3, &[0], &[0]).is_some());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::diff::similarity::calculate_char_similarity;
+use crate::models::{ArticleChange, ArticleChangeType};
+use regex::Regex;
+use std::sync::OnceLock;
+
+static EXCEPTION_PATTERN: OnceLock<Regex> = OnceLock::new();
+static CONDITION_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// 除……外 / 但……除外 exception clauses
+fn get_exception_pattern() -> &'static Regex {
+    EXCEPTION_PATTERN.get_or_init(|| {
+        Regex::new(r"但[^。；;]*?除外|除[^，,。；;]{1,40}?(?:以)?外").unwrap()
+    })
+}
+
+/// 如果……则 / 若……则 conditional clauses
+fn get_condition_pattern() -> &'static Regex {
+    CONDITION_PATTERN.get_or_init(|| {
+        Regex::new(r"(?:如果|若)[^。；;]*?则").unwrap()
+    })
+}
+
+/// Find exception clauses (除……外, 但……除外) in article content
+pub fn find_exceptions(content: &str) -> Vec<&str> {
+    get_exception_pattern().find_iter(content).map(|m| m.as_str()).collect()
+}
+
+/// Find conditional clauses (如果……则) in article content
+pub fn find_conditions(content: &str) -> Vec<&str> {
+    get_condition_pattern().find_iter(content).map(|m| m.as_str()).collect()
+}
+
+/// Character similarity at which a clause only on the old side and one only on the new
+/// side are taken as the same clause, edited
+const EDITED_CLAUSE_SIMILARITY: f32 = 0.5;
+
+/// Tag matched articles whose exception or conditional structure changed.
+///
+/// These edits narrow or widen an article's scope even when the text stays
+/// highly similar, so they are flagged with "exception-added"/"exception-removed"
+/// and "condition-added"/"condition-removed". A clause reworded in place is paired
+/// with its old wording and flagged "exception-modified"/"condition-modified" instead.
+pub fn annotate_scope_changes(changes: &mut [ArticleChange]) {
+    for change in changes.iter_mut() {
+        if matches!(change.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Preamble) {
+            continue;
+        }
+        let (Some(old_art), Some(new_list)) = (change.old_article.as_ref(), change.new_articles.as_ref()) else {
+            continue;
+        };
+        let new_content: String = new_list.iter().map(|a| a.content.as_ref()).collect::<Vec<_>>().join("\n");
+
+        let mut tags = Vec::new();
+        for (kind, finder) in [("exception", find_exceptions as fn(&str) -> Vec<&str>), ("condition", find_conditions)] {
+            let old_found = finder(&old_art.content);
+            let new_found = finder(&new_content);
+            let added: Vec<&str> = new_found.iter().copied().filter(|c| !old_found.contains(c)).collect();
+            let removed: Vec<&str> = old_found.iter().copied().filter(|c| !new_found.contains(c)).collect();
+
+            // Most similar pairs first, so one rewording does not claim another's counterpart
+            let mut pairs: Vec<(f32, usize, usize)> = removed.iter().enumerate()
+                .flat_map(|(i, old)| added.iter().enumerate().map(move |(j, new)| (calculate_char_similarity(old, new), i, j)))
+                .filter(|&(score, _, _)| score >= EDITED_CLAUSE_SIMILARITY)
+                .collect();
+            pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let (mut paired_old, mut paired_new) = (vec![false; removed.len()], vec![false; added.len()]);
+            for (_, i, j) in pairs {
+                if !paired_old[i] && !paired_new[j] {
+                    paired_old[i] = true;
+                    paired_new[j] = true;
+                }
+            }
+            if paired_new.iter().any(|&p| !p) {
+                tags.push(format!("{}-added", kind));
+            }
+            if paired_old.iter().any(|&p| !p) {
+                tags.push(format!("{}-removed", kind));
+            }
+            if paired_old.iter().any(|&p| p) {
+                tags.push(format!("{}-modified", kind));
+            }
+        }
+        change.tags.extend(tags);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_find_exceptions() {
+        assert_eq!(find_exceptions("除法律另有规定外，应当公开。"), vec!["除法律另有规定外"]);
+        assert_eq!(find_exceptions("应当公开，但涉及国家秘密的除外。"), vec!["但涉及国家秘密的除外"]);
+        assert!(find_exceptions("应当公开。").is_empty());
+    }
+
+    #[test]
+    fn test_exception_added() {
+        let old_text = "第十条 网络运营者收集的个人信息应当向监管部门报送。";
+        let new_text = "第十条 除法律、行政法规另有规定外，网络运营者收集的个人信息应当向监管部门报送。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].tags.contains(&"exception-added".to_string()));
+        assert!(!changes[0].tags.contains(&"exception-removed".to_string()));
+
+        let reverse = align_articles(new_text, old_text, 0.6, false);
+        assert!(reverse[0].tags.contains(&"exception-removed".to_string()));
+    }
+
+    #[test]
+    fn test_exception_modified() {
+        let old_text = "第十条 除法律另有规定外，网络运营者收集的个人信息应当向监管部门报送。";
+        let new_text = "第十条 除法律、行政法规另有规定外，网络运营者收集的个人信息应当向监管部门报送。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        assert!(changes[0].tags.contains(&"exception-modified".to_string()));
+        assert!(!changes[0].tags.iter().any(|t| t == "exception-added" || t == "exception-removed"));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::models::{ArticleChange, Change};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a over `parts`, each followed by a 0xFF byte (never part of UTF-8) so ("ab", "c")
+/// and ("a", "bc") hash apart. Unlike `DefaultHasher` the value is fixed across Rust
+/// releases, so stored links and annotations keep resolving after an upgrade.
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0xFF)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+/// Hex ids for `hashes`; a hash seen before gets "-2", "-3"… in order of appearance, so
+/// identical changes (the same line deleted twice) stay distinguishable
+fn unique_ids(hashes: impl Iterator<Item = u64>) -> Vec<Arc<str>> {
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+    hashes.map(|hash| {
+        let count = seen.entry(hash).or_insert(0);
+        *count += 1;
+        match *count {
+            1 => format!("{:016x}", hash).into(),
+            n => format!("{:016x}-{}", hash, n).into(),
+        }
+    }).collect()
+}
+
+/// Set each change's id from its old and new content. Line numbers are left out, so an
+/// edit keeps its id when text elsewhere in the document changes.
+pub fn assign_change_ids(changes: &mut [Change]) {
+    let ids = unique_ids(changes.iter().map(|c| {
+        fnv1a(&[c.old_content.as_deref().unwrap_or(""), c.new_content.as_deref().unwrap_or("")])
+    }));
+    for (change, id) in changes.iter_mut().zip(ids) {
+        change.id = id;
+    }
+}
+
+/// Set each article change's id from the old and new article numbers and contents, and
+/// the ids of its clause-level changes and details
+pub fn assign_article_change_ids(changes: &mut [ArticleChange]) {
+    let ids = unique_ids(changes.iter().map(|c| {
+        let mut parts: Vec<&str> = Vec::new();
+        for article in c.old_article.iter().chain(c.new_articles.iter().flatten()) {
+            parts.extend([article.number.as_ref(), article.content.as_ref()]);
+        }
+        fnv1a(&parts)
+    }));
+    for (change, id) in changes.iter_mut().zip(ids) {
+        change.id = id;
+        if let Some(clauses) = change.clause_changes.as_mut() {
+            assign_article_change_ids(clauses);
+        }
+        if let Some(details) = change.details.as_mut() {
+            assign_change_ids(details);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+    use crate::diff::compare_texts;
+
+    #[test]
+    fn test_fnv1a() {
+        // Reference values of 64-bit FNV-1a
+        let mut hash = FNV_OFFSET_BASIS;
+        for byte in b"a" {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        assert_eq!(hash, 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(fnv1a(&["ab", "c"]), fnv1a(&["a", "bc"]));
+    }
+
+    #[test]
+    fn test_change_ids_are_stable() {
+        let old = "第一条 内容。\n重复的行。\n第二条 处一万元以下罚款。\n重复的行。";
+        let new = "第一条 内容。\n第二条 处五万元以下罚款。";
+        let first = compare_texts(old, new, vec![]);
+        let ids: Vec<&str> = first.changes.iter().map(|c| c.id.as_ref()).collect();
+        assert!(ids.iter().all(|id| !id.is_empty()));
+        let unique: std::collections::HashSet<&&str> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        // Same input, same ids; an unrelated edit above leaves the penalty change's id alone
+        let again = compare_texts(old, new, vec![]);
+        assert_eq!(ids, again.changes.iter().map(|c| c.id.as_ref()).collect::<Vec<_>>());
+        let penalty_id = |result: &crate::models::DiffResult| result.changes.iter()
+            .find(|c| c.new_content.as_deref().is_some_and(|n| n.contains("五万元")))
+            .map(|c| c.id.clone())
+            .unwrap();
+        let edited = compare_texts(&old.replace("第一条 内容。", "第一条 新内容。"), new, vec![]);
+        assert_eq!(penalty_id(&first), penalty_id(&edited));
+
+        let changes = align_articles(old, new, 0.6, false);
+        let again = align_articles(old, new, 0.6, false);
+        assert!(changes.iter().all(|c| c.id.len() == 16));
+        assert_eq!(
+            changes.iter().map(|c| &c.id).collect::<Vec<_>>(),
+            again.iter().map(|c| &c.id).collect::<Vec<_>>()
+        );
+    }
+}
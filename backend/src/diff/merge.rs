@@ -0,0 +1,203 @@
+use crate::diff::aligner::align_articles_with_options;
+use crate::diff::apply::render_article;
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, CompareOptions, MergeArticle, MergeStatus, ThreeWayResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// A base article as (number, start line); both alignments parse the base text the same way
+type BaseKey = (Arc<str>, usize);
+
+/// What one draft did to the base text: the articles each base article became (empty when
+/// deleted) and the articles it added, each after the base article it follows
+struct Revision {
+    versions: HashMap<BaseKey, Vec<ArticleInfo>>,
+    added: Vec<(Option<BaseKey>, ArticleInfo)>,
+}
+
+fn revision(changes: &[ArticleChange]) -> Revision {
+    let mut versions: HashMap<BaseKey, Vec<ArticleInfo>> = HashMap::new();
+    let mut added = Vec::new();
+    let mut anchor = None;
+    for change in changes {
+        let sources: Vec<&ArticleInfo> = match &change.merged_from {
+            Some(sources) => sources.iter().collect(),
+            None => change.old_article.iter().collect(),
+        };
+        if sources.is_empty() {
+            added.extend(change.new_articles.iter().flatten().map(|article| (anchor.clone(), article.clone())));
+            continue;
+        }
+        for source in sources {
+            let key: BaseKey = (source.number.clone(), source.start_line);
+            let version = versions.entry(key.clone()).or_default();
+            if change.change_type != ArticleChangeType::Deleted {
+                version.extend(change.new_articles.iter().flatten().cloned());
+                anchor = Some(key);
+            }
+        }
+    }
+    Revision { versions, added }
+}
+
+/// Titles and texts, which is what a draft changes; numbers shift whenever an article is
+/// inserted or deleted before them
+fn wording(articles: &[ArticleInfo]) -> Vec<(Option<&str>, &str)> {
+    articles.iter().map(|a| (a.title.as_deref(), a.content.as_ref())).collect()
+}
+
+/// Compare two drafts (A and B) revising the same base text, article by article.
+///
+/// Each base article is aligned into both drafts: one touched by a single draft takes that
+/// draft's version, one changed the same way in both is taken once, and one changed
+/// differently (including edited in one draft and deleted in the other) is a conflict.
+/// Articles added by a draft follow the base article they follow there. The merged text
+/// renders articles only, without 编/章/节 headings, and keeps the numbers of the version
+/// taken, so drafts that both insert articles may need renumbering afterwards.
+pub fn compare3(base_text: &str, a_text: &str, b_text: &str, options: &CompareOptions) -> ThreeWayResult {
+    let (a_alignment, b_alignment) = rayon::join(
+        || align_articles_with_options(base_text, a_text, options),
+        || align_articles_with_options(base_text, b_text, options),
+    );
+    let (mut a_rev, mut b_rev) = (revision(&a_alignment.changes), revision(&b_alignment.changes));
+
+    let mut base_articles: Vec<ArticleInfo> = a_alignment.changes.iter()
+        .chain(&b_alignment.changes)
+        .flat_map(|c| c.merged_from.clone().unwrap_or_else(|| c.old_article.iter().cloned().collect()))
+        .collect();
+    base_articles.sort_by_key(|a| a.start_line);
+    base_articles.dedup_by(|x, y| x.number == y.number && x.start_line == y.start_line);
+
+    let mut articles = additions(None, &a_rev.added, &b_rev.added);
+    for base in &base_articles {
+        let key: BaseKey = (base.number.clone(), base.start_line);
+        let a = a_rev.versions.remove(&key).unwrap_or_else(|| vec![base.clone()]);
+        let b = b_rev.versions.remove(&key).unwrap_or_else(|| vec![base.clone()]);
+        let base_wording = wording(std::slice::from_ref(base));
+        let (status, merged) = match (wording(&a) != base_wording, wording(&b) != base_wording) {
+            (false, false) => (MergeStatus::Unchanged, Some(vec![base.clone()])),
+            (true, false) => (MergeStatus::ChangedInA, Some(a.clone())),
+            (false, true) => (MergeStatus::ChangedInB, Some(b.clone())),
+            _ if wording(&a) == wording(&b) => (MergeStatus::ChangedInBoth, Some(a.clone())),
+            _ => (MergeStatus::Conflict, None),
+        };
+        articles.push(MergeArticle { status, base: Some(base.clone()), a_articles: a, b_articles: b, merged });
+        articles.extend(additions(Some(&key), &a_rev.added, &b_rev.added));
+    }
+
+    let mut warnings = a_alignment.warnings;
+    for warning in b_alignment.warnings {
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    ThreeWayResult {
+        conflicts: articles.iter().filter(|a| a.status == MergeStatus::Conflict).count(),
+        merged_text: merged_text(&articles),
+        articles,
+        warnings,
+    }
+}
+
+/// Articles the drafts added after `anchor`, A's first; one both drafts added with the same
+/// wording is listed once
+fn additions(
+    anchor: Option<&BaseKey>,
+    a_added: &[(Option<BaseKey>, ArticleInfo)],
+    b_added: &[(Option<BaseKey>, ArticleInfo)],
+) -> Vec<MergeArticle> {
+    let same = |x: &ArticleInfo, y: &ArticleInfo| x.title == y.title && x.content == y.content;
+    let mut out = Vec::new();
+    for (_, a) in a_added.iter().filter(|(key, _)| key.as_ref() == anchor) {
+        let twin = b_added.iter().find(|(_, b)| same(a, b)).map(|(_, b)| b.clone());
+        out.push(MergeArticle {
+            status: if twin.is_some() { MergeStatus::ChangedInBoth } else { MergeStatus::ChangedInA },
+            base: None,
+            a_articles: vec![a.clone()],
+            b_articles: twin.into_iter().collect(),
+            merged: Some(vec![a.clone()]),
+        });
+    }
+    for (_, b) in b_added.iter().filter(|(key, _)| key.as_ref() == anchor) {
+        if !a_added.iter().any(|(_, a)| same(a, b)) {
+            out.push(MergeArticle {
+                status: MergeStatus::ChangedInB,
+                base: None,
+                a_articles: Vec::new(),
+                b_articles: vec![b.clone()],
+                merged: Some(vec![b.clone()]),
+            });
+        }
+    }
+    out
+}
+
+/// Render the merge, writing each article once (several base articles merged into one
+/// share it) and each conflict between git-style markers
+fn merged_text(articles: &[MergeArticle]) -> String {
+    let render = |list: &[ArticleInfo]| list.iter().map(render_article).collect::<Vec<_>>().join("\n");
+    let mut emitted: HashSet<(Arc<str>, usize, Arc<str>)> = HashSet::new();
+    let mut blocks: Vec<String> = Vec::new();
+    for article in articles {
+        match &article.merged {
+            Some(list) => {
+                for info in list {
+                    if emitted.insert((info.number.clone(), info.start_line, info.content.clone())) {
+                        blocks.push(render_article(info));
+                    }
+                }
+            }
+            None => {
+                let block = format!("<<<<<<< A\n{}\n=======\n{}\n>>>>>>> B", render(&article.a_articles), render(&article.b_articles));
+                if blocks.last() != Some(&block) {
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+
+    let mut result = String::new();
+    for line in blocks.iter().flat_map(|block| block.lines()) {
+        let trimmed = line.trim_end();
+        if !trimmed.is_empty() {
+            result.push_str(trimmed);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare3() {
+        let base = "第一条 为了规范管理，制定本法。\n第二条 网络运营者应当建立管理制度。\n第三条 违反本法规定的，处一万元以下罚款。\n第四条 本法自公布之日起施行。";
+        let a = "第一条 为了规范管理，制定本法。\n第二条 网络运营者应当建立健全安全管理制度。\n第三条 违反本法规定的，处五万元以下罚款。\n第四条 本法自公布之日起施行。\n第五条 国务院有关部门应当制定实施细则。";
+        let b = "第一条 为了规范管理，制定本法。\n第二条 网络运营者应当建立管理制度。\n第三条 违反本法规定的，处十万元以下罚款。\n第四条 本法自2025年1月1日起施行。";
+
+        let result = compare3(base, a, b, &CompareOptions::default());
+        let statuses: Vec<(MergeStatus, Option<&str>)> = result.articles.iter()
+            .map(|a| (a.status, a.base.as_ref().map(|b| b.number.as_ref())))
+            .collect();
+        assert_eq!(statuses, vec![
+            (MergeStatus::Unchanged, Some("一")),
+            (MergeStatus::ChangedInA, Some("二")),
+            (MergeStatus::Conflict, Some("三")),
+            (MergeStatus::ChangedInB, Some("四")),
+            (MergeStatus::ChangedInA, None),
+        ]);
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.merged_text, "第一条 为了规范管理，制定本法。\n\
+            第二条 网络运营者应当建立健全安全管理制度。\n\
+            <<<<<<< A\n第三条 违反本法规定的，处五万元以下罚款。\n=======\n第三条 违反本法规定的，处十万元以下罚款。\n>>>>>>> B\n\
+            第四条 本法自2025年1月1日起施行。\n\
+            第五条 国务院有关部门应当制定实施细则。\n");
+
+        // The same edit in both drafts merges cleanly
+        let result = compare3(base, a, a, &CompareOptions::default());
+        assert_eq!(result.conflicts, 0);
+        assert_eq!(result.articles[1].status, MergeStatus::ChangedInBoth);
+        assert_eq!(result.articles.last().unwrap().status, MergeStatus::ChangedInBoth);
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+/// Bands of the LSH index and rows (hash values) per band. A pair becomes a candidate when
+/// one band matches entirely, which is likely above a Jaccard similarity of about
+/// (1 / BANDS)^(1 / ROWS) ≈ 0.18, low enough to keep the fragments of splits and merges.
+const BANDS: usize = 32;
+const ROWS: usize = 2;
+
+/// MinHash signature of a token set: the minimum of each of `BANDS * ROWS` hash functions
+/// over the tokens. Two signatures agree in a position with probability equal to the
+/// Jaccard similarity of their sets.
+pub fn signature(tokens: &HashSet<Arc<str>>) -> Vec<u64> {
+    let mut mins = vec![u64::MAX; BANDS * ROWS];
+    for token in tokens {
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        let base = hasher.finish();
+        for (seed, min) in mins.iter_mut().enumerate() {
+            *min = (*min).min(mix(base ^ (seed as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)));
+        }
+    }
+    mins
+}
+
+/// splitmix64 finalizer, turning one token hash into independent-looking hash functions
+pub fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// For each old signature, the new signatures sharing at least one LSH band with it
+pub fn candidate_pairs(old_signatures: &[Vec<u64>], new_signatures: &[Vec<u64>]) -> Vec<HashSet<usize>> {
+    let mut buckets: HashMap<(usize, &[u64]), Vec<usize>> = HashMap::new();
+    for (new_idx, sig) in new_signatures.iter().enumerate() {
+        for (band, rows) in sig.chunks(ROWS).enumerate() {
+            buckets.entry((band, rows)).or_default().push(new_idx);
+        }
+    }
+
+    old_signatures.iter().map(|sig| {
+        sig.chunks(ROWS).enumerate()
+            .filter_map(|(band, rows)| buckets.get(&(band, rows)))
+            .flatten()
+            .copied()
+            .collect()
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlp::tokenizer::tokenize_to_set;
+
+    #[test]
+    fn test_candidate_pairs() {
+        let old = [
+            "网络运营者应当建立健全用户信息保护制度，加强对用户个人信息的保护。",
+            "国家支持企业、研究机构和高等学校参与网络安全国家标准的制定。",
+        ];
+        let new = [
+            "国家支持企业、研究机构、高等学校和行业组织参与网络安全国家标准的制定。",
+            "本法自二〇一七年六月一日起施行。",
+            "网络运营者应当建立健全用户信息保护制度，加强对用户个人信息的保护。",
+        ];
+        let sigs = |texts: &[&str]| -> Vec<Vec<u64>> { texts.iter().map(|t| signature(&tokenize_to_set(t))).collect() };
+        let candidates = candidate_pairs(&sigs(&old), &sigs(&new));
+
+        assert!(candidates[0].contains(&2));
+        assert!(candidates[1].contains(&0));
+        assert!(!candidates[0].contains(&1) && !candidates[1].contains(&1));
+    }
+}
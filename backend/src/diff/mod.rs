@@ -1,86 +1,78 @@
 pub mod aligner;
+pub mod amend;
+pub mod apply;
+pub mod assignment;
+pub mod budget;
+pub mod cache;
+pub mod conditions;
+pub mod ids;
+pub mod merge;
+pub mod minhash;
+pub mod moves;
+pub mod patches;
+pub mod penalties;
+pub mod references;
+pub mod renumbering;
 pub mod similarity;
+pub mod simhash;
+pub mod stats;
+pub mod structure;
+pub mod tables;
+pub mod timeline;
+pub mod values;
 
 #[cfg(test)]
 mod aligner_tests;
 
 
 
-use similar::{ChangeTag, TextDiff};
-use crate::models::{Change, ChangeType, DiffResult, DiffStats, Entity};
+use similar::{Algorithm, ChangeTag, TextDiff};
+use crate::models::{Change, ChangeType, DiffAlgorithm, DiffResult, DiffStats, Entity, Granularity, Position};
+use crate::diff::ids::assign_change_ids;
+use crate::nlp::formatter::strip_format;
+use crate::nlp::tokenizer::tokenize;
 
-/// Compare two texts and generate diff result
+/// Compare two texts line by line and generate diff result
 pub fn compare_texts(old_text: &str, new_text: &str, entities: Vec<Entity>) -> DiffResult {
+    compare_texts_with_granularity(old_text, new_text, Granularity::Line, entities)
+}
+
+/// Compare two texts in units of `granularity`. Lines are reported one change each; finer
+/// units are grouped into one change per run of equal, deleted or inserted text.
+pub fn compare_texts_with_granularity(old_text: &str, new_text: &str, granularity: Granularity, entities: Vec<Entity>) -> DiffResult {
+    compare_texts_with_algorithm(old_text, new_text, granularity, DiffAlgorithm::default(), entities)
+}
+
+/// `compare_texts_with_granularity` with the sequence diff `algorithm` picks
+pub fn compare_texts_with_algorithm(
+    old_text: &str,
+    new_text: &str,
+    granularity: Granularity,
+    algorithm: DiffAlgorithm,
+    entities: Vec<Entity>,
+) -> DiffResult {
     // Trim and normalize lines for better stability
     let old_normalized: String = old_text.lines().map(|l| l.trim_end()).collect::<Vec<_>>().join("\n");
     let new_normalized: String = new_text.lines().map(|l| l.trim_end()).collect::<Vec<_>>().join("\n");
 
-    let diff = TextDiff::from_lines(&old_normalized, &new_normalized);
+    let old_units = split_units(&old_normalized, granularity);
+    let new_units = split_units(&new_normalized, granularity);
+    let (changes, similarity) = diff_units(&old_units, &new_units, granularity != Granularity::Line, algorithm);
 
-    let mut changes = Vec::new();
-    let mut old_line = 1;
-    let mut new_line = 1;
-    let mut additions = 0;
-    let mut deletions = 0;
-    let mut modifications = 0;
-    let mut unchanged = 0;
-
-    for change in diff.iter_all_changes() {
-        let value = change.value();
-
-        match change.tag() {
-            ChangeTag::Insert => {
-                changes.push(Change {
-                    change_type: ChangeType::Add,
-                    old_line: None,
-                    new_line: Some(new_line),
-                    old_content: None,
-                    new_content: Some(value.into()),
-                    entities: None,
-                });
-                new_line += 1;
-                additions += 1;
-            }
-            ChangeTag::Delete => {
-                changes.push(Change {
-                    change_type: ChangeType::Delete,
-                    old_line: Some(old_line),
-                    new_line: None,
-                    old_content: Some(value.into()),
-                    new_content: None,
-                    entities: None,
-                });
-                old_line += 1;
-                deletions += 1;
-            }
-            ChangeTag::Equal => {
-                let arc_val: std::sync::Arc<str> = value.into();
-                changes.push(Change {
-                    change_type: ChangeType::Unchanged,
-                    old_line: Some(old_line),
-                    new_line: Some(new_line),
-                    old_content: Some(arc_val.clone()),
-                    new_content: Some(arc_val),
-                    entities: None,
-                });
-                old_line += 1;
-                new_line += 1;
-                unchanged += 1;
-            }
-        }
-    }
+    let count = |change_type: ChangeType| changes.iter().filter(|c| c.change_type == change_type).count();
+    let (additions, deletions, unchanged) = (count(ChangeType::Add), count(ChangeType::Delete), count(ChangeType::Unchanged));
 
     // Merge adjacent add/delete into modify
     let merged_changes = merge_adjacent_changes(changes);
-    modifications = merged_changes.iter()
+    let modifications = merged_changes.iter()
         .filter(|c| c.change_type == ChangeType::Modify)
         .count();
-
-    // Calculate similarity using ratio
-    let similarity = diff.ratio();
+    let (chars_added, chars_deleted) = merged_changes.iter()
+        .map(changed_chars)
+        .fold((0, 0), |(added, deleted), (a, d)| (added + a, deleted + d));
 
     DiffResult {
-        similarity: similarity as f32,
+        similarity,
         changes: merged_changes,
         article_changes: None, // Will be populated by aligner in API layer
         entities,
@@ -89,8 +81,195 @@ pub fn compare_texts(old_text: &str, new_text: &str, entities: Vec<Entity>) -> D
             deletions,
             modifications,
             unchanged,
+            chars_added,
+            chars_deleted,
+            ..Default::default()
         },
+        ..Default::default()
+    }
+}
+
+/// Characters a change adds and deletes: the whole content of an added or deleted unit,
+/// the differing spans of a modified one
+fn changed_chars(change: &Change) -> (usize, usize) {
+    let chars = |content: &Option<std::sync::Arc<str>>| content.as_deref().map_or(0, |c| c.chars().count());
+    let spans = |ranges: &Option<Vec<Position>>| ranges.iter().flatten().map(|r| r.end - r.start).sum::<usize>();
+    match change.change_type {
+        ChangeType::Add => (chars(&change.new_content), 0),
+        ChangeType::Delete => (0, chars(&change.old_content)),
+        ChangeType::Modify => (spans(&change.new_ranges), spans(&change.old_ranges)),
+        ChangeType::Unchanged => (0, 0),
+    }
+}
+
+/// Turn Modify changes whose sides are equal once punctuation and/or whitespace are removed
+/// into Unchanged, updating the stats
+pub fn ignore_format_changes(result: &mut DiffResult, punctuation: bool, whitespace: bool) {
+    for change in result.changes.iter_mut().filter(|c| c.change_type == ChangeType::Modify) {
+        let strip = |content: &Option<std::sync::Arc<str>>| strip_format(content.as_deref().unwrap_or(""), punctuation, whitespace);
+        if strip(&change.old_content) != strip(&change.new_content) {
+            continue;
+        }
+        let (added, deleted) = changed_chars(change);
+        result.stats.chars_added -= added;
+        result.stats.chars_deleted -= deleted;
+        change.change_type = ChangeType::Unchanged;
+        change.old_ranges = None;
+        change.new_ranges = None;
+        result.stats.modifications -= 1;
+        result.stats.additions -= 1;
+        result.stats.deletions -= 1;
+        result.stats.unchanged += 1;
+    }
+}
+
+/// Keep `context` unchanged units on either side of each change and replace every longer
+/// run with one "…" marker whose `elided` count says how many units it stands for; `None`
+/// drops unchanged units without markers. Stats are left as computed on the full diff.
+pub fn elide_unchanged(changes: Vec<Change>, context: Option<usize>) -> Vec<Change> {
+    let Some(context) = context else {
+        return changes.into_iter().filter(|c| c.change_type != ChangeType::Unchanged).collect();
+    };
+    let near_change: Vec<bool> = {
+        let changed: Vec<usize> = (0..changes.len()).filter(|&i| changes[i].change_type != ChangeType::Unchanged).collect();
+        let mut near = vec![false; changes.len()];
+        for i in changed {
+            for flag in &mut near[i.saturating_sub(context)..(i + context + 1).min(changes.len())] {
+                *flag = true;
+            }
+        }
+        near
+    };
+
+    let mut result: Vec<Change> = Vec::new();
+    for (change, keep) in changes.into_iter().zip(near_change) {
+        if keep {
+            result.push(change);
+            continue;
+        }
+        match result.last_mut() {
+            Some(Change { elided: Some(count), .. }) => *count += 1,
+            _ => result.push(Change {
+                id: Default::default(),
+                change_type: ChangeType::Unchanged,
+                old_line: change.old_line,
+                new_line: change.new_line,
+                old_content: Some("…".into()),
+                new_content: Some("…".into()),
+                entities: None,
+                old_ranges: None,
+                new_ranges: None,
+                elided: Some(1),
+            }),
+        }
     }
+    result
+}
+
+/// Diff two texts word by word (jieba tokens), one change per run of equal, deleted or
+/// inserted words; a deletion directly followed by an insertion becomes a Modify.
+/// Line numbers count from 1 within each text.
+pub fn word_diff(old_text: &str, new_text: &str) -> Vec<Change> {
+    unit_diff(old_text, new_text, Granularity::Word, DiffAlgorithm::default())
+}
+
+/// `word_diff` in units of `granularity`: sentence units read better than words for
+/// articles written as one long paragraph
+pub fn unit_diff(old_text: &str, new_text: &str, granularity: Granularity, algorithm: DiffAlgorithm) -> Vec<Change> {
+    let (old_units, new_units) = (split_units(old_text, granularity), split_units(new_text, granularity));
+    let (changes, _) = diff_units(&old_units, &new_units, granularity != Granularity::Line, algorithm);
+    merge_adjacent_changes(changes)
+}
+
+/// Cut `text` into diff units that concatenate back to it
+fn split_units(text: &str, granularity: Granularity) -> Vec<&str> {
+    match granularity {
+        Granularity::Char => text.char_indices().map(|(i, c)| &text[i..i + c.len_utf8()]).collect(),
+        Granularity::Word => {
+            // jieba yields every segment of the input in order, whitespace included
+            let mut start = 0;
+            tokenize(text).iter().map(|token| {
+                let unit = &text[start..start + token.len()];
+                start += token.len();
+                unit
+            }).collect()
+        }
+        Granularity::Line => text.split_inclusive('\n').collect(),
+        Granularity::Sentence => split_sentences(text),
+    }
+}
+
+/// Characters that end a sentence unit: full stops, and the ；/： that separate the clauses
+/// of a long provision
+const SENTENCE_TERMINATORS: &str = "。；：！？;!?\n";
+
+/// Split after each sentence terminator (and line break), keeping the terminator
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if SENTENCE_TERMINATORS.contains(c) {
+            sentences.push(&text[start..i + c.len_utf8()]);
+            start = i + c.len_utf8();
+        }
+    }
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+    sentences
+}
+
+/// Diff two unit sequences into changes with 1-based line numbers, plus the similarity
+/// ratio. `group_runs` joins consecutive units of the same kind into one change.
+fn diff_units(old_units: &[&str], new_units: &[&str], group_runs: bool, algorithm: DiffAlgorithm) -> (Vec<Change>, f32) {
+    let algorithm = match algorithm {
+        DiffAlgorithm::Myers => Algorithm::Myers,
+        DiffAlgorithm::Patience => Algorithm::Patience,
+        DiffAlgorithm::Lcs => Algorithm::Lcs,
+    };
+    let diff = TextDiff::configure().algorithm(algorithm).diff_slices(old_units, new_units);
+
+    // Runs of one tag: (tag, text, old line, new line where the run starts)
+    let mut runs: Vec<(ChangeTag, String, usize, usize)> = Vec::new();
+    let (mut old_line, mut new_line) = (1, 1);
+    for change in diff.iter_all_changes() {
+        let (tag, unit) = (change.tag(), change.value());
+        match runs.last_mut() {
+            Some(run) if group_runs && run.0 == tag => run.1.push_str(unit),
+            _ => runs.push((tag, unit.to_string(), old_line, new_line)),
+        }
+
+        // A line unit advances one line even without its trailing newline (the last line)
+        let breaks = if group_runs { unit.matches('\n').count() } else { 1 };
+        if tag != ChangeTag::Insert {
+            old_line += breaks;
+        }
+        if tag != ChangeTag::Delete {
+            new_line += breaks;
+        }
+    }
+
+    let changes = runs.into_iter().map(|(tag, text, old_line, new_line)| {
+        let (change_type, in_old, in_new) = match tag {
+            ChangeTag::Equal => (ChangeType::Unchanged, true, true),
+            ChangeTag::Delete => (ChangeType::Delete, true, false),
+            ChangeTag::Insert => (ChangeType::Add, false, true),
+        };
+        let text: std::sync::Arc<str> = text.into();
+        Change {
+            id: Default::default(),
+            change_type,
+            old_line: in_old.then_some(old_line),
+            new_line: in_new.then_some(new_line),
+            old_content: in_old.then(|| text.clone()),
+            new_content: in_new.then_some(text),
+            entities: None,
+            old_ranges: None,
+            new_ranges: None,
+            elided: None,
+        }
+    }).collect();
+    (changes, diff.ratio())
 }
 
 /// Merge adjacent add/delete changes into modifications.
@@ -120,43 +299,159 @@ fn merge_adjacent_changes(changes: Vec<Change>) -> Vec<Change> {
             i += 1;
         }
 
-        // If we found both, pair them up as Modify as much as possible
-        let max_pairs = deletes.len().max(adds.len());
-        for j in 0..max_pairs {
-            let del = deletes.get(j);
-            let add = adds.get(j);
-
-            match (del, add) {
-                (Some(d), Some(a)) => {
-                    merged.push(Change {
-                        change_type: ChangeType::Modify,
-                        old_line: d.old_line,
-                        new_line: a.new_line,
-                        old_content: d.old_content.clone(),
-                        new_content: a.new_content.clone(),
-                        entities: None,
-                    });
-                }
-                (Some(d), None) => {
-                    merged.push(d.clone());
-                }
-                (None, Some(a)) => {
-                    merged.push(a.clone());
-                }
-                (None, None) => {}
+        // Pair them up as Modify where the lines correspond; the rest stay deletes and adds
+        let (mut next_del, mut next_add) = (0, 0);
+        for (d, a) in pair_block(&deletes, &adds) {
+            merged.extend(deletes[next_del..d].iter().cloned());
+            merged.extend(adds[next_add..a].iter().cloned());
+            let (del, add) = (&deletes[d], &adds[a]);
+            let (old_ranges, new_ranges) = intraline_ranges(
+                del.old_content.as_deref().unwrap_or(""),
+                add.new_content.as_deref().unwrap_or(""),
+            );
+            merged.push(Change {
+                id: Default::default(),
+                change_type: ChangeType::Modify,
+                old_line: del.old_line,
+                new_line: add.new_line,
+                old_content: del.old_content.clone(),
+                new_content: add.new_content.clone(),
+                entities: None,
+                old_ranges: Some(old_ranges),
+                new_ranges: Some(new_ranges),
+                elided: None,
+            });
+            (next_del, next_add) = (d + 1, a + 1);
+        }
+        merged.extend(deletes[next_del..].iter().cloned());
+        merged.extend(adds[next_add..].iter().cloned());
+    }
+
+    assign_change_ids(&mut merged);
+    merged
+}
+
+/// Minimum `bigram_similarity` for a deleted and an added line to pair as a Modify
+const PAIR_THRESHOLD: f32 = 0.3;
+
+/// Above this many (delete, add) combinations a block pairs positionally, as scoring every
+/// combination would cost more than the pairing is worth
+const MAX_PAIRING_CELLS: usize = 250_000;
+
+/// Indices of the (delete, add) pairs of a block that become Modify changes, in order.
+///
+/// A lone deletion followed by a lone insertion is a replacement and always pairs. Larger
+/// blocks pair the order-preserving set of lines scoring at least `PAIR_THRESHOLD` with
+/// the highest total similarity, so a line inserted before an edited one does not shift
+/// every later pair.
+fn pair_block(deletes: &[Change], adds: &[Change]) -> Vec<(usize, usize)> {
+    let (n, m) = (deletes.len(), adds.len());
+    if n.max(m) <= 1 || n * m > MAX_PAIRING_CELLS {
+        return (0..n.min(m)).map(|i| (i, i)).collect();
+    }
+
+    let delete_grams: Vec<_> = deletes.iter().map(|d| bigrams(d.old_content.as_deref().unwrap_or(""))).collect();
+    let add_grams: Vec<_> = adds.iter().map(|a| bigrams(a.new_content.as_deref().unwrap_or(""))).collect();
+    let score = |i: usize, j: usize| {
+        let sim = bigram_similarity(&delete_grams[i], &add_grams[j]);
+        if sim >= PAIR_THRESHOLD { Some(sim) } else { None }
+    };
+    // best[i][j]: highest total similarity pairing the first i deletes with the first j adds
+    let mut best = vec![vec![0.0f32; m + 1]; n + 1];
+    let mut sims = vec![vec![None; m]; n];
+    for i in 1..=n {
+        for j in 1..=m {
+            sims[i - 1][j - 1] = score(i - 1, j - 1);
+            let paired = sims[i - 1][j - 1].map_or(f32::MIN, |sim| best[i - 1][j - 1] + sim);
+            best[i][j] = paired.max(best[i - 1][j]).max(best[i][j - 1]);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 && j > 0 {
+        match sims[i - 1][j - 1] {
+            Some(sim) if best[i][j] == best[i - 1][j - 1] + sim => {
+                pairs.push((i - 1, j - 1));
+                i -= 1;
+                j -= 1;
             }
+            _ if best[i][j] == best[i - 1][j] => i -= 1,
+            _ => j -= 1,
         }
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// Character bigrams of a line (whitespace dropped; a single character stands for itself),
+/// sorted so that two lines' shared bigrams are counted in one merge
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut grams: Vec<(char, char)> = match chars.as_slice() {
+        [c] => vec![(*c, *c)],
+        _ => chars.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+    };
+    grams.sort_unstable();
+    grams
+}
 
-        // If we are at a point where the next one is Unchanged, just continue the outer loop
+/// Dice coefficient of two lines' `bigrams`; cheap enough to score every pairing in a block
+fn bigram_similarity(a: &[(char, char)], b: &[(char, char)]) -> f32 {
+    let total = a.len() + b.len();
+    if total == 0 {
+        return 1.0;
     }
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    2.0 * shared as f32 / total as f32
+}
 
-    merged
+/// Char spans (end exclusive) of `old` and `new` that a char-level diff marks as removed
+/// and inserted, adjacent characters merged into one span
+fn intraline_ranges(old: &str, new: &str) -> (Vec<Position>, Vec<Position>) {
+    let push = |ranges: &mut Vec<Position>, start: usize, len: usize| match ranges.last_mut() {
+        Some(last) if last.end == start => last.end += len,
+        _ => ranges.push(Position { start, end: start + len }),
+    };
+
+    let diff = TextDiff::from_chars(old, new);
+    let (mut old_ranges, mut new_ranges) = (Vec::new(), Vec::new());
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for change in diff.iter_all_changes() {
+        let len = change.value().chars().count();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old_pos += len;
+                new_pos += len;
+            }
+            ChangeTag::Delete => {
+                push(&mut old_ranges, old_pos, len);
+                old_pos += len;
+            }
+            ChangeTag::Insert => {
+                push(&mut new_ranges, new_pos, len);
+                new_pos += len;
+            }
+        }
+    }
+    (old_ranges, new_ranges)
 }
 
 /// Calculate similarity score (0.0 to 1.0)
 pub fn calculate_similarity(old_text: &str, new_text: &str) -> f32 {
     let diff = TextDiff::from_words(old_text, new_text);
-    diff.ratio() as f32
+    diff.ratio()
 }
 
 #[cfg(test)]
@@ -173,6 +468,167 @@ mod tests {
         assert!(result.stats.modifications > 0 || result.stats.additions > 0);
     }
 
+    #[test]
+    fn test_intraline_ranges() {
+        let result = compare_texts("第一条 处一万元以下罚款。\n第二条 内容。", "第一条 处五万元以下罚款，并没收。\n第二条 内容。", Vec::new());
+        let modify = result.changes.iter().find(|c| c.change_type == ChangeType::Modify).unwrap();
+        let spans = |ranges: &Option<Vec<Position>>| ranges.as_ref().unwrap().iter().map(|r| (r.start, r.end)).collect::<Vec<_>>();
+        assert_eq!(spans(&modify.old_ranges), vec![(5, 6)]);
+        assert_eq!(spans(&modify.new_ranges), vec![(5, 6), (12, 16)]);
+        assert!(result.changes.iter().filter(|c| c.change_type == ChangeType::Unchanged).all(|c| c.old_ranges.is_none()));
+    }
+
+    #[test]
+    fn test_similarity_pairing() {
+        // A new line inserted above an edited one: the edit pairs with its own old line
+        let old = "第一条 内容。\n第二条 网络运营者应当建立管理制度。\n第三条 内容。";
+        let new = "第一条 内容。\n第二条 国家支持网络安全技术研究。\n第三条 网络运营者应当建立健全安全管理制度。\n第四条 内容。";
+        let result = compare_texts(old, new, vec![]);
+        let summary: Vec<(ChangeType, Option<usize>, Option<usize>)> = result.changes.iter()
+            .map(|c| (c.change_type.clone(), c.old_line, c.new_line))
+            .collect();
+        assert_eq!(summary, vec![
+            (ChangeType::Unchanged, Some(1), Some(1)),
+            (ChangeType::Add, None, Some(2)),
+            (ChangeType::Modify, Some(2), Some(3)),
+            (ChangeType::Modify, Some(3), Some(4)),
+        ]);
+
+        // Unrelated lines in a block stay a delete and an add
+        let result = compare_texts("甲\n第二条 网络运营者应当建立管理制度。\n第三条 违反本法规定的，依法处罚。", "甲\n第二条 本法自公布之日起施行。\n第三条 国家鼓励开发网络数据安全保护技术。", vec![]);
+        assert_eq!(result.stats.modifications, 0);
+        assert!(bigram_similarity(&bigrams("处一万元以下罚款。"), &bigrams("处五万元以下罚款。")) > PAIR_THRESHOLD);
+    }
+
+    #[test]
+    fn test_pairing_at_cell_cap() {
+        // 500 x 500 lines is the largest block still scored pair by pair
+        let lines = |prefix: &str| (1..=500)
+            .map(|i| format!("第{}条 {}网络运营者应当按照第{}项规定履行安全保护义务。", i, prefix, i * 7))
+            .collect::<Vec<_>>()
+            .join("\n");
+        // One unrelated line above the edits: scored pairing skips it, positional would not
+        let (old, edited) = (lines("旧"), lines("新"));
+        let new = format!("本法自公布之日起施行。\n{}", &edited[..edited.rfind('\n').unwrap()]);
+        let start = std::time::Instant::now();
+        let result = compare_texts(&old, &new, vec![]);
+        assert!(start.elapsed() < std::time::Duration::from_secs(3), "took {:?}", start.elapsed());
+        assert_eq!(result.stats.modifications, 499);
+    }
+
+    #[test]
+    fn test_granularity() {
+        let summary = |result: DiffResult| -> Vec<(ChangeType, String, String)> {
+            result.changes.into_iter()
+                .map(|c| (c.change_type, c.old_content.unwrap_or_default().to_string(), c.new_content.unwrap_or_default().to_string()))
+                .collect()
+        };
+        let (old, new) = ("第一条 处一万元罚款。\n第二条 内容。", "第一条 处五万元罚款。\n第二条 内容。");
+
+        let chars = summary(compare_texts_with_granularity(old, new, Granularity::Char, vec![]));
+        assert_eq!(chars.len(), 3);
+        assert_eq!(chars[1], (ChangeType::Modify, "一".into(), "五".into()));
+
+        let words = compare_texts_with_granularity(old, new, Granularity::Word, vec![]);
+        let modify = words.changes.iter().find(|c| c.change_type == ChangeType::Modify).unwrap();
+        assert!(modify.old_content.as_deref().unwrap().contains('一') && !modify.old_content.as_deref().unwrap().contains('第'));
+        assert_eq!(words.changes.iter().map(|c| c.old_content.as_deref().unwrap_or("")).collect::<String>(), old);
+
+        let lines = summary(compare_texts_with_granularity(old, new, Granularity::Line, vec![]));
+        assert_eq!(lines[0], (ChangeType::Modify, "第一条 处一万元罚款。\n".into(), "第一条 处五万元罚款。\n".into()));
+
+        let sentences = summary(compare_texts_with_granularity("第一条 甲。乙。丙。", "第一条 甲。丁。丙。", Granularity::Sentence, vec![]));
+        assert_eq!(sentences[1], (ChangeType::Modify, "乙。".into(), "丁。".into()));
+        assert_eq!(split_sentences("有下列情形之一的：（一）甲；（二）乙。"), vec!["有下列情形之一的：", "（一）甲；", "（二）乙。"]);
+
+        let options: crate::models::CompareOptions = serde_json::from_str(r#"{"granularity": "sentence"}"#).unwrap();
+        assert_eq!(options.granularity, Granularity::Sentence);
+    }
+
+    #[test]
+    fn test_ignore_format_changes() {
+        let old = "第一条 网络运营者，网络服务提供者应当遵守本法。\n第二条 内容。";
+        let new = "第一条 网络运营者、网络服务提供者应当遵守本法。\n第二条  内容";
+
+        let mut result = compare_texts(old, new, vec![]);
+        ignore_format_changes(&mut result, true, false);
+        let kinds: Vec<ChangeType> = result.changes.iter().map(|c| c.change_type.clone()).collect();
+        assert_eq!(kinds, vec![ChangeType::Unchanged, ChangeType::Modify]);
+
+        let mut result = compare_texts(old, new, vec![]);
+        ignore_format_changes(&mut result, true, true);
+        assert!(result.changes.iter().all(|c| c.change_type == ChangeType::Unchanged));
+        assert_eq!((result.stats.modifications, result.stats.additions, result.stats.unchanged), (0, 0, 2));
+    }
+
+    #[test]
+    fn test_elide_unchanged() {
+        let old: String = (1..=10).map(|i| format!("第{}行\n", i)).collect();
+        let new = old.replace("第5行", "第五行");
+        let changes = compare_texts(&old, &new, vec![]).changes;
+
+        let elided = elide_unchanged(changes.clone(), Some(1));
+        let summary: Vec<(ChangeType, Option<usize>, Option<usize>)> = elided.iter()
+            .map(|c| (c.change_type.clone(), c.old_line, c.elided))
+            .collect();
+        assert_eq!(summary, vec![
+            (ChangeType::Unchanged, Some(1), Some(3)),
+            (ChangeType::Unchanged, Some(4), None),
+            (ChangeType::Modify, Some(5), None),
+            (ChangeType::Unchanged, Some(6), None),
+            (ChangeType::Unchanged, Some(7), Some(4)),
+        ]);
+        assert_eq!(elided[0].old_content.as_deref(), Some("…"));
+
+        let omitted = elide_unchanged(changes, None);
+        assert_eq!(omitted.len(), 1);
+        assert_eq!(omitted[0].change_type, ChangeType::Modify);
+    }
+
+    #[test]
+    fn test_word_diff() {
+        let changes = word_diff("处一万元以下罚款。\n情节严重的，吊销许可证。", "处五万元以下罚款。\n情节严重的，吊销许可证。");
+        let summary: Vec<(ChangeType, Option<&str>, Option<&str>)> = changes.iter()
+            .map(|c| (c.change_type.clone(), c.old_content.as_deref(), c.new_content.as_deref()))
+            .collect();
+        assert_eq!(summary[0], (ChangeType::Unchanged, Some("处"), Some("处")));
+        assert_eq!(summary[1].0, ChangeType::Modify);
+        assert!(summary[1].1.unwrap().contains('一') && summary[1].2.unwrap().contains('五'));
+        assert_eq!(summary.last().unwrap().0, ChangeType::Unchanged);
+
+        let changes = word_diff("第一行\n旧的内容", "第一行\n新增的内容");
+        let modified = changes.iter().find(|c| c.change_type != ChangeType::Unchanged).unwrap();
+        assert_eq!((modified.old_line, modified.new_line), (Some(2), Some(2)));
+    }
+
+    #[test]
+    fn test_char_stats() {
+        let result = compare_texts("处一万元以下罚款。\n本法自公布之日起施行。", "处十万元以下罚款。\n新增一条。\n本法自公布之日起施行。", vec![]);
+        assert_eq!((result.stats.chars_added, result.stats.chars_deleted), (1 + 6, 1));
+
+        let mut result = compare_texts("第一条 内容。", "第一条 内容", vec![]);
+        assert_eq!((result.stats.chars_added, result.stats.chars_deleted), (0, 1));
+        ignore_format_changes(&mut result, true, false);
+        assert_eq!((result.stats.chars_added, result.stats.chars_deleted), (0, 0));
+    }
+
+    #[test]
+    fn test_diff_algorithm() {
+        // 第二条 moves ahead of 第一条; every article ends in the same boilerplate line
+        let old = "第一条 甲。\n本条。\n第二条 乙。\n本条。\n第三条 丙。\n本条。";
+        let new = "第二条 乙。\n本条。\n第一条 甲。\n本条。\n第三条 丙。\n本条。";
+        let after_first = |algorithm: DiffAlgorithm| {
+            let result = compare_texts_with_algorithm(old, new, Granularity::Line, algorithm, vec![]);
+            let idx = result.changes.iter().position(|c| c.old_content.as_deref() == Some("第一条 甲。\n")).unwrap();
+            result.changes[idx + 1].change_type.clone()
+        };
+        // Myers pairs 第一条 with 第二条's boilerplate line; patience keeps each article whole
+        assert_eq!(after_first(DiffAlgorithm::Myers), ChangeType::Delete);
+        assert_eq!(after_first(DiffAlgorithm::Patience), ChangeType::Unchanged);
+
+        assert!(serde_json::from_str::<DiffAlgorithm>("\"histogram\"").is_err());
+    }
+
     #[test]
     fn test_similarity() {
         assert_eq!(calculate_similarity("test", "test"), 1.0);
@@ -0,0 +1,143 @@
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, ArticleMove};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Hierarchy labels of an article (`ArticleInfo::parents`)
+type Parents<'a> = &'a [Arc<str>];
+
+/// A 1:1 matched article: index into the changes, the old list and the new list
+struct Matched {
+    change: usize,
+    old_idx: usize,
+    new_idx: usize,
+}
+
+/// Reclassify matched articles that changed place as Moved, tagged "moved" with the
+/// hierarchy before and after.
+///
+/// An article moved when it left the 编/章/节 most articles of its old heading went to
+/// (so renaming or renumbering a chapter moves nothing), or when it falls out of the
+/// longest run of matched articles that kept their relative order.
+pub fn annotate_moves(changes: &mut [ArticleChange], old_articles: &[ArticleInfo], new_articles: &[ArticleInfo]) {
+    let mut matched = Vec::new();
+    for (change_idx, change) in changes.iter().enumerate() {
+        if !matches!(change.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Modified | ArticleChangeType::Renumbered) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().filter(|list| list.len() == 1).and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+        if let (Some(old_idx), Some(new_idx)) = (position_of(old_articles, old_art), position_of(new_articles, new_art)) {
+            matched.push(Matched { change: change_idx, old_idx, new_idx });
+        }
+    }
+
+    // Where the articles of each old heading ended up, by count
+    let mut destinations: HashMap<Parents, BTreeMap<Parents, usize>> = HashMap::new();
+    for m in &matched {
+        let old_parents = old_articles[m.old_idx].parents.as_slice();
+        let new_parents = new_articles[m.new_idx].parents.as_slice();
+        *destinations.entry(old_parents).or_default().entry(new_parents).or_insert(0) += 1;
+    }
+    let majority: HashMap<Parents, Parents> = destinations.iter().map(|(&from, counts)| {
+        // Ties keep the articles where they were
+        let to = counts.iter().max_by_key(|&(&to, &count)| (count, to == from)).map(|(&to, _)| to).unwrap_or(from);
+        (from, to)
+    }).collect();
+
+    matched.sort_by_key(|m| m.old_idx);
+    let in_order = longest_increasing(&matched.iter().map(|m| m.new_idx).collect::<Vec<_>>());
+
+    for (pos, m) in matched.iter().enumerate() {
+        let (old_art, new_art) = (&old_articles[m.old_idx], &new_articles[m.new_idx]);
+        let left_heading = majority.get(old_art.parents.as_slice()) != Some(&new_art.parents.as_slice());
+        if !left_heading && in_order[pos] {
+            continue;
+        }
+
+        let change = &mut changes[m.change];
+        change.change_type = ArticleChangeType::Moved;
+        change.tags.push("moved".to_string());
+        change.moved = Some(ArticleMove {
+            from: old_art.parents.clone(),
+            to: new_art.parents.clone(),
+        });
+    }
+}
+
+/// Marks the members of one longest strictly increasing subsequence of `values`
+pub fn longest_increasing(values: &[usize]) -> Vec<bool> {
+    // tails[k]: index of the smallest tail of an increasing run of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = vec![None; values.len()];
+    for (i, &value) in values.iter().enumerate() {
+        let k = tails.partition_point(|&t| values[t] < value);
+        prev[i] = k.checked_sub(1).map(|k| tails[k]);
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+
+    let mut members = vec![false; values.len()];
+    let mut cursor = tails.last().copied();
+    while let Some(i) = cursor {
+        members[i] = true;
+        cursor = prev[i];
+    }
+    members
+}
+
+/// Index of `article` in `articles`, by number and start line
+pub fn position_of(articles: &[ArticleInfo], article: &ArticleInfo) -> Option<usize> {
+    articles.iter().position(|a| a.number == article.number && a.start_line == article.start_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_longest_increasing() {
+        assert_eq!(longest_increasing(&[0, 1, 4, 2, 3]), vec![true, true, false, true, true]);
+        assert_eq!(longest_increasing(&[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_article_moved_to_other_chapter() {
+        let old = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n第二条 本法适用于中华人民共和国境内。\n第三条 国家坚持网络安全与信息化发展并重。\n\
+                   第二章 网络安全支持与促进\n第四条 国家建立和完善网络安全标准体系。\n第五条 国家支持网络安全技术研究开发。";
+        let new = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n第二条 本法适用于中华人民共和国境内。\n\
+                   第二章 网络安全支持与促进\n第三条 国家坚持网络安全与信息化发展并重。\n第四条 国家建立和完善网络安全标准体系。\n第五条 国家支持网络安全技术研究开发。";
+
+        let changes = align_articles(old, new, 0.6, false);
+        let moved: Vec<_> = changes.iter().filter(|c| c.change_type == ArticleChangeType::Moved).collect();
+        assert_eq!(moved.len(), 1);
+        assert!(moved[0].tags.contains(&"moved".to_string()));
+        let hierarchy = moved[0].moved.as_ref().unwrap();
+        assert_eq!(hierarchy.from, vec![Arc::from("一 总则")]);
+        assert_eq!(hierarchy.to, vec![Arc::from("二 网络安全支持与促进")]);
+
+        // Renaming a chapter moves nothing
+        let renamed = old.replace("第二章 网络安全支持与促进", "第二章 网络安全促进");
+        let changes = align_articles(old, &renamed, 0.6, false);
+        assert!(changes.iter().all(|c| c.change_type != ArticleChangeType::Moved));
+    }
+
+    #[test]
+    fn test_article_moved_out_of_order() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 本法适用于中华人民共和国境内。\n第三条 国家坚持网络安全与信息化发展并重。\n第四条 国家建立和完善网络安全标准体系。";
+        let new = "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。\n第三条 本法适用于中华人民共和国境内。\n第四条 国家坚持网络安全与信息化发展并重。";
+
+        let changes = align_articles(old, new, 0.6, false);
+        let moved: Vec<_> = changes.iter().filter(|c| c.change_type == ArticleChangeType::Moved).collect();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].old_article.as_ref().unwrap().number.as_ref(), "四");
+        assert!(moved[0].tags.contains(&"renumbered".to_string()));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::ast::article_label;
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo};
+use similar::TextDiff;
+use std::sync::Arc;
+
+/// Lines of unchanged context around each hunk
+const PATCH_CONTEXT_LINES: usize = 3;
+
+/// Render each matched, changed article as its own unified-diff patch.
+///
+/// Returns `(new article number, patch)` pairs in change order. Patches use
+/// `a/第X条` / `b/第Y条` headers so renumbered articles keep both numbers.
+/// Added, deleted, split and merged articles are not included.
+pub fn to_per_article_patches(changes: &[ArticleChange]) -> Vec<(Arc<str>, String)> {
+    changes.iter().filter_map(|change| {
+        if matches!(
+            change.change_type,
+            ArticleChangeType::Unchanged | ArticleChangeType::Added | ArticleChangeType::Deleted
+                | ArticleChangeType::Split | ArticleChangeType::Merged
+        ) {
+            return None;
+        }
+        let old_art = change.old_article.as_ref()?;
+        let new_art = change.new_articles.as_ref().filter(|list| list.len() == 1)?.first()?;
+        if old_art.content == new_art.content {
+            return None;
+        }
+        Some((new_art.number.clone(), article_patch(old_art, new_art)))
+    }).collect()
+}
+
+fn article_patch(old_art: &ArticleInfo, new_art: &ArticleInfo) -> String {
+    let old_content = with_trailing_newline(&old_art.content);
+    let new_content = with_trailing_newline(&new_art.content);
+    TextDiff::from_lines(&old_content, &new_content)
+        .unified_diff()
+        .context_radius(PATCH_CONTEXT_LINES)
+        .header(&format!("a/{}", article_label(&old_art.number)), &format!("b/{}", article_label(&new_art.number)))
+        .to_string()
+}
+
+fn with_trailing_newline(content: &str) -> String {
+    if content.ends_with('\n') { content.to_string() } else { format!("{}\n", content) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_per_article_patch() {
+        let old_text = "第一条 为了保障网络安全，制定本法。\n\
+                        第二条 网络运营者应当履行下列义务：\n\
+                        （一）制定内部安全管理制度；\n\
+                        （二）留存网络日志不少于三个月。";
+        let new_text = "第一条 为了保障网络安全，制定本法。\n\
+                        第二条 网络运营者应当履行下列义务：\n\
+                        （一）制定内部安全管理制度；\n\
+                        （二）留存网络日志不少于六个月。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        let patches = to_per_article_patches(&changes);
+        assert_eq!(patches.len(), 1);
+
+        let (number, patch) = &patches[0];
+        assert_eq!(number.as_ref(), "二");
+        assert!(patch.starts_with("--- a/第二条\n+++ b/第二条\n@@ "));
+        assert!(patch.lines().any(|l| l.starts_with('-') && l.contains("三个月")));
+        assert!(patch.lines().any(|l| l.starts_with('+') && l.contains("六个月")));
+        assert!(patch.lines().any(|l| l.starts_with(' ') && l.contains("内部安全管理制度")));
+    }
+}
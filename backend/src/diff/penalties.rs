@@ -0,0 +1,98 @@
+use crate::models::{ArticleChange, ArticleChangeType, EntityType, PenaltyChange};
+use crate::nlp::{NEREngine, RegexNER};
+use std::sync::Arc;
+
+/// Characters that end a penalty phrase ("吊销许可证，" -> "吊销许可证")
+const PHRASE_BOUNDARY: &[char] = &['，', '。', '；', '、', '：', ',', ';', '\n'];
+
+/// Penalty terms and amounts in article content, in order of appearance.
+/// Penalty keywords are extended to the end of their phrase so "吊销许可证"
+/// and "吊销营业执照" stay distinguishable.
+pub fn extract_penalties(content: &str) -> Vec<Arc<str>> {
+    let entities = RegexNER::new().extract_entities(content).unwrap_or_default();
+    entities.iter().filter_map(|entity| match entity.entity_type {
+        EntityType::Penalty => {
+            let rest = &content[entity.position.start..];
+            let end = rest.find(PHRASE_BOUNDARY).unwrap_or(rest.len());
+            Some(rest[..end].into())
+        }
+        EntityType::Amount => Some(entity.value.clone()),
+        _ => None,
+    }).collect()
+}
+
+/// Matched articles whose penalties changed, with before/after details.
+/// Articles without a penalty difference are left out.
+pub fn penalty_changes(changes: &[ArticleChange]) -> Vec<PenaltyChange> {
+    changes.iter().filter_map(|change| {
+        if matches!(change.change_type, ArticleChangeType::Added | ArticleChangeType::Deleted | ArticleChangeType::Preamble) {
+            return None;
+        }
+        let old_art = change.old_article.as_ref()?;
+        let new_list = change.new_articles.as_ref()?;
+        let new_art = new_list.first()?;
+
+        let old_penalties = extract_penalties(&old_art.content);
+        let new_penalties: Vec<Arc<str>> = new_list.iter().flat_map(|a| extract_penalties(&a.content)).collect();
+        let added = multiset_difference(&new_penalties, &old_penalties);
+        let removed = multiset_difference(&old_penalties, &new_penalties);
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+
+        let mut tags = Vec::new();
+        if !added.is_empty() {
+            tags.push("penalty-added".to_string());
+        }
+        if !removed.is_empty() {
+            tags.push("penalty-removed".to_string());
+        }
+        Some(PenaltyChange {
+            old_number: old_art.number.clone(),
+            new_number: new_art.number.clone(),
+            old_penalties,
+            new_penalties,
+            added,
+            removed,
+            tags,
+        })
+    }).collect()
+}
+
+/// Items of `a` not matched one-for-one by items of `b`
+fn multiset_difference(a: &[Arc<str>], b: &[Arc<str>]) -> Vec<Arc<str>> {
+    let mut remaining: Vec<&Arc<str>> = b.iter().collect();
+    a.iter().filter(|item| {
+        match remaining.iter().position(|other| other == item) {
+            Some(idx) => {
+                remaining.swap_remove(idx);
+                false
+            }
+            None => true,
+        }
+    }).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_penalty_added() {
+        let old_text = "第六十条 违反本法规定的，由有关主管部门责令改正，给予警告。\n\
+                        第六十一条 网络运营者应当加强对其用户发布的信息的管理。";
+        let new_text = "第六十条 违反本法规定的，由有关主管部门责令改正，给予警告；情节严重的，吊销许可证。\n\
+                        第六十一条 网络运营者应当加强对其用户发布的信息的管理工作。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        let report = penalty_changes(&changes);
+        assert_eq!(report.len(), 1);
+
+        let entry = &report[0];
+        assert_eq!(entry.new_number.as_ref(), "六十");
+        assert_eq!(entry.tags, vec!["penalty-added".to_string()]);
+        assert_eq!(entry.added, vec![Arc::<str>::from("吊销许可证")]);
+        assert!(entry.removed.is_empty());
+    }
+}
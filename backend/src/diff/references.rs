@@ -0,0 +1,194 @@
+use crate::ast::article_label;
+use crate::diff::similarity::calculate_char_similarity;
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, NodeType, ReferenceShift};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Relative references whose target depends on the surrounding document
+const RELATIVE_REFERENCES: &[&str] = &["前条", "本条", "前款", "本款"];
+
+/// Paragraphs whose referenced text falls below this similarity count as shifted
+const PARAGRAPH_SHIFT_THRESHOLD: f32 = 0.6;
+
+/// A relative reference found in article content
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelativeReference {
+    pub kind: &'static str,
+    /// Zero-based paragraph (款) of the article the reference appears in
+    pub paragraph: usize,
+}
+
+/// Concrete target of a relative reference
+#[derive(Debug, Clone)]
+struct ResolvedTarget {
+    label: String,
+    article_idx: usize,
+    paragraph: Option<usize>,
+}
+
+/// Find 前条/本条/前款/本款 references in order of appearance
+pub fn find_relative_references(content: &str) -> Vec<RelativeReference> {
+    let mut refs = Vec::new();
+    for (paragraph, line) in paragraphs(content).iter().enumerate() {
+        let mut hits: Vec<(usize, &'static str)> = RELATIVE_REFERENCES.iter()
+            .flat_map(|&kind| line.match_indices(kind).map(move |(pos, _)| (pos, kind)))
+            .collect();
+        hits.sort_by_key(|(pos, _)| *pos);
+        refs.extend(hits.into_iter().map(|(_, kind)| RelativeReference { kind, paragraph }));
+    }
+    refs
+}
+
+/// Flag matched articles whose 前条/前款 references now point at different provisions.
+///
+/// 前条 targets are compared through the alignment (the old target's counterpart must be
+/// the new target); 前款 targets are compared by the text of the referenced paragraph.
+/// Corresponding provisions across laws are skipped, their references never shared a target.
+pub fn annotate_reference_shifts(
+    changes: &mut [ArticleChange],
+    old_articles: &[ArticleInfo],
+    new_articles: &[ArticleInfo],
+) {
+    // Old article position -> new article number, as decided by the alignment
+    let mut counterpart: HashMap<usize, Arc<str>> = HashMap::new();
+    for change in changes.iter() {
+        if let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().and_then(|list| list.first()),
+        ) {
+            if let Some(idx) = position_of(old_articles, old_art) {
+                counterpart.insert(idx, new_art.number.clone());
+            }
+        }
+    }
+
+    for change in changes.iter_mut() {
+        if matches!(change.change_type, ArticleChangeType::Preamble | ArticleChangeType::Corresponding) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().filter(|list| list.len() == 1).and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+        let (Some(old_idx), Some(new_idx)) = (position_of(old_articles, old_art), position_of(new_articles, new_art)) else {
+            continue;
+        };
+
+        let old_refs = find_relative_references(&old_art.content);
+        let new_refs = find_relative_references(&new_art.content);
+
+        let mut shifts = Vec::new();
+        for kind in ["前条", "前款"] {
+            let old_targets = old_refs.iter().filter(|r| r.kind == kind).map(|r| resolve(old_articles, old_idx, r));
+            let new_targets = new_refs.iter().filter(|r| r.kind == kind).map(|r| resolve(new_articles, new_idx, r));
+
+            for (old_target, new_target) in old_targets.zip(new_targets) {
+                let shifted = match (&old_target, &new_target) {
+                    (Some(o), Some(n)) if kind == "前条" => {
+                        counterpart.get(&o.article_idx) != Some(&new_articles[n.article_idx].number)
+                    }
+                    (Some(o), Some(n)) => {
+                        let old_text = paragraph_text(old_articles, o);
+                        let new_text = paragraph_text(new_articles, n);
+                        calculate_char_similarity(&old_text, &new_text) < PARAGRAPH_SHIFT_THRESHOLD
+                    }
+                    (None, None) => false,
+                    _ => true,
+                };
+
+                if shifted {
+                    shifts.push(ReferenceShift {
+                        reference: kind.into(),
+                        old_target: old_target.map(|t| t.label.into()),
+                        new_target: new_target.map(|t| t.label.into()),
+                    });
+                }
+            }
+        }
+
+        if !shifts.is_empty() {
+            change.tags.push("reference-shifted".to_string());
+            change.reference_shifts = Some(shifts);
+        }
+    }
+}
+
+fn resolve(articles: &[ArticleInfo], idx: usize, reference: &RelativeReference) -> Option<ResolvedTarget> {
+    let article = &articles[idx];
+    match reference.kind {
+        "前条" => {
+            let prev_idx = idx.checked_sub(1)?;
+            let prev = &articles[prev_idx];
+            if prev.node_type != NodeType::Article {
+                return None;
+            }
+            Some(ResolvedTarget { label: article_label(&prev.number), article_idx: prev_idx, paragraph: None })
+        }
+        "前款" => {
+            let paragraph = reference.paragraph.checked_sub(1)?;
+            Some(ResolvedTarget {
+                label: format!("{}第{}款", article_label(&article.number), paragraph + 1),
+                article_idx: idx,
+                paragraph: Some(paragraph),
+            })
+        }
+        "本款" => Some(ResolvedTarget {
+            label: format!("{}第{}款", article_label(&article.number), reference.paragraph + 1),
+            article_idx: idx,
+            paragraph: Some(reference.paragraph),
+        }),
+        _ => Some(ResolvedTarget { label: article_label(&article.number), article_idx: idx, paragraph: None }),
+    }
+}
+
+fn paragraphs(content: &str) -> Vec<&str> {
+    content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect()
+}
+
+fn paragraph_text(articles: &[ArticleInfo], target: &ResolvedTarget) -> String {
+    let content = &articles[target.article_idx].content;
+    match target.paragraph {
+        Some(p) => paragraphs(content).get(p).map(|s| s.to_string()).unwrap_or_default(),
+        None => content.to_string(),
+    }
+}
+
+fn position_of(articles: &[ArticleInfo], article: &ArticleInfo) -> Option<usize> {
+    articles.iter().position(|a| a.number == article.number && a.start_line == article.start_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_find_relative_references() {
+        let refs = find_relative_references("违反本条规定的，依照前条处罚。\n前款规定的情形除外。");
+        let kinds: Vec<_> = refs.iter().map(|r| (r.kind, r.paragraph)).collect();
+        assert_eq!(kinds, vec![("本条", 0), ("前条", 0), ("前款", 1)]);
+    }
+
+    #[test]
+    fn test_inserted_article_shifts_previous_reference() {
+        let old = "第一条 网络运营者应当建立管理制度。\n第二条 网络运营者应当采取技术措施。\n第三条 违反前条规定的，由主管部门责令改正。";
+        let new = "第一条 网络运营者应当建立管理制度。\n第二条 网络运营者应当采取技术措施。\n第三条 网络运营者应当制定应急预案。\n第四条 违反前条规定的，由主管部门责令改正。";
+
+        let changes = align_articles(old, new, 0.6, false);
+        let moved = changes.iter()
+            .find(|c| c.old_article.as_ref().is_some_and(|a| a.number.as_ref() == "三"))
+            .expect("Old article 3 should be matched");
+
+        assert!(moved.tags.contains(&"reference-shifted".to_string()));
+        let shift = &moved.reference_shifts.as_ref().unwrap()[0];
+        assert_eq!(shift.reference.as_ref(), "前条");
+        assert_eq!(shift.old_target.as_deref(), Some("第二条"));
+        assert_eq!(shift.new_target.as_deref(), Some("第三条"));
+
+        // Identical documents never flag shifts
+        let unchanged = align_articles(old, old, 0.6, false);
+        assert!(unchanged.iter().all(|c| c.reference_shifts.is_none()));
+    }
+}
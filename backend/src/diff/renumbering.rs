@@ -0,0 +1,77 @@
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, NodeType, NumberMapping, NumberMappingStatus};
+
+/// Old→new article number table derived from the alignment, in change order.
+///
+/// Matched articles map one to one; a split maps its old number to every fragment and a
+/// merge maps every source to the merged article. Deleted articles have no new number and
+/// new ones no old number. Preamble, 附则 text and 附件 blocks are left out.
+pub fn renumbering_map(changes: &[ArticleChange]) -> Vec<NumberMapping> {
+    let mut rows = Vec::new();
+    for change in changes {
+        let old_list: Vec<&ArticleInfo> = match &change.merged_from {
+            Some(sources) => sources.iter().collect(),
+            None => change.old_article.iter().collect(),
+        };
+        let new_list: Vec<&ArticleInfo> = change.new_articles.iter().flatten().collect();
+
+        let row = |old: Option<&ArticleInfo>, new: Option<&ArticleInfo>, status| NumberMapping {
+            old_number: old.map(|a| a.number.clone()),
+            new_number: new.map(|a| a.number.clone()),
+            status,
+        };
+        match change.change_type {
+            ArticleChangeType::Preamble => {}
+            ArticleChangeType::Added => rows.extend(new_list.iter().filter(|a| is_article(a)).map(|&a| row(None, Some(a), NumberMappingStatus::New))),
+            ArticleChangeType::Deleted => rows.extend(old_list.iter().filter(|a| is_article(a)).map(|&a| row(Some(a), None, NumberMappingStatus::Deleted))),
+            ArticleChangeType::Split | ArticleChangeType::Merged => {
+                let status = if change.change_type == ArticleChangeType::Split { NumberMappingStatus::Split } else { NumberMappingStatus::Merged };
+                for &old in old_list.iter().filter(|a| is_article(a)) {
+                    rows.extend(new_list.iter().filter(|a| is_article(a)).map(|&new| row(Some(old), Some(new), status)));
+                }
+            }
+            _ => {
+                let (Some(&old), Some(&new)) = (old_list.first(), new_list.first()) else {
+                    continue;
+                };
+                if !is_article(old) || !is_article(new) {
+                    continue;
+                }
+                let status = if change.change_type == ArticleChangeType::Replaced {
+                    NumberMappingStatus::Replaced
+                } else if old.number == new.number {
+                    NumberMappingStatus::Same
+                } else {
+                    NumberMappingStatus::Renumbered
+                };
+                rows.push(row(Some(old), Some(new), status));
+            }
+        }
+    }
+    rows
+}
+
+fn is_article(article: &ArticleInfo) -> bool {
+    article.node_type == NodeType::Article
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_renumbering_map() {
+        let old = "第一条 为了保障网络安全，制定本法。\n第二条 国家建立和完善网络安全标准体系。\n第三条 本法自公布之日起施行。";
+        let new = "第一条 为了保障网络安全，制定本法。\n第二条 国家支持网络安全技术研究开发。\n第三条 国家建立和完善网络安全标准体系。";
+
+        let changes = align_articles(old, new, 0.6, false);
+        let rows = renumbering_map(&changes);
+        let rows: Vec<_> = rows.iter().map(|r| (r.old_number.as_deref(), r.new_number.as_deref(), r.status)).collect();
+        assert_eq!(rows, vec![
+            (Some("一"), Some("一"), NumberMappingStatus::Same),
+            (None, Some("二"), NumberMappingStatus::New),
+            (Some("二"), Some("三"), NumberMappingStatus::Renumbered),
+            (Some("三"), None, NumberMappingStatus::Deleted),
+        ]);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::diff::minhash::mix;
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Hamming distance above which two articles of comparable length are too far apart to be
+/// worth scoring. Unrelated articles of one law share boilerplate bigrams (本法, 应当…) yet
+/// still average about 31 bits apart; pairs scoring over 0.5 stay within about 24.
+pub const SIMHASH_MAX_DISTANCE: u32 = 28;
+/// Length ratio under which the shorter text may be a split or merge fragment of the longer,
+/// whose fingerprint says little about containment, so the pair is never skipped
+const FRAGMENT_LENGTH_RATIO: f32 = 0.5;
+
+/// 64-bit SimHash of `content` over its character bigrams (whitespace dropped), each
+/// weighted by how often it occurs. Similar texts get fingerprints a few bits apart.
+/// The bigram hash is FNV-1a, stable across builds, so persisted fingerprints stay valid.
+pub fn fingerprint(content: &str) -> u64 {
+    let chars: Vec<char> = content.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut weights = [0i32; 64];
+    for pair in chars.windows(2) {
+        let hash = mix(fnv1a(pair));
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights.iter().enumerate()
+        .filter(|&(_, &w)| w > 0)
+        .fold(0, |acc, (bit, _)| acc | 1 << bit)
+}
+
+fn fnv1a(chars: &[char]) -> u64 {
+    chars.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &c| (hash ^ c as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Whether two articles are clearly unrelated: fingerprints more than `SIMHASH_MAX_DISTANCE`
+/// bits apart and lengths too close for one to be a fragment of the other
+pub fn clearly_unrelated(fingerprint_a: u64, len_a: usize, fingerprint_b: u64, len_b: usize) -> bool {
+    let (short, long) = (len_a.min(len_b), len_a.max(len_b));
+    if long == 0 || (short as f32) < long as f32 * FRAGMENT_LENGTH_RATIO {
+        return false;
+    }
+    (fingerprint_a ^ fingerprint_b).count_ones() > SIMHASH_MAX_DISTANCE
+}
+
+/// Fingerprints as 16 hex digits in JSON, which JavaScript numbers cannot hold exactly
+pub mod hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(fingerprint: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:016x}", fingerprint))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        u64::from_str_radix(&text, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_distance() {
+        let original = fingerprint("网络运营者应当建立健全用户信息保护制度，加强对用户个人信息的保护。");
+        let edited = fingerprint("网络运营者应当建立健全用户信息保护制度，加强对用户个人信息和隐私的保护。");
+        let unrelated = fingerprint("本法自二〇一七年六月一日起施行。");
+
+        assert_eq!(original, fingerprint("网络运营者应当建立健全用户信息保护制度，\n加强对用户个人信息的保护。"));
+        assert!((original ^ edited).count_ones() < SIMHASH_MAX_DISTANCE / 2);
+        assert!((original ^ unrelated).count_ones() > SIMHASH_MAX_DISTANCE);
+        assert_eq!(fingerprint(""), 0);
+
+        // Too different in length for the fingerprints to decide
+        assert!(clearly_unrelated(original, 30, unrelated, 16));
+        assert!(!clearly_unrelated(original, 30, unrelated, 12));
+    }
+
+    #[test]
+    fn test_fingerprint_json_is_hex() {
+        let json = serde_json::to_value(crate::diff::aligner::flatten_articles(&crate::ast::parse_article("第一条 内容。"))).unwrap();
+        let hex = json[0]["fingerprint"].as_str().unwrap();
+        assert_eq!(hex.len(), 16);
+        assert_eq!(u64::from_str_radix(hex, 16).unwrap(), fingerprint("内容。"));
+    }
+}
@@ -1,5 +1,5 @@
 use similar::TextDiff;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Legal keywords that carry significant weight in similarity calculation
@@ -10,11 +10,71 @@ const LEGAL_KEYWORDS: &[&str] = &[
     "刑事", "法律", "规定", "依法", "权利", "义务",
 ];
 
-use crate::models::SimilarityScore;
+use crate::models::{CompareOptions, SimilarityScore, SimilarityWeights};
+use crate::nlp::tokenizer::tokenize_to_counts;
+
+/// How often each term occurs in a text
+pub type TermCounts = HashMap<Arc<str>, usize>;
+
+/// Tokens of one text: the distinct terms for Jaccard and containment, and how often each
+/// occurs for cosine similarity
+#[derive(Debug, Clone, Default)]
+pub struct Terms {
+    pub set: HashSet<Arc<str>>,
+    pub counts: TermCounts,
+}
+
+impl Terms {
+    pub fn of(text: &str) -> Self {
+        Self::from_counts(tokenize_to_counts(text))
+    }
+
+    pub fn from_counts(counts: TermCounts) -> Self {
+        Self { set: counts.keys().cloned().collect(), counts }
+    }
+}
+
+/// Tunable inputs for the composite similarity score
+#[derive(Debug, Clone)]
+pub struct SimilarityConfig {
+    /// Terms used by the keyword-weight dimension
+    pub keywords: Vec<Arc<str>>,
+    /// Weights combining the dimensions into the composite
+    pub weights: SimilarityWeights,
+}
+
+impl Default for SimilarityConfig {
+    fn default() -> Self {
+        Self {
+            keywords: LEGAL_KEYWORDS.iter().map(|&kw| Arc::from(kw)).collect(),
+            weights: SimilarityWeights::default(),
+        }
+    }
+}
+
+impl SimilarityConfig {
+    /// Build from request options. Custom keywords extend the default list
+    /// unless `replace_similarity_keywords` is set.
+    pub fn from_options(options: &CompareOptions) -> Self {
+        let mut config = Self { weights: options.similarity_weights.clone(), ..Self::default() };
+        if let Some(custom) = &options.similarity_keywords {
+            if options.replace_similarity_keywords {
+                config.keywords.clear();
+            }
+            for kw in custom {
+                let kw = kw.trim();
+                if !kw.is_empty() && !config.keywords.iter().any(|k| k.as_ref() == kw) {
+                    config.keywords.push(kw.into());
+                }
+            }
+        }
+        config
+    }
+}
 
 /// Calculate character-level similarity using the similar crate
 pub fn calculate_char_similarity(text1: &str, text2: &str) -> f32 {
-    TextDiff::from_chars(text1, text2).ratio() as f32
+    TextDiff::from_chars(text1, text2).ratio()
 }
 
 /// Calculate Jaccard similarity coefficient based on token sets
@@ -51,17 +111,36 @@ pub fn calculate_containment_similarity(tokens1: &HashSet<Arc<str>>, tokens2: &H
     intersection as f32 / min_size as f32
 }
 
+/// Cosine similarity of term-frequency vectors. Unlike the set-based measures, a key term
+/// repeated throughout both texts weighs more than one mentioned in passing.
+pub fn calculate_cosine_similarity(counts1: &TermCounts, counts2: &TermCounts) -> f32 {
+    if counts1.is_empty() || counts2.is_empty() {
+        return if counts1.is_empty() && counts2.is_empty() { 1.0 } else { 0.0 };
+    }
+
+    let dot: f64 = counts1.iter()
+        .filter_map(|(term, &a)| counts2.get(term).map(|&b| (a * b) as f64))
+        .sum();
+    let norm = |counts: &TermCounts| counts.values().map(|&c| (c * c) as f64).sum::<f64>().sqrt();
+    (dot / (norm(counts1) * norm(counts2))).min(1.0) as f32
+}
+
 /// Calculate legal keyword weight based on keyword overlap
 /// This gives extra weight when important legal terms are preserved
 pub fn calculate_legal_keyword_weight(text1: &str, text2: &str) -> f32 {
-    let keywords1: HashSet<&str> = LEGAL_KEYWORDS.iter()
-        .filter(|&kw| text1.contains(kw))
-        .copied()
+    calculate_keyword_weight(text1, text2, LEGAL_KEYWORDS)
+}
+
+/// Keyword overlap weight over a caller-supplied keyword list
+pub fn calculate_keyword_weight<S: AsRef<str>>(text1: &str, text2: &str, keywords: &[S]) -> f32 {
+    let keywords1: HashSet<&str> = keywords.iter()
+        .map(|kw| kw.as_ref())
+        .filter(|kw| text1.contains(kw))
         .collect();
 
-    let keywords2: HashSet<&str> = LEGAL_KEYWORDS.iter()
-        .filter(|&kw| text2.contains(kw))
-        .copied()
+    let keywords2: HashSet<&str> = keywords.iter()
+        .map(|kw| kw.as_ref())
+        .filter(|kw| text2.contains(kw))
         .collect();
 
     if keywords1.is_empty() && keywords2.is_empty() {
@@ -82,17 +161,28 @@ pub fn calculate_legal_keyword_weight(text1: &str, text2: &str) -> f32 {
 pub fn calculate_composite_similarity(
     text1: &str,
     text2: &str,
-    tokens1: &HashSet<Arc<str>>,
-    tokens2: &HashSet<Arc<str>>,
+    terms1: &Terms,
+    terms2: &Terms,
+) -> crate::models::SimilarityScore {
+    calculate_composite_similarity_with(text1, text2, terms1, terms2, &SimilarityConfig::default())
+}
+
+/// Composite similarity using the given configuration (e.g. request-specific keywords)
+pub fn calculate_composite_similarity_with(
+    text1: &str,
+    text2: &str,
+    terms1: &Terms,
+    terms2: &Terms,
+    config: &SimilarityConfig,
 ) -> crate::models::SimilarityScore {
     // FAST PATH 1: Identity
     if text1 == text2 {
-        return SimilarityScore::new(1.0, 1.0, 1.0, 1.0);
+        return SimilarityScore::with_weights(1.0, 1.0, 1.0, 1.0, 1.0, &config.weights);
     }
 
     // FAST PATH 2: Empty
     if text1.is_empty() || text2.is_empty() {
-        return SimilarityScore::new(0.0, 0.0, 0.0, 0.5);
+        return SimilarityScore::with_weights(0.0, 0.0, 0.0, 0.5, 0.0, &config.weights);
     }
 
     // FAST PATH 3: Length Pruning
@@ -102,17 +192,18 @@ pub fn calculate_composite_similarity(
     let ratio = if len1 > len2 { len2 as f32 / len1 as f32 } else { len1 as f32 / len2 as f32 };
 
     // Low length ratio + low Jaccard means we can skip heavy LCS
-    let jaccard_sim = calculate_jaccard_similarity(tokens1, tokens2);
+    let jaccard_sim = calculate_jaccard_similarity(&terms1.set, &terms2.set);
+    let cosine_sim = calculate_cosine_similarity(&terms1.counts, &terms2.counts);
 
     if ratio < 0.2 && jaccard_sim < 0.1 {
-        return SimilarityScore::new(ratio * 0.5, jaccard_sim, 0.0, 0.5);
+        return SimilarityScore::with_weights(ratio * 0.5, jaccard_sim, 0.0, 0.5, cosine_sim, &config.weights);
     }
 
     let char_sim = calculate_char_similarity(text1, text2);
-    let containment_sim = calculate_containment_similarity(tokens1, tokens2);
-    let keyword_weight = calculate_legal_keyword_weight(text1, text2);
+    let containment_sim = calculate_containment_similarity(&terms1.set, &terms2.set);
+    let keyword_weight = calculate_keyword_weight(text1, text2, &config.keywords);
 
-    let composite = char_sim * 0.3 + jaccard_sim * 0.2 + containment_sim * 0.3 + keyword_weight * 0.2;
+    let composite = config.weights.combine(char_sim, jaccard_sim, containment_sim, keyword_weight, cosine_sim);
 
     // Final safety: only return 1.0 if strings are EXACTLY identical
     // Otherwise cap at 0.99
@@ -127,6 +218,7 @@ pub fn calculate_composite_similarity(
         jaccard_similarity: jaccard_sim,
         containment_similarity: containment_sim,
         keyword_weight,
+        cosine_similarity: cosine_sim,
         composite: final_composite,
     }
 }
@@ -183,6 +275,20 @@ mod tests {
         assert!((score - 0.75).abs() < 0.01);
     }
 
+    #[test]
+    fn test_cosine_counts_repeated_terms() {
+        let counts = |words: &[(&str, usize)]| -> TermCounts { words.iter().map(|&(w, c)| (Arc::from(w), c)).collect() };
+        let base = counts(&[("主管部门", 3), ("备案", 1)]);
+        let same_emphasis = counts(&[("主管部门", 3), ("公告", 1)]);
+        let other_emphasis = counts(&[("主管部门", 1), ("公告", 3)]);
+
+        // Same term sets on the right, so Jaccard scores both pairs alike
+        assert!(calculate_cosine_similarity(&base, &same_emphasis) > 0.85);
+        assert!(calculate_cosine_similarity(&base, &other_emphasis) < 0.35);
+        assert_eq!(calculate_cosine_similarity(&TermCounts::new(), &TermCounts::new()), 1.0);
+        assert_eq!(calculate_cosine_similarity(&base, &TermCounts::new()), 0.0);
+    }
+
     #[test]
     fn test_keyword_weight_with_keywords() {
         let text1 = "违反规定的，应当给予处罚";
@@ -201,15 +307,41 @@ mod tests {
         assert_eq!(weight, 0.5);
     }
 
+    #[test]
+    fn test_custom_keywords_change_weight() {
+        let text1 = "纳税人应当按期申报";
+        let text2 = "纳税人按期申报缴纳";
+
+        // Defaults only see 应当 on one side
+        let default_weight = calculate_legal_keyword_weight(text1, text2);
+        assert_eq!(default_weight, 0.0);
+
+        let options = CompareOptions {
+            similarity_keywords: Some(vec!["纳税".to_string()]),
+            ..Default::default()
+        };
+        let config = SimilarityConfig::from_options(&options);
+        assert!(config.keywords.iter().any(|k| k.as_ref() == "应当"), "Custom keywords augment by default");
+
+        let tax_weight = calculate_keyword_weight(text1, text2, &config.keywords);
+        assert!(tax_weight > default_weight, "Shared tax keyword should raise the weight");
+
+        let replaced = SimilarityConfig::from_options(&CompareOptions {
+            similarity_keywords: Some(vec!["纳税".to_string()]),
+            replace_similarity_keywords: true,
+            ..Default::default()
+        });
+        assert_eq!(calculate_keyword_weight(text1, text2, &replaced.keywords), 1.0);
+    }
+
     #[test]
     fn test_composite_similarity() {
         let text1 = "第五条 网络运营者应当建立安全管理制度";
         let text2 = "第五条 网络运营者应当建立管理制度";
 
-        let tokens1: HashSet<Arc<str>> = ["网络", "运营者", "应当", "建立", "安全", "管理", "制度"]
-            .iter().map(|s| Arc::from(*s)).collect();
-        let tokens2: HashSet<Arc<str>> = ["网络", "运营者", "应当", "建立", "管理", "制度"]
-            .iter().map(|s| Arc::from(*s)).collect();
+        let terms = |words: &[&str]| Terms::from_counts(words.iter().map(|s| (Arc::from(*s), 1)).collect());
+        let tokens1 = terms(&["网络", "运营者", "应当", "建立", "安全", "管理", "制度"]);
+        let tokens2 = terms(&["网络", "运营者", "应当", "建立", "管理", "制度"]);
 
         let score = calculate_composite_similarity(text1, text2, &tokens1, &tokens2);
 
@@ -218,4 +350,41 @@ mod tests {
         assert!(score.containment_similarity > 0.9);
         assert!(score.composite > 0.65);
     }
+
+    #[test]
+    fn test_similarity_weights() {
+        let text1 = "第五条 网络运营者应当建立安全管理制度";
+        let text2 = "第五条 网络运营者应当建立管理制度";
+        let (tokens1, tokens2) = (Terms::of(text1), Terms::of(text2));
+
+        let config = SimilarityConfig::from_options(&CompareOptions {
+            similarity_weights: SimilarityWeights { char: 1.0, jaccard: 0.0, containment: 0.0, keyword: 0.0, cosine: 0.0 },
+            ..Default::default()
+        });
+        let score = calculate_composite_similarity_with(text1, text2, &tokens1, &tokens2, &config);
+        assert!((score.composite - score.char_similarity).abs() < 1e-6);
+
+        // Cosine only counts when asked for
+        assert_eq!(SimilarityWeights::default().cosine, 0.0);
+        let config = SimilarityConfig::from_options(&CompareOptions {
+            similarity_weights: SimilarityWeights { cosine: 1.0, ..SimilarityWeights::default() },
+            ..Default::default()
+        });
+        let with_cosine = calculate_composite_similarity_with(text1, text2, &tokens1, &tokens2, &config);
+        let default = calculate_composite_similarity_with(text1, text2, &tokens1, &tokens2, &SimilarityConfig::default());
+        assert_eq!(with_cosine.cosine_similarity, default.cosine_similarity);
+        assert!((with_cosine.composite - default.composite).abs() > 1e-6);
+
+        // Only the ratio of the weights matters; all-zero weights mean the defaults
+        let doubled = SimilarityWeights { char: 0.6, jaccard: 0.4, containment: 0.6, keyword: 0.4, cosine: 0.0 };
+        let zero = SimilarityWeights { char: 0.0, jaccard: 0.0, containment: 0.0, keyword: 0.0, cosine: 0.0 };
+        assert!((doubled.combine(0.5, 0.4, 0.9, 1.0, 0.6) - SimilarityWeights::default().combine(0.5, 0.4, 0.9, 1.0, 0.6)).abs() < 1e-6);
+        assert!((zero.combine(0.5, 0.4, 0.9, 1.0, 0.6) - SimilarityWeights::default().combine(0.5, 0.4, 0.9, 1.0, 0.6)).abs() < 1e-6);
+
+        // Omitted dimensions weigh nothing once weights are given
+        let weights: SimilarityWeights = serde_json::from_str(r#"{"char": 1}"#).unwrap();
+        assert_eq!(weights, SimilarityWeights { char: 1.0, jaccard: 0.0, containment: 0.0, keyword: 0.0, cosine: 0.0 });
+        let options: CompareOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.similarity_weights, SimilarityWeights::default());
+    }
 }
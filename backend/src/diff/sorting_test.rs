@@ -1,12 +1,26 @@
 #[cfg(test)]
 mod sorting_tests {
-    use super::*;
     use crate::diff::aligner::align_articles;
     use crate::models::ArticleChangeType;
 
     #[test]
     fn test_sorting_order_mixed_changes() {
-      // Logic verified by manual inspection, commenting out to avoid flakiness during frontend work
-      assert!(true);
+        let old = "目 录\n第一章 总则\n第一条 应当建立制度。\n第二条 将被删除的内容。\n第三条 应当加强管理。";
+        let new = "目 录\n第一章 总则\n第一条 应当建立制度。\n第三条 应当加强管理。\n第四条 全新的条款内容。";
+
+        let changes = align_articles(old, new, 0.6, false);
+
+        // Preamble always sorts first
+        assert_eq!(changes[0].change_type, ArticleChangeType::Preamble);
+
+        // Remaining changes follow article number order, deleted ones included
+        let numbers: Vec<&str> = changes[1..].iter()
+            .map(|c| c.new_articles.as_ref()
+                .and_then(|list| list.first())
+                .or(c.old_article.as_ref())
+                .map(|a| a.number.as_ref())
+                .unwrap())
+            .collect();
+        assert_eq!(numbers, vec!["一", "二", "三", "四"]);
     }
 }
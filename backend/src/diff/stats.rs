@@ -0,0 +1,54 @@
+use crate::models::{Entity, EntityDelta, EntityType};
+use std::collections::HashMap;
+
+fn values_of<'a>(entities: &'a [Entity], entity_type: &EntityType) -> Vec<&'a str> {
+    entities.iter().filter(|e| e.entity_type == *entity_type).map(|e| e.value.as_ref()).collect()
+}
+
+/// Per-type entity counts of two versions, with the values each one gained and lost. Types
+/// found in neither version are left out.
+pub fn entity_deltas(old: &[Entity], new: &[Entity]) -> Vec<EntityDelta> {
+    let types = [EntityType::Date, EntityType::Scope, EntityType::Registry, EntityType::Penalty, EntityType::Amount, EntityType::Other];
+    types.into_iter().filter_map(|entity_type| {
+        let (old_values, new_values) = (values_of(old, &entity_type), values_of(new, &entity_type));
+        if old_values.is_empty() && new_values.is_empty() {
+            return None;
+        }
+
+        // Net occurrences of each value: positive when the new text has more
+        let mut balance: HashMap<&str, isize> = HashMap::new();
+        for value in &old_values {
+            *balance.entry(value).or_insert(0) -= 1;
+        }
+        for value in &new_values {
+            *balance.entry(value).or_insert(0) += 1;
+        }
+        Some(EntityDelta {
+            entity_type,
+            old_count: old_values.len(),
+            new_count: new_values.len(),
+            added: balance.values().filter(|&&n| n > 0).map(|&n| n as usize).sum(),
+            removed: balance.values().filter(|&&n| n < 0).map(|&n| n.unsigned_abs()).sum(),
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlp::{NEREngine, RegexNER};
+
+    #[test]
+    fn test_entity_deltas() {
+        let ner = RegexNER::new();
+        let old = ner.extract_entities("处五万元以下罚款，责令停业六个月。").unwrap();
+        let new = ner.extract_entities("处五万元以上二十万元以下罚款，责令停业六个月。").unwrap();
+
+        let amounts = entity_deltas(&old, &new).into_iter()
+            .find(|d| d.entity_type == EntityType::Amount)
+            .unwrap();
+        assert_eq!((amounts.old_count, amounts.new_count, amounts.added, amounts.removed), (1, 2, 1, 0));
+        assert!(entity_deltas(&old, &old).iter().all(|d| d.added == 0 && d.removed == 0));
+        assert!(entity_deltas(&[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::diff::moves::longest_increasing;
+use crate::models::{ArticleChange, ArticleNode, HeadingInfo, NodeType, StructureChange, StructureChangeType};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Heading levels from the top down; a level is matched only once its parents are
+const LEVELS: [NodeType; 4] = [NodeType::Part, NodeType::SubPart, NodeType::Chapter, NodeType::Section];
+
+/// A heading with the index of its enclosing heading in the same list
+struct Heading {
+    node_type: NodeType,
+    info: HeadingInfo,
+    parent: Option<usize>,
+    path: Vec<Arc<str>>, // Labels down to and including this heading, as in `ArticleInfo::parents`
+}
+
+impl Heading {
+    /// Whether an article with these hierarchy labels sits under this heading
+    fn contains(&self, parents: &[Arc<str>]) -> bool {
+        parents.starts_with(&self.path)
+    }
+}
+
+fn collect_headings(node: &ArticleNode, parent: Option<usize>, labels: &[Arc<str>], out: &mut Vec<Heading>) {
+    for child in &node.children {
+        if !LEVELS.contains(&child.node_type) {
+            continue;
+        }
+        let label: Arc<str> = match &child.title {
+            Some(title) => format!("{} {}", child.number, title).into(),
+            None => child.number.clone(),
+        };
+        let mut path = labels.to_vec();
+        path.push(label);
+
+        let idx = out.len();
+        out.push(Heading {
+            node_type: child.node_type.clone(),
+            info: HeadingInfo {
+                number: child.number.clone(),
+                title: child.title.clone(),
+                start_line: child.start_line,
+                parents: labels.to_vec(),
+            },
+            parent,
+            path: path.clone(),
+        });
+        collect_headings(child, Some(idx), &path, out);
+    }
+}
+
+/// Diff the 编/分编/章/节 skeleton of two documents, grouped by level from the top down.
+///
+/// Headings are paired level by level: same number, title and parent first, then the same
+/// title (renumbered, or moved when the parent differs), then the heading most of whose
+/// matched articles (per `article_changes`) ended up under the other one (renamed, or moved),
+/// then the same number under the same parent (renamed). A parent is the same when the
+/// enclosing headings were paired with each other, so renaming a chapter does not make all
+/// its sections look moved. Headings paired as they were but out of order among their
+/// siblings are reordered; unpaired headings are added or deleted.
+pub fn diff_structure(old_root: &ArticleNode, new_root: &ArticleNode, article_changes: &[ArticleChange]) -> Vec<StructureChange> {
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    collect_headings(old_root, None, &[], &mut old);
+    collect_headings(new_root, None, &[], &mut new);
+
+    // Hierarchy labels of each matched article on both sides
+    let matched: Vec<_> = article_changes.iter().filter_map(|change| {
+        let old_art = change.old_article.as_ref()?;
+        let new_art = change.new_articles.as_ref()?.first()?;
+        Some((old_art.parents.as_slice(), new_art.parents.as_slice()))
+    }).collect();
+    let shared_articles = |o: &Heading, n: &Heading| -> (usize, usize) {
+        let under_old = matched.iter().filter(|(old_parents, _)| o.contains(old_parents));
+        let total = under_old.clone().count();
+        (under_old.filter(|(_, new_parents)| n.contains(new_parents)).count(), total)
+    };
+
+    let mut paired: HashMap<usize, usize> = HashMap::new();
+    let mut changes = Vec::new();
+    for level in &LEVELS {
+        let mut unpaired_old: Vec<usize> = (0..old.len()).filter(|&i| old[i].node_type == *level).collect();
+        let mut new_idx: Vec<usize> = (0..new.len()).filter(|&i| new[i].node_type == *level).collect();
+        let mut level_changes: Vec<StructureChange> = Vec::new();
+
+        let same_parent = |paired: &HashMap<usize, usize>, o: usize, n: usize| match (old[o].parent, new[n].parent) {
+            (None, None) => true,
+            (Some(op), Some(np)) => paired.get(&op) == Some(&np),
+            _ => false,
+        };
+        let mut level_pairs: Vec<(usize, usize, Option<StructureChangeType>)> = Vec::new();
+        for pass in 0..4 {
+            let mut remaining = Vec::new();
+            for &o in &unpaired_old {
+                let found = match pass {
+                    2 => new_idx.iter().enumerate()
+                        .map(|(pos, &n)| (pos, shared_articles(&old[o], &new[n])))
+                        .filter(|&(_, (shared, total))| shared > 0 && shared * 2 > total)
+                        .max_by_key(|&(_, (shared, _))| shared)
+                        .map(|(pos, _)| pos),
+                    _ => new_idx.iter().position(|&n| {
+                        let (old_info, new_info) = (&old[o].info, &new[n].info);
+                        match pass {
+                            0 => old_info.number == new_info.number && old_info.title == new_info.title && same_parent(&paired, o, n),
+                            1 => old_info.title.is_some() && old_info.title == new_info.title,
+                            _ => old_info.number == new_info.number && same_parent(&paired, o, n),
+                        }
+                    }),
+                };
+                let Some(pos) = found else {
+                    remaining.push(o);
+                    continue;
+                };
+                let n = new_idx.remove(pos);
+                let change_type = match pass {
+                    0 => None,
+                    1 | 2 if !same_parent(&paired, o, n) => Some(StructureChangeType::Moved),
+                    1 => Some(StructureChangeType::Renumbered),
+                    _ => Some(StructureChangeType::Renamed),
+                };
+                paired.insert(o, n);
+                level_pairs.push((o, n, change_type));
+            }
+            unpaired_old = remaining;
+        }
+
+        // Siblings that kept their parent but not their relative order were reordered
+        level_pairs.sort_unstable_by_key(|&(o, _, _)| o);
+        let mut siblings: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+        for (idx, &(o, _, change_type)) in level_pairs.iter().enumerate() {
+            if change_type != Some(StructureChangeType::Moved) {
+                siblings.entry(old[o].parent).or_default().push(idx);
+            }
+        }
+        for group in siblings.values() {
+            let in_order = longest_increasing(&group.iter().map(|&idx| level_pairs[idx].1).collect::<Vec<_>>());
+            for (&idx, kept) in group.iter().zip(in_order) {
+                let change_type = &mut level_pairs[idx].2;
+                if !kept && matches!(change_type, None | Some(StructureChangeType::Renumbered)) {
+                    *change_type = Some(StructureChangeType::Reordered);
+                }
+            }
+        }
+        for &(o, n, change_type) in &level_pairs {
+            if let Some(change_type) = change_type {
+                level_changes.push(StructureChange {
+                    change_type,
+                    node_type: level.clone(),
+                    old: Some(old[o].info.clone()),
+                    new: Some(new[n].info.clone()),
+                });
+            }
+        }
+
+        for o in unpaired_old {
+            level_changes.push(StructureChange {
+                change_type: StructureChangeType::Deleted,
+                node_type: level.clone(),
+                old: Some(old[o].info.clone()),
+                new: None,
+            });
+        }
+        for n in new_idx {
+            level_changes.push(StructureChange {
+                change_type: StructureChangeType::Added,
+                node_type: level.clone(),
+                old: None,
+                new: Some(new[n].info.clone()),
+            });
+        }
+        // Deleted headings sort by their old line, everything else by the new one
+        level_changes.sort_by_key(|c| c.new.as_ref().or(c.old.as_ref()).map(|h| h.start_line));
+        changes.extend(level_changes);
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_diff_structure() {
+        let old = "第一章 总则\n第一条 内容。\n第二章 管理\n第一节 登记\n第二条 内容。\n第二节 监督\n第三条 内容。\n第三章 法律责任\n第四条 内容。";
+        let new = "第一章 一般规定\n第一条 内容。\n第二章 管理\n第一节 登记\n第二条 内容。\n第三章 监督检查\n第一节 监督\n第三条 内容。\n第四章 法律责任\n第四条 内容。";
+
+        let changes = diff_structure(&parse_article(old), &parse_article(new), &[]);
+        let summary: Vec<_> = changes.iter()
+            .map(|c| (
+                c.change_type,
+                c.node_type.clone(),
+                c.old.as_ref().map(|h| h.number.to_string()),
+                c.new.as_ref().map(|h| h.number.to_string()),
+            ))
+            .collect();
+        assert_eq!(summary, vec![
+            (StructureChangeType::Renamed, NodeType::Chapter, Some("一".into()), Some("一".into())),
+            (StructureChangeType::Added, NodeType::Chapter, None, Some("三".into())),
+            (StructureChangeType::Renumbered, NodeType::Chapter, Some("三".into()), Some("四".into())),
+            (StructureChangeType::Moved, NodeType::Section, Some("二".into()), Some("一".into())),
+        ]);
+        assert_eq!(changes[3].new.as_ref().unwrap().parents, vec![Arc::from("三 监督检查")]);
+
+        // Renaming a chapter leaves its sections where they were
+        let renamed = old.replace("第二章 管理", "第二章 登记管理");
+        let changes = diff_structure(&parse_article(old), &parse_article(&renamed), &[]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change_type, StructureChangeType::Renamed);
+    }
+
+    #[test]
+    fn test_diff_structure_by_contained_articles() {
+        let old = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n\
+                   第二章 网络运行安全\n第二条 国家实行网络安全等级保护制度。\n\
+                   第三章 法律责任\n第三条 违反本法规定的，由主管部门责令改正。";
+        let new = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n\
+                   第二章 监测预警\n第二条 国家建立网络安全监测预警和信息通报制度。\n\
+                   第三章 网络运行安全保障\n第三条 国家实行网络安全等级保护制度。\n\
+                   第四章 法律责任\n第四条 违反本法规定的，由主管部门责令改正。";
+
+        let (old_ast, new_ast) = (parse_article(old), parse_article(new));
+        let summary = |changes: Vec<StructureChange>| -> Vec<_> {
+            changes.iter()
+                .map(|c| (c.change_type, c.old.as_ref().map(|h| h.number.to_string()), c.new.as_ref().map(|h| h.number.to_string())))
+                .collect()
+        };
+        // The renamed chapter is found through its article, not taken for the inserted one
+        let articles = align_articles(old, new, 0.6, false);
+        assert_eq!(summary(diff_structure(&old_ast, &new_ast, &articles)), vec![
+            (StructureChangeType::Added, None, Some("二".into())),
+            (StructureChangeType::Renamed, Some("二".into()), Some("三".into())),
+            (StructureChangeType::Renumbered, Some("三".into()), Some("四".into())),
+        ]);
+
+        // Two chapters trading places: one of them is reported as reordered
+        let swapped = "第一章 总则\n第一条 为了保障网络安全，制定本法。\n\
+                       第二章 法律责任\n第二条 违反本法规定的，由主管部门责令改正。\n\
+                       第三章 网络运行安全\n第三条 国家实行网络安全等级保护制度。";
+        let articles = align_articles(old, swapped, 0.6, false);
+        assert_eq!(summary(diff_structure(&old_ast, &parse_article(swapped), &articles)), vec![
+            (StructureChangeType::Renumbered, Some("三".into()), Some("二".into())),
+            (StructureChangeType::Reordered, Some("二".into()), Some("三".into())),
+        ]);
+    }
+}
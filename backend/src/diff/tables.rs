@@ -0,0 +1,104 @@
+use crate::ast::parse_tables;
+use crate::models::{ArticleChange, ArticleChangeType, TableCellChange};
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+/// Cell-level changes between the tables of two versions of an article. Tables pair up by
+/// position; rows are matched on their first cell so inserted rows do not shift the rest.
+pub fn diff_tables(old_content: &str, new_content: &str) -> Vec<TableCellChange> {
+    let old_tables = parse_tables(old_content);
+    let new_tables = parse_tables(new_content);
+    let empty = Vec::new();
+    let mut changes = Vec::new();
+
+    for table in 0..old_tables.len().max(new_tables.len()) {
+        let old_rows = old_tables.get(table).map_or(&empty, |t| &t.rows);
+        let new_rows = new_tables.get(table).map_or(&empty, |t| &t.rows);
+        let old_keys: Vec<&str> = old_rows.iter().map(|row| row[0].as_str()).collect();
+        let new_keys: Vec<&str> = new_rows.iter().map(|row| row[0].as_str()).collect();
+
+        let mut push_row = |old_row: Option<usize>, new_row: Option<usize>| {
+            let old_cells = old_row.map_or(&[][..], |r| old_rows[r].as_slice());
+            let new_cells = new_row.map_or(&[][..], |r| new_rows[r].as_slice());
+            for column in 0..old_cells.len().max(new_cells.len()) {
+                let old_value = old_cells.get(column);
+                let new_value = new_cells.get(column);
+                if old_value != new_value {
+                    changes.push(TableCellChange {
+                        table: table + 1,
+                        old_row: old_row.map(|r| r + 1),
+                        new_row: new_row.map(|r| r + 1),
+                        column: column + 1,
+                        old_value: old_value.map(|v| v.as_str().into()),
+                        new_value: new_value.map(|v| v.as_str().into()),
+                    });
+                }
+            }
+        };
+
+        for op in capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys) {
+            match op {
+                DiffOp::Equal { old_index, new_index, len } => {
+                    for i in 0..len {
+                        push_row(Some(old_index + i), Some(new_index + i));
+                    }
+                }
+                DiffOp::Delete { old_index, old_len, .. } => {
+                    for i in 0..old_len {
+                        push_row(Some(old_index + i), None);
+                    }
+                }
+                DiffOp::Insert { new_index, new_len, .. } => {
+                    for i in 0..new_len {
+                        push_row(None, Some(new_index + i));
+                    }
+                }
+                // Rows whose key cell changed: pair them up in order
+                DiffOp::Replace { old_index, old_len, new_index, new_len } => {
+                    for i in 0..old_len.max(new_len) {
+                        push_row((i < old_len).then_some(old_index + i), (i < new_len).then_some(new_index + i));
+                    }
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Attach cell-level table changes to every matched, non-identical article pair
+pub fn annotate_table_changes(changes: &mut [ArticleChange]) {
+    for change in changes.iter_mut() {
+        if matches!(change.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Preamble) {
+            continue;
+        }
+        let (Some(old_art), Some(new_art)) = (
+            change.old_article.as_ref(),
+            change.new_articles.as_ref().filter(|list| list.len() == 1).and_then(|list| list.first()),
+        ) else {
+            continue;
+        };
+        let table_changes = diff_tables(&old_art.content, &new_art.content);
+        if !table_changes.is_empty() {
+            change.table_changes = Some(table_changes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_tables() {
+        let old = "按下表处罚：\n违法行为｜罚款\n未登记｜一万元\n逾期｜五千元";
+        let new = "按下表处罚：\n违法行为｜罚款\n未登记｜两万元\n虚假登记｜三万元\n逾期｜五千元";
+        let changes = diff_tables(old, new);
+
+        assert_eq!(changes.len(), 3, "{:?}", changes);
+        let modified = &changes[0];
+        assert_eq!((modified.old_row, modified.new_row, modified.column), (Some(2), Some(2), 2));
+        assert_eq!((modified.old_value.as_deref(), modified.new_value.as_deref()), (Some("一万元"), Some("两万元")));
+        // The inserted row reports each of its cells; 逾期 still pairs with itself
+        assert!(changes[1..].iter().all(|c| c.old_row.is_none() && c.new_row == Some(3)));
+        assert_eq!(changes[2].new_value.as_deref(), Some("三万元"));
+    }
+}
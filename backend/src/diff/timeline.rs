@@ -0,0 +1,129 @@
+use crate::diff::aligner::{align_articles_with_options, AlignmentResult};
+use crate::models::{ArticleChangeType, ArticleHistory, ArticleInfo, CompareOptions, TimelineResult, VersionEvent};
+use rayon::prelude::*;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An article within one version as (number, start line)
+type ArticleKey = (Arc<str>, usize);
+
+fn key(article: &ArticleInfo) -> ArticleKey {
+    (article.number.clone(), article.start_line)
+}
+
+/// Follow every article through an ordered chain of versions (oldest first).
+///
+/// Neighbouring versions are aligned pairwise and the alignments chained: an article keeps
+/// its history while each step matches it to a successor. A split continues into the first
+/// piece and starts a history for each other piece; a merge continues the first source and
+/// ends the others. Fewer than two versions yield no histories.
+pub fn align_versions(texts: &[&str], options: &CompareOptions) -> TimelineResult {
+    let alignments: Vec<AlignmentResult> = texts.par_windows(2)
+        .map(|pair| align_articles_with_options(pair[0], pair[1], options))
+        .collect();
+
+    let mut histories: Vec<ArticleHistory> = Vec::new();
+    let start = |version: usize, article: &ArticleInfo, event: Option<VersionEvent>| {
+        let mut versions = vec![None; texts.len()];
+        versions[version] = Some(article.clone());
+        ArticleHistory { introduced: version, removed: None, versions, events: event.into_iter().collect() }
+    };
+
+    // Articles of the first version, in document order
+    let mut current: HashMap<ArticleKey, usize> = HashMap::new();
+    if let Some(first) = alignments.first() {
+        let mut originals: Vec<&ArticleInfo> = first.changes.iter()
+            .flat_map(|c| c.merged_from.iter().flatten().chain(c.old_article.iter()))
+            .collect();
+        originals.sort_by_key(|a| a.start_line);
+        for article in originals {
+            if let Entry::Vacant(slot) = current.entry(key(article)) {
+                slot.insert(histories.len());
+                histories.push(start(0, article, None));
+            }
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (step, alignment) in alignments.iter().enumerate() {
+        let version = step + 1;
+        let mut next: HashMap<ArticleKey, usize> = HashMap::new();
+        for change in &alignment.changes {
+            let event = VersionEvent { version, change_type: change.change_type.clone(), similarity: change.similarity };
+            let sources: Vec<&ArticleInfo> = match &change.merged_from {
+                Some(sources) => sources.iter().collect(),
+                None => change.old_article.iter().collect(),
+            };
+            let targets: &[ArticleInfo] = match change.change_type {
+                ArticleChangeType::Deleted => &[],
+                _ => change.new_articles.as_deref().unwrap_or_default(),
+            };
+
+            for source in &sources {
+                let Some(&idx) = current.get(&key(source)) else {
+                    continue;
+                };
+                let history = &mut histories[idx];
+                match targets.first() {
+                    // Merged into an article another source already continues into
+                    Some(target) if !next.contains_key(&key(target)) => {
+                        history.versions[version] = Some(target.clone());
+                        if change.change_type != ArticleChangeType::Unchanged {
+                            history.events.push(event.clone());
+                        }
+                        next.insert(key(target), idx);
+                    }
+                    _ => {
+                        history.removed = Some(version);
+                        history.events.push(event.clone());
+                    }
+                }
+            }
+
+            // New articles, and the further pieces of a split
+            let continued = if sources.is_empty() { 0 } else { 1 };
+            for target in targets.iter().skip(continued) {
+                if let Entry::Vacant(slot) = next.entry(key(target)) {
+                    slot.insert(histories.len());
+                    histories.push(start(version, target, Some(event.clone())));
+                }
+            }
+        }
+        warnings.extend(alignment.warnings.iter().map(|w| format!("versions {}→{}: {}", step, version, w)));
+        current = next;
+    }
+
+    TimelineResult { versions: texts.len(), articles: histories, warnings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_versions() {
+        let v0 = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络运营者应当建立管理制度。\n第三条 违反本法规定的，依法给予处罚。";
+        let v1 = "第一条 为了规范网络安全管理，保护公民合法权益，制定本法。\n第二条 网络运营者应当建立管理制度。\n第三条 违反本法规定的，依法给予处罚。\n第四条 本法自公布之日起施行。";
+        let v2 = "第一条 为了规范网络安全管理，保护公民合法权益，制定本法。\n第二条 违反本法规定的，依法给予处罚。\n第三条 本法自公布之日起施行。";
+
+        let timeline = align_versions(&[v0, v1, v2], &CompareOptions::default());
+        assert_eq!(timeline.versions, 3);
+
+        let summary: Vec<_> = timeline.articles.iter()
+            .map(|h| (h.introduced, h.removed, h.events.iter().map(|e| (e.version, e.change_type.clone())).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(summary, vec![
+            (0, None, vec![(1, ArticleChangeType::Modified)]),
+            (0, Some(2), vec![(2, ArticleChangeType::Deleted)]),
+            (0, None, vec![(2, ArticleChangeType::Renumbered)]),
+            (1, None, vec![(1, ArticleChangeType::Added), (2, ArticleChangeType::Renumbered)]),
+        ]);
+
+        let numbers: Vec<Option<&str>> = timeline.articles[2].versions.iter()
+            .map(|a| a.as_ref().map(|a| a.number.as_ref()))
+            .collect();
+        assert_eq!(numbers, vec![Some("三"), Some("三"), Some("二")]);
+        assert_eq!(timeline.articles[3].versions[0].as_ref().map(|a| a.number.as_ref()), None);
+    }
+}
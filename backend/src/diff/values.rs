@@ -0,0 +1,151 @@
+use crate::models::{ArticleChange, ArticleChangeType, EntityType, ValueChange, ValueDirection, ValueKind};
+use crate::nlp::numerals::chinese_to_int;
+use crate::nlp::{NEREngine, RegexNER};
+use regex::Regex;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+use std::sync::OnceLock;
+
+static CALENDAR_DATE_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_calendar_date_pattern() -> &'static Regex {
+    CALENDAR_DATE_PATTERN.get_or_init(|| {
+        Regex::new(r"^(\d{4})年(?:(\d{1,2})月)?(?:(\d{1,2})日)?$").unwrap()
+    })
+}
+
+/// Value of an amount such as "五万元", "200元" or "一亿五千万元" in 元
+fn amount_in_yuan(value: &str) -> f64 {
+    let digits = value.trim_end_matches('元');
+    match digits.split_once('亿') {
+        Some((yi, rest)) => chinese_to_int(yi) as f64 * 1e8 + chinese_to_int(rest) as f64,
+        None => chinese_to_int(digits) as f64,
+    }
+}
+
+/// Classify a date entity and normalize it: "六个月" and "二年" are periods in months,
+/// "2024年1月1日" (or a bare four-digit year) a calendar date as yyyymmdd
+fn date_value(value: &str) -> (ValueKind, f64) {
+    if let Some(caps) = get_calendar_date_pattern().captures(value) {
+        let part = |idx: usize| caps.get(idx).and_then(|m| m.as_str().parse::<f64>().ok()).unwrap_or(0.0);
+        return (ValueKind::Date, part(1) * 10000.0 + part(2) * 100.0 + part(3));
+    }
+    match value.strip_suffix("个月") {
+        Some(months) => (ValueKind::Duration, chinese_to_int(months) as f64),
+        None => (ValueKind::Duration, chinese_to_int(value.trim_end_matches('年')) as f64 * 12.0),
+    }
+}
+
+/// Amounts, periods and dates in article content, in order of appearance
+pub fn extract_values(content: &str) -> Vec<(ValueKind, String, f64)> {
+    let mut entities = RegexNER::new().extract_entities(content).unwrap_or_default();
+    entities.sort_by_key(|e| e.position.start);
+    entities.iter().filter_map(|entity| match entity.entity_type {
+        EntityType::Amount => Some((ValueKind::Amount, entity.value.to_string(), amount_in_yuan(&entity.value))),
+        EntityType::Date => {
+            let (kind, number) = date_value(&entity.value);
+            Some((kind, entity.value.to_string(), number))
+        }
+        _ => None,
+    }).collect()
+}
+
+/// Pair the values of each kind that an edit replaced, in order, and keep those whose
+/// normalized number moved; rewriting "五万元" as "50000元" is not a value change
+pub fn value_changes(old_content: &str, new_content: &str) -> Vec<ValueChange> {
+    let (old_values, new_values) = (extract_values(old_content), extract_values(new_content));
+    let mut changes = Vec::new();
+    for kind in [ValueKind::Amount, ValueKind::Duration, ValueKind::Date] {
+        let old: Vec<&(ValueKind, String, f64)> = old_values.iter().filter(|v| v.0 == kind).collect();
+        let new: Vec<&(ValueKind, String, f64)> = new_values.iter().filter(|v| v.0 == kind).collect();
+        let old_text: Vec<&str> = old.iter().map(|v| v.1.as_str()).collect();
+        let new_text: Vec<&str> = new.iter().map(|v| v.1.as_str()).collect();
+
+        for op in capture_diff_slices(Algorithm::Myers, &old_text, &new_text) {
+            let DiffOp::Replace { old_index, old_len, new_index, new_len } = op else {
+                continue;
+            };
+            for offset in 0..old_len.min(new_len) {
+                let (before, after) = (old[old_index + offset], new[new_index + offset]);
+                let direction = if after.2 > before.2 {
+                    ValueDirection::Increased
+                } else if after.2 < before.2 {
+                    ValueDirection::Decreased
+                } else {
+                    continue;
+                };
+                changes.push(ValueChange {
+                    kind,
+                    old_value: before.1.as_str().into(),
+                    new_value: after.1.as_str().into(),
+                    old_number: before.2,
+                    new_number: after.2,
+                    direction,
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// Attach the value changes of each matched article and tag it "value-increased" and/or
+/// "value-decreased". Corresponding provisions of two different laws are not versions of
+/// one another, so their figures are not compared.
+pub fn annotate_value_changes(changes: &mut [ArticleChange]) {
+    for change in changes.iter_mut() {
+        if matches!(change.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Preamble | ArticleChangeType::Corresponding) {
+            continue;
+        }
+        let (Some(old_art), Some(new_list)) = (change.old_article.as_ref(), change.new_articles.as_ref()) else {
+            continue;
+        };
+        let new_content: String = new_list.iter().map(|a| a.content.as_ref()).collect::<Vec<_>>().join("\n");
+        let found = value_changes(&old_art.content, &new_content);
+        if found.is_empty() {
+            continue;
+        }
+        for (direction, tag) in [(ValueDirection::Increased, "value-increased"), (ValueDirection::Decreased, "value-decreased")] {
+            if found.iter().any(|v| v.direction == direction) {
+                change.tags.push(tag.to_string());
+            }
+        }
+        change.value_changes = Some(found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_value_changes() {
+        let changes = value_changes(
+            "违反本法规定的，处五万元以下罚款，责令停业六个月；自2021年1月1日起施行。",
+            "违反本法规定的，处二十万元以下罚款，责令停业一年；自2024年1月1日起施行。",
+        );
+        let summary: Vec<(ValueKind, &str, &str, f64, f64, ValueDirection)> = changes.iter()
+            .map(|c| (c.kind, c.old_value.as_ref(), c.new_value.as_ref(), c.old_number, c.new_number, c.direction))
+            .collect();
+        assert_eq!(summary, vec![
+            (ValueKind::Amount, "五万元", "二十万元", 50000.0, 200000.0, ValueDirection::Increased),
+            (ValueKind::Duration, "六个月", "一年", 6.0, 12.0, ValueDirection::Increased),
+            (ValueKind::Date, "2021年1月1日", "2024年1月1日", 20210101.0, 20240101.0, ValueDirection::Increased),
+        ]);
+
+        // The same value written differently, or untouched, is not a change
+        assert!(value_changes("处五万元以下罚款。", "处50000元以下罚款。").is_empty());
+        assert!(value_changes("处五万元以下罚款。", "可以处五万元以下罚款。").is_empty());
+    }
+
+    #[test]
+    fn test_annotate_value_changes() {
+        let old_text = "第十条 违反本法规定的，处十万元以下罚款。";
+        let new_text = "第十条 违反本法规定的，处一万元以下罚款。";
+
+        let changes = align_articles(old_text, new_text, 0.6, false);
+        let values = changes[0].value_changes.as_ref().expect("value changes");
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].direction, ValueDirection::Decreased);
+        assert!(changes[0].tags.contains(&"value-decreased".to_string()));
+    }
+}
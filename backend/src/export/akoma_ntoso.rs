@@ -0,0 +1,286 @@
+use crate::ast::{article_label, toc_entry_label};
+use crate::models::{ArticleNode, DocumentMeta, NodeType};
+use crate::nlp::article_sort_key;
+use regex::Regex;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+const AKN_NAMESPACE: &str = "http://docs.oasis-open.org/legaldocml/ns/akn/3.0";
+
+static CN_DATE_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_cn_date_pattern() -> &'static Regex {
+    CN_DATE_PATTERN.get_or_init(|| Regex::new(r"(\d{4})年(\d{1,2})月(\d{1,2})日").unwrap())
+}
+
+/// Serialize a parsed document as Akoma Ntoso (LegalDocML) XML.
+///
+/// eIds follow the AKN naming convention and depend only on structural numbers, so the
+/// same article keeps its eId across versions: containers nest (`part_1__chp_2__sec_1`),
+/// articles are document-wide (`art_5`, bis articles `art_29-1`) and clauses/items hang off
+/// their article (`art_5__para_1__point_2`).
+pub fn to_akoma_ntoso(root: &ArticleNode, meta: &DocumentMeta) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(xml, "<akomaNtoso xmlns=\"{}\">", AKN_NAMESPACE);
+    xml.push_str("  <act name=\"act\">\n");
+    write_meta(&mut xml, meta);
+
+    let nodes: Vec<&ArticleNode> = if root.number.as_ref() == "root" { root.children.iter().collect() } else { vec![root] };
+    let preamble = nodes.iter().find(|n| n.node_type == NodeType::Preamble);
+    let toc = nodes.iter().find(|n| n.node_type == NodeType::Toc);
+    if preamble.is_some() || toc.is_some() {
+        xml.push_str("    <preface>\n");
+        for line in preamble.map(|p| content_lines(&p.content)).unwrap_or_default() {
+            if meta.title.as_deref() == Some(line) {
+                let _ = writeln!(xml, "      <p><docTitle>{}</docTitle></p>", escape(line));
+            } else {
+                let _ = writeln!(xml, "      <p>{}</p>", escape(line));
+            }
+        }
+        if let Some(toc) = toc {
+            xml.push_str("      <toc>\n");
+            for entry in &toc.children {
+                write_toc_entry(&mut xml, entry, "", 1);
+            }
+            xml.push_str("      </toc>\n");
+        }
+        xml.push_str("    </preface>\n");
+    }
+
+    xml.push_str("    <body>\n");
+    for node in nodes.iter().filter(|n| !matches!(n.node_type, NodeType::Preamble | NodeType::Toc | NodeType::Annex)) {
+        write_node(&mut xml, node, "", 3);
+    }
+    xml.push_str("    </body>\n");
+
+    let annexes: Vec<&&ArticleNode> = nodes.iter().filter(|n| n.node_type == NodeType::Annex).collect();
+    if !annexes.is_empty() {
+        xml.push_str("    <attachments>\n");
+        for (i, annex) in annexes.iter().enumerate() {
+            let pad = "      ";
+            let _ = writeln!(xml, "{}<attachment eId=\"att_{}\">", pad, i + 1);
+            let _ = writeln!(xml, "{}  <doc name=\"annex\">", pad);
+            let _ = writeln!(xml, "{}    <mainBody>", pad);
+            let _ = writeln!(xml, "{}      <p>{}</p>", pad, escape(&annex_heading(annex)));
+            for line in content_lines(&annex.content) {
+                let _ = writeln!(xml, "{}      <p>{}</p>", pad, escape(line));
+            }
+            let _ = writeln!(xml, "{}    </mainBody>", pad);
+            let _ = writeln!(xml, "{}  </doc>", pad);
+            let _ = writeln!(xml, "{}</attachment>", pad);
+        }
+        xml.push_str("    </attachments>\n");
+    }
+
+    xml.push_str("  </act>\n</akomaNtoso>\n");
+    xml
+}
+
+fn write_meta(xml: &mut String, meta: &DocumentMeta) {
+    xml.push_str("    <meta>\n      <identification source=\"#law_diff\">\n        <FRBRWork>\n");
+    xml.push_str("          <FRBRthis value=\"/akn/cn/act/main\"/>\n");
+    xml.push_str("          <FRBRuri value=\"/akn/cn/act\"/>\n");
+    if let Some(title) = &meta.title {
+        let _ = writeln!(xml, "          <FRBRname value=\"{}\"/>", escape(title));
+    }
+    for (name, date) in [("adopted", &meta.adopted), ("promulgated", &meta.promulgated), ("effective", &meta.effective)] {
+        if let Some(iso) = date.as_deref().and_then(iso_date) {
+            let _ = writeln!(xml, "          <FRBRdate date=\"{}\" name=\"{}\"/>", iso, name);
+        }
+    }
+    if meta.issuing_authority.is_some() {
+        xml.push_str("          <FRBRauthor href=\"#author\"/>\n");
+    }
+    xml.push_str("          <FRBRcountry value=\"cn\"/>\n        </FRBRWork>\n      </identification>\n");
+    if let Some(authority) = &meta.issuing_authority {
+        let _ = writeln!(
+            xml,
+            "      <references source=\"#law_diff\">\n        <TLCOrganization eId=\"author\" href=\"/akn/ontology/organizations/cn/author\" showAs=\"{}\"/>\n      </references>",
+            escape(authority)
+        );
+    }
+    xml.push_str("    </meta>\n");
+}
+
+fn write_node(xml: &mut String, node: &ArticleNode, prefix: &str, depth: usize) {
+    let pad = "  ".repeat(depth);
+    let (element, eid, num) = match node.node_type {
+        NodeType::Part => ("part", join_eid(prefix, &format!("part_{}", eid_number(&node.number))), format!("第{}编", node.number)),
+        NodeType::SubPart => ("subpart", join_eid(prefix, &format!("subpart_{}", eid_number(&node.number))), format!("第{}分编", node.number)),
+        NodeType::Chapter => ("chapter", join_eid(prefix, &format!("chp_{}", eid_number(&node.number))), format!("第{}章", node.number)),
+        NodeType::Section => ("section", join_eid(prefix, &format!("sec_{}", eid_number(&node.number))), format!("第{}节", node.number)),
+        NodeType::Supplementary => ("hcontainer", join_eid(prefix, "hcontainer_supplementary"), "附则".to_string()),
+        // Articles are numbered throughout the document, so their eIds skip the containers
+        NodeType::Article => ("article", format!("art_{}", eid_number(&node.number)), article_label(&node.number)),
+        NodeType::Clause => ("paragraph", join_eid(prefix, &format!("para_{}", eid_number(&node.number))), node.number.to_string()),
+        NodeType::Item => ("point", join_eid(prefix, &format!("point_{}", eid_number(&node.number))), node.number.to_string()),
+        NodeType::Preamble | NodeType::Annex | NodeType::Toc | NodeType::TocEntry => return,
+        // Tables are written inside their parent's intro/content block
+        NodeType::Table | NodeType::TableRow | NodeType::TableCell => return,
+    };
+
+    let name = if node.node_type == NodeType::Supplementary { " name=\"supplementaryProvisions\"" } else { "" };
+    let _ = writeln!(xml, "{}<{} eId=\"{}\"{}>", pad, element, eid, name);
+    let _ = writeln!(xml, "{}  <num>{}</num>", pad, escape(&num));
+    if let Some(title) = &node.title {
+        let _ = writeln!(xml, "{}  <heading>{}</heading>", pad, escape(title));
+    }
+
+    let lines = content_lines(&node.content);
+    let (tables, children): (Vec<&ArticleNode>, Vec<&ArticleNode>) =
+        node.children.iter().partition(|c| c.node_type == NodeType::Table);
+    if !lines.is_empty() || !tables.is_empty() {
+        // Text ahead of sub-units is their intro; a leaf holds it as content
+        let wrapper = if children.is_empty() { "content" } else { "intro" };
+        let _ = writeln!(xml, "{}  <{}>", pad, wrapper);
+        for line in lines {
+            let _ = writeln!(xml, "{}    <p>{}</p>", pad, escape(line));
+        }
+        for table in tables {
+            write_table(xml, table, &eid, depth + 2);
+            for line in content_lines(&table.content) {
+                let _ = writeln!(xml, "{}    <p>{}</p>", pad, escape(line));
+            }
+        }
+        let _ = writeln!(xml, "{}  </{}>", pad, wrapper);
+    }
+    for child in children {
+        write_node(xml, child, &eid, depth + 1);
+    }
+    let _ = writeln!(xml, "{}</{}>", pad, element);
+}
+
+fn write_table(xml: &mut String, table: &ArticleNode, prefix: &str, depth: usize) {
+    let pad = "  ".repeat(depth);
+    let _ = writeln!(xml, "{}<table eId=\"{}\">", pad, join_eid(prefix, &format!("table_{}", table.number)));
+    for row in &table.children {
+        let cells: String = row.children.iter().map(|cell| format!("<td><p>{}</p></td>", escape(&cell.content))).collect();
+        let _ = writeln!(xml, "{}  <tr>{}</tr>", pad, cells);
+    }
+    let _ = writeln!(xml, "{}</table>", pad);
+}
+
+/// One `tocItem` per entry, linked to the eId its heading gets in the body
+fn write_toc_entry(xml: &mut String, entry: &ArticleNode, prefix: &str, level: usize) {
+    let eid = match entry.content.as_ref() {
+        "编" => join_eid(prefix, &format!("part_{}", eid_number(&entry.number))),
+        "分编" => join_eid(prefix, &format!("subpart_{}", eid_number(&entry.number))),
+        "章" => join_eid(prefix, &format!("chp_{}", eid_number(&entry.number))),
+        "节" => join_eid(prefix, &format!("sec_{}", eid_number(&entry.number))),
+        _ => format!("art_{}", eid_number(&entry.number)),
+    };
+    let _ = writeln!(xml, "        <tocItem href=\"#{}\" level=\"{}\">{}</tocItem>", eid, level, escape(&toc_entry_label(entry)));
+    for child in &entry.children {
+        write_toc_entry(xml, child, &eid, level + 1);
+    }
+}
+
+fn join_eid(prefix: &str, component: &str) -> String {
+    if prefix.is_empty() { component.to_string() } else { format!("{}__{}", prefix, component) }
+}
+
+/// Arabic eId number: "二十九" -> "29", "二十九之一" -> "29-1"
+fn eid_number(number: &str) -> String {
+    match article_sort_key(number) {
+        (base, 0) => base.to_string(),
+        (base, sub) => format!("{}-{}", base, sub),
+    }
+}
+
+fn annex_heading(annex: &ArticleNode) -> String {
+    match &annex.title {
+        Some(title) => format!("{}：{}", annex.number, title),
+        None => annex.number.to_string(),
+    }
+}
+
+fn content_lines(content: &str) -> Vec<&str> {
+    content.lines().map(str::trim).filter(|l| !l.is_empty()).collect()
+}
+
+/// "2016年11月7日" -> "2016-11-07"
+fn iso_date(date: &str) -> Option<String> {
+    let caps = get_cn_date_pattern().captures(date)?;
+    Some(format!("{}-{:0>2}-{:0>2}", &caps[1], &caps[2], &caps[3]))
+}
+
+/// Escape text for XML, shared by every XML-based export. Control characters other than
+/// tab, newline and carriage return are illegal in XML 1.0 even as character references,
+/// so they are dropped.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c < ' ' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{extract_document_meta, parse_article};
+
+    #[test]
+    fn test_akoma_ntoso_export() {
+        let text = "中华人民共和国网络安全法\n（2016年11月7日第十二届全国人民代表大会常务委员会第二十四次会议通过）\n\
+                    第一章 总则\n第一条 为了保障网络安全，制定本法。\n\
+                    第二章 网络运行安全\n第一节 一般规定\n第二十九条之一 网络运营者应当履行下列义务：\n（一）制定内部安全管理制度；\n1. 确定负责人\n\
+                    第三十条 本法自2017年6月1日起施行。";
+        let ast = parse_article(text);
+        let xml = to_akoma_ntoso(&ast, &extract_document_meta(&ast));
+
+        assert!(xml.contains("<docTitle>中华人民共和国网络安全法</docTitle>"));
+        assert!(xml.contains("<FRBRdate date=\"2016-11-07\" name=\"adopted\"/>"));
+        assert!(xml.contains("<FRBRdate date=\"2017-06-01\" name=\"effective\"/>"));
+        assert!(xml.contains("<chapter eId=\"chp_2\">"));
+        assert!(xml.contains("<section eId=\"chp_2__sec_1\">"));
+        assert!(xml.contains("<article eId=\"art_29-1\">\n            <num>第二十九条之一</num>\n            <intro>"));
+        assert!(xml.contains("<paragraph eId=\"art_29-1__para_1\">"));
+        assert!(xml.contains("<point eId=\"art_29-1__para_1__point_1\">"));
+        assert!(xml.contains("<article eId=\"art_1\">"));
+
+        // Every opened element is closed
+        for element in ["article", "chapter", "section", "paragraph", "point"] {
+            assert_eq!(xml.matches(&format!("<{} ", element)).count(), xml.matches(&format!("</{}>", element)).count());
+        }
+    }
+
+    #[test]
+    fn test_toc_items_link_to_body() {
+        let text = "目录\n第一章 总则……1\n第一节 适用范围……1\n第一章 总则\n第一节 适用范围\n第一条 内容。";
+        let ast = parse_article(text);
+        let xml = to_akoma_ntoso(&ast, &extract_document_meta(&ast));
+
+        assert!(xml.contains("<tocItem href=\"#chp_1\" level=\"1\">第一章 总则</tocItem>"));
+        assert!(xml.contains("<tocItem href=\"#chp_1__sec_1\" level=\"2\">第一节 适用范围</tocItem>"));
+        assert!(xml.contains("<section eId=\"chp_1__sec_1\">"));
+    }
+
+    #[test]
+    fn test_table_export() {
+        let text = "第一条 按下表处罚：\n违法行为｜罚款\n未登记｜一万元";
+        let options = crate::models::ParserOptions { detect_tables: true, ..Default::default() };
+        let ast = crate::ast::parse_article_with_options(text, &options);
+        let xml = to_akoma_ntoso(&ast, &extract_document_meta(&ast));
+
+        assert!(xml.contains("<content>\n          <p>按下表处罚：</p>\n          <table eId=\"art_1__table_1\">\n            <tr><td><p>违法行为</p></td><td><p>罚款</p></td></tr>"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b & \"c\""), "a&lt;b &amp; &quot;c&quot;");
+        assert_eq!(escape("第一条\u{000C}内容\t\u{0001}。"), "第一条内容\t。");
+
+        let xml = to_akoma_ntoso(&parse_article("第一条 内容\u{000C}。"), &Default::default());
+        assert!(xml.contains("内容。") && !xml.contains('\u{000C}'));
+    }
+}
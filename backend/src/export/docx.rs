@@ -0,0 +1,181 @@
+use crate::diff::apply::render_article;
+use crate::export::akoma_ntoso::escape;
+use crate::export::html::redline_segments;
+use crate::models::{ArticleChangeType, ArticleInfo, ChangeType, DiffResult};
+use similar::ChangeTag;
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// Name Word shows on every tracked change
+const AUTHOR: &str = "条文对比";
+
+const CONTENT_TYPES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>\
+</Types>";
+
+const RELATIONSHIPS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/>\
+</Relationships>";
+
+/// WordprocessingML body built from redline segments, one paragraph per line
+struct Body {
+    xml: String,
+    runs: String,
+    next_id: usize,
+}
+
+impl Body {
+    fn revision(&mut self, element: &str) -> String {
+        self.next_id += 1;
+        format!("<w:{} w:id=\"{}\" w:author=\"{}\"", element, self.next_id, AUTHOR)
+    }
+
+    /// Append text; a newline ends the paragraph, and one inside an insertion or deletion
+    /// marks the paragraph break itself as inserted or deleted
+    fn push(&mut self, tag: ChangeTag, text: &str) {
+        for (i, piece) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.end_paragraph(tag);
+            }
+            if piece.is_empty() {
+                continue;
+            }
+            let piece = escape(piece);
+            match tag {
+                ChangeTag::Equal => {
+                    let _ = write!(self.runs, "<w:r><w:t xml:space=\"preserve\">{}</w:t></w:r>", piece);
+                }
+                ChangeTag::Insert => {
+                    let open = self.revision("ins");
+                    let _ = write!(self.runs, "{}><w:r><w:t xml:space=\"preserve\">{}</w:t></w:r></w:ins>", open, piece);
+                }
+                ChangeTag::Delete => {
+                    let open = self.revision("del");
+                    let _ = write!(self.runs, "{}><w:r><w:delText xml:space=\"preserve\">{}</w:delText></w:r></w:del>", open, piece);
+                }
+            }
+        }
+    }
+
+    fn end_paragraph(&mut self, mark: ChangeTag) {
+        let properties = match mark {
+            ChangeTag::Equal => String::new(),
+            ChangeTag::Insert => format!("<w:pPr><w:rPr>{}/></w:rPr></w:pPr>", self.revision("ins")),
+            ChangeTag::Delete => format!("<w:pPr><w:rPr>{}/></w:rPr></w:pPr>", self.revision("del")),
+        };
+        let _ = write!(self.xml, "<w:p>{}{}</w:p>", properties, self.runs);
+        self.runs.clear();
+    }
+}
+
+/// The `word/document.xml` part: the new text with the diff as tracked changes. The line
+/// diff covers the whole document, headings included; a structure-only diff is rebuilt
+/// article by article.
+pub fn document_xml(result: &DiffResult) -> String {
+    let mut body = Body { xml: String::new(), runs: String::new(), next_id: 0 };
+    if result.changes.is_empty() {
+        let render = |articles: &[ArticleInfo]| articles.iter().map(render_article).collect::<Vec<_>>().join("\n");
+        for change in result.article_changes.iter().flatten() {
+            let old = match &change.merged_from {
+                Some(sources) => render(sources),
+                None => render(change.old_article.as_slice()),
+            };
+            let new = render(change.new_articles.as_deref().unwrap_or_default());
+            match change.change_type {
+                ArticleChangeType::Unchanged => body.push(ChangeTag::Equal, &new),
+                ArticleChangeType::Deleted => body.push(ChangeTag::Delete, &old),
+                _ => {
+                    for (tag, text) in redline_segments(&old, &new) {
+                        body.push(tag, &text);
+                    }
+                }
+            }
+            body.end_paragraph(match change.change_type {
+                ArticleChangeType::Added => ChangeTag::Insert,
+                ArticleChangeType::Deleted => ChangeTag::Delete,
+                _ => ChangeTag::Equal,
+            });
+        }
+    }
+    for change in &result.changes {
+        let (old, new) = (change.old_content.as_deref().unwrap_or(""), change.new_content.as_deref().unwrap_or(""));
+        match change.change_type {
+            // An elided "…" marker stands on a line of its own
+            ChangeType::Unchanged if change.elided.is_some() => body.push(ChangeTag::Equal, "…\n"),
+            ChangeType::Unchanged => body.push(ChangeTag::Equal, old),
+            ChangeType::Delete => body.push(ChangeTag::Delete, old),
+            ChangeType::Add => body.push(ChangeTag::Insert, new),
+            ChangeType::Modify => {
+                for (tag, text) in redline_segments(old, new) {
+                    body.push(tag, &text);
+                }
+            }
+        }
+    }
+    if !body.runs.is_empty() {
+        body.end_paragraph(ChangeTag::Equal);
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+        <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"><w:body>{}</w:body></w:document>",
+        body.xml
+    )
+}
+
+/// Package a comparison as a .docx of the new version whose insertions and deletions are
+/// Word tracked changes (w:ins/w:del), ready to accept or reject in Word
+pub fn diff_to_docx(result: &DiffResult) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in [
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", RELATIONSHIPS.to_string()),
+        ("word/document.xml", document_xml(result)),
+    ] {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::compare_texts;
+    use std::io::Read;
+
+    #[test]
+    fn test_diff_to_docx() {
+        let old = "第一章 总则\n第一条 网络日志留存不少于三个月。\n第二条 本条将被删除。";
+        let new = "第一章 总则\n第一条 网络日志留存不少于六个月。\n第三条 新增<条款>。";
+        let result = compare_texts(old, new, vec![]);
+
+        let xml = document_xml(&result);
+        assert!(xml.contains("<w:p><w:r><w:t xml:space=\"preserve\">第一章 总则</w:t></w:r></w:p>"));
+        assert!(xml.contains("不少于</w:t></w:r><w:del w:id=\"1\" w:author=\"条文对比\"><w:r><w:delText xml:space=\"preserve\">三</w:delText></w:r></w:del>\
+            <w:ins w:id=\"2\" w:author=\"条文对比\"><w:r><w:t xml:space=\"preserve\">六</w:t></w:r></w:ins>"));
+        assert!(xml.contains("新增&lt;条款&gt;"));
+
+        let bytes = diff_to_docx(&result).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert!(archive.by_name("[Content_Types].xml").is_ok());
+        let mut packaged = String::new();
+        archive.by_name("word/document.xml").unwrap().read_to_string(&mut packaged).unwrap();
+        assert_eq!(packaged, xml);
+    }
+
+    #[test]
+    fn test_docx_drops_control_characters() {
+        let result = compare_texts("第一条 内容。", "第一条 内容\u{000C}已修改。", vec![]);
+        let xml = document_xml(&result);
+        assert!(!xml.chars().any(|c| c < ' ' && !matches!(c, '\t' | '\n' | '\r')));
+        assert!(xml.contains("已修改"));
+    }
+}
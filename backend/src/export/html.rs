@@ -0,0 +1,159 @@
+use crate::diff::aligner::count_by_type;
+use crate::export::akoma_ntoso::escape;
+use crate::export::markdown::{change_heading, change_type_name, joined_content, old_side};
+use crate::models::{ArticleChange, ArticleChangeType, ChangeType, DiffResult};
+use similar::{ChangeTag, TextDiff};
+use std::fmt::Write;
+
+const STYLE: &str = "body{font-family:\"Noto Serif SC\",\"Songti SC\",SimSun,serif;max-width:960px;margin:2em auto;padding:0 1em;line-height:1.8;color:#1f2328}\
+header{border-bottom:2px solid #d0d7de;margin-bottom:1.5em}\
+table{border-collapse:collapse;margin:.5em 0}td,th{border:1px solid #d0d7de;padding:.2em .8em;text-align:left}\
+section{border-left:4px solid #d0d7de;padding:.2em 1em;margin:1em 0}\
+section.added{border-color:#1a7f37}section.deleted{border-color:#cf222e}section.unchanged{color:#656d76}\
+h2{font-size:1.1em;margin:.3em 0}.badge{font-size:.8em;font-weight:normal;border-radius:4px;padding:0 .4em;margin-left:.5em;background:#eaeef2}\
+ins{text-decoration:underline;color:#1a7f37;background:#e6ffec}del{text-decoration:line-through;color:#cf222e;background:#ffebe9}\
+.warning{color:#9a6700}";
+
+/// Runs of equal, deleted and inserted characters turning `old` into `new`
+pub fn redline_segments(old: &str, new: &str) -> Vec<(ChangeTag, String)> {
+    let mut segments: Vec<(ChangeTag, String)> = Vec::new();
+    for change in TextDiff::from_chars(old, new).iter_all_changes() {
+        match segments.last_mut() {
+            Some((tag, text)) if *tag == change.tag() => text.push_str(change.value()),
+            _ => segments.push((change.tag(), change.value().to_string())),
+        }
+    }
+    segments
+}
+
+/// Render a comparison as a standalone HTML redline: a summary header (similarity, article
+/// change counts, warnings), then one section per article with insertions underlined and
+/// deletions struck through. A diff without article changes is redlined line by line.
+pub fn diff_to_html(result: &DiffResult) -> String {
+    let title = result.new_meta.as_ref()
+        .or(result.old_meta.as_ref())
+        .and_then(|meta| meta.title.as_deref())
+        .unwrap_or("条文对比");
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape(title), STYLE
+    );
+    write_summary(&mut out, title, result);
+
+    match &result.article_changes {
+        Some(changes) => {
+            for change in changes {
+                write_article(&mut out, change);
+            }
+        }
+        None => {
+            out.push_str("<section class=\"modified\">\n<p>");
+            for change in &result.changes {
+                let (old, new) = (change.old_content.as_deref().unwrap_or(""), change.new_content.as_deref().unwrap_or(""));
+                match change.change_type {
+                    ChangeType::Unchanged => write_segments(&mut out, &[(ChangeTag::Equal, old.to_string())]),
+                    ChangeType::Delete => write_segments(&mut out, &[(ChangeTag::Delete, old.to_string())]),
+                    ChangeType::Add => write_segments(&mut out, &[(ChangeTag::Insert, new.to_string())]),
+                    // Line breaks come from the units' own newlines, so word and character
+                    // units run on within their line
+                    ChangeType::Modify => write_segments(&mut out, &redline_segments(old, new)),
+                }
+            }
+            out.push_str("</p>\n</section>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn write_summary(out: &mut String, title: &str, result: &DiffResult) {
+    let _ = write!(out, "<header>\n<h1>{}</h1>\n<p>相似度：{:.1}%", escape(title), result.similarity * 100.0);
+    if result.stats.chars_added + result.stats.chars_deleted > 0 {
+        let _ = write!(out, "　新增 {} 字，删除 {} 字", result.stats.chars_added, result.stats.chars_deleted);
+    }
+    out.push_str("</p>\n");
+
+    let counts = match (&result.article_changes, result.stats.articles_by_type.is_empty()) {
+        (Some(changes), true) => count_by_type(changes),
+        _ => result.stats.articles_by_type.clone(),
+    };
+    let counts: Vec<_> = counts.iter().filter(|(t, _)| **t != ArticleChangeType::Unchanged).collect();
+    if !counts.is_empty() {
+        out.push_str("<table>\n<tr><th>变更</th><th>条数</th></tr>\n");
+        for (change_type, count) in counts {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", change_type_name(change_type), count);
+        }
+        out.push_str("</table>\n");
+    }
+    for warning in &result.warnings {
+        let _ = writeln!(out, "<p class=\"warning\">⚠ {}</p>", escape(warning));
+    }
+    out.push_str("</header>\n");
+}
+
+fn write_article(out: &mut String, change: &ArticleChange) {
+    let old_articles = old_side(change);
+    let new_articles = change.new_articles.as_deref().unwrap_or_default();
+    let (old, new) = (joined_content(old_articles, "\n"), joined_content(new_articles, "\n"));
+    let heading = change_heading(old_articles, new_articles);
+    let class = format!("{:?}", change.change_type).to_lowercase();
+
+    let _ = write!(out, "<section class=\"{}\" id=\"{}\">\n<h2>{}", class, escape(&change.id), escape(&heading));
+    if change.change_type != ArticleChangeType::Unchanged {
+        let _ = write!(out, "<span class=\"badge\">{}</span>", change_type_name(&change.change_type));
+    }
+    out.push_str("</h2>\n<p>");
+    match change.change_type {
+        ArticleChangeType::Unchanged => write_segments(out, &[(ChangeTag::Equal, new)]),
+        ArticleChangeType::Deleted => write_segments(out, &[(ChangeTag::Delete, old)]),
+        _ => write_segments(out, &redline_segments(&old, &new)),
+    }
+    out.push_str("</p>\n</section>\n");
+}
+
+fn write_segments(out: &mut String, segments: &[(ChangeTag, String)]) {
+    for (tag, text) in segments {
+        let text = escape(text).replace('\n', "<br>\n");
+        match tag {
+            ChangeTag::Equal => out.push_str(&text),
+            ChangeTag::Delete => { let _ = write!(out, "<del>{}</del>", text); }
+            ChangeTag::Insert => { let _ = write!(out, "<ins>{}</ins>", text); }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+    use crate::diff::compare_texts;
+
+    #[test]
+    fn test_diff_to_html() {
+        let old = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于三个月。\n第三条 本条将被删除。";
+        let new = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于六个月。\n第三条 本条将被删除。\n第四条 数据<跨境>传输应当评估。";
+        let result = DiffResult {
+            similarity: 0.8,
+            article_changes: Some(align_articles(old, new, 0.6, false)),
+            ..Default::default()
+        };
+        let html = diff_to_html(&result);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("相似度：80.0%"));
+        assert!(html.contains("<tr><td>修改</td><td>1</td></tr>"));
+        assert!(html.contains("留存不少于<del>三</del><ins>六</ins>个月"));
+        assert!(html.contains("<ins>数据&lt;跨境&gt;传输应当评估。</ins>"));
+        assert!(html.contains("<section class=\"unchanged\""));
+        assert!(html.ends_with("</html>\n"));
+
+        // Without article changes the line diff is redlined
+        let html = diff_to_html(&compare_texts("甲\n乙", "甲\n丙", vec![]));
+        assert!(html.contains("甲<br>\n<del>乙</del><ins>丙</ins></p>"));
+        let html = diff_to_html(&compare_texts("甲\n乙\n丁", "甲\n丙\n丁", vec![]));
+        assert!(html.contains("甲<br>\n<del>乙</del><ins>丙</ins><br>\n丁</p>"));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::ast::{article_label, toc_entry_label};
+use crate::diff::patches::to_per_article_patches;
+use crate::models::{ArticleChange, ArticleChangeType, ArticleInfo, ArticleNode, DiffResult, NodeType};
+
+/// Render a parsed document as Markdown: 编/章/节 become headings, articles bold-numbered
+/// paragraphs and clauses/items nested list entries.
+pub fn ast_to_markdown(root: &ArticleNode) -> String {
+    let mut out = String::new();
+    if root.number.as_ref() == "root" {
+        for child in &root.children {
+            render_node(child, 0, &mut out);
+        }
+    } else {
+        render_node(root, 0, &mut out);
+    }
+    out
+}
+
+fn render_node(node: &ArticleNode, depth: usize, out: &mut String) {
+    let heading = |level: usize, marker: &str| {
+        let text = match &node.title {
+            Some(title) => format!("第{}{} {}", node.number, marker, title),
+            None => format!("第{}{}", node.number, marker),
+        };
+        format!("{} {}\n\n", "#".repeat(level), text)
+    };
+    match node.node_type {
+        NodeType::Preamble => out.push_str(&paragraphs(&node.content)),
+        NodeType::Part => out.push_str(&heading(1, "编")),
+        NodeType::SubPart => out.push_str(&heading(2, "分编")),
+        NodeType::Chapter => out.push_str(&heading(2, "章")),
+        NodeType::Section => out.push_str(&heading(3, "节")),
+        NodeType::Supplementary => out.push_str("## 附则\n\n"),
+        NodeType::Annex => {
+            match &node.title {
+                Some(title) => out.push_str(&format!("## {}：{}\n\n", node.number, title)),
+                None => out.push_str(&format!("## {}\n\n", node.number)),
+            }
+            out.push_str(&paragraphs(&node.content));
+        }
+        NodeType::Article => {
+            let title = node.title.as_ref().map(|t| format!("（{}）", t)).unwrap_or_default();
+            out.push_str(&format!("**{}** {}{}\n", article_label(&node.number), title, node.content.trim()));
+            for child in &node.children {
+                render_node(child, 0, out);
+            }
+            out.push('\n');
+            return;
+        }
+        NodeType::Clause | NodeType::Item => {
+            out.push_str(&format!("{}- {}\n", "  ".repeat(depth), node.content.trim().replace('\n', " ")));
+            for child in &node.children {
+                render_node(child, depth + 1, out);
+            }
+            return;
+        }
+        NodeType::Toc => {
+            out.push_str("## 目录\n\n");
+            for child in &node.children {
+                render_node(child, 0, out);
+            }
+            out.push_str(&paragraphs(&node.content));
+            out.push('\n');
+            return;
+        }
+        NodeType::Table => {
+            out.push('\n');
+            for (i, row) in node.children.iter().enumerate() {
+                let cells: Vec<String> = row.children.iter().map(|c| c.content.replace('|', "\\|")).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                if i == 0 {
+                    out.push_str(&format!("|{}\n", "---|".repeat(cells.len())));
+                }
+            }
+            out.push('\n');
+            out.push_str(&paragraphs(&node.content));
+            return;
+        }
+        NodeType::TableRow | NodeType::TableCell => return,
+        NodeType::TocEntry => {
+            out.push_str(&format!("{}- {}\n", "  ".repeat(depth), toc_entry_label(node)));
+            for child in &node.children {
+                render_node(child, depth + 1, out);
+            }
+            return;
+        }
+    }
+    for child in &node.children {
+        render_node(child, depth, out);
+    }
+}
+
+fn paragraphs(content: &str) -> String {
+    content.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| format!("{}\n\n", l))
+        .collect()
+}
+
+/// Render a comparison as Markdown: overall similarity, warnings, a change table and one
+/// `diff` block per changed article.
+pub fn diff_to_markdown(result: &DiffResult) -> String {
+    let mut out = String::from("# 条文对比\n\n");
+    out.push_str(&format!("相似度：{:.1}%\n\n", result.similarity * 100.0));
+    for warning in &result.warnings {
+        out.push_str(&format!("> ⚠ {}\n", warning));
+    }
+    if !result.warnings.is_empty() {
+        out.push('\n');
+    }
+
+    let Some(changes) = &result.article_changes else {
+        return out;
+    };
+    let changed: Vec<&ArticleChange> = changes.iter()
+        .filter(|c| c.change_type != ArticleChangeType::Unchanged)
+        .collect();
+    if changed.is_empty() {
+        out.push_str("条文无变化。\n");
+        return out;
+    }
+
+    out.push_str("| 变更 | 原条文 | 新条文 | 相似度 |\n|---|---|---|---|\n");
+    for change in &changed {
+        let old = change.old_article.as_ref().map(unit_label).unwrap_or_else(|| "—".to_string());
+        let new = change.new_articles.as_ref()
+            .filter(|list| !list.is_empty())
+            .map(|list| units_label(list))
+            .unwrap_or_else(|| "—".to_string());
+        let similarity = change.similarity.map(|s| format!("{:.0}%", s * 100.0)).unwrap_or_default();
+        out.push_str(&format!("| {} | {} | {} | {} |\n", change_type_name(&change.change_type), old, new, similarity));
+    }
+
+    let patches = to_per_article_patches(changes);
+    if !patches.is_empty() {
+        out.push_str("\n## 修改详情\n");
+        for (number, patch) in patches {
+            out.push_str(&format!("\n### {}\n\n```diff\n{}```\n", article_label(&number), patch));
+        }
+    }
+    out
+}
+
+pub fn unit_label(info: &ArticleInfo) -> String {
+    match info.node_type {
+        NodeType::Article => article_label(&info.number),
+        NodeType::Preamble => "序言".to_string(),
+        _ => info.number.to_string(),
+    }
+}
+
+/// Labels of several units, as "第五条、第六条"
+pub fn units_label(articles: &[ArticleInfo]) -> String {
+    articles.iter().map(unit_label).collect::<Vec<_>>().join("、")
+}
+
+/// The old side of a change: the merged sources, or the single old article
+pub fn old_side(change: &ArticleChange) -> &[ArticleInfo] {
+    change.merged_from.as_deref().unwrap_or(change.old_article.as_slice())
+}
+
+/// Heading of a change across both sides, as "第五条 → 第六条"; one label when the sides
+/// agree or only one side exists
+pub fn change_heading(old: &[ArticleInfo], new: &[ArticleInfo]) -> String {
+    match (units_label(old), units_label(new)) {
+        (old, new) if new.is_empty() || old == new => old,
+        (old, new) if old.is_empty() => new,
+        (old, new) => format!("{} → {}", old, new),
+    }
+}
+
+/// Contents of several units joined by `separator`
+pub fn joined_content(articles: &[ArticleInfo], separator: &str) -> String {
+    articles.iter().map(|a| a.content.as_ref()).collect::<Vec<_>>().join(separator)
+}
+
+pub fn change_type_name(change_type: &ArticleChangeType) -> &'static str {
+    match change_type {
+        ArticleChangeType::Unchanged => "未变",
+        ArticleChangeType::Modified => "修改",
+        ArticleChangeType::Renumbered => "重新编号",
+        ArticleChangeType::Split => "拆分",
+        ArticleChangeType::Merged => "合并",
+        ArticleChangeType::Moved => "移动",
+        ArticleChangeType::Added => "新增",
+        ArticleChangeType::Deleted => "删除",
+        ArticleChangeType::Replaced => "替换",
+        ArticleChangeType::Preamble => "序言",
+        ArticleChangeType::Changed => "变更",
+        ArticleChangeType::Corresponding => "对应",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_ast_to_markdown() {
+        let text = "第一章 总则\n第一条 【立法目的】为了规范管理，制定本法。\n第二条 有下列情形之一的，予以处罚：\n（一）未经许可的；\n1. 情节轻微的\n（二）超出范围的。";
+        let markdown = ast_to_markdown(&parse_article(text));
+
+        assert_eq!(markdown, "## 第一章 总则\n\n\
+            **第一条** （立法目的）为了规范管理，制定本法。\n\n\
+            **第二条** 有下列情形之一的，予以处罚：\n\
+            - （一）未经许可的；\n  - 1.情节轻微的\n\
+            - （二）超出范围的。\n\n");
+    }
+
+    #[test]
+    fn test_table_to_markdown() {
+        let text = "第一条 按下表处罚：\n违法行为｜罚款\n未登记｜一万元";
+        let options = crate::models::ParserOptions { detect_tables: true, ..Default::default() };
+        let markdown = ast_to_markdown(&crate::ast::parse_article_with_options(text, &options));
+
+        assert_eq!(markdown, "**第一条** 按下表处罚：\n\n| 违法行为 | 罚款 |\n|---|---|\n| 未登记 | 一万元 |\n\n\n");
+    }
+
+    #[test]
+    fn test_toc_to_markdown() {
+        let text = "目录\n第一章 总则……1\n第一节 适用范围……1\n第二章 附则……3\n第一章 总则\n第一条 内容。";
+        let markdown = ast_to_markdown(&parse_article(text));
+
+        assert!(markdown.starts_with("## 目录\n\n- 第一章 总则\n  - 第一节 适用范围\n- 第二章 附则\n\n## 第一章"));
+    }
+
+    #[test]
+    fn test_diff_to_markdown() {
+        let old = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于三个月。\n第三条 本条将被删除。";
+        let new = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于六个月。";
+        let result = DiffResult {
+            similarity: 0.8,
+            article_changes: Some(align_articles(old, new, 0.6, false)),
+            ..Default::default()
+        };
+        let markdown = diff_to_markdown(&result);
+
+        assert!(markdown.contains("相似度：80.0%"));
+        assert!(markdown.contains("| 删除 | 第三条 | — |"));
+        assert!(markdown.contains("### 第二条\n\n```diff\n--- a/第二条\n+++ b/第二条\n"));
+        assert!(markdown.contains("+网络日志留存不少于六个月。"));
+        assert!(!markdown.contains("| 未变 |"));
+    }
+}
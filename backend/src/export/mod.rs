@@ -0,0 +1,7 @@
+pub mod akoma_ntoso;
+pub mod docx;
+pub mod html;
+pub mod markdown;
+pub mod patch;
+pub mod pdf;
+pub mod spreadsheet;
@@ -0,0 +1,83 @@
+use crate::diff::patches::to_per_article_patches;
+use crate::models::{ChangeType, DiffResult};
+use similar::TextDiff;
+
+/// Lines of unchanged context around each hunk, as `git diff` uses
+const PATCH_CONTEXT_LINES: usize = 3;
+
+/// Render a comparison as a unified diff (`--- a/old`, `+++ b/new`, `@@` hunks) that
+/// `git apply` and review tools accept.
+///
+/// Both texts are rebuilt from the change list, whatever granularity it was computed at,
+/// and diffed line by line. Edits reported as Unchanged because they only touch formatting
+/// still appear, since the patch has to reproduce the new text. A result with no line
+/// changes (a structure-only comparison) falls back to one patch per changed article.
+///
+/// None for a diff trimmed to `context_lines`: the elided runs leave no way to number the
+/// hunks against the real texts.
+pub fn diff_to_patch(result: &DiffResult) -> Option<String> {
+    if result.changes.is_empty() {
+        return Some(result.article_changes.as_deref()
+            .map(|changes| to_per_article_patches(changes).into_iter().map(|(_, patch)| patch).collect())
+            .unwrap_or_default());
+    }
+    if result.changes.iter().any(|c| c.elided.is_some()) {
+        return None;
+    }
+
+    let (mut old_text, mut new_text) = (String::new(), String::new());
+    for change in &result.changes {
+        match change.change_type {
+            ChangeType::Add => {}
+            _ => old_text.push_str(change.old_content.as_deref().unwrap_or("")),
+        }
+        match change.change_type {
+            ChangeType::Delete => {}
+            _ => new_text.push_str(change.new_content.as_deref().or(change.old_content.as_deref()).unwrap_or("")),
+        }
+    }
+    // Compared texts lose their final newline; without it every patch would end in a
+    // `\ No newline at end of file` marker
+    for text in [&mut old_text, &mut new_text] {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+
+    Some(TextDiff::from_lines(&old_text, &new_text)
+        .unified_diff()
+        .context_radius(PATCH_CONTEXT_LINES)
+        .header("a/old", "b/new")
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::compare_texts_with_granularity;
+    use crate::models::Granularity;
+
+    #[test]
+    fn test_diff_to_patch() {
+        let old_text = "第一条 为了保障网络安全，制定本法。\n第二条 留存网络日志不少于三个月。\n第三条 本法自公布之日起施行。\n";
+        let new_text = "第一条 为了保障网络安全，制定本法。\n第二条 留存网络日志不少于六个月。\n第三条 本法自公布之日起施行。\n";
+
+        // Word- and line-level results give the same line patch
+        for granularity in [Granularity::Word, Granularity::Line] {
+            let result = compare_texts_with_granularity(old_text, new_text, granularity, vec![]);
+            assert_eq!(diff_to_patch(&result).unwrap(), "--- a/old\n+++ b/new\n@@ -1,3 +1,3 @@\n \
+                第一条 为了保障网络安全，制定本法。\n\
+                -第二条 留存网络日志不少于三个月。\n\
+                +第二条 留存网络日志不少于六个月。\n \
+                第三条 本法自公布之日起施行。\n");
+        }
+
+        let unchanged = compare_texts_with_granularity(old_text, old_text, Granularity::Line, vec![]);
+        assert!(diff_to_patch(&unchanged).unwrap().is_empty());
+
+        // A trimmed diff cannot be numbered against the full texts
+        let mut trimmed = compare_texts_with_granularity(old_text, new_text, Granularity::Line, vec![]);
+        trimmed.changes[0].elided = Some(1);
+        assert_eq!(diff_to_patch(&trimmed), None);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::diff::aligner::count_by_type;
+use crate::export::html::redline_segments;
+use crate::export::markdown::{change_heading, change_type_name, joined_content, old_side};
+use crate::models::{ArticleChange, ArticleChangeType, DiffResult};
+use similar::ChangeTag;
+use std::fmt::Write;
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const BODY_SIZE: f32 = 11.0;
+
+/// Text style of a run: its color, and the underline or strike-through of a redline
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Normal,
+    Muted,
+    Warning,
+    Inserted,
+    Deleted,
+}
+
+impl Style {
+    fn color(self) -> (f32, f32, f32) {
+        match self {
+            Style::Normal => (0.12, 0.14, 0.16),
+            Style::Muted => (0.4, 0.43, 0.46),
+            Style::Warning => (0.6, 0.4, 0.0),
+            Style::Inserted => (0.1, 0.5, 0.22),
+            Style::Deleted => (0.81, 0.13, 0.18),
+        }
+    }
+}
+
+/// Width of `c` in ems: the font is set up with full-width CJK glyphs and half-width ASCII
+fn char_width(c: char) -> f32 {
+    if c.is_ascii() { 0.5 } else { 1.0 }
+}
+
+/// PDF hex string of `text` in UTF-16BE, the encoding of the UniGB-UTF16-H CMap
+fn hex(text: &str) -> String {
+    text.encode_utf16().map(|unit| format!("{:04X}", unit)).collect()
+}
+
+/// Content streams of the report's pages, filled top to bottom with wrapped lines
+struct Pages {
+    done: Vec<String>,
+    current: String,
+    y: f32,
+}
+
+impl Pages {
+    fn new() -> Self {
+        Pages { done: Vec::new(), current: String::new(), y: PAGE_HEIGHT - MARGIN }
+    }
+
+    fn break_page(&mut self) {
+        self.done.push(std::mem::take(&mut self.current));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Draw one line of runs at the left margin, moving to a new page when it would not fit
+    fn line(&mut self, runs: &[(Style, String)], size: f32) {
+        let height = size * 1.7;
+        if self.y - height < MARGIN {
+            self.break_page();
+        }
+        self.y -= height;
+        let mut x = MARGIN;
+        for (style, text) in runs {
+            let (r, g, b) = style.color();
+            let width: f32 = text.chars().map(char_width).sum::<f32>() * size;
+            let _ = writeln!(self.current, "BT /F1 {} Tf {} {} {} rg {:.2} {:.2} Td <{}> Tj ET", size, r, g, b, x, self.y, hex(text));
+            let decoration = match style {
+                Style::Inserted => Some(self.y - size * 0.15),
+                Style::Deleted => Some(self.y + size * 0.35),
+                _ => None,
+            };
+            if let Some(at) = decoration {
+                let _ = writeln!(self.current, "{} {} {} RG 0.6 w {:.2} {:.2} m {:.2} {:.2} l S", r, g, b, x, at, x + width, at);
+            }
+            x += width;
+        }
+    }
+
+    /// Wrap styled segments to the text width, starting a new line at each newline
+    fn paragraph(&mut self, segments: &[(Style, String)], size: f32) {
+        let max_width = PAGE_WIDTH - 2.0 * MARGIN;
+        let mut runs: Vec<(Style, String)> = Vec::new();
+        let mut width = 0.0;
+        for (style, text) in segments {
+            for c in text.chars() {
+                if c == '\n' || width + char_width(c) * size > max_width {
+                    self.line(&runs, size);
+                    runs.clear();
+                    width = 0.0;
+                    if c == '\n' {
+                        continue;
+                    }
+                }
+                match runs.last_mut() {
+                    Some((last, run)) if last == style => run.push(c),
+                    _ => runs.push((*style, c.to_string())),
+                }
+                width += char_width(c) * size;
+            }
+        }
+        if !runs.is_empty() {
+            self.line(&runs, size);
+        }
+    }
+
+    fn text(&mut self, style: Style, text: &str, size: f32) {
+        self.paragraph(&[(style, text.to_string())], size);
+    }
+
+    /// Finished pages, each numbered in its footer
+    fn finish(mut self) -> Vec<String> {
+        if !self.current.is_empty() {
+            self.break_page();
+        }
+        let count = self.done.len();
+        for (i, page) in self.done.iter_mut().enumerate() {
+            let footer = format!("第 {} 页 / 共 {} 页", i + 1, count);
+            let x = (PAGE_WIDTH - footer.chars().map(char_width).sum::<f32>() * 9.0) / 2.0;
+            let _ = writeln!(page, "BT /F1 9 Tf 0.4 0.43 0.46 rg {:.2} {:.2} Td <{}> Tj ET", x, MARGIN / 2.0, hex(&footer));
+        }
+        self.done
+    }
+}
+
+/// Render a comparison as a PDF report for archiving: a summary page (similarity, character
+/// counts, article changes by type, warnings), per-chapter statistics, then every changed
+/// article redlined with insertions underlined in green and deletions struck through in red.
+///
+/// Text is set in the PDF reader's STSong-Light (Adobe-GB1), which every mainstream reader
+/// ships, so no font file has to be embedded.
+pub fn diff_to_pdf(result: &DiffResult) -> Vec<u8> {
+    let title = result.new_meta.as_ref()
+        .or(result.old_meta.as_ref())
+        .and_then(|meta| meta.title.as_deref())
+        .unwrap_or("条文对比");
+    let changes = result.article_changes.as_deref().unwrap_or_default();
+
+    let mut pages = Pages::new();
+    pages.text(Style::Normal, "条文对比报告", 20.0);
+    pages.text(Style::Muted, title, 14.0);
+    pages.gap(12.0);
+    pages.text(Style::Normal, &format!("相似度：{:.1}%", result.similarity * 100.0), BODY_SIZE);
+    if result.stats.chars_added + result.stats.chars_deleted > 0 {
+        pages.text(Style::Normal, &format!("新增 {} 字，删除 {} 字", result.stats.chars_added, result.stats.chars_deleted), BODY_SIZE);
+    }
+    let counts = if result.stats.articles_by_type.is_empty() { count_by_type(changes) } else { result.stats.articles_by_type.clone() };
+    let counts: Vec<String> = counts.iter()
+        .filter(|(t, _)| **t != ArticleChangeType::Unchanged)
+        .map(|(t, n)| format!("{} {} 条", change_type_name(t), n))
+        .collect();
+    if !counts.is_empty() {
+        pages.text(Style::Normal, &format!("条文变更：{}", counts.join("，")), BODY_SIZE);
+    }
+    for warning in &result.warnings {
+        pages.text(Style::Warning, &format!("⚠ {}", warning), BODY_SIZE);
+    }
+
+    if !result.stats.chapters.is_empty() {
+        pages.gap(12.0);
+        pages.text(Style::Normal, "分章统计", 14.0);
+        for chapter in &result.stats.chapters {
+            let counts: Vec<String> = chapter.counts.iter()
+                .filter(|(t, _)| **t != ArticleChangeType::Unchanged)
+                .map(|(t, n)| format!("{} {}", change_type_name(t), n))
+                .collect();
+            let counts = if counts.is_empty() { "无变化".to_string() } else { counts.join("，") };
+            pages.text(Style::Normal, &format!("{}：{}", chapter.chapter, counts), BODY_SIZE);
+        }
+    }
+
+    let changed: Vec<&ArticleChange> = changes.iter().filter(|c| c.change_type != ArticleChangeType::Unchanged).collect();
+    if !changed.is_empty() {
+        pages.break_page();
+        pages.text(Style::Normal, "逐条对照", 14.0);
+        for change in changed {
+            write_article(&mut pages, change);
+        }
+    }
+
+    write_document(title, &pages.finish())
+}
+
+fn write_article(pages: &mut Pages, change: &ArticleChange) {
+    let old_articles = old_side(change);
+    let new_articles = change.new_articles.as_deref().unwrap_or_default();
+    let heading = change_heading(old_articles, new_articles);
+
+    pages.gap(8.0);
+    pages.paragraph(&[
+        (Style::Normal, heading),
+        (Style::Muted, format!("（{}）", change_type_name(&change.change_type))),
+    ], 12.0);
+    let segments: Vec<(Style, String)> = redline_segments(&joined_content(old_articles, "\n"), &joined_content(new_articles, "\n")).into_iter()
+        .map(|(tag, text)| match tag {
+            ChangeTag::Equal => (Style::Normal, text),
+            ChangeTag::Insert => (Style::Inserted, text),
+            ChangeTag::Delete => (Style::Deleted, text),
+        })
+        .collect();
+    pages.paragraph(&segments, BODY_SIZE);
+}
+
+/// Assemble the PDF file: catalog, page tree, font, document info and one page object and
+/// content stream per page, followed by the cross-reference table
+fn write_document(title: &str, pages: &[String]) -> Vec<u8> {
+    const FIRST_PAGE: usize = 7;
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", FIRST_PAGE + 2 * i)).collect();
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()),
+        "<< /Type /Font /Subtype /Type0 /BaseFont /STSong-Light /Encoding /UniGB-UTF16-H /DescendantFonts [4 0 R] >>".to_string(),
+        "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /STSong-Light \
+            /CIDSystemInfo << /Registry (Adobe) /Ordering (GB1) /Supplement 4 >> \
+            /FontDescriptor 5 0 R /DW 1000 /W [1 95 500] >>".to_string(),
+        "<< /Type /FontDescriptor /FontName /STSong-Light /Flags 6 /FontBBox [-25 -254 1000 880] \
+            /ItalicAngle 0 /Ascent 880 /Descent -120 /CapHeight 880 /StemV 93 >>".to_string(),
+        format!("<< /Title <FEFF{}> /Producer (law-compare) >>", hex(title)),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH, PAGE_HEIGHT, FIRST_PAGE + 2 * i + 1
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(trailer, "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref);
+    pdf.extend_from_slice(trailer.as_bytes());
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_diff_to_pdf() {
+        let old = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于三个月。\n第三条 本条将被删除。";
+        let new = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于六个月。";
+        let result = DiffResult {
+            similarity: 0.8,
+            article_changes: Some(align_articles(old, new, 0.6, false)),
+            ..Default::default()
+        };
+        let pdf = diff_to_pdf(&result);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        // Summary page and the redline page
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains(&hex("相似度：80.0%")));
+        assert!(text.contains(&hex("条文变更：修改 1 条，删除 1 条")));
+        assert!(text.contains(&format!("<{}> Tj", hex("六"))));
+
+        // startxref points at the cross-reference table
+        let start: usize = text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        assert!(pdf[start..].starts_with(b"xref\n"));
+    }
+
+    #[test]
+    fn test_paragraph_wraps() {
+        let mut pages = Pages::new();
+        pages.text(Style::Normal, &"法".repeat(80), BODY_SIZE);
+        // 483pt of text width fits 43 full-width characters at 11pt
+        assert_eq!(pages.current.matches(" Tj ET").count(), 2);
+        assert!(pages.current.contains(&hex(&"法".repeat(43))));
+    }
+}
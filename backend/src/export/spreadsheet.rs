@@ -0,0 +1,176 @@
+use crate::export::akoma_ntoso::escape;
+use crate::export::markdown::{change_type_name, joined_content, old_side, units_label};
+use crate::models::{ArticleChange, ArticleChangeType};
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+const HEADERS: [&str; 7] = ["编号", "原条文", "新条文", "变更", "相似度", "标签", "摘要"];
+
+/// Characters of article text kept in the excerpt column
+const EXCERPT_CHARS: usize = 60;
+
+/// One row of the change table
+pub struct ChangeRow {
+    pub id: String,
+    pub old_number: String,
+    pub new_number: String,
+    pub change_type: &'static str,
+    pub similarity: Option<f32>,
+    pub tags: String,
+    pub excerpt: String,
+}
+
+/// The changed articles as table rows, in document order; the excerpt is the start of the
+/// new text (the old text for a deletion) on one line
+pub fn change_rows(changes: &[ArticleChange]) -> Vec<ChangeRow> {
+    changes.iter()
+        .filter(|c| c.change_type != ArticleChangeType::Unchanged)
+        .map(|change| {
+            let old_articles = old_side(change);
+            let new_articles = match change.change_type {
+                ArticleChangeType::Deleted => &[],
+                _ => change.new_articles.as_deref().unwrap_or_default(),
+            };
+            let source = if new_articles.is_empty() { old_articles } else { new_articles };
+            let text: String = joined_content(source, " ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut excerpt: String = text.chars().take(EXCERPT_CHARS).collect();
+            if text.chars().count() > EXCERPT_CHARS {
+                excerpt.push('…');
+            }
+            ChangeRow {
+                id: change.id.to_string(),
+                old_number: units_label(old_articles),
+                new_number: units_label(new_articles),
+                change_type: change_type_name(&change.change_type),
+                similarity: change.similarity,
+                tags: change.tags.join(", "),
+                excerpt,
+            }
+        })
+        .collect()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The change table as CSV, with a BOM so Excel reads it as UTF-8
+pub fn to_csv(changes: &[ArticleChange]) -> String {
+    let mut out = String::from("\u{feff}");
+    out.push_str(&HEADERS.join(","));
+    out.push_str("\r\n");
+    for row in change_rows(changes) {
+        let similarity = row.similarity.map(|s| format!("{:.3}", s)).unwrap_or_default();
+        let fields = [&row.id, &row.old_number, &row.new_number, row.change_type, &similarity, &row.tags, &row.excerpt];
+        out.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+const XLSX_CONTENT_TYPES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+<Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+</Types>";
+
+const XLSX_RELATIONSHIPS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>";
+
+const XLSX_WORKBOOK: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+<sheets><sheet name=\"条文变更\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>";
+
+const XLSX_WORKBOOK_RELATIONSHIPS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/>\
+</Relationships>";
+
+/// The `xl/worksheets/sheet1.xml` part: strings inline, similarity as a number
+pub fn sheet_xml(changes: &[ArticleChange]) -> String {
+    let mut rows = String::new();
+    let string_cell = |col: char, row: usize, value: &str| {
+        format!("<c r=\"{}{}\" t=\"inlineStr\"><is><t>{}</t></is></c>", col, row, escape(value))
+    };
+    rows.push_str("<row r=\"1\">");
+    for (col, header) in ('A'..).zip(HEADERS) {
+        rows.push_str(&string_cell(col, 1, header));
+    }
+    rows.push_str("</row>");
+    for (i, row) in change_rows(changes).into_iter().enumerate() {
+        let r = i + 2;
+        let _ = write!(rows, "<row r=\"{}\">", r);
+        for (col, value) in ('A'..).zip([&row.id, &row.old_number, &row.new_number, row.change_type]) {
+            rows.push_str(&string_cell(col, r, value));
+        }
+        if let Some(similarity) = row.similarity {
+            let _ = write!(rows, "<c r=\"E{}\"><v>{}</v></c>", r, similarity);
+        }
+        rows.push_str(&string_cell('F', r, &row.tags));
+        rows.push_str(&string_cell('G', r, &row.excerpt));
+        rows.push_str("</row>");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+        <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>{}</sheetData></worksheet>",
+        rows
+    )
+}
+
+/// The change table as an .xlsx workbook with one sheet
+pub fn to_xlsx(changes: &[ArticleChange]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in [
+        ("[Content_Types].xml", XLSX_CONTENT_TYPES.to_string()),
+        ("_rels/.rels", XLSX_RELATIONSHIPS.to_string()),
+        ("xl/workbook.xml", XLSX_WORKBOOK.to_string()),
+        ("xl/_rels/workbook.xml.rels", XLSX_WORKBOOK_RELATIONSHIPS.to_string()),
+        ("xl/worksheets/sheet1.xml", sheet_xml(changes)),
+    ] {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_change_table_export() {
+        let old = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于三个月。\n第三条 本条将被删除。";
+        let new = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于六个月，\"重要\"数据除外。";
+        let changes = align_articles(old, new, 0.6, false);
+
+        let csv = to_csv(&changes);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], "\u{feff}编号,原条文,新条文,变更,相似度,标签,摘要");
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].contains(",第二条,第二条,修改,"));
+        assert!(lines[1].ends_with(",\"网络日志留存不少于六个月，\"\"重要\"\"数据除外。\""));
+        assert!(lines[2].contains(",第三条,,删除,"));
+        assert!(lines[2].ends_with(",本条将被删除。"));
+
+        let sheet = sheet_xml(&changes);
+        assert!(sheet.contains("<c r=\"D2\" t=\"inlineStr\"><is><t>修改</t></is></c>"));
+        assert!(sheet.contains("&quot;重要&quot;"));
+        assert!(to_xlsx(&changes).unwrap().starts_with(b"PK"));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod api;
+pub mod ast;
+pub mod diff;
+pub mod export;
+pub mod models;
+pub mod nlp;
+pub mod report;
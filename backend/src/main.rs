@@ -1,13 +1,9 @@
-mod api;
-mod ast;
-mod diff;
-mod models;
-mod nlp;
-
 use axum::http::{header, Method};
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use law_compare_backend::{api, ast};
+
 #[tokio::main]
 async fn main() {
     // Initialize tracing
@@ -19,6 +15,16 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Structural regex overrides (patterns.toml / LAW_DIFF_*_PATTERN) must be in place before any parse
+    match ast::install_patterns_from_env() {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Loaded {} structural pattern override(s)", count),
+        Err(err) => {
+            tracing::error!("Invalid structural pattern configuration: {:#}", err);
+            std::process::exit(1);
+        }
+    }
+
     // Configure CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 /// Article change type for structural diff
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum ArticleChangeType {
+    #[default]
     Unchanged,
     Modified,
     Renumbered, // Content similar but number changed
@@ -15,6 +18,8 @@ pub enum ArticleChangeType {
     Deleted,
     Replaced,   // Number reused but content is completely different
     Preamble,   // Metadata/Intro/TOC
+    Changed,    // Modified/Renumbered/Replaced collapsed by `simplify_change_types`
+    Corresponding, // Counterpart provision in another law (`cross_law`)
 }
 
 /// Minimal info about an article for diff reference
@@ -25,15 +30,32 @@ pub struct ArticleInfo {
     pub content: Arc<str>,
     pub title: Option<Arc<str>>,
     pub start_line: usize,
+    #[serde(default)]
+    pub start_offset: usize, // Char offset of the article start in the parsed text
+    #[serde(default)]
+    pub end_offset: usize,   // Char offset just past the article (clauses included)
     pub node_type: NodeType,
     #[serde(default)]
-    pub parents: Vec<Arc<str>>, // Hierarchy context (e.g. ["第一章 总则"])
+    pub parents: Vec<Arc<str>>, // Hierarchy context: number and title of each 编/章/节 (e.g. ["一 总则"])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourcePosition>, // Start in the text as submitted, before normalization
+    #[serde(default, with = "crate::diff::simhash::hex")]
+    pub fingerprint: u64, // SimHash of the content (`simhash::fingerprint`), as hex in JSON
+}
+
+/// A position in the text as the user submitted it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourcePosition {
+    pub line: usize,   // 1-based
+    pub offset: usize, // Char offset
 }
 
 /// Structural change in an article
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ArticleChange {
+    #[serde(default)]
+    pub id: Arc<str>, // Deterministic id from the article numbers and contents, for deep links
     #[serde(rename = "type")]
     pub change_type: ArticleChangeType,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -43,9 +65,109 @@ pub struct ArticleChange {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub similarity: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_detail: Option<SimilarityScore>, // Components behind `similarity`, for 1:1 matches
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<Vec<Change>>, // Detailed word-level diff
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chars_added: Option<usize>, // Churn: inserted characters (char-level diff)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chars_removed: Option<usize>, // Churn: deleted characters (char-level diff)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_shifts: Option<Vec<ReferenceShift>>, // 前条/前款 now resolving elsewhere
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clause_changes: Option<Vec<ArticleChange>>, // Clause-level alignment within a matched article
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merged_from: Option<Vec<ArticleInfo>>, // All source articles of a consolidated merge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_changes: Option<Vec<TableCellChange>>, // Cell-level changes in the article's tables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_changes: Option<Vec<ValueChange>>, // Amounts, periods and dates whose value changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved: Option<ArticleMove>, // Hierarchy before and after, for Moved articles
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_review: bool, // 1:1 match barely above threshold or barely ahead of another candidate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runner_up: Option<RunnerUp>, // Next-best candidate of a match that needs review
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest: Option<Vec<RunnerUp>>, // Closest counterparts of an Added or Deleted article, best first
+}
+
+/// Which side of a match the runner-up (or nearest counterpart) is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnerUpSide {
+    Old, // Another old article that fits the new article almost as well
+    New, // Another new article the old article fits almost as well
+}
+
+/// A counterpart article and its score: the runner-up of an ambiguous 1:1 match, or a
+/// nearest candidate of an unmatched article
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerUp {
+    pub side: RunnerUpSide,
+    pub article: ArticleInfo,
+    pub similarity: f32,
+}
+
+/// The 编/章/节 labels a moved article sat under in each version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleMove {
+    pub from: Vec<Arc<str>>,
+    pub to: Vec<Arc<str>>,
+}
+
+/// What a changed value measures, and the unit its normalized number is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    Amount,   // 元
+    Duration, // Months (一年 = 12)
+    Date,     // yyyymmdd, with 00 for a missing month or day
+}
+
+/// Whether a value went up or down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueDirection {
+    Increased,
+    Decreased,
+}
+
+/// A number in a matched article that changed ("五万元" -> "二十万元", "六个月" -> "一年")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueChange {
+    pub kind: ValueKind,
+    pub old_value: Arc<str>,
+    pub new_value: Arc<str>,
+    pub old_number: f64,
+    pub new_number: f64,
+    pub direction: ValueDirection,
+}
+
+/// A table cell that differs between the two versions of an article. Positions are 1-based;
+/// a row missing on one side was added or deleted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCellChange {
+    pub table: usize,
+    pub old_row: Option<usize>,
+    pub new_row: Option<usize>,
+    pub column: usize,
+    pub old_value: Option<Arc<str>>,
+    pub new_value: Option<Arc<str>>,
+}
+
+/// A relative reference (前条/前款) that resolves to a different provision after the change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceShift {
+    pub reference: Arc<str>,          // e.g. "前条"
+    pub old_target: Option<Arc<str>>, // Resolved in the old document, e.g. "第二条"
+    pub new_target: Option<Arc<str>>, // Resolved in the new document
 }
 
 /// Article node type in AST
@@ -53,12 +175,20 @@ pub struct ArticleChange {
 #[serde(rename_all = "lowercase")]
 pub enum NodeType {
     Part,     // 编
+    SubPart,  // 分编, between 编 and 章 in the Civil Code
     Chapter,  // 章
     Section,  // 节
     Article,  // 条
     Clause,   // 款
     Item,     // 项
     Preamble, // 序言/目录/前言
+    Supplementary, // 附则
+    Annex,    // 附件
+    Toc,      // 目录 block; children are TocEntry nodes
+    TocEntry, // One 目录 line: number and title, content holds the level (编/分编/章/节/条)
+    Table,     // 表格 inside an article or annex; children are TableRow nodes, content the text after it
+    TableRow,  // Children are TableCell nodes
+    TableCell, // Cell text in content
 }
 
 /// AST node for legal article structure
@@ -71,6 +201,12 @@ pub struct ArticleNode {
     pub children: Vec<ArticleNode>,
     #[serde(default)]
     pub start_line: usize,
+    #[serde(default)]
+    pub start_offset: usize, // Char offset into the text given to the parser
+    #[serde(default)]
+    pub end_offset: usize,   // Exclusive; covers all children
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indent: Option<Arc<str>>, // Leading whitespace of the node's first line as submitted
 }
 
 /// Change type in diff
@@ -87,6 +223,8 @@ pub enum ChangeType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Change {
+    #[serde(default)]
+    pub id: Arc<str>, // Deterministic id from the changed contents, for deep links
     #[serde(rename = "type")]
     pub change_type: ChangeType,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -99,6 +237,12 @@ pub struct Change {
     pub new_content: Option<Arc<str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entities: Option<Vec<Entity>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_ranges: Option<Vec<Position>>, // Char spans of old_content that differ (Modify only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_ranges: Option<Vec<Position>>, // Char spans of new_content that differ (Modify only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elided: Option<usize>, // "…" marker standing in for this many unchanged units (context_lines)
 }
 
 /// Entity type for NER
@@ -129,14 +273,139 @@ pub struct Position {
     pub end: usize,
 }
 
-/// Diff statistics
+/// A term defined in the document (本法所称X，是指Y)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Definition {
+    pub term: Arc<str>,
+    pub definition: Arc<str>,
+    pub article_number: Arc<str>, // Article the definition appears in
+}
+
+/// Defined terms of one document, for /api/glossary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct Glossary {
+    pub title: Option<Arc<str>>,
+    pub terms: Vec<Definition>,
+}
+
+/// Which NER engine handled a request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NerInfo {
+    pub engine: Arc<str>, // NEREngine::name()
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bert_fallback: Option<bool>, // Hybrid mode only: whether BERT was invoked
+}
+
+/// Diff statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DiffStats {
     pub additions: usize,
     pub deletions: usize,
     pub modifications: usize,
     pub unchanged: usize,
+    #[serde(default)]
+    pub chars_added: usize, // Inserted characters; only the differing spans of modified lines count
+    #[serde(default)]
+    pub chars_deleted: usize,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub articles_by_type: BTreeMap<ArticleChangeType, usize>, // Article changes per type
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<ChapterStats>, // Article changes per 章, in document order
+}
+
+/// Article change counts within one chapter, e.g. 第三章: 12 modified, 3 added
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterStats {
+    pub chapter: Arc<str>, // "第三章 法律责任"
+    pub counts: BTreeMap<ArticleChangeType, usize>,
+}
+
+/// Aggregate numbers of a comparison, without article texts (/api/compare/stats)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareStats {
+    pub old_articles: usize,
+    pub new_articles: usize,
+    pub articles_by_type: BTreeMap<ArticleChangeType, usize>,
+    pub chapters: Vec<ChapterStats>,
+    pub average_similarity: f32, // Over all article changes, unmatched ones counting 0
+    pub structure_changes: usize,
+    pub entities: Vec<EntityDelta>,
+    pub warnings: Vec<String>,
+    pub quality: AlignmentQuality,
+}
+
+/// How well the two texts aligned at all; a low matched ratio over a sparse matrix means
+/// the comparison is probably meaningless (wrong document, unrelated laws)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignmentQuality {
+    pub matched_ratio: f32,            // Share of old and new articles paired with a counterpart
+    pub average_match_similarity: f32, // Mean similarity of the pairs, unmatched articles left out
+    pub forced_number_matches: usize,  // Same-numbered pairs matched despite scoring below the threshold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matrix_sparsity: Option<f32>,  // Share of article pairs scoring below 0.4; none when aligned by number only
+}
+
+/// Occurrences of one entity type in each version; `added`/`removed` count the values
+/// found only in the new or only in the old text, repeats included
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityDelta {
+    pub entity_type: EntityType,
+    pub old_count: usize,
+    pub new_count: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// A matched article whose penalties (处罚/罚款/吊销…, amounts) changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PenaltyChange {
+    pub old_number: Arc<str>,
+    pub new_number: Arc<str>,
+    pub old_penalties: Vec<Arc<str>>,
+    pub new_penalties: Vec<Arc<str>>,
+    pub added: Vec<Arc<str>>,
+    pub removed: Vec<Arc<str>>,
+    pub tags: Vec<String>, // "penalty-added" / "penalty-removed"
+}
+
+/// How an old article number carries over to the new text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberMappingStatus {
+    Same,
+    Renumbered,
+    Replaced, // Number kept but the article now says something else
+    Split,
+    Merged,
+    Deleted,
+    New,
+}
+
+/// One row of the old→new article number table, for updating cross-references
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberMapping {
+    pub old_number: Option<Arc<str>>, // None for new articles
+    pub new_number: Option<Arc<str>>, // None for deleted articles
+    pub status: NumberMappingStatus,
+}
+
+/// One old/new article pair with its full similarity breakdown (debug output)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarityPair {
+    pub old_number: Arc<str>,
+    pub new_number: Arc<str>,
+    pub score: SimilarityScore,
 }
 
 /// Multi-dimensional similarity score
@@ -146,32 +415,661 @@ pub struct SimilarityScore {
     pub jaccard_similarity: f32,
     pub containment_similarity: f32,
     pub keyword_weight: f32,
+    #[serde(default)]
+    pub cosine_similarity: f32, // Over term frequencies, so repeated terms count
     pub composite: f32,
 }
 
 impl SimilarityScore {
-    pub fn new(char_sim: f32, jaccard_sim: f32, containment_sim: f32, keyword_weight: f32) -> Self {
-        let composite = char_sim * 0.3 + jaccard_sim * 0.2 + containment_sim * 0.3 + keyword_weight * 0.2;
+    pub fn new(char_sim: f32, jaccard_sim: f32, containment_sim: f32, keyword_weight: f32, cosine_sim: f32) -> Self {
+        Self::with_weights(char_sim, jaccard_sim, containment_sim, keyword_weight, cosine_sim, &SimilarityWeights::default())
+    }
+
+    pub fn with_weights(char_sim: f32, jaccard_sim: f32, containment_sim: f32, keyword_weight: f32, cosine_sim: f32, weights: &SimilarityWeights) -> Self {
+        let composite = weights.combine(char_sim, jaccard_sim, containment_sim, keyword_weight, cosine_sim);
         Self {
             char_similarity: char_sim,
             jaccard_similarity: jaccard_sim,
             containment_similarity: containment_sim,
             keyword_weight,
+            cosine_similarity: cosine_sim,
             composite,
         }
     }
 }
 
-/// Complete diff result
+/// Weights of the dimensions in the composite similarity. They are relative: the composite
+/// is divided by their sum, so {"char": 1} alone scores by characters only. A weights object
+/// stands on its own: dimensions it omits weigh 0, not their default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityWeights {
+    #[serde(default)]
+    pub char: f32,
+    #[serde(default)]
+    pub jaccard: f32,
+    #[serde(default)]
+    pub containment: f32,
+    #[serde(default)]
+    pub keyword: f32,
+    #[serde(default)]
+    pub cosine: f32,
+}
+
+impl Default for SimilarityWeights {
+    // Cosine is opt-in, so default scores (and the thresholds tuned on them) are unchanged
+    fn default() -> Self {
+        Self { char: 0.3, jaccard: 0.2, containment: 0.3, keyword: 0.2, cosine: 0.0 }
+    }
+}
+
+impl SimilarityWeights {
+    /// Weighted mean of the five dimensions; weights that are all zero (or negative) fall
+    /// back to the defaults
+    pub fn combine(&self, char_sim: f32, jaccard_sim: f32, containment_sim: f32, keyword_weight: f32, cosine_sim: f32) -> f32 {
+        let weights = [self.char, self.jaccard, self.containment, self.keyword, self.cosine].map(|w| w.max(0.0));
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return Self::default().combine(char_sim, jaccard_sim, containment_sim, keyword_weight, cosine_sim);
+        }
+        let values = [char_sim, jaccard_sim, containment_sim, keyword_weight, cosine_sim];
+        weights.iter().zip(values).map(|(w, v)| w * v).sum::<f32>() / total
+    }
+}
+
+/// Structural diff restricted to one top-level Part (编), e.g. 总则 or 分则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartResult {
+    pub number: Arc<str>,
+    pub title: Option<Arc<str>>,
+    pub similarity: f32,
+    pub stats: DiffStats,
+    pub article_changes: Vec<ArticleChange>,
+}
+
+/// Heading-level operation between two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StructureChangeType {
+    Added,
+    Deleted,
+    Renamed,    // Same number, new title ("总则" -> "一般规定")
+    Renumbered, // Same title and parent, new number
+    Moved,      // Now under a different 编/分编/章
+    Reordered,  // Same parent, out of order among its siblings (renumbered or not)
+}
+
+/// A 编/分编/章/节 heading as it appears in one version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadingInfo {
+    pub number: Arc<str>,
+    pub title: Option<Arc<str>>,
+    pub start_line: usize,
+    #[serde(default)]
+    pub parents: Vec<Arc<str>>, // Enclosing headings, labelled like ArticleInfo::parents
+}
+
+/// One heading-level change from the tree diff
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct StructureChange {
+    #[serde(rename = "type")]
+    pub change_type: StructureChangeType,
+    pub node_type: NodeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<HeadingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<HeadingInfo>,
+}
+
+/// Complete diff result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DiffResult {
     pub similarity: f32,
     pub changes: Vec<Change>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub article_changes: Option<Vec<ArticleChange>>, // Structural diff result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure_changes: Option<Vec<StructureChange>>, // 编/章/节 added, renamed, renumbered or moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preamble_changes: Option<Vec<ArticleChange>>, // Preambles aligned apart, with `preamble: "separate"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_results: Option<Vec<PartResult>>, // Per-Part (编) results when partitioned
     pub entities: Vec<Entity>,
     pub stats: DiffStats,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>, // Non-fatal issues, e.g. "degraded-alignment"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ner_info: Option<NerInfo>, // Engine used for entity detection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_meta: Option<DocumentMeta>, // Which versions were compared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_meta: Option<DocumentMeta>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool, // Alignment hit the time budget or was cancelled; article changes are partial
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<AlignmentQuality>, // Alignment metrics, with article changes
+}
+
+/// Three-way comparison request: two parallel drafts revising the same base law
+#[derive(Debug, Deserialize)]
+pub struct CompareThreeRequest {
+    pub base_text: String,
+    pub a_text: String,
+    pub b_text: String,
+    #[serde(default)]
+    pub options: CompareOptions,
+}
+
+/// How a base article (or an article new in a draft) fared across the two drafts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStatus {
+    Unchanged,
+    ChangedInA,    // Only draft A touched it; A's version is taken
+    ChangedInB,    // Only draft B touched it; B's version is taken
+    ChangedInBoth, // Both drafts made the same change
+    Conflict,      // The drafts changed it differently
+}
+
+/// One article of a three-way comparison. An empty side means the article is absent there
+/// (deleted, or added only by the other draft).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeArticle {
+    pub status: MergeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<ArticleInfo>,
+    pub a_articles: Vec<ArticleInfo>,
+    pub b_articles: Vec<ArticleInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merged: Option<Vec<ArticleInfo>>, // Auto-merged version; None for conflicts
+}
+
+/// Three-way comparison with the merged text; conflicts are written between
+/// `<<<<<<< A` / `=======` / `>>>>>>> B` markers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreeWayResult {
+    pub articles: Vec<MergeArticle>,
+    pub conflicts: usize,
+    pub merged_text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Summary report request: two texts and how many of the most changed articles to excerpt
+#[derive(Debug, Deserialize)]
+pub struct SummaryRequest {
+    pub old_text: String,
+    pub new_text: String,
+    #[serde(default)]
+    pub options: CompareOptions,
+    #[serde(default = "default_summary_top_n")]
+    pub top_n: usize,
+}
+
+fn default_summary_top_n() -> usize {
+    10
+}
+
+/// Timeline request: successive versions of one law, oldest first
+#[derive(Debug, Deserialize)]
+pub struct TimelineRequest {
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub options: CompareOptions,
+}
+
+/// Something that happened to an article on the way into `version`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionEvent {
+    pub version: usize, // Index into the submitted versions
+    #[serde(rename = "type")]
+    pub change_type: ArticleChangeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f32>,
+}
+
+/// One article followed through every version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleHistory {
+    pub introduced: usize, // First version containing the article
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<usize>, // First version no longer containing it (deleted or merged away)
+    pub versions: Vec<Option<ArticleInfo>>, // The article in each version, None where absent
+    pub events: Vec<VersionEvent>, // Additions, edits, renumberings, splits, merges and deletion
+}
+
+/// Per-article evolution across a chain of versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineResult {
+    pub versions: usize,
+    pub articles: Vec<ArticleHistory>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Apply request: rebuild a document from a base text and a change set
+#[derive(Debug, Deserialize)]
+pub struct ApplyRequest {
+    pub base_text: String,
+    pub changes: Vec<ArticleChange>,
+}
+
+/// One editing instruction from an amendment decision (修改决定)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AmendmentInstruction {
+    /// 将第X条修改为：“…”
+    Replace { article: Arc<str>, text: Arc<str> },
+    /// 删去第X条
+    Delete { article: Arc<str> },
+    /// 在第X条后增加一条，作为第Y条：“…”
+    Insert { after: Option<Arc<str>>, text: Arc<str> },
+    /// 将第X条中的“A”修改为“B”; without an article the replacement applies to the whole text
+    ReplaceWords { article: Option<Arc<str>>, from: Arc<str>, to: Arc<str> },
+}
+
+/// Amend request: a base law text plus the amendment decision to apply to it
+#[derive(Debug, Deserialize)]
+pub struct AmendRequest {
+    pub base_text: String,
+    pub amendment_text: String,
+    #[serde(default)]
+    pub options: CompareOptions,
+}
+
+/// Consolidated text produced by applying an amendment decision
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendResult {
+    pub text: String,
+    pub instructions: Vec<AmendmentInstruction>,
+    pub warnings: Vec<String>, // Instructions that could not be recognized or applied
+    pub diff: DiffResult,
+}
+
+/// Export request: a text to parse, two texts to compare, or an already computed AST or diff
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ExportRequest {
+    Text(String),
+    Compare(Box<CompareRequest>),
+    Diff(Box<DiffResult>),
+    Ast(ArticleNode),
+}
+
+/// Number normalization request (第二百零一条 <-> 第201条)
+#[derive(Debug, Deserialize)]
+pub struct NormalizeNumbersRequest {
+    pub text: String,
+    #[serde(default = "default_true")]
+    pub to_arabic: bool,
+}
+
+/// Statute metadata taken from the title block and closing provisions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMeta {
+    pub title: Option<Arc<str>>,
+    pub adopted: Option<Arc<str>>,           // 通过日期
+    pub promulgated: Option<Arc<str>>,       // 公布日期
+    pub effective: Option<Arc<str>>,         // 施行日期
+    pub issuing_authority: Option<Arc<str>>, // e.g. 全国人民代表大会常务委员会
+    pub amendments: Vec<Arc<str>>,           // "根据…修正" history entries
+    pub document_number: Option<Arc<str>>,   // 文号, e.g. 法释〔2021〕1号
+    #[serde(default)]
+    pub document_type: DocumentType,         // Absent from metadata stored before it was detected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promulgation_order: Option<PromulgationOrder>, // 国务院令/主席令 block above the title
+}
+
+/// The decree that promulgated a regulation or law, printed above its title
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromulgationOrder {
+    pub issuer: Arc<str>,               // e.g. 中华人民共和国国务院令
+    pub number: Arc<str>,               // 第752号, 2019年第1号
+    pub announcement: Option<Arc<str>>, // 《…》已经…通过，现予公布，自…起施行。
+    pub signatory: Option<Arc<str>>,    // e.g. 总理　李克强
+    pub signed: Option<Arc<str>>,       // 签署日期
+}
+
+/// /api/parse response: the AST's own fields, as before metadata and diagnostics were
+/// added, alongside `meta` and `diagnostics`
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseResult {
+    #[serde(flatten)]
+    pub ast: ArticleNode,
+    pub meta: DocumentMeta,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A recoverable problem found while parsing; the tree is still usable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub code: Arc<str>, // e.g. "empty-article", "numbering"
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>, // 1-based source line
+}
+
+/// A successfully parsed document and its diagnostics
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedDocument {
+    pub ast: ArticleNode,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parser behavior toggles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParserOptions {
+    // Keep a leading 【title】/（title） in the article content as well as in `title`
+    #[serde(default)]
+    pub keep_title_in_content: bool,
+
+    // Treat the block after "目录" as a table of contents rather than structure
+    #[serde(default = "default_true")]
+    pub detect_toc: bool,
+
+    // Keep text before the first structural marker as a Preamble node
+    #[serde(default = "default_true")]
+    pub capture_preamble: bool,
+
+    // Drop Part/Chapter/Section nodes that ended up with no content or children
+    #[serde(default = "default_true")]
+    pub prune_empty: bool,
+
+    // Break "…：（一）…；（二）…" onto separate lines so inline clauses become child nodes
+    #[serde(default)]
+    pub split_inline_clauses: bool,
+
+    // Lines (compared after trimming) that separate several documents in one text, e.g. "---"
+    #[serde(default)]
+    pub document_delimiters: Vec<String>,
+
+    // Turn each unmarked paragraph after an article's first one into a numbered Clause (款)
+    #[serde(default)]
+    pub implicit_clauses: bool,
+
+    // Chinese 第X条 structure, or English "Section 1." structure (the "english-statute" grammar)
+    #[serde(default)]
+    pub language: Language,
+
+    // Clause/item marker styles recognized at the start of a line
+    #[serde(default = "default_marker_styles")]
+    pub marker_styles: Vec<MarkerStyle>,
+
+    // Turn ｜-separated or column-aligned rows into Table nodes and diff them cell by cell
+    #[serde(default)]
+    pub detect_tables: bool,
+
+    // Registered StructureGrammar recognizing the structure; None is the grammar of `language`
+    #[serde(default)]
+    pub grammar: Option<String>,
+
+    // Kind of document; None detects it from the header (a 法释〔…〕号 line is a judicial interpretation)
+    #[serde(default)]
+    pub document_type: Option<DocumentType>,
+}
+
+/// Kind of legal document, which decides how headings above the articles are read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentType {
+    #[default]
+    Statute,                // 法律、行政法规: 第X编/章/节
+    JudicialInterpretation, // 司法解释: 法释〔2021〕1号 header, 第X条 grouped under 一、二、 headings if at all
+}
+
+/// Language of the statute, selecting its grammar unless `ParserOptions::grammar` names one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Chinese,
+    English,
+}
+
+/// Clause/item marker styles. Chinese numerals mark clauses (`NodeType::Clause`, 款),
+/// arabic numerals and circled numbers mark items (`NodeType::Item`, 项), except inside full
+/// parentheses where both are clauses. `Stem` and `Ordinal` mark clauses in older
+/// regulations, `Explicit` documents that spell out 第一款/第一项 at the start of each unit;
+/// all three are off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkerStyle {
+    Paren,        // （一） (一) （1）
+    ClosingParen, // 一) 一） 1) 1）
+    Circled,      // ①
+    Dot,          // 1.
+    Comma,        // 1、
+    Stem,         // 甲、 乙、 丙、, numbered 一, 二, 三
+    Ordinal,      // 其一， 其二、
+    Explicit,     // 第一款 clauses, 第二项 items, but not "第二款规定的…"
+}
+
+fn default_marker_styles() -> Vec<MarkerStyle> {
+    vec![MarkerStyle::Paren, MarkerStyle::ClosingParen, MarkerStyle::Circled, MarkerStyle::Dot, MarkerStyle::Comma]
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            keep_title_in_content: false,
+            detect_toc: true,
+            capture_preamble: true,
+            prune_empty: true,
+            split_inline_clauses: false,
+            document_delimiters: Vec::new(),
+            implicit_clauses: false,
+            language: Language::default(),
+            marker_styles: default_marker_styles(),
+            detect_tables: false,
+            grammar: None,
+            document_type: None,
+        }
+    }
+}
+
+/// Kind of numbering problem reported by /api/validate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NumberingIssue {
+    Gap,        // Numbers skipped before this one
+    Duplicate,  // Number already used
+    OutOfOrder, // Number lower than one that came before
+}
+
+/// One article or heading whose number breaks the sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberingFinding {
+    pub kind: NumberingIssue,
+    pub node_type: NodeType,
+    pub number: Arc<str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Arc<str>>, // Label the sequence called for, e.g. "第十三条"
+    pub start_line: usize,
+    pub message: String,
+}
+
+/// How a 目录 entry and the parsed headings disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TocIssue {
+    Missing,       // Listed in the 目录, not found in the body
+    Unlisted,      // Heading in the body the 目录 leaves out
+    TitleMismatch, // Same heading, different title
+}
+
+/// One 目录 entry or body heading that fails the cross-check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TocFinding {
+    pub kind: TocIssue,
+    pub level: Arc<str>, // 编, 章 or 节
+    pub number: Arc<str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toc_title: Option<Arc<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_title: Option<Arc<str>>,
+    pub start_line: usize, // Body heading, or the 目录 line for missing headings
+    pub message: String,
+}
+
+/// /api/validate response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub findings: Vec<NumberingFinding>,
+    #[serde(default)]
+    pub toc_findings: Vec<TocFinding>,
+}
+
+/// /api/parse, /api/validate and /api/glossary request: one document, as a bare text or with
+/// parser options
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ParseRequest {
+    Text(String),
+    WithOptions {
+        text: String,
+        #[serde(default)]
+        options: ParserOptions,
+    },
+}
+
+/// Request of the /documents variants: several texts, each further split on
+/// `options.document_delimiters`, as a bare array or with parser options
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ParseDocumentsRequest {
+    Documents(Vec<String>),
+    WithOptions {
+        documents: Vec<String>,
+        #[serde(default)]
+        options: ParserOptions,
+    },
+}
+
+/// A step of `normalize_legal_text`. Stages always run in the order listed here, whatever
+/// order a request names them in; dropping empty lines at the end is not optional.
+/// `Reflow` is off unless a request names it, since it can glue a short title onto its text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NormalizeStage {
+    Bom,             // U+FEFF byte order marks
+    ZeroWidth,       // zero-width spaces and joiners
+    Nbsp,            // non-breaking spaces -> ' '
+    ControlChars,    // control characters other than line breaks and tabs
+    Width,           // full-width space -> two spaces
+    Markers,         // 第十二條 -> 第十二条, 第 1 条 -> 第1条
+    Reflow,          // lines hard-wrapped at the page width (PDF copies) -> paragraphs
+    LineSplitting,   // 编/分编/章/节 headings on their own line
+    ArticleBreaking, // 第X条 on its own line, except cross-references
+}
+
+impl NormalizeStage {
+    pub const ALL: [NormalizeStage; 9] = [
+        NormalizeStage::Bom,
+        NormalizeStage::ZeroWidth,
+        NormalizeStage::Nbsp,
+        NormalizeStage::ControlChars,
+        NormalizeStage::Width,
+        NormalizeStage::Markers,
+        NormalizeStage::Reflow,
+        NormalizeStage::LineSplitting,
+        NormalizeStage::ArticleBreaking,
+    ];
+
+    /// Every stage except the opt-in `Reflow`
+    pub fn defaults() -> Vec<NormalizeStage> {
+        Self::ALL.into_iter().filter(|s| *s != NormalizeStage::Reflow).collect()
+    }
+}
+
+fn default_normalize_stages() -> Vec<NormalizeStage> {
+    NormalizeStage::defaults()
+}
+
+/// Unit the line diff compares in: runs of characters, jieba words or sentences, or
+/// whole lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    Char,
+    #[default]
+    Word,
+    Line,
+    Sentence,
+}
+
+/// Sequence diff algorithm. Patience anchors on lines that occur once on each side, which
+/// keeps heavily reordered statutes from turning into interleaved delete/insert noise.
+/// `similar` offers no histogram diff, so "histogram" is rejected rather than approximated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffAlgorithm {
+    #[default]
+    Myers,
+    Patience,
+    Lcs,
+}
+
+/// How articles are paired 1:1 before splits, merges and leftovers are looked at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlignmentStrategy {
+    /// Order-preserving LCS over the similarity matrix, then greedy for moved articles
+    #[default]
+    Heuristic,
+    /// Global assignment maximizing the total similarity (Hungarian algorithm); slower, but
+    /// does not mis-assign groups of mutually similar articles
+    Optimal,
+}
+
+/// What happens to preamble blocks (序言, 目录, text before the first article)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreambleMode {
+    /// Aligned with the articles
+    #[default]
+    Align,
+    /// Left out of the comparison
+    Skip,
+    /// Aligned among themselves and reported apart, outside stats and similarity
+    Separate,
+}
+
+/// A reviewer-asserted match between an old and a new article, by number ("第五条", "五" or "5")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleAnchor {
+    pub old_number: String,
+    pub new_number: String,
+}
+
+/// Order of the reported article changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOrder {
+    /// New-document order; deleted articles sit where they were
+    #[default]
+    New,
+    /// Old-document order; added articles follow the article before them
+    Old,
+    /// Grouped by change type, added first; document order within a type
+    Type,
+    /// Least similar first; unmatched articles count as 0
+    Similarity,
 }
 
 /// Compare request
@@ -183,18 +1081,118 @@ pub struct CompareRequest {
     pub options: CompareOptions,
 }
 
-#[derive(Debug, Deserialize, Default)]
+/// Compare request over documents already parsed, e.g. the `ast` of two /api/parse results
+#[derive(Debug, Deserialize)]
+pub struct ParsedCompareRequest {
+    pub old_ast: ArticleNode,
+    pub new_ast: ArticleNode,
+    #[serde(default)]
+    pub options: CompareOptions,
+}
+
+/// Cooperative cancellation flag shared between a request handler and its blocking worker
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Guard cancelling the token when dropped, e.g. with the handler future of a client
+    /// that disconnected
+    pub fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop(self.clone())
+    }
+}
+
+/// See `CancelToken::cancel_on_drop`
+#[derive(Debug)]
+pub struct CancelOnDrop(CancelToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// A milestone of a running alignment, streamed by /api/compare/stream
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum AlignmentProgress {
+    #[serde(rename_all = "camelCase")]
+    Parsed { old_articles: usize, new_articles: usize }, // Both texts parsed and flattened
+    Matrix { percent: usize },                             // Share of similarity matrix rows scored
+    Stage { name: &'static str, matches: usize },          // Changes found once this alignment stage ran
+}
+
+impl AlignmentProgress {
+    /// Server-Sent Event name, the same as the "event" field
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            AlignmentProgress::Parsed { .. } => "parsed",
+            AlignmentProgress::Matrix { .. } => "matrix",
+            AlignmentProgress::Stage { .. } => "stage",
+        }
+    }
+}
+
+/// Receiver of alignment progress; reports nowhere by default
+#[derive(Clone, Default)]
+pub struct ProgressSink(Option<Arc<dyn Fn(AlignmentProgress) + Send + Sync>>);
+
+impl ProgressSink {
+    pub fn new(report: impl Fn(AlignmentProgress) + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(report)))
+    }
+
+    pub fn report(&self, progress: AlignmentProgress) {
+        if let Some(report) = &self.0 {
+            report(progress);
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl std::fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProgressSink").field(&self.is_active()).finish()
+    }
+}
+
+#[derive(Debug, Deserialize)]
 pub struct CompareOptions {
     #[serde(default = "default_true")]
     pub detect_entities: bool,
-    #[serde(default = "default_word_granularity")]
-    pub granularity: String,
+    #[serde(default)]
+    pub granularity: Granularity, // Unit of the line diff and of matched articles' details
+    #[serde(default)]
+    pub algorithm: DiffAlgorithm, // Sequence diff behind the line diff and article details
+    #[serde(default)]
+    pub context_lines: Option<usize>, // Keep this many unchanged units around each change, eliding the rest
+    #[serde(default)]
+    pub omit_unchanged: bool, // Drop unchanged units from the line diff altogether
     #[serde(default)]
 
     pub ner_mode: Option<String>, // "regex", "bert", or "hybrid"
     #[serde(default = "default_align_threshold")]
     pub align_threshold: f32,
     #[serde(default)]
+    pub alignment_strategy: AlignmentStrategy, // 1:1 article pairing: "heuristic" (fast) or "optimal"
+    #[serde(default)]
+    pub sort_by: ChangeOrder, // "new" (default), "old", "type" or "similarity"
+    #[serde(default)]
+    pub preamble: PreambleMode, // "align" (default), "skip" or "separate" (into preamble_changes)
+    #[serde(default)]
+    pub anchors: Vec<ArticleAnchor>, // Article pairs the user asserts match; locked before any heuristic runs
+    #[serde(default)]
     pub format_text: bool,
 
     // Similarity filter options
@@ -202,16 +1200,165 @@ pub struct CompareOptions {
     pub max_similarity: Option<f32>,
     #[serde(default)]
     pub invert_similarity: bool,
+
+    // Report chars_added/chars_removed for matched articles
+    #[serde(default)]
+    pub compute_churn: bool,
+
+    // Compare each top-level Part (编) independently
+    #[serde(default)]
+    pub partition_by_part: bool,
+
+    // Above this many old×new article pairs, align by number only
+    #[serde(default = "default_max_matrix_cells")]
+    pub max_matrix_cells: usize,
+
+    // Above this many old×new article pairs, fully score only MinHash/LSH candidate pairs
+    // whose SimHash fingerprints are not clearly unrelated
+    #[serde(default = "default_lsh_min_cells")]
+    pub lsh_min_cells: usize,
+
+    // Stop aligning after this many milliseconds and return the matches found so far
+    #[serde(default)]
+    pub time_budget_ms: Option<u64>,
+
+    // Set by the API when the client disconnects; alignment stops as on an expired budget
+    #[serde(skip)]
+    pub cancel: CancelToken,
+
+    // Set by /api/compare/stream to report alignment milestones
+    #[serde(skip)]
+    pub progress: ProgressSink,
+
+    // Relative weights of the composite similarity dimensions used for alignment
+    #[serde(default)]
+    pub similarity_weights: SimilarityWeights,
+
+    // Domain keywords for the similarity keyword-weight dimension
+    #[serde(default)]
+    pub similarity_keywords: Option<Vec<String>>,
+    #[serde(default)]
+    pub replace_similarity_keywords: bool, // Replace instead of augmenting the default list
+
+    // Report whitespace/punctuation-only edits as Unchanged (they are always tagged "cosmetic-only").
+    // Article mode only; covers everything the two ignore_* flags below cover, so when it is set
+    // they change nothing there
+    #[serde(default)]
+    pub cosmetic_as_unchanged: bool,
+
+    // Report edits that only touch punctuation (，/、 swaps) or whitespace as Unchanged, tagged "cosmetic-only".
+    // Narrower than cosmetic_as_unchanged: each only strips its own class before the sides are
+    // compared. Also applied to the line diff
+    #[serde(default)]
+    pub ignore_punctuation: bool,
+    #[serde(default)]
+    pub ignore_whitespace: bool,
+
+    // Align clauses (（一）/（二）…) inside matched articles and report clause-level changes
+    #[serde(default)]
+    pub clause_alignment: bool,
+
+    // Emit one Merged change per target (with `merged_from`) instead of one per source article
+    #[serde(default)]
+    pub consolidate_merges: bool,
+
+    // Map the provisions of two different laws: mutual best matches at a lower threshold,
+    // reported as Corresponding, with no renumbering, split or merge detection
+    #[serde(default)]
+    pub cross_law: bool,
+
+    // Join a bare "第X条" heading line with the following non-heading line
+    #[serde(default)]
+    pub attach_deferred_content: bool,
+
+    // Strip page numbers and running headers, and rejoin lines broken at the page width (PDF copies)
+    #[serde(default)]
+    pub clean_pdf_artifacts: bool,
+
+    // Minimum composite score for /api/similarity-pairs records
+    #[serde(default = "default_similarity_floor")]
+    pub similarity_floor: f32,
+
+    // Convert both texts to simplified (or traditional) characters before comparing (`opencc` feature)
+    #[serde(default)]
+    pub to_simplified: bool,
+    #[serde(default)]
+    pub to_traditional: bool,
+
+    // Collapse Modified/Renumbered/Replaced into one Changed type (tags keep the detail)
+    #[serde(default)]
+    pub simplify_change_types: bool,
+
+    // Normalization stages run before parsing; leave one out to skip it
+    #[serde(default = "default_normalize_stages")]
+    pub normalize_stages: Vec<NormalizeStage>,
+
+    #[serde(default)]
+    pub parser: ParserOptions,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            detect_entities: default_true(),
+            granularity: Granularity::default(),
+            algorithm: DiffAlgorithm::default(),
+            context_lines: None,
+            omit_unchanged: false,
+            ner_mode: None,
+            align_threshold: default_align_threshold(),
+            alignment_strategy: AlignmentStrategy::default(),
+            sort_by: ChangeOrder::default(),
+            preamble: PreambleMode::default(),
+            anchors: Vec::new(),
+            format_text: false,
+            min_similarity: None,
+            max_similarity: None,
+            invert_similarity: false,
+            compute_churn: false,
+            partition_by_part: false,
+            max_matrix_cells: default_max_matrix_cells(),
+            lsh_min_cells: default_lsh_min_cells(),
+            time_budget_ms: None,
+            cancel: CancelToken::default(),
+            progress: ProgressSink::default(),
+            similarity_weights: SimilarityWeights::default(),
+            similarity_keywords: None,
+            replace_similarity_keywords: false,
+            cosmetic_as_unchanged: false,
+            ignore_punctuation: false,
+            ignore_whitespace: false,
+            clause_alignment: false,
+            consolidate_merges: false,
+            cross_law: false,
+            attach_deferred_content: false,
+            clean_pdf_artifacts: false,
+            similarity_floor: default_similarity_floor(),
+            to_simplified: false,
+            to_traditional: false,
+            simplify_change_types: false,
+            normalize_stages: default_normalize_stages(),
+            parser: ParserOptions::default(),
+        }
+    }
 }
 
 fn default_align_threshold() -> f32 {
     0.6
 }
 
-fn default_true() -> bool {
-    true
+fn default_max_matrix_cells() -> usize {
+    4_000_000
 }
 
-fn default_word_granularity() -> String {
-    "word".to_string()
+fn default_lsh_min_cells() -> usize {
+    250_000
+}
+
+fn default_similarity_floor() -> f32 {
+    0.3
+}
+
+fn default_true() -> bool {
+    true
 }
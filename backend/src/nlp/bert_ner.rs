@@ -3,7 +3,9 @@ use rust_bert::pipelines::ner::{NERModel, NerToken};
 #[cfg(feature = "bert")]
 use std::sync::{Arc, Mutex};
 
-use crate::models::{Entity, EntityType, Position};
+use crate::models::Entity;
+#[cfg(feature = "bert")]
+use crate::models::{EntityType, Position};
 use super::ner_trait::NEREngine;
 use anyhow::Result;
 
@@ -0,0 +1,79 @@
+use regex::Regex;
+use std::sync::OnceLock;
+use crate::ast::extract_document_meta;
+use crate::diff::aligner::flatten_articles;
+use crate::models::{ArticleNode, Definition, Glossary, NodeType};
+
+static DEFINITION_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// Matches 「本法所称X，是指Y」 and list-style 「（一）X，是指Y」 definitions (also 系指)
+fn get_definition_pattern() -> &'static Regex {
+    DEFINITION_PATTERN.get_or_init(|| {
+        Regex::new(r"(?:所称|^\s*[（(][一二三四五六七八九十]+[）)]\s*)[“「\x22]?([^，,。；;“”「」\x22]+?)[”」\x22]?[，,]?[是系]指([^。；;]+)").unwrap()
+    })
+}
+
+/// The glossary of a parsed document: its title and every term it defines (所称X是指Y),
+/// in order of appearance
+pub fn extract_glossary(ast: &ArticleNode) -> Glossary {
+    Glossary {
+        title: extract_document_meta(ast).title,
+        terms: definitions_in(ast),
+    }
+}
+
+fn definitions_in(ast: &ArticleNode) -> Vec<Definition> {
+    let mut definitions = Vec::new();
+    for article in flatten_articles(ast) {
+        if article.node_type != NodeType::Article {
+            continue;
+        }
+        for line in article.content.lines() {
+            for caps in get_definition_pattern().captures_iter(line) {
+                definitions.push(Definition {
+                    term: caps[1].trim().into(),
+                    definition: caps[2].trim().into(),
+                    article_number: article.number.clone(),
+                });
+            }
+        }
+    }
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_article;
+    use crate::nlp::formatter::normalize_legal_text;
+
+    #[test]
+    fn test_extract_definitions() {
+        let text = "第一条 为了保障网络安全，制定本法。\n\
+                    第二条 本法所称网络运营者，是指网络的所有者、管理者和网络服务提供者。\n\
+                    第三条 本法下列用语的含义：\n\
+                    （一）“个人信息”，是指以电子或者其他方式记录的能够识别自然人身份的各种信息。";
+
+        let definitions = extract_glossary(&parse_article(&normalize_legal_text(text))).terms;
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].term.as_ref(), "网络运营者");
+        assert_eq!(definitions[0].article_number.as_ref(), "二");
+        assert!(definitions[0].definition.starts_with("网络的所有者"));
+        assert_eq!(definitions[1].term.as_ref(), "个人信息");
+        assert_eq!(definitions[1].article_number.as_ref(), "三");
+    }
+
+    #[test]
+    fn test_extract_glossary() {
+        let text = "网络安全审查办法\n\
+                    第一条 为了确保关键信息基础设施供应链安全，制定本办法。\n\
+                    第二十一条 本办法所称网络产品和服务，系指核心网络设备、重要通信产品。\n\
+                    第二十二条 本办法自2022年2月15日起施行。";
+        let glossary = extract_glossary(&parse_article(&normalize_legal_text(text)));
+
+        assert_eq!(glossary.title.as_deref(), Some("网络安全审查办法"));
+        assert_eq!(glossary.terms.len(), 1);
+        assert_eq!(glossary.terms[0].term.as_ref(), "网络产品和服务");
+        assert_eq!(glossary.terms[0].article_number.as_ref(), "二十一");
+    }
+}
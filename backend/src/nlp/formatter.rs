@@ -1,21 +1,95 @@
 use regex::Regex;
 use std::sync::OnceLock;
+use super::numerals::chinese_to_int;
+use crate::models::{NormalizeStage, SourcePosition};
 
-static FORMAT_PATTERN: OnceLock<Regex> = OnceLock::new();
+static REFERENCE_TAIL_PATTERN: OnceLock<Regex> = OnceLock::new();
+static BLOCK_MARKER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static HEADING_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ARTICLE_MARKER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static TRADITIONAL_MARKER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static SPACED_NUMBER_PATTERN: OnceLock<Regex> = OnceLock::new();
+static MAJOR_HEADING_PATTERN: OnceLock<Regex> = OnceLock::new();
+static ARTICLE_BREAK_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn get_reference_tail_pattern() -> &'static Regex {
+    REFERENCE_TAIL_PATTERN.get_or_init(|| {
+        Regex::new(r"^(?:之[^一二三四五六七八九十]|规定|的|[、，至]|[和及]第|或者第|第[一二三四五六七八九十百千零两\d]+[款项目])").unwrap()
+    })
+}
+
+/// Structural markers that open a new block: a line starting with one is never the
+/// continuation of a wrapped line
+fn get_block_marker_pattern() -> &'static Regex {
+    BLOCK_MARKER_PATTERN.get_or_init(|| {
+        Regex::new(r"^(第[一二三四五六七八九十百千零两\d]+(?:分编|[编章节条])|[（(]|[一二三四五六七八九十]+[、)）]|\d+[.、)）]|[①-⑳]|附\s*则|附件)").unwrap()
+    })
+}
+
+fn get_heading_pattern() -> &'static Regex {
+    HEADING_PATTERN.get_or_init(|| Regex::new(r"^第[一二三四五六七八九十百千零两\d]+(?:分编|[编章节])").unwrap())
+}
+
+fn get_article_marker_pattern() -> &'static Regex {
+    ARTICLE_MARKER_PATTERN.get_or_init(|| Regex::new(r"第([一二三四五六七八九十百千零两\d]+)条").unwrap())
+}
+
+fn get_traditional_marker_pattern() -> &'static Regex {
+    TRADITIONAL_MARKER_PATTERN.get_or_init(|| {
+        Regex::new(r"第[ 　]*[一二三四五六七八九十百千万零两壹貳參肆伍陸柒捌玖拾佰仟萬兩〇\d]+[ 　]*(?:分[编編]|[条條编編章节節项項款])").unwrap()
+    })
+}
+
+fn get_spaced_number_pattern() -> &'static Regex {
+    SPACED_NUMBER_PATTERN.get_or_init(|| Regex::new(r"第 +(\d+) *(分编|[编章节条])").unwrap())
+}
+
+fn get_major_heading_pattern() -> &'static Regex {
+    MAJOR_HEADING_PATTERN.get_or_init(|| Regex::new(r"(\s*)(第[一二三四五六七八九十百\d]+(?:分编|[编章节]))").unwrap())
+}
+
+fn get_article_break_pattern() -> &'static Regex {
+    ARTICLE_BREAK_PATTERN.get_or_init(|| Regex::new(r"([。！？；\)）】\s])(第[一二三四五六七八九十百\d]+条)").unwrap())
+}
+
+/// Whether the text right after a 第X条/款/项 marker continues a cross-reference
+/// ("第三十条第二款规定的…", "第五条、第六条", "第九条的规定") rather than starting the
+/// unit's own text
+pub fn is_reference_tail(after_marker: &str) -> bool {
+    get_reference_tail_pattern().is_match(after_marker.trim_start())
+}
+
+/// Traditional characters used in structural markers and their simplified forms
+const TRADITIONAL_MARKER_CHARS: &[(char, char)] = &[
+    ('條', '条'), ('編', '编'), ('節', '节'), ('項', '项'),
+    ('壹', '一'), ('貳', '二'), ('參', '三'), ('肆', '四'), ('伍', '五'),
+    ('陸', '六'), ('柒', '七'), ('捌', '八'), ('玖', '九'), ('拾', '十'),
+    ('佰', '百'), ('仟', '千'), ('萬', '万'), ('兩', '两'), ('〇', '零'),
+];
+
+/// Rewrite traditional-character markers (第十二條, 第壹編, 第3項) to simplified
+/// form so Taiwan-style statutes parse into the same tree. The mapping is
+/// character-for-character, so offsets into the text are preserved.
+pub fn normalize_traditional_markers(text: &str) -> String {
+    get_traditional_marker_pattern().replace_all(text, |caps: &regex::Captures| {
+        caps[0].chars().map(|c| {
+            TRADITIONAL_MARKER_CHARS.iter().find(|(t, _)| *t == c).map_or(c, |(_, s)| *s)
+        }).collect::<String>()
+    }).into_owned()
+}
 
 /// Normalize legal text by ensuring standard structural components (Articles, Clauses)
 /// start on their own lines. This improves diff granularity.
 pub fn normalize_legal_text(text: &str) -> String {
-    // Stage 0: Normalize full-width spaces to double spaces to preserve indentation visual
-    let mut text = text.replace('\u{3000}', "  ");
-
-    // Stage 1: Major structural components (编, 章, 节) - always force newline but preserve leading space
-    let major_re = Regex::new(r"(\s*)(第[一二三四五六七八九十百\d]+[编章节])").unwrap();
-    text = major_re.replace_all(&text, "\n$1$2").to_string();
+    normalize_with_stages(text, &NormalizeStage::defaults())
+}
 
-    // Stage 1.1: Articles (条) - Force newline for "第X条"
-    let article_re = Regex::new(r"([。！？；\)）】\s])(第[一二三四五六七八九十百\d]+条)").unwrap();
-    text = article_re.replace_all(&text, "$1\n$2").to_string();
+/// `normalize_legal_text` running only the `stages` given, in pipeline order
+pub fn normalize_with_stages(text: &str, stages: &[NormalizeStage]) -> String {
+    let mut text = text.to_string();
+    for stage in NormalizeStage::ALL.iter().filter(|s| stages.contains(s)) {
+        text = apply_stage(*stage, &text);
+    }
 
     // Cleanup: remove empty lines and trim only the end
     let mut result = String::new();
@@ -30,6 +104,253 @@ pub fn normalize_legal_text(text: &str) -> String {
     result
 }
 
+fn apply_stage(stage: NormalizeStage, text: &str) -> String {
+    match stage {
+        // Invisible characters that stop ^第…条 and friends from matching
+        NormalizeStage::Bom => text.replace('\u{FEFF}', ""),
+        NormalizeStage::ZeroWidth => text.chars().filter(|c| !matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}')).collect(),
+        NormalizeStage::Nbsp => text.replace(['\u{00A0}', '\u{2007}', '\u{202F}'], " "),
+        NormalizeStage::ControlChars => text.chars().filter(|c| !c.is_control() || c.is_whitespace()).collect(),
+
+        // Full-width spaces become double spaces to preserve indentation visual
+        NormalizeStage::Width => text.replace('\u{3000}', "  "),
+
+        // Traditional-character markers, and spaced numbers ("第 1 条" -> "第1条")
+        NormalizeStage::Markers => get_spaced_number_pattern().replace_all(&normalize_traditional_markers(text), "第$1$2").into_owned(),
+
+        NormalizeStage::Reflow => reflow_hard_wraps(text),
+
+        // Major structural components (编, 分编, 章, 节) - always force newline but preserve leading space
+        NormalizeStage::LineSplitting => get_major_heading_pattern().replace_all(text, "\n$1$2").into_owned(),
+
+        // Articles (条) - Force newline for "第X条", unless a sentence opens with a reference
+        // to one ("。第三十条第二款规定的情形…"); then headings glued to a preceding CJK
+        // character (missing 。)
+        NormalizeStage::ArticleBreaking => {
+            let text = get_article_break_pattern().replace_all(text, |caps: &regex::Captures| {
+                if is_reference_tail(&text[caps.get(0).unwrap().end()..]) {
+                    caps[0].to_string()
+                } else {
+                    format!("{}\n{}", &caps[1], &caps[2])
+                }
+            });
+            split_glued_article_headings(&text)
+        }
+    }
+}
+
+/// Join a bare article heading ("第五条" alone on its line) with the following
+/// non-heading line, recovering content that was deferred to the next line.
+/// Expects text already passed through `normalize_legal_text`.
+pub fn attach_deferred_article_content(text: &str) -> String {
+    let bare_heading_re = Regex::new(r"^\s*第[一二三四五六七八九十百千零两\d]+条\s*$").unwrap();
+    let marker_re = Regex::new(r"^\s*(第[一二三四五六七八九十百千零两\d]+(?:分编|[编章节条])|[（(][一二三四五六七八九十百千零\d]+[)）])").unwrap();
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        match lines.get(i + 1) {
+            Some(next) if bare_heading_re.is_match(line) && !marker_re.is_match(next) => {
+                result.push_str(line.trim_end());
+                result.push(' ');
+                result.push_str(next.trim());
+                i += 2;
+            }
+            _ => {
+                result.push_str(line);
+                i += 1;
+            }
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// Split "…规定第五条国家…" into two lines when 第五条 is the next article in sequence
+/// and what follows it does not continue a cross-reference ("依照第五条规定",
+/// "第五条 的规定"), with or without whitespace after 条.
+fn split_glued_article_headings(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last_number = 0;
+    for line in text.lines() {
+        let mut segment_start = 0;
+        for caps in get_article_marker_pattern().captures_iter(line) {
+            let m = caps.get(0).unwrap();
+            let number = chinese_to_int(&caps[1]);
+            if line[..m.start()].trim().is_empty() {
+                last_number = number;
+                continue;
+            }
+
+            let glued_to_cjk = line[..m.start()].chars().next_back()
+                .is_some_and(|c| ('\u{4e00}'..='\u{9fff}').contains(&c));
+            let heading = glued_to_cjk && !is_reference_tail(&line[m.end()..]);
+            if heading && last_number > 0 && number == last_number + 1 {
+                result.push_str(&line[segment_start..m.start()]);
+                result.push('\n');
+                segment_start = m.start();
+                last_number = number;
+            }
+        }
+        result.push_str(&line[segment_start..]);
+        result.push('\n');
+    }
+    result
+}
+
+/// Lines repeated at least this often are treated as running page headers/footers
+const REPEATED_HEADER_MIN_COUNT: usize = 3;
+/// Only lines at least this long are candidates for rejoining a page-width line break
+const BROKEN_LINE_MIN_CHARS: usize = 20;
+
+/// Remove artifacts of text copied out of PDFs, before `normalize_legal_text`:
+/// standalone page numbers ("12", "- 12 -", "第3页 共10页"), running headers/footers
+/// (short lines repeated on every page; the first occurrence is kept), and lines broken
+/// at the page width, including hyphenated English words.
+pub fn clean_pdf_artifacts(text: &str) -> String {
+    let page_number_re = Regex::new(r"^(?:[-—–]\s*)?\d{1,4}(?:\s*[-—–])?$|^第\s*\d+\s*页(?:\s*[，,]?\s*共\s*\d+\s*页)?$|^\d+\s*/\s*\d+$|^(?i:page)\s+\d+(?:\s+of\s+\d+)?$").unwrap();
+    let marker_re = get_block_marker_pattern();
+
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let mut counts = std::collections::HashMap::new();
+    for line in &lines {
+        *counts.entry(*line).or_insert(0usize) += 1;
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut kept: Vec<String> = Vec::new();
+    for line in lines {
+        if page_number_re.is_match(line) {
+            continue;
+        }
+        let repeated = counts[line] >= REPEATED_HEADER_MIN_COUNT
+            && line.chars().count() <= 40
+            && !marker_re.is_match(line);
+        if repeated && !seen.insert(line) {
+            continue;
+        }
+
+        if !kept.last_mut().is_some_and(|prev| rejoin_wrapped_line(prev, line)) {
+            kept.push(line.to_string());
+        }
+    }
+
+    let mut result = kept.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Merge lines hard-wrapped at the page width back into logical paragraphs: a line of at
+/// least `BROKEN_LINE_MIN_CHARS` that does not end in sentence punctuation absorbs the next
+/// one unless that starts with a structural marker. Blank lines are kept as paragraph breaks.
+pub fn reflow_hard_wraps(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut after_blank = true;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let joined = !after_blank && !trimmed.is_empty()
+            && lines.last_mut().is_some_and(|prev| rejoin_wrapped_line(prev, trimmed));
+        if !joined {
+            lines.push(line.to_string());
+        }
+        after_blank = trimmed.is_empty();
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Append `line` to `prev` when `prev` was broken at the page width, including in the
+/// middle of a hyphenated English word; returns whether it did
+fn rejoin_wrapped_line(prev: &mut String, line: &str) -> bool {
+    if prev.ends_with('-') && prev.chars().rev().nth(1).is_some_and(|c| c.is_ascii_alphabetic())
+        && line.starts_with(|c: char| c.is_ascii_lowercase()) {
+        prev.pop();
+        prev.push_str(line);
+        return true;
+    }
+    let prev_open = prev.trim().chars().count() >= BROKEN_LINE_MIN_CHARS
+        && !get_heading_pattern().is_match(prev.trim_start())
+        && prev.trim_end().chars().next_back().is_some_and(|c| !"。；;：:！？!?…」』”）)】".contains(c));
+    let continues = line.starts_with(|c: char| ('\u{4e00}'..='\u{9fff}').contains(&c) || "，、".contains(c))
+        && !get_block_marker_pattern().is_match(line);
+    if prev_open && continues {
+        prev.truncate(prev.trim_end().len());
+        prev.push_str(line);
+    }
+    prev_open && continues
+}
+
+/// Punctuation commonly swapped between sources (full-width and ASCII)
+const CJK_PUNCTUATION: &str = "，。、；：？！…—–·“”‘’「」『』（）《》〈〉【】〔〕［］｛｝～";
+
+/// Whether a character only affects presentation (whitespace or punctuation)
+pub fn is_cosmetic_char(c: char) -> bool {
+    c.is_whitespace() || is_punctuation_char(c)
+}
+
+/// ASCII or CJK punctuation
+pub fn is_punctuation_char(c: char) -> bool {
+    c.is_ascii_punctuation() || CJK_PUNCTUATION.contains(c)
+}
+
+/// Remove whitespace and punctuation so purely cosmetic edits compare equal
+pub fn strip_cosmetic(text: &str) -> String {
+    text.chars().filter(|&c| !is_cosmetic_char(c)).collect()
+}
+
+/// Remove punctuation and/or whitespace, for `CompareOptions::ignore_punctuation`/`ignore_whitespace`
+pub fn strip_format(text: &str, punctuation: bool, whitespace: bool) -> String {
+    text.chars()
+        .filter(|&c| !((punctuation && is_punctuation_char(c)) || (whitespace && c.is_whitespace())))
+        .collect()
+}
+
+/// How far ahead in the original text a character is searched for before it is treated as
+/// inserted by the transformation
+const SOURCE_MAP_WINDOW: usize = 2000;
+
+/// Where each line of `transformed` (normalized, PDF-cleaned…) starts in `original`,
+/// indexed like `transformed.lines()`.
+///
+/// The formatter only moves whitespace, rewrites traditional markers one character for one
+/// and drops artifacts, so the visible characters of `transformed` are matched in order
+/// against those of `original`.
+pub fn source_line_map(original: &str, transformed: &str) -> Vec<SourcePosition> {
+    let mut visible = Vec::new();
+    let (mut line, mut offset) = (1, 0);
+    for c in original.chars() {
+        if !c.is_whitespace() {
+            let simplified = TRADITIONAL_MARKER_CHARS.iter().find(|(t, _)| *t == c).map_or(c, |(_, s)| *s);
+            visible.push((simplified, SourcePosition { line, offset }));
+        }
+        if c == '\n' {
+            line += 1;
+        }
+        offset += 1;
+    }
+
+    let mut next = 0;
+    let mut map = Vec::new();
+    for transformed_line in transformed.lines() {
+        let mut start = None;
+        for c in transformed_line.chars().filter(|c| !c.is_whitespace()) {
+            let end = visible.len().min(next + SOURCE_MAP_WINDOW);
+            if let Some(found) = (next..end).find(|&k| visible[k].0 == c) {
+                start.get_or_insert(visible[found].1);
+                next = found + 1;
+            }
+        }
+        // Blank or unmatched lines sit where the previous match left off
+        let fallback = visible.get(next).or(visible.last()).map_or(SourcePosition { line: 1, offset: 0 }, |v| v.1);
+        map.push(start.unwrap_or(fallback));
+    }
+    map
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,6 +362,132 @@ mod tests {
         assert_eq!(normalize_legal_text(input), expected);
     }
 
+    #[test]
+    fn test_normalize_stages() {
+        // Invisible characters in front of markers no longer hide them
+        let input = "\u{FEFF}第一条\u{00A0}内容\u{0007}。\u{200B}第二条 内容。";
+        assert_eq!(normalize_legal_text(input), "第一条 内容。\n第二条 内容。\n");
+
+        let stages: Vec<NormalizeStage> = NormalizeStage::ALL.into_iter()
+            .filter(|s| *s != NormalizeStage::ArticleBreaking)
+            .collect();
+        assert_eq!(normalize_with_stages("第一条 内容。第二条 内容。", &stages), "第一条 内容。第二条 内容。\n");
+        assert_eq!(normalize_with_stages("\u{FEFF}第一章\u{3000}总则", &[]), "\u{FEFF}第一章\u{3000}总则\n");
+
+        let options: crate::models::CompareOptions = serde_json::from_str(r#"{"normalize_stages": ["bom", "zeroWidth", "articleBreaking"]}"#).unwrap();
+        assert_eq!(options.normalize_stages, vec![NormalizeStage::Bom, NormalizeStage::ZeroWidth, NormalizeStage::ArticleBreaking]);
+    }
+
+    #[test]
+    fn test_reference_not_split_as_article() {
+        let input = "第三十一条 有下列情形的，依法处理。第三十条第二款规定的情形除外。第三十二条 内容。";
+        let expected = "第三十一条 有下列情形的，依法处理。第三十条第二款规定的情形除外。\n第三十二条 内容。\n";
+        assert_eq!(normalize_legal_text(input), expected);
+        assert!(is_reference_tail("第二款规定的"));
+        assert!(is_reference_tail(" 的规定执行"));
+        assert!(!is_reference_tail("之一 国家鼓励开发"));
+    }
+
+    #[test]
+    fn test_split_glued_article_heading() {
+        let input = "第四条 国家建立网络安全监测预警制度并按照规定统一发布第五条 国家鼓励开发网络安全保护技术。";
+        let expected = "第四条 国家建立网络安全监测预警制度并按照规定统一发布\n第五条 国家鼓励开发网络安全保护技术。\n";
+        assert_eq!(normalize_legal_text(input), expected);
+
+        // Inline references and non-sequential numbers are left alone
+        let input = "第四条 依照本法第五条规定处理。\n第六条 有关部门依照第九条 的规定执行。";
+        assert_eq!(normalize_legal_text(input), "第四条 依照本法第五条规定处理。\n第六条 有关部门依照第九条 的规定执行。\n");
+        let input = "第五条 依照本法第六条 的规定处理。";
+        assert_eq!(normalize_legal_text(input), "第五条 依照本法第六条 的规定处理。\n");
+
+        // No whitespace after 条
+        let input = "第四条 国家建立网络安全监测预警制度并按照规定统一发布第五条国家鼓励开发网络安全保护技术。";
+        let expected = "第四条 国家建立网络安全监测预警制度并按照规定统一发布\n第五条国家鼓励开发网络安全保护技术。\n";
+        assert_eq!(normalize_legal_text(input), expected);
+    }
+
+    #[test]
+    fn test_attach_deferred_article_content() {
+        let input = normalize_legal_text("第五条\n　　国家支持网络安全技术研究开发。\n第六条\n（一）建立制度；");
+        let expected = "第五条 国家支持网络安全技术研究开发。\n第六条\n（一）建立制度；\n";
+        assert_eq!(attach_deferred_article_content(&input), expected);
+    }
+
+    #[test]
+    fn test_normalize_traditional_markers() {
+        assert_eq!(normalize_traditional_markers("第十二條 本法所稱…"), "第十二条 本法所稱…");
+        assert_eq!(normalize_traditional_markers("第壹編 總則"), "第一编 總則");
+        let input = "第 1 條 為規範公司之組織。第 2 條 本法所稱公司。";
+        assert_eq!(normalize_legal_text(input), "第1条 為規範公司之組織。\n第2条 本法所稱公司。\n");
+    }
+
+    #[test]
+    fn test_source_line_map() {
+        let input = "标题\n\n第一条 内容。第二條 内容。\n　　（一）事项；";
+        let normalized = normalize_legal_text(input);
+        let map = source_line_map(input, &normalized);
+        let lines: Vec<&str> = normalized.lines().collect();
+        assert_eq!(lines[2], "第二条 内容。");
+        assert_eq!(map.len(), lines.len());
+        assert_eq!(map[0], SourcePosition { line: 1, offset: 0 });
+        // Both glued articles come from line 3; the second starts 7 characters in
+        assert_eq!(map[1], SourcePosition { line: 3, offset: 4 });
+        assert_eq!(map[2], SourcePosition { line: 3, offset: 11 });
+        assert_eq!(map[3].line, 4);
+    }
+
+    #[test]
+    fn test_clean_pdf_artifacts() {
+        let input = "中华人民共和国网络安全法\n\
+                     第一条 为了保障网络安全，维护网络空间主权和国家安全、社会公共利益，\n\
+                     保护公民、法人和其他组织的合法权益，制定本法。\n\
+                     - 1 -\n\
+                     中华人民共和国网络安全法\n\
+                     第二条 在中华人民共和国境内建设、运营网络，\n\
+                     第2页 共3页\n\
+                     中华人民共和国网络安全法\n\
+                     适用本法。\n\
+                     Section 3. Operators shall ensure secu-\nrity of networks.";
+        let expected = "中华人民共和国网络安全法\n\
+                        第一条 为了保障网络安全，维护网络空间主权和国家安全、社会公共利益，保护公民、法人和其他组织的合法权益，制定本法。\n\
+                        第二条 在中华人民共和国境内建设、运营网络，适用本法。\n\
+                        Section 3. Operators shall ensure security of networks.\n";
+        assert_eq!(clean_pdf_artifacts(input), expected);
+    }
+
+    #[test]
+    fn test_reflow_hard_wraps() {
+        let input = "中华人民共和国网络安全法\n\
+                     第一条 为了保障网络安全，维护网络空间主权和国家安全、社会公共利益，保护\n\
+                     公民、法人和其他组织的合法权益，制定本法。\n\
+                     第二条 在中华人民共和国境内建设、运营、维护和使用网络，以及网络安全的监\n\
+                     督管理，适用本法。下列网络适用本法，但是法律另有规定的除外\n\
+                     （一）关键信息基础设施；\n\n\
+                     第三条 国家坚持网络安全与信息化发展并重，遵循积极利用、科学发展\n\n\
+                     的方针。";
+        let expected = "中华人民共和国网络安全法\n\
+                        第一条 为了保障网络安全，维护网络空间主权和国家安全、社会公共利益，保护公民、法人和其他组织的合法权益，制定本法。\n\
+                        第二条 在中华人民共和国境内建设、运营、维护和使用网络，以及网络安全的监督管理，适用本法。下列网络适用本法，但是法律另有规定的除外\n\
+                        （一）关键信息基础设施；\n\n\
+                        第三条 国家坚持网络安全与信息化发展并重，遵循积极利用、科学发展\n\n\
+                        的方针。\n";
+        assert_eq!(reflow_hard_wraps(input), expected);
+
+        // Opt-in as a normalization stage
+        assert_eq!(normalize_legal_text(input).lines().count(), 8);
+        let mut stages = NormalizeStage::defaults();
+        stages.push(NormalizeStage::Reflow);
+        assert_eq!(normalize_with_stages(input, &stages).lines().count(), 6);
+    }
+
+    #[test]
+    fn test_strip_cosmetic() {
+        assert_eq!(strip_cosmetic("应当， 建立（制度）。"), "应当建立制度");
+        assert_eq!(strip_cosmetic("a, b;\tc"), "abc");
+        assert_eq!(strip_format("应当， 建立（制度）。", true, false), "应当 建立制度");
+        assert_eq!(strip_format("应当， 建立（制度）。", false, true), "应当，建立（制度）。");
+    }
+
     #[test]
     fn test_normalize_clauses() {
         let input = "第一条 内容。（一）款一；（二）款二。";
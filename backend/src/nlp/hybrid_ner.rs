@@ -4,6 +4,8 @@ use crate::models::{Entity, EntityType};
 use super::{ner_trait::NEREngine, regex_ner::RegexNER, bert_ner::BertNER};
 #[cfg(feature = "bert")]
 use anyhow::Result;
+#[cfg(feature = "bert")]
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(feature = "bert")]
 /// Hybrid NER: Uses regex first, then BERT for low-confidence regions
@@ -11,6 +13,7 @@ pub struct HybridNER {
     regex_ner: RegexNER,
     bert_ner: BertNER,
     confidence_threshold: f32,
+    bert_triggered: AtomicBool, // Set once BERT has been consulted
 }
 
 #[cfg(feature = "bert")]
@@ -23,6 +26,7 @@ impl HybridNER {
             regex_ner: RegexNER::new(),
             bert_ner: BertNER::new(&model_path)?,
             confidence_threshold: 0.88, // Use BERT if regex confidence < 88%
+            bert_triggered: AtomicBool::new(false),
         })
     }
 
@@ -87,6 +91,7 @@ impl NEREngine for HybridNER {
                 avg_confidence * 100.0
             );
 
+            self.bert_triggered.store(true, Ordering::Relaxed);
             let bert_entities = self.bert_ner.extract_entities(text)?;
             Ok(Self::merge_entities(regex_entities, bert_entities))
         } else {
@@ -101,4 +106,8 @@ impl NEREngine for HybridNER {
     fn confidence_range(&self) -> (f32, f32) {
         (0.88, 0.99)
     }
+
+    fn fallback_triggered(&self) -> Option<bool> {
+        Some(self.bert_triggered.load(Ordering::Relaxed))
+    }
 }
@@ -1,5 +1,8 @@
 pub mod tokenizer;
 pub mod formatter;
+pub mod definitions;
+pub mod numerals;
+pub mod opencc;
 pub mod ner_trait;
 pub mod regex_ner;
 pub mod bert_ner;
@@ -10,6 +13,8 @@ pub mod hybrid_ner;
 pub use tokenizer::{tokenize, tokenize_with_dict, WordManager};
 pub use ner_trait::{NEREngine, NERMode, create_ner_engine};
 pub use regex_ner::RegexNER;
+pub use definitions::extract_glossary;
+pub use numerals::{article_sort_key, chinese_to_int, int_to_chinese, normalize_article_numbers};
 pub use bert_ner::BertNER;
 
 #[cfg(feature = "bert")]
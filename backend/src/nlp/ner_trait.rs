@@ -1,4 +1,4 @@
-use crate::models::{Entity, EntityType, Position};
+use crate::models::Entity;
 use anyhow::Result;
 
 /// NER (Named Entity Recognition) trait abstraction
@@ -12,12 +12,18 @@ pub trait NEREngine: Send + Sync {
 
     /// Get the typical confidence range for this engine
     fn confidence_range(&self) -> (f32, f32);
+
+    /// Whether a fallback engine was invoked so far (hybrid engines only)
+    fn fallback_triggered(&self) -> Option<bool> {
+        None
+    }
 }
 
 /// NER engine type configuration
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum NERMode {
     /// Fast regex-based NER (85-90% accuracy)
+    #[default]
     Regex,
     /// BERT-based NER (95%+ accuracy, requires model)
     #[cfg(feature = "bert")]
@@ -27,13 +33,8 @@ pub enum NERMode {
     Hybrid,
 }
 
-impl Default for NERMode {
-    fn default() -> Self {
-        Self::Regex
-    }
-}
-
 impl NERMode {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "regex" => Some(Self::Regex),
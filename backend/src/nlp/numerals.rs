@@ -0,0 +1,178 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+static NUMBERED_HEADING_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+const DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const UNITS: [&str; 4] = ["", "十", "百", "千"];
+
+fn get_numbered_heading_pattern() -> &'static Regex {
+    NUMBERED_HEADING_PATTERN.get_or_init(|| {
+        Regex::new(r"第([一二三四五六七八九十百千万亿零两]+|\d+)(分编|编|章|节|条)").unwrap()
+    })
+}
+
+/// Convert a Chinese (or arabic) numeral such as "二百零一" to its value; 0 when the value
+/// does not fit a `usize`, as for text that is not a numeral
+pub fn chinese_to_int(s: &str) -> usize {
+    checked_chinese_to_int(s).unwrap_or(0)
+}
+
+/// `chinese_to_int`, or None when the value overflows
+pub fn checked_chinese_to_int(s: &str) -> Option<usize> {
+    if s == "root" { return Some(0); }
+    if s == "0" || s.is_empty() { return Some(0); }
+
+    let mut high = 0usize;
+    let mut result = 0usize;
+    let mut temp = 0usize;
+
+    let mut mapping = std::collections::HashMap::new();
+    mapping.insert('零', 0); mapping.insert('一', 1); mapping.insert('二', 2); mapping.insert('两', 2);
+    mapping.insert('三', 3); mapping.insert('四', 4); mapping.insert('五', 5); mapping.insert('六', 6);
+    mapping.insert('七', 7); mapping.insert('八', 8); mapping.insert('九', 9); mapping.insert('十', 10);
+    mapping.insert('百', 100); mapping.insert('千', 1000); mapping.insert('万', 10000); mapping.insert('亿', 100_000_000);
+
+    for c in s.chars() {
+        if let Some(&v) = mapping.get(&c) {
+            if v == 100_000_000 {
+                // 亿 scales everything before it, 万 groups included: 三千万亿 is (30_000_000) * 10^8
+                high = high.checked_add(result)?.checked_add(temp)?.max(1).checked_mul(v)?;
+                result = 0;
+                temp = 0;
+            } else if v == 10000 {
+                // 万 scales everything before it since the last 亿: 二十万 is (20) * 10000, a bare 万 is 10000
+                result = result.checked_add(temp)?.max(1).checked_mul(v)?;
+                temp = 0;
+            } else if v >= 10 {
+                if temp == 0 { temp = 1; }
+                result = result.checked_add(temp.checked_mul(v)?)?;
+                temp = 0;
+            } else {
+                temp = temp.checked_mul(10)?.checked_add(v)?;
+            }
+        } else if let Some(d) = c.to_digit(10) {
+            temp = temp.checked_mul(10)?.checked_add(d as usize)?;
+        }
+    }
+    high.checked_add(result)?.checked_add(temp)
+}
+
+/// Ordering key for article numbers, including inserted "之一" (bis) articles:
+/// "二十九" -> (29, 0), "二十九之一" -> (29, 1), so bis articles sort right after their base.
+pub fn article_sort_key(number: &str) -> (usize, usize) {
+    match number.split_once('之') {
+        Some((base, sub)) => (chinese_to_int(base), chinese_to_int(sub)),
+        None => (chinese_to_int(number), 0),
+    }
+}
+
+/// Convert a value to its Chinese numeral, e.g. 201 -> "二百零一", 12 -> "十二",
+/// 120_000_005 -> "一亿二千万零五"
+pub fn int_to_chinese(n: usize) -> String {
+    if n == 0 {
+        return "零".to_string();
+    }
+    let result = grouped(n);
+    // 10-19 read as 十/十一… rather than 一十/一十一
+    match result.strip_prefix("一十") {
+        Some(rest) => format!("十{}", rest),
+        None => result,
+    }
+}
+
+/// Numeral of a positive value in 亿 and 万 groups; a group with leading zeros opens with 零
+fn grouped(n: usize) -> String {
+    for (unit, scale) in [("亿", 100_000_000), ("万", 10_000)] {
+        if n >= scale {
+            let (high, low) = (grouped(n / scale), n % scale);
+            return match low {
+                0 => format!("{}{}", high, unit),
+                low if low < scale / 10 => format!("{}{}零{}", high, unit, grouped(low)),
+                low => format!("{}{}{}", high, unit, grouped(low)),
+            };
+        }
+    }
+    below_ten_thousand(n)
+}
+
+fn below_ten_thousand(n: usize) -> String {
+    let digits: Vec<usize> = n.to_string().chars().map(|c| c.to_digit(10).unwrap() as usize).collect();
+    let mut result = String::new();
+    let mut pending_zero = false;
+    for (i, &d) in digits.iter().enumerate() {
+        let unit = UNITS[digits.len() - 1 - i];
+        if d == 0 {
+            pending_zero = !result.is_empty();
+            continue;
+        }
+        if pending_zero {
+            result.push('零');
+            pending_zero = false;
+        }
+        result.push(DIGITS[d]);
+        result.push_str(unit);
+    }
+    result
+}
+
+/// Rewrite 第X条/章/节/编/分编 numbers to arabic (第201条) or Chinese (第二百零一条) numerals
+pub fn normalize_article_numbers(text: &str, to_arabic: bool) -> String {
+    get_numbered_heading_pattern().replace_all(text, |caps: &regex::Captures| {
+        // A number too large to hold is left as written
+        let Some(value) = checked_chinese_to_int(&caps[1]) else {
+            return caps[0].to_string();
+        };
+        let number = if to_arabic { value.to_string() } else { int_to_chinese(value) };
+        format!("第{}{}", number, &caps[2])
+    }).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for n in [1, 10, 12, 20, 100, 105, 110, 201, 1000, 1010, 1234, 10_001, 20_300, 100_000_000, 100_010_000, 120_000_005, 1_234_567_890, 1_000_000_000_000] {
+            let chinese = int_to_chinese(n);
+            assert_eq!(chinese_to_int(&chinese), n, "{} -> {}", n, chinese);
+        }
+        assert_eq!(int_to_chinese(201), "二百零一");
+        assert_eq!(int_to_chinese(12), "十二");
+        assert_eq!(int_to_chinese(110), "一百一十");
+        assert_eq!(int_to_chinese(1005), "一千零五");
+        assert_eq!(chinese_to_int("二十万"), 200_000);
+        assert_eq!(chinese_to_int("一千五百万"), 15_000_000);
+        assert_eq!(int_to_chinese(120_000_005), "一亿二千万零五");
+        assert_eq!(int_to_chinese(1_200_000_000), "十二亿");
+        assert_eq!(chinese_to_int("三亿零五十万"), 300_500_000);
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(checked_chinese_to_int("99999999999999999999"), None);
+        assert_eq!(checked_chinese_to_int("九千九百九十九万亿亿"), None);
+        assert_eq!(chinese_to_int("99999999999999999999"), 0);
+
+        let text = "第99999999999999999999条 内容。依照第十二条。";
+        assert_eq!(normalize_article_numbers(text, false), text);
+        assert_eq!(normalize_article_numbers(text, true), "第99999999999999999999条 内容。依照第12条。");
+    }
+
+    #[test]
+    fn test_article_sort_key() {
+        assert_eq!(article_sort_key("二十九"), (29, 0));
+        assert_eq!(article_sort_key("二十九之一"), (29, 1));
+        assert!(article_sort_key("二十九之一") < article_sort_key("二十九之二"));
+        assert!(article_sort_key("二十九之二") < article_sort_key("三十"));
+    }
+
+    #[test]
+    fn test_normalize_article_numbers() {
+        let text = "第二百零一条 依照本法第十二条的规定。";
+        let arabic = normalize_article_numbers(text, true);
+        assert_eq!(arabic, "第201条 依照本法第12条的规定。");
+        assert_eq!(normalize_article_numbers(&arabic, false), text);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::models::CompareOptions;
+use std::borrow::Cow;
+#[cfg(feature = "opencc")]
+use std::collections::HashMap;
+#[cfg(feature = "opencc")]
+use std::sync::OnceLock;
+
+/// Traditional/simplified pairs written as alternating characters ("條条編编…"), after
+/// OpenCC's STCharacters/TSCharacters tables restricted to characters with a single
+/// counterpart, so conversion works both ways
+#[cfg(feature = "opencc")]
+const PAIRS: &[&str] = &[
+    // Structure and drafting
+    "條条編编節节項项規规則则總总屬属稱称謂谓錄录記记冊册號号類类級级",
+    "頒颁廢废訂订刪删補补變变換换轉转讓让屆届選选舉举罷罢辦办參参與与為为",
+    "從从來来過过進进還还這这麼么說说話话語语讀读寫写錯错誤误視视觀观覺觉",
+    "見见現现實实陳陈舊旧態态勢势無无論论雖虽並并個个們们會会議议當当應应",
+    "該该須须時时間间內内門门開开閉闭問问題题對对給给請请許许報报計计畫画",
+    "設设統统標标質质驗验試试點点較较額额萬万億亿兩两雙双歲岁滿满盡尽",
+    "齡龄緊紧嚴严輕轻傳传達达遞递郵邮據据證证書书執执紀纪組组織织員员領领",
+    "導导長长頭头專专圖图館馆廣广聽听聲声樂乐歡欢戲戏劇剧學学師师藝艺術术",
+    // Institutions and procedure
+    "國国際际關关機机構构團团體体單单審审決决訴诉訟讼罰罚處处懲惩違违",
+    "權权責责義义務务職职監监檢检測测評评價价費费貨货幣币銀银錢钱賬账資资",
+    "財财稅税貿贸買买賣卖購购銷销貸贷債债賠赔償偿損损壞坏繳缴納纳減减",
+    "偵侦羈羁釋释獄狱辯辩護护訊讯詢询鑑鉴緩缓暫暂剝剥奪夺沒没",
+    // Subject matter
+    "縣县鄉乡鎮镇區区園园場场廠厂礦矿農农漁渔獵猎樹树葉叶車车輛辆駕驾駛驶",
+    "鐵铁橋桥燈灯飛飞灣湾島岛陸陆東东華华漢汉幫帮協协調调婦妇兒儿孫孙親亲",
+    "離离繼继遺遗囑嘱贈赠養养撫抚負负擔担險险勞劳動动傷伤殘残礙碍衛卫",
+    "醫医藥药療疗環环災灾滅灭氣气棄弃網网絡络電电腦脑數数線线約约營营",
+    "運运輸输業业產产經经濟济濱滨僑侨簽签邊边戰战爭争軍军隊队備备亂乱襲袭",
+    "擊击槍枪彈弹毀毁竊窃盜盗搶抢騙骗詐诈賭赌販贩穢秽誘诱脅胁綁绑殺杀毆殴",
+    "擾扰維维穩稳圍围",
+];
+
+/// Traditional forms whose simplified counterpart is also a traditional character in its
+/// own right (乾/幹/干, 後/后, 準/准) or stands for several (髮/發/发); converted to
+/// simplified only. Characters simplified text still uses as they are (著 in 著作权, 覆 in
+/// 覆盖) are left out, since one-for-one conversion cannot tell those words apart.
+#[cfg(feature = "opencc")]
+const TO_SIMPLIFIED_ONLY: &[&str] = &[
+    "乾干幹干於于隻只祇只臺台檯台颱台餘余鬆松係系繫系裡里裏里麵面雲云後后",
+    "徵征複复復复衝冲製制穀谷醜丑佔占儘尽併并剋克嚮向捨舍歷历曆历",
+    "鬥斗髮发發发爲为週周佈布準准範范註注劃划託托傭佣幾几汙污",
+];
+
+#[cfg(feature = "opencc")]
+fn pairs(tables: &'static [&'static str]) -> impl Iterator<Item = (char, char)> {
+    tables.iter().flat_map(|row| {
+        let chars: Vec<char> = row.chars().collect();
+        chars.chunks(2).map(|pair| (pair[0], pair[1])).collect::<Vec<_>>()
+    })
+}
+
+#[cfg(feature = "opencc")]
+fn simplified_map() -> &'static HashMap<char, char> {
+    static MAP: OnceLock<HashMap<char, char>> = OnceLock::new();
+    MAP.get_or_init(|| pairs(PAIRS).chain(pairs(TO_SIMPLIFIED_ONLY)).collect())
+}
+
+#[cfg(feature = "opencc")]
+fn traditional_map() -> &'static HashMap<char, char> {
+    static MAP: OnceLock<HashMap<char, char>> = OnceLock::new();
+    MAP.get_or_init(|| pairs(PAIRS).map(|(traditional, simplified)| (simplified, traditional)).collect())
+}
+
+/// Convert traditional characters to simplified, one character for one
+#[cfg(feature = "opencc")]
+pub fn to_simplified(text: &str) -> String {
+    let map = simplified_map();
+    text.chars().map(|c| *map.get(&c).unwrap_or(&c)).collect()
+}
+
+/// Convert simplified characters to traditional, one character for one. Characters with
+/// several traditional forms (后, 发, 干…) are left as they are, so comparing through
+/// `to_simplified` is the more reliable direction.
+#[cfg(feature = "opencc")]
+pub fn to_traditional(text: &str) -> String {
+    let map = traditional_map();
+    text.chars().map(|c| *map.get(&c).unwrap_or(&c)).collect()
+}
+
+/// Bring a compared text into the script `options` asks for (`to_simplified` wins when both
+/// are set). Conversion is character for character, so line numbers and offsets still hold.
+#[cfg(feature = "opencc")]
+pub fn convert_script<'a>(text: &'a str, options: &CompareOptions) -> Cow<'a, str> {
+    if options.to_simplified {
+        Cow::Owned(to_simplified(text))
+    } else if options.to_traditional {
+        Cow::Owned(to_traditional(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Without the `opencc` feature there are no conversion tables; texts are compared as submitted
+#[cfg(not(feature = "opencc"))]
+pub fn convert_script<'a>(text: &'a str, options: &CompareOptions) -> Cow<'a, str> {
+    if options.to_simplified || options.to_traditional {
+        tracing::warn!("Simplified/traditional conversion requested but the `opencc` feature is disabled");
+    }
+    Cow::Borrowed(text)
+}
+
+#[cfg(all(test, feature = "opencc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_are_pairs() {
+        for row in PAIRS.iter().chain(TO_SIMPLIFIED_ONLY) {
+            let chars: Vec<char> = row.chars().collect();
+            assert_eq!(chars.len() % 2, 0, "odd row: {}", row);
+            assert!(chars.chunks(2).all(|pair| pair[0] != pair[1]), "identity pair in {}", row);
+        }
+    }
+
+    #[test]
+    fn test_script_conversion() {
+        let traditional = "第十二條 網絡運營者應當履行安全保護義務，並對違反規定的行為給予處罰。";
+        let simplified = "第十二条 网络运营者应当履行安全保护义务，并对违反规定的行为给予处罚。";
+        assert_eq!(to_simplified(traditional), simplified);
+        assert_eq!(to_traditional(simplified), traditional);
+
+        // Ambiguous characters only convert towards simplified
+        assert_eq!(to_simplified("以後發布"), "以后发布");
+        assert_eq!(to_traditional("以后发布"), "以后发布");
+        assert_eq!(to_simplified("著作权覆盖范围"), "著作权覆盖范围");
+
+        let options = CompareOptions { to_simplified: true, ..Default::default() };
+        assert_eq!(convert_script("條", &options), "条");
+        assert!(matches!(convert_script("條", &CompareOptions::default()), Cow::Borrowed("條")));
+    }
+}
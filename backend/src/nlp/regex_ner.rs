@@ -40,6 +40,20 @@ fn get_scope_pattern() -> &'static Regex {
     })
 }
 
+/// Confidence gained by a maximally specific match over its type's base confidence
+const SPECIFICITY_BONUS: f32 = 0.1;
+
+/// Scale a per-type base confidence by how specific the match is: longer matches
+/// and ones with more structure (digits, 年/月/日/元 units) earn up to `SPECIFICITY_BONUS`.
+fn scaled_confidence(base: f32, value: &str) -> f32 {
+    let length = value.chars().count() as f32;
+    let structure = value.chars()
+        .filter(|c| c.is_ascii_digit() || "年月日元万亿个".contains(*c))
+        .count() as f32;
+    let specificity = (length / 10.0).min(1.0) * 0.5 + (structure / 6.0).min(1.0) * 0.5;
+    base + SPECIFICITY_BONUS * specificity
+}
+
 /// Regex-based NER engine (fast, lightweight)
 pub struct RegexNER;
 
@@ -49,6 +63,12 @@ impl RegexNER {
     }
 }
 
+impl Default for RegexNER {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NEREngine for RegexNER {
     fn extract_entities(&self, text: &str) -> Result<Vec<Entity>> {
         let mut entities = Vec::new();
@@ -58,7 +78,7 @@ impl NEREngine for RegexNER {
             entities.push(Entity {
                 entity_type: EntityType::Date,
                 value: m.as_str().into(),
-                confidence: 0.85 + (rand::random::<f32>() * 0.05),
+                confidence: scaled_confidence(0.80, m.as_str()),
                 position: Position {
                     start: m.start(),
                     end: m.end(),
@@ -71,7 +91,7 @@ impl NEREngine for RegexNER {
             entities.push(Entity {
                 entity_type: EntityType::Amount,
                 value: m.as_str().into(),
-                confidence: 0.88 + (rand::random::<f32>() * 0.05),
+                confidence: scaled_confidence(0.83, m.as_str()),
                 position: Position {
                     start: m.start(),
                     end: m.end(),
@@ -84,7 +104,7 @@ impl NEREngine for RegexNER {
             entities.push(Entity {
                 entity_type: EntityType::Penalty,
                 value: m.as_str().into(),
-                confidence: 0.90 + (rand::random::<f32>() * 0.05),
+                confidence: scaled_confidence(0.85, m.as_str()),
                 position: Position {
                     start: m.start(),
                     end: m.end(),
@@ -97,7 +117,7 @@ impl NEREngine for RegexNER {
             entities.push(Entity {
                 entity_type: EntityType::Registry,
                 value: m.as_str().into(),
-                confidence: 0.87 + (rand::random::<f32>() * 0.05),
+                confidence: scaled_confidence(0.82, m.as_str()),
                 position: Position {
                     start: m.start(),
                     end: m.end(),
@@ -110,7 +130,7 @@ impl NEREngine for RegexNER {
             entities.push(Entity {
                 entity_type: EntityType::Scope,
                 value: m.as_str().into(),
-                confidence: 0.86 + (rand::random::<f32>() * 0.05),
+                confidence: scaled_confidence(0.81, m.as_str()),
                 position: Position {
                     start: m.start(),
                     end: m.end(),
@@ -129,7 +149,7 @@ impl NEREngine for RegexNER {
     }
 
     fn confidence_range(&self) -> (f32, f32) {
-        (0.85, 0.92)
+        (0.80, 0.95)
     }
 }
 
@@ -147,7 +167,22 @@ mod tests {
             .filter(|e| e.entity_type == EntityType::Date)
             .collect();
 
-        assert!(dates.len() >= 1);
+        assert!(!dates.is_empty());
+    }
+
+    #[test]
+    fn test_confidence_scales_with_specificity() {
+        let ner = RegexNER::new();
+        let entities = ner.extract_entities("保存网络日志不少于六个月，从2024年1月1日起执行").unwrap();
+
+        let confidence_of = |value: &str| entities.iter()
+            .find(|e| e.value.as_ref() == value)
+            .map(|e| e.confidence)
+            .unwrap();
+        assert!(confidence_of("2024年1月1日") > confidence_of("六个月"));
+
+        let (low, high) = ner.confidence_range();
+        assert!(entities.iter().all(|e| e.confidence >= low && e.confidence <= high));
     }
 
     #[test]
@@ -20,22 +20,30 @@ pub fn tokenize(text: &str) -> Vec<String> {
 /// Tokenize text into a HashSet for Jaccard similarity calculation
 /// Filters out single-character tokens to reduce noise
 pub fn tokenize_to_set(text: &str) -> std::collections::HashSet<Arc<str>> {
-    use std::collections::HashSet;
     let jieba = get_jieba();
     jieba.cut(text, false)
         .into_iter()
         .filter(|w| w.chars().count() > 1) // Filter out single characters (properly for unicode)
-        .map(|w| Arc::from(w))
+        .map(Arc::from)
         .collect()
 }
 
+/// Tokenize text into term frequencies for cosine similarity, filtered like `tokenize_to_set`
+pub fn tokenize_to_counts(text: &str) -> std::collections::HashMap<Arc<str>, usize> {
+    let mut counts = std::collections::HashMap::new();
+    for word in get_jieba().cut(text, false).into_iter().filter(|w| w.chars().count() > 1) {
+        *counts.entry(Arc::from(word)).or_insert(0) += 1;
+    }
+    counts
+}
+
 
 /// Tokenize with custom dictionary support
 pub fn tokenize_with_dict(text: &str, custom_words: &[String]) -> Vec<String> {
     let jieba = Jieba::new();
 
     // Add custom words to dictionary
-    for word in custom_words {
+    for _word in custom_words {
         // Note: jieba-rs doesn't support runtime dictionary modification easily
         // In production, you'd pre-build a custom dictionary file
     }
@@ -128,6 +136,6 @@ mod tests {
         assert_eq!(manager.get_words().len(), 0);
 
         manager.load_legal_terms();
-        assert!(manager.get_words().len() > 0);
+        assert!(!manager.get_words().is_empty());
     }
 }
@@ -0,0 +1,142 @@
+use crate::diff::aligner::count_by_type;
+use crate::export::html::redline_segments;
+use crate::export::markdown::{change_heading, change_type_name, joined_content, old_side, units_label};
+use crate::models::{ArticleChange, ArticleChangeType};
+use similar::ChangeTag;
+use std::fmt::Write;
+
+/// Characters of text shown before the first edit of an excerpt, and at most in total
+const EXCERPT_LEAD: usize = 20;
+const EXCERPT_CHARS: usize = 100;
+
+fn one_line(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut line: String = text.chars().take(max_chars).collect();
+    if text.chars().count() > max_chars {
+        line.push('…');
+    }
+    line
+}
+
+/// The start of an article's redline, from shortly before its first edit, with deletions
+/// as ~~struck~~ and insertions as **bold** text
+fn redline_excerpt(old: &str, new: &str) -> String {
+    let segments = redline_segments(old, new);
+    let mut out = String::new();
+    let mut budget = EXCERPT_CHARS;
+    for (i, (tag, text)) in segments.iter().enumerate() {
+        let text = text.replace('\n', " ");
+        let chars: Vec<char> = text.chars().collect();
+        let shown: String = match tag {
+            // Keep only the tail of the text leading up to the first edit
+            ChangeTag::Equal if i == 0 && chars.len() > EXCERPT_LEAD => {
+                out.push('…');
+                chars[chars.len() - EXCERPT_LEAD..].iter().collect()
+            }
+            _ => chars.iter().take(budget).collect(),
+        };
+        let len = shown.chars().count();
+        match tag {
+            ChangeTag::Equal => out.push_str(&shown),
+            ChangeTag::Delete => { let _ = write!(out, "~~{}~~", shown); }
+            ChangeTag::Insert => { let _ = write!(out, "**{}**", shown); }
+        }
+        budget -= len.min(budget);
+        if budget == 0 {
+            if i + 1 < segments.len() || len < chars.len() {
+                out.push('…');
+            }
+            break;
+        }
+    }
+    out
+}
+
+/// Characters an article change inserted and deleted, by a char-level diff
+fn changed_chars(old: &str, new: &str) -> usize {
+    redline_segments(old, new).iter()
+        .filter(|(tag, _)| *tag != ChangeTag::Equal)
+        .map(|(_, text)| text.chars().count())
+        .sum()
+}
+
+/// Summarize article changes as Markdown for readers of the revision: counts by change
+/// type, the added and deleted articles, and the `top_n` matched articles with the most
+/// changed characters, each with an excerpt of its redline.
+pub fn change_summary(changes: &[ArticleChange], top_n: usize) -> String {
+    let mut out = String::from("# 修订摘要\n\n");
+    let counts = count_by_type(changes);
+    let changed: usize = counts.iter().filter(|(t, _)| **t != ArticleChangeType::Unchanged).map(|(_, n)| n).sum();
+    if changed == 0 {
+        out.push_str("条文无变化。\n");
+        return out;
+    }
+
+    let _ = writeln!(out, "共 {} 处条文变更。\n\n| 变更 | 条数 |\n|---|---|", changed);
+    for (change_type, count) in &counts {
+        let _ = writeln!(out, "| {} | {} |", change_type_name(change_type), count);
+    }
+
+    for (change_type, heading) in [(ArticleChangeType::Added, "新增条文"), (ArticleChangeType::Deleted, "删除条文")] {
+        let listed: Vec<&ArticleChange> = changes.iter().filter(|c| c.change_type == change_type).collect();
+        if listed.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "\n## {}\n", heading);
+        for change in listed {
+            let articles = match change_type {
+                ArticleChangeType::Added => change.new_articles.clone().unwrap_or_default(),
+                _ => change.old_article.iter().cloned().collect(),
+            };
+            let _ = writeln!(out, "- **{}**：{}", units_label(&articles), one_line(&joined_content(&articles, " "), EXCERPT_CHARS / 2));
+        }
+    }
+
+    // Matched articles by how much of their text changed
+    let mut edited: Vec<(usize, &ArticleChange, String, String)> = changes.iter()
+        .filter(|c| !matches!(c.change_type, ArticleChangeType::Unchanged | ArticleChangeType::Added | ArticleChangeType::Deleted))
+        .filter_map(|c| {
+            let old = old_side(c);
+            let new = c.new_articles.as_deref().filter(|list| !list.is_empty())?;
+            let (old_text, new_text) = (joined_content(old, " "), joined_content(new, " "));
+            let amount = changed_chars(&old_text, &new_text);
+            let heading = change_heading(old, new);
+            (amount > 0).then(|| (amount, c, heading, redline_excerpt(&old_text, &new_text)))
+        })
+        .collect();
+    edited.sort_by_key(|e| std::cmp::Reverse(e.0));
+    if top_n > 0 && !edited.is_empty() {
+        let _ = writeln!(out, "\n## 改动最大的条文\n");
+        for (rank, (amount, change, heading, excerpt)) in edited.iter().take(top_n).enumerate() {
+            let similarity = change.similarity.map(|s| format!("，相似度 {:.0}%", s * 100.0)).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{}. **{}**（{}{}，变动 {} 字）\n   > {}",
+                rank + 1, heading, change_type_name(&change.change_type), similarity, amount, excerpt
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::aligner::align_articles;
+
+    #[test]
+    fn test_change_summary() {
+        let old = "第一条 为了规范网络安全管理，制定本法。\n第二条 网络日志留存不少于三个月。\n第三条 网络运营者应当履行安全保护义务。\n第四条 本条将被删除。";
+        let new = "第一条 为了规范网络安全管理，维护网络空间主权，制定本法。\n第二条 网络日志留存不少于六个月。\n第三条 网络运营者应当履行安全保护义务。\n第五条 国家支持网络安全技术研究。";
+        let summary = change_summary(&align_articles(old, new, 0.6, false), 1);
+
+        assert!(summary.starts_with("# 修订摘要\n\n共 4 处条文变更。"));
+        assert!(summary.contains("| 修改 | 2 |"));
+        assert!(summary.contains("## 新增条文\n\n- **第五条**：国家支持网络安全技术研究。\n"));
+        assert!(summary.contains("## 删除条文\n\n- **第四条**：本条将被删除。\n"));
+        // Only the top article: 第一条 gained more text than 第二条 changed
+        assert!(summary.contains("1. **第一条**（修改"));
+        assert!(summary.contains("> 为了规范网络安全管理，**维护网络空间主权，**制定本法。"));
+        assert!(!summary.contains("2. **"));
+    }
+}
@@ -20,7 +20,7 @@ import {
 import { motion, AnimatePresence } from 'framer-motion';
 import { ThemeToggle } from '@/components/theme-toggle';
 import { compareLegalTextsAsync } from '@/lib/diff-utils';
-import { DiffResult, ViewMode } from '@/lib/types';
+import { AlignmentProgress, DiffResult, ViewMode } from '@/lib/types';
 import GitDiffView from '@/components/diff/GitDiffView';
 import SideBySideView from '@/components/diff/SideBySideView';
 import AnchorNavigation from '@/components/diff/AnchorNavigation';
@@ -37,6 +37,7 @@ export default function Home() {
   const [newText, setNewText] = useState('');
   const [diffResult, setDiffResult] = useState<DiffResult | null>(null);
   const [loading, setLoading] = useState(false);
+  const [progress, setProgress] = useState<AlignmentProgress | null>(null);
   const [viewMode, setViewMode] = useState<ViewMode>('article-structure');
   const [language, setLanguage] = useState<'zh' | 'en'>('zh');
 
@@ -125,6 +126,7 @@ export default function Home() {
     if (!oldText && !newText) return;
 
     setLoading(true);
+    setProgress(null);
     await new Promise(resolve => setTimeout(resolve, 600));
 
     try {
@@ -138,7 +140,9 @@ export default function Home() {
         minSimilarity,
         maxSimilarity,
         invertSimilarity,
-        type: type
+        type: type,
+        // The structure view aligns articles, which takes long enough on large codes to stream
+        onProgress: type === 'structure' ? setProgress : undefined,
       });
 
       setDiffResult(result);
@@ -378,6 +382,7 @@ export default function Home() {
             {loading ? (
               <span className="flex items-center gap-2">
                 <Zap className="w-4 h-4 animate-spin" /> {t('analyzing')}
+                {progress && <span className="text-xs opacity-80">{progressLabel(progress)}</span>}
               </span>
             ) : (
               <span className="flex items-center gap-2">
@@ -417,3 +422,15 @@ export default function Home() {
     </div>
   );
 }
+
+// Compact status of a streamed comparison: articles parsed, matrix share, matches per stage
+function progressLabel(progress: AlignmentProgress): string {
+  switch (progress.event) {
+    case 'parsed':
+      return `${progress.oldArticles} ↔ ${progress.newArticles}`;
+    case 'matrix':
+      return `${progress.percent}%`;
+    case 'stage':
+      return `${progress.name} · ${progress.matches}`;
+  }
+}
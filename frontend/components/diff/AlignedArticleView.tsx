@@ -51,7 +51,7 @@ export function AlignedArticleView({
       if (c.type) presentTags.add(c.type);
       if (c.tags) c.tags.forEach(t => presentTags.add(t));
     });
-    const order = ['added', 'deleted', 'modified', 'renumbered', 'split', 'merged', 'moved', 'replaced', 'preamble', 'unchanged'];
+    const order = ['added', 'deleted', 'modified', 'changed', 'renumbered', 'split', 'merged', 'moved', 'replaced', 'preamble', 'unchanged'];
     return order.filter(tag => presentTags.has(tag));
   }, [changes]);
 
@@ -327,6 +327,7 @@ function getTagLabel(tag: string, lang: 'zh' | 'en'): string {
     added: "新增",
     deleted: "删除",
     modified: "修改",
+    changed: "变更",
     renumbered: "更号",
     split: "拆分",
     merged: "合并",
@@ -426,6 +427,7 @@ function ChangeTypeBadge({ type, language }: { type: string, language: 'zh' | 'e
     added: "bg-emerald-600 text-white shadow-emerald-200",
     deleted: "bg-rose-600 text-white shadow-rose-200",
     modified: "bg-amber-500 text-white shadow-amber-200",
+    changed: "bg-amber-500 text-white shadow-amber-200",
     renumbered: "bg-violet-600 text-white shadow-violet-200",
     split: "bg-sky-600 text-white shadow-sky-200",
     merged: "bg-indigo-600 text-white shadow-indigo-200",
@@ -450,6 +452,7 @@ function TagDot({ tag, isSelected }: { tag: string, isSelected: boolean }) {
         added: "bg-emerald-500",
         deleted: "bg-rose-500",
         modified: "bg-amber-500",
+        changed: "bg-amber-500",
         renumbered: "bg-violet-500",
         split: "bg-sky-500",
         merged: "bg-indigo-500",
@@ -478,7 +481,7 @@ function EmptyState({ label }: { label: string }) {
 }
 
 function ArticleCard({ article, type, side, compareTo, isMulti }: ArticleCardProps) {
-  const isModifiedLike = ['modified', 'renumbered', 'split', 'merged', 'preamble', 'replaced'].includes(type);
+  const isModifiedLike = ['modified', 'changed', 'renumbered', 'split', 'merged', 'preamble', 'replaced'].includes(type);
   let contentDisplay: React.ReactNode = article.content;
 
   if (isModifiedLike && compareTo) {
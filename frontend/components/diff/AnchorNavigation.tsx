@@ -15,6 +15,7 @@ function getTagLabel(tag: string, lang: 'zh' | 'en' = 'zh'): string {
     added: "新增",
     deleted: "删除",
     modified: "修改",
+    changed: "变更",
     renumbered: "编号变更",
     split: "拆分",
     merged: "合并",
@@ -20,6 +20,20 @@ export function ArticleChangeView({ changes }: ArticleChangeViewProps) {
   );
 }
 
+function scoreBreakdown(change: ArticleChange): string | undefined {
+  const score = change.scoreDetail;
+  if (!score) return undefined;
+  const pct = (value: number) => `${(value * 100).toFixed(1)}%`;
+  return `字符 ${pct(score.char_similarity)} · 词集 ${pct(score.jaccard_similarity)} · 包含 ${pct(score.containment_similarity)} · 关键词 ${pct(score.keyword_weight)} · 词频 ${pct(score.cosine_similarity ?? 0)}`;
+}
+
+function runnerUpHint(change: ArticleChange): string | undefined {
+  const runnerUp = change.runnerUp;
+  if (!runnerUp) return undefined;
+  const side = runnerUp.side === 'old' ? '原' : '新';
+  return `次优候选：${side}第${runnerUp.article.number}条 (${(runnerUp.similarity * 100).toFixed(1)}%)`;
+}
+
 interface ArticleChangeCardProps {
   change: ArticleChange;
 }
@@ -36,9 +50,14 @@ function ArticleChangeCard({ change }: ArticleChangeCardProps) {
                      {typeConfig.icon}
                      {typeConfig.label}
                  </Badge>
+                 {change.needsReview && (
+                   <Badge variant="outline" className="border-amber-500/50 text-amber-400" title={runnerUpHint(change)}>
+                     待复核
+                   </Badge>
+                 )}
              </div>
              {change.similarity !== undefined && (
-               <div className="flex items-center gap-2">
+               <div className="flex items-center gap-2" title={scoreBreakdown(change)}>
                  <span className="text-xs text-muted-foreground uppercase tracking-wider">Similarity</span>
                  <span className={cn("text-sm font-bold font-mono", change.similarity > 0.8 ? "text-green-400" : "text-amber-400")}>
                     {(change.similarity * 100).toFixed(1)}%
@@ -56,7 +75,13 @@ function ArticleChangeCard({ change }: ArticleChangeCardProps) {
               <DeletedArticle article={change.oldArticle} />
             )}
 
-            {(change.type === 'modified' || change.type === 'renumbered' || change.type === 'unchanged') &&
+            {change.nearest && change.nearest.length > 0 && (
+              <div className="mt-2 text-xs text-muted-foreground">
+                最接近：{change.nearest.map(c => `${c.side === 'old' ? '原' : '新'}第${c.article.number}条 (${(c.similarity * 100).toFixed(1)}%)`).join('，')}
+              </div>
+            )}
+
+            {(change.type === 'modified' || change.type === 'changed' || change.type === 'renumbered' || change.type === 'moved' || change.type === 'corresponding' || change.type === 'unchanged') &&
              change.oldArticle && change.newArticles && (
               <ModifiedArticle
                 oldArticle={change.oldArticle}
@@ -196,12 +221,24 @@ function getTypeConfig(type: ArticleChangeType) {
       borderColor: 'border-red-500/30',
       icon: <ListX className="w-3 h-3" />
     },
+    changed: {
+      label: '内容变更',
+      badgeVariant: 'info',
+      borderColor: 'border-blue-500/30',
+      icon: <X className="w-3 h-3 rotate-45" />
+    },
     replaced: {
       label: '内容替换',
       badgeVariant: 'info',
       borderColor: 'border-orange-500/30',
       icon: <Check className="w-3 h-3" />
     },
+    corresponding: {
+      label: '对应条款',
+      badgeVariant: 'info',
+      borderColor: 'border-teal-500/30',
+      icon: <MoveHorizontal className="w-3 h-3" />
+    },
     preamble: {
       label: '规范导语',
       badgeVariant: 'outline',
@@ -74,6 +74,7 @@ export const DiffResultViewer = React.memo(({
             stats.deletions++;
             break;
           case 'modified':
+          case 'changed':
           case 'renumbered':
           case 'split':
           case 'merged':
@@ -1,5 +1,5 @@
 import * as Diff from 'diff';
-import { Change, DiffResult, EntityType } from './types';
+import { AlignmentProgress, Change, DiffResult, EntityType } from './types';
 
 const BACKEND_API_URL = '/api/compare';
 const BACKEND_HEALTH_URL = '/health';
@@ -33,11 +33,20 @@ export async function compareLegalTextsAsync(
     minSimilarity?: number;
     maxSimilarity?: number;
     invertSimilarity?: boolean;
+    granularity: 'line' | 'word' | 'char' | 'sentence';
+    algorithm: 'myers' | 'patience' | 'lcs';
+    contextLines: number;
+    alignmentStrategy: 'heuristic' | 'optimal';
+    omitUnchanged: boolean;
     type: 'full' | 'git' | 'structure';
+    onProgress: (progress: AlignmentProgress) => void; // Streams the comparison of `type` (full or structure) as SSE
   }>
 ): Promise<DiffResult> {
   const type = options?.type || 'full';
-  const endpoint = type === 'full' ? '/api/compare' : `/api/compare/${type}`;
+  const path = type === 'full' ? '/api/compare' : `/api/compare/${type}`;
+  const endpoint = options?.onProgress
+    ? (type === 'structure' ? `${path}/stream` : '/api/compare/stream')
+    : path;
 
   try {
     const response = await fetch(endpoint, {
@@ -52,10 +61,15 @@ export async function compareLegalTextsAsync(
           detect_entities: options?.detectEntities ?? true,
           ner_mode: 'regex',
           align_threshold: options?.alignThreshold ?? 0.6,
+          alignment_strategy: options?.alignmentStrategy,
           format_text: options?.formatText ?? true,
           min_similarity: options?.minSimilarity,
           max_similarity: options?.maxSimilarity,
           invert_similarity: options?.invertSimilarity,
+          granularity: options?.granularity ?? 'line',
+          algorithm: options?.algorithm,
+          context_lines: options?.contextLines,
+          omit_unchanged: options?.omitUnchanged,
         },
       }),
     });
@@ -64,7 +78,9 @@ export async function compareLegalTextsAsync(
       throw new Error(`Backend API error: ${response.statusText}`);
     }
 
-    const data = await response.json();
+    const data = options?.onProgress
+      ? await readCompareStream(response, options.onProgress)
+      : await response.json();
     return transformBackendResponse(data);
   } catch (error) {
     console.warn(`Backend comparison (${type}) failed, falling back to local processing:`, error);
@@ -72,6 +88,39 @@ export async function compareLegalTextsAsync(
   }
 }
 
+/**
+ * Read the Server-Sent Events of /api/compare/stream (or /api/compare/structure/stream), reporting progress events
+ * and resolving with the data of the final "result" event
+ */
+async function readCompareStream(
+  response: Response,
+  onProgress: (progress: AlignmentProgress) => void
+): Promise<any> {
+  if (!response.body) {
+    throw new Error('Backend stream has no body');
+  }
+  const reader = response.body.pipeThrough(new TextDecoderStream()).getReader();
+  let buffer = '';
+  for (;;) {
+    const { done, value } = await reader.read();
+    if (done) break;
+    buffer += value;
+
+    // Events are separated by a blank line; keep the incomplete tail for the next chunk
+    const events = buffer.split('\n\n');
+    buffer = events.pop() ?? '';
+    for (const block of events) {
+      const lines = block.split('\n');
+      const name = lines.find(l => l.startsWith('event:'))?.slice(6).trim();
+      const data = lines.filter(l => l.startsWith('data:')).map(l => l.slice(5).trim()).join('\n');
+      if (!name || !data) continue;
+      if (name === 'result') return JSON.parse(data);
+      onProgress(JSON.parse(data));
+    }
+  }
+  throw new Error('Backend stream ended without a result');
+}
+
 /**
  * Transform backend response to frontend DiffResult format
  */
@@ -80,18 +129,23 @@ function transformBackendResponse(data: any): DiffResult {
   // We still map explicitly to ensure type safety and handle optional fields correctly.
 
   const changes = data.changes.map((c: any) => ({
+    id: c.id,
     type: c.type,
     oldLine: c.oldLine,
     newLine: c.newLine,
     oldContent: c.oldContent,
     newContent: c.newContent,
     entities: c.entities,
+    oldRanges: c.oldRanges,
+    newRanges: c.newRanges,
+    elided: c.elided,
   }));
 
   // Transform article changes if present
   let articleChanges = undefined;
   if (data.articleChanges) {
     articleChanges = data.articleChanges.map((ac: any) => ({
+      id: ac.id,
       type: ac.type,
       oldArticle: ac.oldArticle ? {
         number: ac.oldArticle.number,
@@ -116,6 +170,11 @@ function transformBackendResponse(data: any): DiffResult {
          newContent: d.newContent
       })) : undefined,
       tags: ac.tags || [],
+      scoreDetail: ac.scoreDetail,
+      moved: ac.moved,
+      needsReview: ac.needsReview,
+      runnerUp: ac.runnerUp,
+      nearest: ac.nearest,
     }));
   }
 
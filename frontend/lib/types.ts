@@ -1,13 +1,17 @@
 // Legal article structure types
 export interface CompareOptions {
   detectEntities: boolean;
-  granularity: 'line' | 'word' | 'char';
+  granularity: 'line' | 'word' | 'char' | 'sentence';
+  algorithm?: 'myers' | 'patience' | 'lcs';
+  contextLines?: number; // Unchanged lines kept around each change; the rest collapse into "…"
+  omitUnchanged?: boolean;
   nerMode?: 'regex' | 'bert' | 'hybrid';
   alignThreshold?: number;
+  alignmentStrategy?: 'heuristic' | 'optimal';
   formatText?: boolean;
 }
 export interface ArticleNode {
-  type: 'chapter' | 'section' | 'article' | 'clause' | 'item' | 'preamble';
+  type: 'chapter' | 'section' | 'article' | 'clause' | 'item' | 'preamble' | 'supplementary' | 'annex' | 'toc' | 'tocentry' | 'table' | 'tablerow' | 'tablecell' | 'subpart';
   number: string;
   title?: string;
   content: string;
@@ -26,12 +30,16 @@ export interface LegalArticle {
 export type ChangeType = 'add' | 'delete' | 'modify' | 'unchanged';
 
 export interface Change {
+  id?: string; // Stable across re-runs, for deep links and annotations
   type: ChangeType;
   oldLine?: number;
   newLine?: number;
   oldContent?: string;
   newContent?: string;
   entities?: Entity[];
+  oldRanges?: { start: number; end: number }[]; // Char spans that differ (modify only)
+  newRanges?: { start: number; end: number }[];
+  elided?: number; // Set on "…" markers: how many unchanged lines they stand for
 }
 
 // Structural Diff Types (Pro Feature)
@@ -45,26 +53,60 @@ export type ArticleChangeType =
   | 'added'
   | 'deleted'
   | 'replaced'
-  | 'preamble';   // Metadata/Intro/TOC
+  | 'preamble'    // Metadata/Intro/TOC
+  | 'changed'     // Collapsed modified/renumbered/replaced (simplify_change_types)
+  | 'corresponding'; // Counterpart provision in another law (cross_law)
 
 export interface ArticleInfo {
   number: string;
   content: string;
   title?: string;
   startLine: number;
-  nodeType: 'chapter' | 'section' | 'article' | 'clause' | 'item' | 'preamble';
+  startOffset?: number; // Char offsets into the parsed text
+  endOffset?: number;
+  nodeType: 'chapter' | 'section' | 'article' | 'clause' | 'item' | 'preamble' | 'supplementary' | 'annex' | 'toc' | 'tocentry' | 'table' | 'tablerow' | 'tablecell' | 'subpart';
   parents?: string[];
+  source?: { line: number; offset: number }; // Start in the submitted text, before normalization
+  fingerprint?: string; // 64-bit SimHash of the content, 16 hex digits
+}
+
+export interface SimilarityScore {
+  char_similarity: number;
+  jaccard_similarity: number;
+  containment_similarity: number;
+  keyword_weight: number;
+  cosine_similarity?: number; // Term-frequency cosine
+  composite: number;
 }
 
 export interface ArticleChange {
+  id?: string;
   type: ArticleChangeType;
   oldArticle?: ArticleInfo;
   newArticles?: ArticleInfo[];
   similarity?: number;
+  scoreDetail?: SimilarityScore; // Components behind `similarity`, for 1:1 matches
   details?: Change[]; // Word-level diff within matched articles
   tags?: string[];
+  moved?: { from: string[]; to: string[] }; // Hierarchy before and after, for moved articles
+  needsReview?: boolean; // Match barely above threshold or barely ahead of the runner-up
+  runnerUp?: Candidate;
+  nearest?: Candidate[]; // Closest counterparts of an added or deleted article, best first
+}
+
+// Another article an ambiguous or unmatched article could correspond to
+export interface Candidate {
+  side: 'old' | 'new';
+  article: ArticleInfo;
+  similarity: number;
 }
 
+// Milestones streamed by /api/compare/stream and /api/compare/structure/stream while aligning
+export type AlignmentProgress =
+  | { event: 'parsed'; oldArticles: number; newArticles: number }
+  | { event: 'matrix'; percent: number }
+  | { event: 'stage'; name: string; matches: number };
+
 export interface DiffResult {
   similarity: number;
   changes: Change[];
@@ -75,6 +117,10 @@ export interface DiffResult {
     deletions: number;
     modifications: number;
     unchanged: number;
+    charsAdded?: number;
+    charsDeleted?: number;
+    articlesByType?: Partial<Record<ArticleChange['type'], number>>;
+    chapters?: { chapter: string; counts: Partial<Record<ArticleChange['type'], number>> }[];
   };
 }
 